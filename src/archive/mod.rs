@@ -0,0 +1,410 @@
+//! Reading of `ar` archives, i.e. static libraries.
+//!
+//! [`Archive`] reads the common format in both its GNU and BSD variants,
+//! which differ in how they store member names longer than 15 bytes, and
+//! GNU thin archives, which only hold the names of their members: files
+//! next to the archive. The symbol index `ranlib` adds is read from the
+//! GNU `/` or `/SYM64/` member or from the BSD `__.SYMDEF` one.
+
+use ::std::fmt::{Display, Formatter};
+use ::json::Json;
+
+/// The magic bytes at the start of an archive.
+pub const ARMAG: &[u8] = b"!<arch>\n";
+/// The magic bytes at the start of a GNU thin archive.
+pub const THINMAG: &[u8] = b"!<thin>\n";
+
+const HEADER_SIZE: usize = 60;
+const HEADER_END: &[u8] = b"`\n";
+
+/// Whether `data` starts like an archive, thin or not.
+pub fn is_archive(data: &[u8]) -> bool {
+    data.starts_with(ARMAG) || data.starts_with(THINMAG)
+}
+
+/// Everything that can go wrong while reading an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// The data doesn't start with `!<arch>\n` or `!<thin>\n`.
+    BadMagic,
+    /// The header or the contents of the member at `offset` extend past
+    /// the end of the archive.
+    Truncated {
+        offset: u64,
+    },
+    /// The header at `offset` doesn't end in "`\n" or has a field that
+    /// isn't a number.
+    BadHeader {
+        offset: u64,
+    },
+    /// The long name of the member at `offset` isn't in the long name
+    /// table.
+    BadName {
+        offset: u64,
+    },
+    /// The symbol index is too short for the symbols it claims to have.
+    BadIndex,
+}
+
+impl Display for ArchiveError {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        use self::ArchiveError::*;
+        match *self {
+            BadMagic => write!(fmt, "Not an archive - it has the wrong magic bytes at the start"),
+            Truncated { offset } => write!(
+                fmt, "The archive member at offset {:#x} extends past the end of the file",
+                offset),
+            BadHeader { offset } => write!(
+                fmt, "Invalid archive member header at offset {:#x}", offset),
+            BadName { offset } => write!(
+                fmt, "Invalid long name of the archive member at offset {:#x}", offset),
+            BadIndex => write!(fmt, "The archive symbol index is corrupt"),
+        }
+    }
+}
+
+impl ::std::error::Error for ArchiveError {}
+
+/// A member of an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveMember<'data> {
+    /// The name, with long names looked up and the `/` GNU ar ends names
+    /// with stripped. In thin archives, the path of the member relative to
+    /// the directory of the archive.
+    pub name: String,
+    /// The offset of the member header in the archive, which the symbol
+    /// index refers to members by.
+    pub offset: u64,
+    pub date: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub mode: u32,
+    /// The size of the contents; in thin archives, of the file.
+    pub size: u64,
+    /// The contents, `None` in thin archives.
+    pub data: Option<&'data [u8]>,
+}
+
+impl<'data> ArchiveMember<'data> {
+    /// Describes the member for `--output=json`.
+    pub fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("name", self.name.as_str().into()),
+            ("offset", self.offset.into()),
+            ("size", self.size.into()),
+        ])
+    }
+}
+
+/// A symbol of the archive index, with the member that defines it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveSymbol {
+    pub name: String,
+    /// The offset of the header of the member defining the symbol.
+    pub member_offset: u64,
+}
+
+/// The symbol index of an archive, in the order of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveIndex {
+    /// Whether the offsets are 64-bit, as in `/SYM64/` and `__.SYMDEF_64`.
+    pub is_64: bool,
+    pub symbols: Vec<ArchiveSymbol>,
+    /// The size of the string table holding the names of the symbols.
+    pub strings_size: u64,
+}
+
+/// An archive read from a byte slice. Members are read as they are
+/// iterated over; only the index and the long name table are read up
+/// front.
+pub struct Archive<'data> {
+    data: &'data [u8],
+    is_thin: bool,
+    long_names: &'data [u8],
+    index: Option<ArchiveIndex>,
+    first_member: usize,
+}
+
+/// The raw fields of a member header, with the numbers parsed.
+struct Header<'data> {
+    name: &'data [u8],
+    date: u64,
+    uid: u32,
+    gid: u32,
+    mode: u32,
+    size: u64,
+}
+
+/// Parses a number field of a header, padded with spaces; blank fields,
+/// as some tools write for the special members, are 0.
+fn parse_field(field: &[u8], radix: u32) -> Option<u64> {
+    let field = ::std::str::from_utf8(field).ok()?.trim_end_matches(' ');
+    if field.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(field, radix).ok()
+}
+
+fn read_header(data: &[u8], offset: usize) -> Result<Header<'_>, ArchiveError> {
+    let header = data.get(offset..offset + HEADER_SIZE)
+        .ok_or(ArchiveError::Truncated { offset: offset as u64 })?;
+    let bad_header = ArchiveError::BadHeader { offset: offset as u64 };
+    if &header[58..60] != HEADER_END {
+        return Err(bad_header);
+    }
+    let field = |range: ::std::ops::Range<usize>, radix| {
+        parse_field(&header[range], radix).ok_or_else(|| bad_header.clone())
+    };
+    Ok(Header {
+        name: &header[..16],
+        date: field(16..28, 10)?,
+        uid: field(28..34, 10)? as u32,
+        gid: field(34..40, 10)? as u32,
+        mode: field(40..48, 8)? as u32,
+        size: field(48..58, 10)?,
+    })
+}
+
+/// Returns the string starting at `offset` of `strings`, up to a NUL or
+/// the end of the table.
+fn get_string(strings: &[u8], offset: usize) -> String {
+    let s = &strings[offset.min(strings.len())..];
+    let end = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+    String::from_utf8_lossy(&s[..end]).into_owned()
+}
+
+/// Reads the big-endian entry `index` of `size` bytes from the start of
+/// `data`, if it's there.
+fn read_be(data: &[u8], index: usize, size: usize) -> Option<u64> {
+    let bytes = data.get(index * size..(index + 1) * size)?;
+    Some(bytes.iter().fold(0, |value, &b| (value << 8) | b as u64))
+}
+
+/// Reads the GNU index of the `/` or, with `is_64`, the `/SYM64/` member:
+/// the number of symbols and their member offsets, big-endian, followed
+/// by their names.
+fn read_gnu_index(data: &[u8], is_64: bool) -> Result<ArchiveIndex, ArchiveError> {
+    let word = if is_64 { 8 } else { 4 };
+    let count = read_be(data, 0, word).ok_or(ArchiveError::BadIndex)? as usize;
+    let strings = count.checked_add(1)
+        .and_then(|n| n.checked_mul(word))
+        .and_then(|start| data.get(start..))
+        .ok_or(ArchiveError::BadIndex)?;
+
+    let mut symbols = Vec::with_capacity(count);
+    let mut name = 0;
+    for i in 0..count {
+        if name >= strings.len() {
+            return Err(ArchiveError::BadIndex);
+        }
+        let end = strings[name..].iter().position(|&c| c == 0).map_or(strings.len(), |n| name + n);
+        symbols.push(ArchiveSymbol {
+            name: String::from_utf8_lossy(&strings[name..end]).into_owned(),
+            member_offset: read_be(data, i + 1, word).unwrap(),
+        });
+        name = end + 1;
+    }
+    Ok(ArchiveIndex { is_64, symbols, strings_size: strings.len() as u64 })
+}
+
+/// Reads the BSD index of the `__.SYMDEF` or, with `is_64`, the
+/// `__.SYMDEF_64` member: the size of an array of name and member offset
+/// pairs, the array, the size of the names and the names. It's in the
+/// byte order of the objects, which is taken to be the one in which the
+/// sizes fit in the member.
+fn read_bsd_index(data: &[u8], is_64: bool) -> Result<ArchiveIndex, ArchiveError> {
+    let word = if is_64 { 8 } else { 4 };
+    let read = |offset: usize, le: bool| {
+        data.get(offset..offset.checked_add(word)?).map(|bytes| {
+            let fold = |value, &b| (value << 8) | b as u64;
+            if le { bytes.iter().rev().fold(0, fold) } else { bytes.iter().fold(0, fold) }
+        })
+    };
+    let fits = |le: bool| {
+        read(0, le)
+            .filter(|&size| size % (2 * word as u64) == 0)
+            .and_then(|size| (size as usize).checked_add(word))
+            .and_then(|offset| read(offset, le).map(|n| (offset, n)))
+            .filter(|&(offset, n)| n <= (data.len() - offset - word) as u64)
+            .is_some()
+    };
+    let le = fits(true);
+    if !le && !fits(false) {
+        return Err(ArchiveError::BadIndex);
+    }
+
+    let count = read(0, le).unwrap() as usize / (2 * word);
+    let strings_start = word + count * 2 * word + word;
+    let strings_size = read(strings_start - word, le).unwrap() as usize;
+    let strings = &data[strings_start..strings_start + strings_size];
+    let symbols = (0..count)
+        .map(|i| {
+            let entry = word + i * 2 * word;
+            ArchiveSymbol {
+                name: get_string(strings, read(entry, le).unwrap() as usize),
+                member_offset: read(entry + word, le).unwrap(),
+            }
+        })
+        .collect();
+    Ok(ArchiveIndex { is_64, symbols, strings_size: strings_size as u64 })
+}
+
+impl<'data> Archive<'data> {
+    /// Reads the magic bytes and the special members at the start of the
+    /// archive: the symbol index and the GNU long name table.
+    pub fn parse(data: &'data [u8]) -> Result<Archive<'data>, ArchiveError> {
+        let is_thin = data.starts_with(THINMAG);
+        if !is_thin && !data.starts_with(ARMAG) {
+            return Err(ArchiveError::BadMagic);
+        }
+        let mut archive = Archive {
+            data,
+            is_thin,
+            long_names: &[],
+            index: None,
+            first_member: ARMAG.len(),
+        };
+
+        while archive.first_member < data.len() {
+            let offset = archive.first_member;
+            let header = read_header(data, offset)?;
+            let name = header.name;
+            if name.starts_with(b"/ ") || name.starts_with(b"/SYM64/ ") {
+                let is_64 = name[1] == b'S';
+                archive.index = Some(read_gnu_index(archive.inline_data(offset, &header)?, is_64)?);
+            } else if name.starts_with(b"// ") {
+                archive.long_names = archive.inline_data(offset, &header)?;
+            } else {
+                let member = archive.read_member(offset)?.0;
+                match &member.name[..] {
+                    "__.SYMDEF" | "__.SYMDEF SORTED" => {
+                        archive.index = Some(read_bsd_index(member.data.unwrap_or(&[]), false)?);
+                    }
+                    "__.SYMDEF_64" | "__.SYMDEF_64 SORTED" => {
+                        archive.index = Some(read_bsd_index(member.data.unwrap_or(&[]), true)?);
+                    }
+                    _ => break,
+                }
+            }
+            archive.first_member = archive.next_member(offset, &header);
+        }
+        Ok(archive)
+    }
+
+    /// Whether this is a GNU thin archive, whose members are files of
+    /// their own.
+    pub fn is_thin(&self) -> bool {
+        self.is_thin
+    }
+
+    /// The symbol index, if the archive has one.
+    pub fn index(&self) -> Option<&ArchiveIndex> {
+        self.index.as_ref()
+    }
+
+    /// The members, except for the symbol index and the long name table.
+    /// Iteration stops after the first error.
+    pub fn members(&self) -> ArchiveMembers<'_, 'data> {
+        ArchiveMembers { archive: self, offset: self.first_member, failed: false }
+    }
+
+    /// Reads the member whose header is at `offset`, e.g. one the symbol
+    /// index refers to.
+    pub fn member_at(&self, offset: u64) -> Result<ArchiveMember<'data>, ArchiveError> {
+        if offset > self.data.len() as u64 {
+            return Err(ArchiveError::Truncated { offset });
+        }
+        self.read_member(offset as usize).map(|(member, _)| member)
+    }
+
+    /// The contents of the member at `offset`, which are in the archive
+    /// even in thin ones for the special members.
+    fn inline_data(&self, offset: usize, header: &Header) -> Result<&'data [u8], ArchiveError> {
+        let start = offset + HEADER_SIZE;
+        (start as u64).checked_add(header.size)
+            .filter(|&end| end <= self.data.len() as u64)
+            .map(|end| &self.data[start..end as usize])
+            .ok_or(ArchiveError::Truncated { offset: offset as u64 })
+    }
+
+    /// The offset of the header following the member at `offset`. Members
+    /// start at even offsets; those of thin archives have no contents
+    /// there, except for the special ones.
+    fn next_member(&self, offset: usize, header: &Header) -> usize {
+        let special = header.name.starts_with(b"/ ")
+            || header.name.starts_with(b"/SYM64/ ")
+            || header.name.starts_with(b"// ");
+        let size = if self.is_thin && !special { 0 } else { header.size as usize };
+        let end = offset + HEADER_SIZE + size;
+        end + (end & 1)
+    }
+
+    /// Reads the member at `offset` and returns it with the offset of the
+    /// next one.
+    fn read_member(&self, offset: usize) -> Result<(ArchiveMember<'data>, usize), ArchiveError> {
+        let header = read_header(self.data, offset)?;
+        let bad_name = ArchiveError::BadName { offset: offset as u64 };
+        let mut data = if self.is_thin { None } else { Some(self.inline_data(offset, &header)?) };
+
+        let field = String::from_utf8_lossy(header.name);
+        let field = field.trim_end_matches(' ');
+        let name = if let Some(length) = field.strip_prefix("#1/") {
+            // BSD long names come first in the contents, padded with NULs.
+            let length = length.parse::<usize>().map_err(|_| bad_name.clone())?;
+            let contents = data.ok_or_else(|| bad_name.clone())?;
+            if length > contents.len() {
+                return Err(bad_name);
+            }
+            data = Some(&contents[length..]);
+            get_string(&contents[..length], 0)
+        } else if let Some(name_offset) = field.strip_prefix('/').filter(|s| !s.is_empty()) {
+            // GNU long names are in the `//` member, each ending in "/\n".
+            let name_offset = name_offset.parse::<usize>().map_err(|_| bad_name.clone())?;
+            let names = self.long_names.get(name_offset..).ok_or(bad_name)?;
+            let end = names.iter().position(|&c| c == b'\n').unwrap_or(names.len());
+            let name = String::from_utf8_lossy(&names[..end]);
+            name.strip_suffix('/').unwrap_or(&name).to_string()
+        } else {
+            field.strip_suffix('/').unwrap_or(field).to_string()
+        };
+
+        let member = ArchiveMember {
+            name,
+            offset: offset as u64,
+            date: header.date,
+            uid: header.uid,
+            gid: header.gid,
+            mode: header.mode,
+            size: data.map_or(header.size, |data| data.len() as u64),
+            data,
+        };
+        Ok((member, self.next_member(offset, &header)))
+    }
+}
+
+/// Iterates over the members of an [`Archive`].
+pub struct ArchiveMembers<'a, 'data: 'a> {
+    archive: &'a Archive<'data>,
+    offset: usize,
+    failed: bool,
+}
+
+impl<'a, 'data> Iterator for ArchiveMembers<'a, 'data> {
+    type Item = Result<ArchiveMember<'data>, ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.archive.data.len() {
+            return None;
+        }
+        match self.archive.read_member(self.offset) {
+            Ok((member, next)) => {
+                self.offset = next;
+                Some(Ok(member))
+            }
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! A zlib (RFC 1950) and deflate (RFC 1951) decoder.
+//!
+//! Huffman codes are decoded a bit at a time from their canonical form, as
+//! zlib's `puff` does. That's slower than zlib's table-driven decoder, but
+//! plenty for the debug sections it's used on, and easy to check against
+//! the RFC.
+
+use super::DecompressError;
+
+const MAX_BITS: usize = 15;
+
+/// Base lengths and extra bits of the length symbols 257..285.
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances and extra bits of the distance symbols 0..29.
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// The order in which the lengths of the code length code are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompresses one or more concatenated zlib streams, which must add up
+/// to exactly `size` bytes. The Adler-32 checksum of each stream is
+/// checked.
+pub fn zlib_decompress(data: &[u8], size: u64) -> Result<Vec<u8>, DecompressError> {
+    // Don't trust a corrupt size with the allocation; deflate can't do
+    // better than about 1:1032 anyway.
+    let capacity = size.min(data.len() as u64 * 1032) as usize;
+    let mut out = Vec::with_capacity(capacity);
+
+    let mut pos = 0;
+    while pos < data.len() {
+        if data.len() - pos < 2 {
+            return Err(DecompressError::Truncated);
+        }
+        let (cmf, flg) = (data[pos], data[pos + 1]);
+        if cmf & 0x0f != 8 || cmf >> 4 > 7 {
+            return Err(DecompressError::Corrupt("unknown zlib compression method"));
+        }
+        if !(cmf as u16 * 256 + flg as u16).is_multiple_of(31) {
+            return Err(DecompressError::Corrupt("bad zlib header check"));
+        }
+        if flg & 0x20 != 0 {
+            return Err(DecompressError::Unsupported("zlib preset dictionary"));
+        }
+
+        let start = out.len();
+        let mut reader = BitReader::new(&data[pos + 2..]);
+        inflate(&mut reader, &mut out, start)?;
+        pos += 2 + reader.byte_pos();
+
+        let checksum = data.get(pos..pos + 4).ok_or(DecompressError::Truncated)?;
+        let checksum = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+        if checksum != adler32(&out[start..]) {
+            return Err(DecompressError::Corrupt("bad zlib checksum"));
+        }
+        pos += 4;
+    }
+
+    if out.len() as u64 != size {
+        return Err(DecompressError::SizeMismatch { expected: size, actual: out.len() as u64 });
+    }
+    Ok(out)
+}
+
+/// Decodes a raw deflate stream into `out`. Matches may only refer back to
+/// the data after `start`, where the stream's output begins.
+fn inflate(reader: &mut BitReader, out: &mut Vec<u8>, start: usize)
+    -> Result<(), DecompressError>
+{
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let len = reader.bits(16)?;
+                let nlen = reader.bits(16)?;
+                if len != !nlen & 0xffff {
+                    return Err(DecompressError::Corrupt("bad stored block length"));
+                }
+                out.extend_from_slice(reader.take_bytes(len as usize)?);
+            }
+            1 => {
+                let (lengths, distances) = fixed_codes();
+                inflate_block(reader, out, start, &lengths, &distances)?;
+            }
+            2 => {
+                let (lengths, distances) = read_dynamic_codes(reader)?;
+                inflate_block(reader, out, start, &lengths, &distances)?;
+            }
+            _ => return Err(DecompressError::Corrupt("bad deflate block type")),
+        }
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+fn inflate_block(
+    reader: &mut BitReader, out: &mut Vec<u8>, start: usize,
+    lengths: &Huffman, distances: &Huffman)
+    -> Result<(), DecompressError>
+{
+    loop {
+        let symbol = lengths.decode(reader)? as usize;
+        if symbol < 256 {
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let symbol = symbol - 257;
+        if symbol >= LENGTH_BASE.len() {
+            return Err(DecompressError::Corrupt("bad length symbol"));
+        }
+        let len = LENGTH_BASE[symbol] as usize + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+        let symbol = distances.decode(reader)? as usize;
+        if symbol >= DIST_BASE.len() {
+            return Err(DecompressError::Corrupt("bad distance symbol"));
+        }
+        let dist = DIST_BASE[symbol] as usize + reader.bits(DIST_EXTRA[symbol] as u32)? as usize;
+        if dist > out.len() - start {
+            return Err(DecompressError::Corrupt("distance too far back"));
+        }
+
+        // The match may overlap the bytes it produces.
+        let from = out.len() - dist;
+        for i in 0..len {
+            let byte = out[from + i];
+            out.push(byte);
+        }
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    for (symbol, len) in lengths.iter_mut().enumerate() {
+        *len = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    // Both sets are complete, so building them can't fail.
+    (Huffman::new(&lengths).unwrap_or_default(), Huffman::new(&[5; 30]).unwrap_or_default())
+}
+
+fn read_dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), DecompressError> {
+    let nlen = reader.bits(5)? as usize + 257;
+    let ndist = reader.bits(5)? as usize + 1;
+    let ncode = reader.bits(4)? as usize + 4;
+    if nlen > 286 || ndist > 30 {
+        return Err(DecompressError::Corrupt("too many length or distance codes"));
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &i in &CODE_LENGTH_ORDER[..ncode] {
+        code_lengths[i] = reader.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; nlen + ndist];
+    let mut i = 0;
+    while i < lengths.len() {
+        let symbol = code_lengths.decode(reader)?;
+        let (len, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                if i == 0 {
+                    return Err(DecompressError::Corrupt("repeated length with no first length"));
+                }
+                (lengths[i - 1], 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+        if i + repeat > lengths.len() {
+            return Err(DecompressError::Corrupt("too many code lengths"));
+        }
+        for length in &mut lengths[i..i + repeat] {
+            *length = len;
+        }
+        i += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(DecompressError::Corrupt("no end-of-block code"));
+    }
+
+    Ok((Huffman::new(&lengths[..nlen])?, Huffman::new(&lengths[nlen..])?))
+}
+
+/// A canonical Huffman code: the number of codes of each length, and the
+/// symbols ordered by their codes.
+#[derive(Debug, Default)]
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Builds the code from the code length of each symbol, 0 meaning the
+    /// symbol doesn't occur. Incomplete codes are allowed, as a single
+    /// distance code is; decoding a missing code fails then.
+    fn new(lengths: &[u8]) -> Result<Huffman, DecompressError> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = left * 2 - count as i32;
+            if left < 0 {
+                return Err(DecompressError::Corrupt("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        counts[0] = 0;
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, DecompressError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..=MAX_BITS {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(DecompressError::Corrupt("bad Huffman code"))
+    }
+}
+
+/// Reads bits from the least significant one up, as deflate stores them.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buf: u64,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader { data, pos: 0, buf: 0, count: 0 }
+    }
+
+    /// Reads `n` bits, up to 32.
+    fn bits(&mut self, n: u32) -> Result<u32, DecompressError> {
+        while self.count < n {
+            let byte = *self.data.get(self.pos).ok_or(DecompressError::Truncated)?;
+            self.buf |= (byte as u64) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+        let value = (self.buf & ((1u64 << n) - 1)) as u32;
+        self.buf >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Skips to the next byte boundary.
+    fn align(&mut self) {
+        let n = self.count % 8;
+        self.buf >>= n;
+        self.count -= n;
+    }
+
+    /// Returns the next `n` whole bytes. The reader must be aligned.
+    fn take_bytes(&mut self, n: usize) -> Result<&'a [u8], DecompressError> {
+        let pos = self.byte_pos();
+        let bytes = self.data.get(pos..pos + n).ok_or(DecompressError::Truncated)?;
+        self.pos = pos + n;
+        self.buf = 0;
+        self.count = 0;
+        Ok(bytes)
+    }
+
+    /// The offset of the first byte that hasn't been read, whole or in
+    /// part.
+    fn byte_pos(&self) -> usize {
+        self.pos - (self.count / 8) as usize
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most that can be summed before b overflows.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
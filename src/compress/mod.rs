@@ -0,0 +1,42 @@
+//! Decompression of the formats used by compressed ELF sections: zlib, for
+//! `ELFCOMPRESS_ZLIB` and the GNU `.zdebug` sections, and zstd, for
+//! `ELFCOMPRESS_ZSTD`. Only decoding is supported.
+
+use ::std::fmt::{Display, Formatter};
+
+pub mod inflate;
+pub mod zstd;
+
+pub use self::inflate::zlib_decompress;
+pub use self::zstd::zstd_decompress;
+
+/// Everything that can go wrong while decompressing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecompressError {
+    /// The input ends in the middle of the compressed data.
+    Truncated,
+    /// The input isn't valid compressed data; says what's wrong with it.
+    Corrupt(&'static str),
+    /// The data uses a feature we don't decode, e.g. a preset dictionary.
+    Unsupported(&'static str),
+    /// The data doesn't decompress to the size it's supposed to have.
+    SizeMismatch {
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl Display for DecompressError {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        use self::DecompressError::*;
+        match *self {
+            Truncated => write!(fmt, "compressed data is truncated"),
+            Corrupt(what) => write!(fmt, "corrupt compressed data: {}", what),
+            Unsupported(what) => write!(fmt, "unsupported compressed data: {}", what),
+            SizeMismatch { expected, actual } => write!(
+                fmt, "data decompresses to {} bytes instead of {}", actual, expected),
+        }
+    }
+}
+
+impl ::std::error::Error for DecompressError {}
@@ -0,0 +1,707 @@
+//! A zstd (RFC 8878) decoder.
+//!
+//! It follows the structure of the RFC and of zstd's educational decoder
+//! rather than being fast. Dictionaries aren't supported, as nothing puts
+//! them in ELF files, and the optional content checksums aren't checked.
+
+use super::DecompressError;
+
+const MAGIC: u64 = 0xfd2f_b528;
+const SKIPPABLE_MAGIC: u64 = 0x184d_2a50;
+
+const LL_MAX_LOG: u32 = 9;
+const ML_MAX_LOG: u32 = 9;
+const OF_MAX_LOG: u32 = 8;
+const WEIGHTS_MAX_LOG: u32 = 6;
+const HUFFMAN_MAX_BITS: u32 = 11;
+
+/// Baselines and extra bits of the literals length codes.
+const LL_BASE: [u32; 36] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    16, 18, 20, 22, 24, 28, 32, 40, 48, 64, 128, 256, 512, 1024, 2048, 4096,
+    8192, 16384, 32768, 65536,
+];
+const LL_BITS: [u8; 36] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11, 12,
+    13, 14, 15, 16,
+];
+
+/// Baselines and extra bits of the match length codes.
+const ML_BASE: [u32; 53] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+    19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34,
+    35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027, 2051,
+    4099, 8195, 16387, 32771, 65539,
+];
+const ML_BITS: [u8; 53] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11,
+    12, 13, 14, 15, 16,
+];
+
+/// The predefined distributions of the sequence codes, with their accuracy
+/// logs.
+const LL_DEFAULT: (&[i16], u32) = (&[
+    4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+    -1, -1, -1, -1,
+], 6);
+const ML_DEFAULT: (&[i16], u32) = (&[
+    1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1,
+    -1, -1, -1, -1, -1,
+], 6);
+const OF_DEFAULT: (&[i16], u32) = (&[
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+], 5);
+
+/// Decompresses one or more concatenated zstd frames, which must add up to
+/// exactly `size` bytes. Skippable frames are skipped.
+pub fn zstd_decompress(data: &[u8], size: u64) -> Result<Vec<u8>, DecompressError> {
+    // A block of 128 KiB can at most come from a 3-byte RLE block.
+    let capacity = size.min(data.len() as u64 * 43691) as usize;
+    let mut out = Vec::with_capacity(capacity);
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let magic = read_le(get(data, pos, 4)?);
+        if magic & 0xffff_fff0 == SKIPPABLE_MAGIC {
+            let len = read_le(get(data, pos + 4, 4)?) as usize;
+            get(data, pos + 8, len)?;
+            pos += 8 + len;
+        } else if magic == MAGIC {
+            pos += 4;
+            pos += decode_frame(&data[pos..], &mut out)?;
+        } else {
+            return Err(DecompressError::Corrupt("bad zstd magic number"));
+        }
+    }
+
+    if out.len() as u64 != size {
+        return Err(DecompressError::SizeMismatch { expected: size, actual: out.len() as u64 });
+    }
+    Ok(out)
+}
+
+/// What a block can take over from the previous blocks of its frame.
+#[derive(Default)]
+struct FrameState {
+    huffman: Option<HuffmanTable>,
+    ll: Option<FseTable>,
+    of: Option<FseTable>,
+    ml: Option<FseTable>,
+    offsets: [usize; 3],
+}
+
+/// Decodes the frame after the magic number into `out` and returns the
+/// size of the frame.
+fn decode_frame(data: &[u8], out: &mut Vec<u8>) -> Result<usize, DecompressError> {
+    let descriptor = get(data, 0, 1)?[0];
+    let content_size_flag = descriptor >> 6;
+    let single_segment = descriptor & 0x20 != 0;
+    let has_checksum = descriptor & 0x04 != 0;
+    let dict_id_flag = descriptor & 0x03;
+    if descriptor & 0x08 != 0 {
+        return Err(DecompressError::Corrupt("reserved bit set in zstd frame header"));
+    }
+
+    let mut pos = 1;
+    if !single_segment {
+        // The window size only matters to decoders that don't keep all
+        // of the output around.
+        pos += 1;
+    }
+    let dict_id_size = [0, 1, 2, 4][dict_id_flag as usize];
+    if read_le(get(data, pos, dict_id_size)?) != 0 {
+        return Err(DecompressError::Unsupported("zstd dictionary"));
+    }
+    pos += dict_id_size;
+    pos += match content_size_flag {
+        0 if single_segment => 1,
+        0 => 0,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+
+    let start = out.len();
+    let mut state = FrameState { offsets: [1, 4, 8], ..FrameState::default() };
+    loop {
+        let header = read_le(get(data, pos, 3)?);
+        pos += 3;
+        let last = header & 1 != 0;
+        let size = (header >> 3) as usize;
+        match (header >> 1) & 3 {
+            0 => {
+                out.extend_from_slice(get(data, pos, size)?);
+                pos += size;
+            }
+            1 => {
+                let byte = get(data, pos, 1)?[0];
+                out.resize(out.len() + size, byte);
+                pos += 1;
+            }
+            2 => {
+                decode_block(get(data, pos, size)?, out, start, &mut state)?;
+                pos += size;
+            }
+            _ => return Err(DecompressError::Corrupt("reserved zstd block type")),
+        }
+        if last {
+            break;
+        }
+    }
+
+    if has_checksum {
+        get(data, pos, 4)?;
+        pos += 4;
+    }
+    Ok(pos)
+}
+
+fn decode_block(block: &[u8], out: &mut Vec<u8>, start: usize, state: &mut FrameState)
+    -> Result<(), DecompressError>
+{
+    let (literals, mut pos) = decode_literals(block, state)?;
+
+    let b0 = get(block, pos, 1)?[0] as usize;
+    let num_sequences = if b0 < 128 {
+        pos += 1;
+        b0
+    } else if b0 < 255 {
+        let b1 = get(block, pos + 1, 1)?[0] as usize;
+        pos += 2;
+        ((b0 - 128) << 8) + b1
+    } else {
+        let rest = read_le(get(block, pos + 1, 2)?) as usize;
+        pos += 3;
+        rest + 0x7f00
+    };
+    if num_sequences == 0 {
+        out.extend_from_slice(&literals);
+        return Ok(());
+    }
+
+    let modes = get(block, pos, 1)?[0];
+    pos += 1;
+    if modes & 3 != 0 {
+        return Err(DecompressError::Corrupt("reserved bits set in zstd sequences header"));
+    }
+    let ll = read_sequence_table(block, &mut pos, modes >> 6, LL_DEFAULT, LL_MAX_LOG, &mut state.ll)?;
+    let of = read_sequence_table(block, &mut pos, (modes >> 4) & 3, OF_DEFAULT, OF_MAX_LOG, &mut state.of)?;
+    let ml = read_sequence_table(block, &mut pos, (modes >> 2) & 3, ML_DEFAULT, ML_MAX_LOG, &mut state.ml)?;
+
+    let mut reader = BackwardReader::new(block.get(pos..).ok_or(DecompressError::Truncated)?)?;
+    let mut ll_state = reader.read(ll.log);
+    let mut of_state = reader.read(of.log);
+    let mut ml_state = reader.read(ml.log);
+
+    let mut literal_pos = 0;
+    for i in 0..num_sequences {
+        let of_code = of.entries[of_state].symbol as u32;
+        let ll_code = ll.entries[ll_state].symbol as usize;
+        let ml_code = ml.entries[ml_state].symbol as usize;
+        if of_code > 31 || ll_code >= LL_BASE.len() || ml_code >= ML_BASE.len() {
+            return Err(DecompressError::Corrupt("bad zstd sequence code"));
+        }
+
+        let offset_value = (1u64 << of_code) + reader.read(of_code) as u64;
+        let match_len = ML_BASE[ml_code] as usize + reader.read(ML_BITS[ml_code] as u32);
+        let literals_len = LL_BASE[ll_code] as usize + reader.read(LL_BITS[ll_code] as u32);
+
+        if i + 1 < num_sequences {
+            ll_state = ll.next_state(ll_state, &mut reader);
+            ml_state = ml.next_state(ml_state, &mut reader);
+            of_state = of.next_state(of_state, &mut reader);
+        }
+
+        let offset = resolve_offset(offset_value as usize, literals_len, &mut state.offsets)?;
+
+        let literals = literals.get(literal_pos..literal_pos + literals_len)
+            .ok_or(DecompressError::Corrupt("zstd sequence needs more literals"))?;
+        out.extend_from_slice(literals);
+        literal_pos += literals_len;
+
+        if offset > out.len() - start {
+            return Err(DecompressError::Corrupt("zstd offset too far back"));
+        }
+        // The match may overlap the bytes it produces.
+        let from = out.len() - offset;
+        for i in 0..match_len {
+            let byte = out[from + i];
+            out.push(byte);
+        }
+    }
+    if !reader.is_empty() {
+        return Err(DecompressError::Corrupt("zstd sequences not fully consumed"));
+    }
+    out.extend_from_slice(&literals[literal_pos..]);
+    Ok(())
+}
+
+/// Turns an offset value into an offset, updating the repeated offsets.
+fn resolve_offset(value: usize, literals_len: usize, offsets: &mut [usize; 3])
+    -> Result<usize, DecompressError>
+{
+    if value > 3 {
+        let offset = value - 3;
+        *offsets = [offset, offsets[0], offsets[1]];
+        return Ok(offset);
+    }
+    // Without literals, repeating the last offset would be pointless, so
+    // the values mean the next one instead.
+    let index = if literals_len == 0 { value + 1 } else { value };
+    let offset = match index {
+        1 => offsets[0],
+        2 => {
+            *offsets = [offsets[1], offsets[0], offsets[2]];
+            offsets[0]
+        }
+        3 => {
+            *offsets = [offsets[2], offsets[0], offsets[1]];
+            offsets[0]
+        }
+        _ => {
+            let offset = offsets[0].wrapping_sub(1);
+            if offset == 0 {
+                return Err(DecompressError::Corrupt("zstd repeated offset is zero"));
+            }
+            *offsets = [offset, offsets[0], offsets[1]];
+            offset
+        }
+    };
+    Ok(offset)
+}
+
+/// Reads the literals section at the start of a compressed block. Returns
+/// the literals and the size of the section.
+fn decode_literals(block: &[u8], state: &mut FrameState)
+    -> Result<(Vec<u8>, usize), DecompressError>
+{
+    let b0 = get(block, 0, 1)?[0];
+    let literals_type = b0 & 3;
+    let size_format = (b0 >> 2) & 3;
+
+    if literals_type < 2 {
+        let (size, header_size) = match size_format {
+            0 | 2 => ((b0 >> 3) as usize, 1),
+            1 => ((read_le(get(block, 0, 2)?) >> 4) as usize, 2),
+            _ => ((read_le(get(block, 0, 3)?) >> 4) as usize, 3),
+        };
+        return if literals_type == 0 {
+            Ok((get(block, header_size, size)?.to_vec(), header_size + size))
+        } else {
+            Ok((vec![get(block, header_size, 1)?[0]; size], header_size + 1))
+        };
+    }
+
+    let (num_streams, size_bits, header_size) = match size_format {
+        0 => (1, 10, 3),
+        1 => (4, 10, 3),
+        2 => (4, 14, 4),
+        _ => (4, 18, 5),
+    };
+    let header = read_le(get(block, 0, header_size)?);
+    let mask = (1 << size_bits) - 1;
+    let regenerated_size = ((header >> 4) & mask) as usize;
+    let compressed_size = ((header >> (4 + size_bits)) & mask) as usize;
+    let mut data = get(block, header_size, compressed_size)?;
+
+    if literals_type == 2 {
+        let (table, size) = read_huffman_table(data)?;
+        state.huffman = Some(table);
+        data = &data[size..];
+    }
+    let table = state.huffman.as_ref()
+        .ok_or(DecompressError::Corrupt("zstd literals repeat a missing Huffman table"))?;
+
+    let mut literals = Vec::with_capacity(regenerated_size);
+    if num_streams == 1 {
+        table.decode_stream(data, regenerated_size, &mut literals)?;
+    } else {
+        let jump_table = get(data, 0, 6)?;
+        let sizes = [
+            read_le(&jump_table[0..2]) as usize,
+            read_le(&jump_table[2..4]) as usize,
+            read_le(&jump_table[4..6]) as usize,
+        ];
+        let segment = regenerated_size.div_ceil(4);
+        let mut pos = 6;
+        for size in &sizes {
+            table.decode_stream(get(data, pos, *size)?, segment, &mut literals)?;
+            pos += size;
+        }
+        let last = regenerated_size.checked_sub(3 * segment)
+            .ok_or(DecompressError::Corrupt("zstd literals too short for four streams"))?;
+        table.decode_stream(get(data, pos, data.len().saturating_sub(pos))?, last, &mut literals)?;
+    }
+    Ok((literals, header_size + compressed_size))
+}
+
+/// Builds the table of one kind of sequence code according to its mode in
+/// the sequences header, reading a description from `block` at `pos` if
+/// the mode has one.
+fn read_sequence_table(
+    block: &[u8], pos: &mut usize, mode: u8,
+    default: (&[i16], u32), max_log: u32, previous: &mut Option<FseTable>)
+    -> Result<FseTable, DecompressError>
+{
+    let table = match mode {
+        0 => FseTable::new(default.0, default.1)?,
+        1 => {
+            let symbol = get(block, *pos, 1)?[0];
+            *pos += 1;
+            FseTable::rle(symbol)
+        }
+        2 => {
+            let data = block.get(*pos..).ok_or(DecompressError::Truncated)?;
+            let (table, size) = FseTable::read(data, max_log)?;
+            *pos += size;
+            table
+        }
+        _ => return previous.clone()
+            .ok_or(DecompressError::Corrupt("zstd sequences repeat a missing table")),
+    };
+    *previous = Some(table.clone());
+    Ok(table)
+}
+
+/// A state of an FSE decoding table: the symbol it decodes to and how to
+/// get to the next state.
+#[derive(Debug, Clone, Copy, Default)]
+struct FseEntry {
+    symbol: u8,
+    num_bits: u8,
+    base: u16,
+}
+
+#[derive(Debug, Clone)]
+struct FseTable {
+    log: u32,
+    entries: Vec<FseEntry>,
+}
+
+impl FseTable {
+    /// Builds the decoding table of a distribution, where -1 stands for a
+    /// probability lower than 1.
+    fn new(distribution: &[i16], log: u32) -> Result<FseTable, DecompressError> {
+        let size = 1usize << log;
+        let mut entries = vec![FseEntry::default(); size];
+        let mut next = vec![0u16; distribution.len()];
+
+        // Symbols with a probability lower than 1 get a state each at the
+        // end of the table.
+        let mut high = size;
+        for (symbol, &p) in distribution.iter().enumerate() {
+            if p == -1 {
+                high -= 1;
+                entries[high].symbol = symbol as u8;
+                next[symbol] = 1;
+            }
+        }
+
+        let step = (size >> 1) + (size >> 3) + 3;
+        let mut pos = 0;
+        for (symbol, &p) in distribution.iter().enumerate() {
+            if p <= 0 {
+                continue;
+            }
+            next[symbol] = p as u16;
+            for _ in 0..p {
+                entries[pos].symbol = symbol as u8;
+                loop {
+                    pos = (pos + step) & (size - 1);
+                    if pos < high {
+                        break;
+                    }
+                }
+            }
+        }
+        if pos != 0 {
+            return Err(DecompressError::Corrupt("bad FSE distribution"));
+        }
+
+        for entry in &mut entries {
+            let state = next[entry.symbol as usize];
+            next[entry.symbol as usize] += 1;
+            let num_bits = log - highest_bit(state as u32);
+            entry.num_bits = num_bits as u8;
+            entry.base = ((state as usize) << num_bits).wrapping_sub(size) as u16;
+        }
+        Ok(FseTable { log, entries })
+    }
+
+    /// A table that always decodes to `symbol`, without reading any bits.
+    fn rle(symbol: u8) -> FseTable {
+        FseTable { log: 0, entries: vec![FseEntry { symbol, num_bits: 0, base: 0 }] }
+    }
+
+    /// Reads a table description from the start of `data`. Returns the
+    /// table and the size of the description.
+    fn read(data: &[u8], max_log: u32) -> Result<(FseTable, usize), DecompressError> {
+        let mut reader = ForwardReader { data, pos: 0 };
+        let log = reader.read(4) + 5;
+        if log > max_log {
+            return Err(DecompressError::Corrupt("FSE accuracy log too large"));
+        }
+
+        let mut distribution = Vec::new();
+        let mut remaining = 1i32 << log;
+        while remaining > 0 && distribution.len() < 256 {
+            let bits = highest_bit(remaining as u32 + 1) + 1;
+            let mut value = reader.read(bits) as i32;
+            let lower_mask = (1 << (bits - 1)) - 1;
+            let threshold = (1 << bits) - 1 - (remaining + 1);
+            if value & lower_mask < threshold {
+                reader.pos -= 1;
+                value &= lower_mask;
+            } else if value > lower_mask {
+                value -= threshold;
+            }
+            let p = value - 1;
+            remaining -= p.abs();
+            distribution.push(p as i16);
+
+            if p == 0 {
+                loop {
+                    let repeat = reader.read(2);
+                    distribution.resize(distribution.len() + repeat as usize, 0);
+                    if repeat != 3 {
+                        break;
+                    }
+                }
+            }
+        }
+        if remaining != 0 || distribution.len() > 256 {
+            return Err(DecompressError::Corrupt("bad FSE table description"));
+        }
+        let size = reader.pos.div_ceil(8);
+        if size > data.len() {
+            return Err(DecompressError::Truncated);
+        }
+        Ok((FseTable::new(&distribution, log)?, size))
+    }
+
+    fn next_state(&self, state: usize, reader: &mut BackwardReader) -> usize {
+        let entry = self.entries[state];
+        entry.base as usize + reader.read(entry.num_bits as u32)
+    }
+}
+
+/// A Huffman decoding table indexed by the next `max_bits` bits of the
+/// stream.
+#[derive(Debug, Clone)]
+struct HuffmanTable {
+    max_bits: u32,
+    symbols: Vec<u8>,
+    num_bits: Vec<u8>,
+}
+
+/// Reads the Huffman tree description at the start of `data`. Returns the
+/// table and the size of the description.
+fn read_huffman_table(data: &[u8]) -> Result<(HuffmanTable, usize), DecompressError> {
+    let header = get(data, 0, 1)?[0] as usize;
+    let mut weights = Vec::new();
+    let size;
+    if header < 128 {
+        // The weights are FSE-compressed with two interleaved states.
+        let data = get(data, 1, header)?;
+        let (table, table_size) = FseTable::read(data, WEIGHTS_MAX_LOG)?;
+        let mut reader = BackwardReader::new(&data[table_size..])?;
+        let mut state1 = reader.read(table.log);
+        let mut state2 = reader.read(table.log);
+        loop {
+            weights.push(table.entries[state1].symbol);
+            state1 = table.next_state(state1, &mut reader);
+            if reader.is_overflowed() {
+                weights.push(table.entries[state2].symbol);
+                break;
+            }
+            weights.push(table.entries[state2].symbol);
+            state2 = table.next_state(state2, &mut reader);
+            if reader.is_overflowed() {
+                weights.push(table.entries[state1].symbol);
+                break;
+            }
+            if weights.len() > 255 {
+                return Err(DecompressError::Corrupt("too many Huffman weights"));
+            }
+        }
+        size = 1 + header;
+    } else {
+        let count = header - 127;
+        for &byte in get(data, 1, count.div_ceil(2))? {
+            weights.push(byte >> 4);
+            weights.push(byte & 0xf);
+        }
+        weights.truncate(count);
+        size = 1 + count.div_ceil(2);
+    }
+    if weights.len() > 255 {
+        return Err(DecompressError::Corrupt("too many Huffman weights"));
+    }
+
+    // The weight of the last symbol is implied: it completes the sum of
+    // 2^(weight - 1) to a power of two.
+    let mut total = 0u32;
+    for &weight in &weights {
+        if weight as u32 > HUFFMAN_MAX_BITS {
+            return Err(DecompressError::Corrupt("Huffman weight too large"));
+        }
+        if weight > 0 {
+            total += 1 << (weight - 1);
+        }
+    }
+    if total == 0 {
+        return Err(DecompressError::Corrupt("no Huffman weights"));
+    }
+    let max_bits = highest_bit(total) + 1;
+    let left = (1 << max_bits) - total;
+    if max_bits > HUFFMAN_MAX_BITS || !left.is_power_of_two() {
+        return Err(DecompressError::Corrupt("bad Huffman weights"));
+    }
+    weights.push(highest_bit(left) as u8 + 1);
+
+    // Longer codes come first in the table; each symbol takes
+    // 2^(weight - 1) entries.
+    let mut rank_start = [0usize; HUFFMAN_MAX_BITS as usize + 2];
+    for &weight in &weights {
+        if weight > 0 {
+            rank_start[weight as usize + 1] += 1 << (weight - 1);
+        }
+    }
+    for i in 1..rank_start.len() {
+        rank_start[i] += rank_start[i - 1];
+    }
+    let table_size = 1usize << max_bits;
+    let mut symbols = vec![0u8; table_size];
+    let mut num_bits = vec![0u8; table_size];
+    for (symbol, &weight) in weights.iter().enumerate() {
+        if weight == 0 {
+            continue;
+        }
+        let start = rank_start[weight as usize];
+        let len = 1usize << (weight - 1);
+        for i in start..start + len {
+            symbols[i] = symbol as u8;
+            num_bits[i] = (max_bits + 1 - weight as u32) as u8;
+        }
+        rank_start[weight as usize] += len;
+    }
+    Ok((HuffmanTable { max_bits, symbols, num_bits }, size))
+}
+
+impl HuffmanTable {
+    /// Decodes `count` literals from a whole Huffman-coded stream.
+    fn decode_stream(&self, data: &[u8], count: usize, out: &mut Vec<u8>)
+        -> Result<(), DecompressError>
+    {
+        let mut reader = BackwardReader::new(data)?;
+        for _ in 0..count {
+            let index = reader.peek(self.max_bits);
+            out.push(self.symbols[index]);
+            reader.consume(self.num_bits[index] as u32);
+        }
+        if !reader.is_empty() {
+            return Err(DecompressError::Corrupt("zstd literals stream not fully consumed"));
+        }
+        Ok(())
+    }
+}
+
+/// Reads bits from the least significant one up.
+struct ForwardReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ForwardReader<'a> {
+    /// Reads `n` bits, up to 16. Bits past the end read as 0; the caller
+    /// checks the position in the end.
+    fn read(&mut self, n: u32) -> u32 {
+        let mut value = 0;
+        for i in 0..n as usize {
+            let bit = self.data.get((self.pos + i) / 8)
+                .map_or(0, |&byte| (byte >> ((self.pos + i) % 8)) & 1);
+            value |= (bit as u32) << i;
+        }
+        self.pos += n as usize;
+        value
+    }
+}
+
+/// Reads a bitstream backwards, from its last bit to its first, as zstd
+/// writes the entropy-coded parts. The last byte holds a 1 bit that marks
+/// where the stream ends.
+struct BackwardReader<'a> {
+    data: &'a [u8],
+    /// The number of bits left to read; negative once more bits have been
+    /// read than there are, which then read as 0.
+    bits_left: isize,
+}
+
+impl<'a> BackwardReader<'a> {
+    fn new(data: &'a [u8]) -> Result<BackwardReader<'a>, DecompressError> {
+        let last = *data.last().ok_or(DecompressError::Truncated)?;
+        if last == 0 {
+            return Err(DecompressError::Corrupt("zstd bitstream without end mark"));
+        }
+        let bits_left = (data.len() - 1) * 8 + highest_bit(last as u32) as usize;
+        Ok(BackwardReader { data, bits_left: bits_left as isize })
+    }
+
+    /// Returns the next `n` bits, up to 32, without consuming them.
+    fn peek(&self, n: u32) -> usize {
+        let start = self.bits_left - n as isize;
+        if start >= 0 {
+            self.bits_at(start as usize, n)
+        } else if self.bits_left > 0 {
+            self.bits_at(0, self.bits_left as usize as u32) << (-start) as usize
+        } else {
+            0
+        }
+    }
+
+    /// Returns the `n` bits from bit `pos` on, all of which are in the
+    /// stream.
+    fn bits_at(&self, pos: usize, n: u32) -> usize {
+        let bytes = &self.data[pos / 8..self.data.len().min(pos / 8 + 8)];
+        let value = read_le(bytes) >> (pos % 8);
+        (value & ((1u64 << n) - 1)) as usize
+    }
+
+    fn consume(&mut self, n: u32) {
+        self.bits_left -= n as isize;
+    }
+
+    fn read(&mut self, n: u32) -> usize {
+        let value = self.peek(n);
+        self.consume(n);
+        value
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bits_left == 0
+    }
+
+    fn is_overflowed(&self) -> bool {
+        self.bits_left < 0
+    }
+}
+
+fn get(data: &[u8], pos: usize, size: usize) -> Result<&[u8], DecompressError> {
+    data.get(pos..pos.saturating_add(size)).ok_or(DecompressError::Truncated)
+}
+
+/// Reads a little-endian number of up to 8 bytes.
+fn read_le(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+fn highest_bit(x: u32) -> u32 {
+    31 - x.leading_zeros()
+}
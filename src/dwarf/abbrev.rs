@@ -0,0 +1,78 @@
+use ::to_host::Endianness;
+use super::dw_form::DW_FORM_implicit_const;
+use super::error::DwarfError;
+use super::reader::DwarfReader;
+
+/// An attribute of an abbreviation: its name and form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttrSpec {
+    pub name: u64,
+    pub form: u64,
+    /// The value of a `DW_FORM_implicit_const` attribute, 0 for other
+    /// forms.
+    pub implicit_const: i64,
+}
+
+/// An abbreviation of `.debug_abbrev`, which describes the layout of the
+/// DIEs that refer to it by its code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbrev {
+    pub code: u64,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<AttrSpec>,
+}
+
+/// The abbreviations of a unit, in the order they're listed.
+#[derive(Debug, Clone, Default)]
+pub struct Abbrevs {
+    abbrevs: Vec<Abbrev>,
+}
+
+impl Abbrevs {
+    /// Reads the table at `offset` of `.debug_abbrev`, up to the 0 code
+    /// that ends it.
+    pub fn read(data: &[u8], offset: u64, endianness: Endianness)
+        -> Result<Abbrevs, DwarfError>
+    {
+        let mut reader = DwarfReader::at(data, offset, endianness, ".debug_abbrev")?;
+        let mut abbrevs = Vec::new();
+        loop {
+            let code = reader.uleb128()?;
+            if code == 0 {
+                break;
+            }
+            let tag = reader.uleb128()?;
+            let has_children = reader.u8()? != 0;
+            let mut attrs = Vec::new();
+            loop {
+                let name = reader.uleb128()?;
+                let form = reader.uleb128()?;
+                if name == 0 && form == 0 {
+                    break;
+                }
+                let implicit_const = if form == DW_FORM_implicit_const {
+                    reader.sleb128()?
+                } else {
+                    0
+                };
+                attrs.push(AttrSpec { name, form, implicit_const });
+            }
+            abbrevs.push(Abbrev { code, tag, has_children, attrs });
+        }
+        Ok(Abbrevs { abbrevs })
+    }
+
+    pub fn get_abbrevs(&self) -> &[Abbrev] {
+        &self.abbrevs
+    }
+
+    /// Finds the abbreviation with `code`.
+    pub fn get(&self, code: u64) -> Option<&Abbrev> {
+        // Codes are usually numbered from 1 in order.
+        match self.abbrevs.get((code as usize).wrapping_sub(1)) {
+            Some(abbrev) if abbrev.code == code => Some(abbrev),
+            _ => self.abbrevs.iter().find(|a| a.code == code),
+        }
+    }
+}
@@ -0,0 +1,531 @@
+//! Call frame information: the CIEs and FDEs of `.eh_frame` and
+//! `.debug_frame`, which tell how to find the caller's registers at each
+//! address, and the search table of `.eh_frame_hdr`, which finds the FDE
+//! of an address without reading all of `.eh_frame`.
+
+#![allow(non_upper_case_globals)]
+
+use ::to_host::Endianness;
+use super::error::DwarfError;
+use super::reader::{DwarfReader, DwarfFormat};
+
+dwarf_names! { u8, get_cfa_name;
+    DW_CFA_nop = 0x00,
+    DW_CFA_set_loc = 0x01,
+    DW_CFA_advance_loc1 = 0x02,
+    DW_CFA_advance_loc2 = 0x03,
+    DW_CFA_advance_loc4 = 0x04,
+    DW_CFA_offset_extended = 0x05,
+    DW_CFA_restore_extended = 0x06,
+    DW_CFA_undefined = 0x07,
+    DW_CFA_same_value = 0x08,
+    DW_CFA_register = 0x09,
+    DW_CFA_remember_state = 0x0a,
+    DW_CFA_restore_state = 0x0b,
+    DW_CFA_def_cfa = 0x0c,
+    DW_CFA_def_cfa_register = 0x0d,
+    DW_CFA_def_cfa_offset = 0x0e,
+    DW_CFA_def_cfa_expression = 0x0f,
+    DW_CFA_expression = 0x10,
+    DW_CFA_offset_extended_sf = 0x11,
+    DW_CFA_def_cfa_sf = 0x12,
+    DW_CFA_def_cfa_offset_sf = 0x13,
+    DW_CFA_val_offset = 0x14,
+    DW_CFA_val_offset_sf = 0x15,
+    DW_CFA_val_expression = 0x16,
+    DW_CFA_MIPS_advance_loc8 = 0x1d,
+    DW_CFA_GNU_window_save = 0x2d,
+    DW_CFA_GNU_args_size = 0x2e,
+    DW_CFA_GNU_negative_offset_extended = 0x2f,
+    DW_CFA_advance_loc = 0x40,
+    DW_CFA_offset = 0x80,
+    DW_CFA_restore = 0xc0,
+}
+
+/// AArch64 reuses `DW_CFA_GNU_window_save` for return address signing.
+pub const DW_CFA_AARCH64_negate_ra_state: u8 = 0x2d;
+pub const DW_CFA_lo_user: u8 = 0x1c;
+pub const DW_CFA_hi_user: u8 = 0x3f;
+
+/// The ID that tells CIEs from FDEs in `.debug_frame`. In `.eh_frame` it
+/// is 0.
+pub const DW_CIE_ID: u32 = 0xffff_ffff;
+pub const DW64_CIE_ID: u64 = 0xffff_ffff_ffff_ffff;
+
+// The pointer encodings of `.eh_frame` and `.eh_frame_hdr`: the low bits
+// tell the format, the next three how the value applies, and the top one
+// whether it points at the actual pointer.
+pub const DW_EH_PE_absptr: u8 = 0x00;
+pub const DW_EH_PE_uleb128: u8 = 0x01;
+pub const DW_EH_PE_udata2: u8 = 0x02;
+pub const DW_EH_PE_udata4: u8 = 0x03;
+pub const DW_EH_PE_udata8: u8 = 0x04;
+pub const DW_EH_PE_signed: u8 = 0x08;
+pub const DW_EH_PE_sleb128: u8 = 0x09;
+pub const DW_EH_PE_sdata2: u8 = 0x0a;
+pub const DW_EH_PE_sdata4: u8 = 0x0b;
+pub const DW_EH_PE_sdata8: u8 = 0x0c;
+pub const DW_EH_PE_pcrel: u8 = 0x10;
+pub const DW_EH_PE_textrel: u8 = 0x20;
+pub const DW_EH_PE_datarel: u8 = 0x30;
+pub const DW_EH_PE_funcrel: u8 = 0x40;
+pub const DW_EH_PE_aligned: u8 = 0x50;
+pub const DW_EH_PE_indirect: u8 = 0x80;
+pub const DW_EH_PE_omit: u8 = 0xff;
+
+/// Describes a pointer encoding like `sdata4, pcrel`.
+pub fn format_pointer_encoding(encoding: u8) -> String {
+    if encoding == DW_EH_PE_omit {
+        return "omit".to_string();
+    }
+    let format = match encoding & 0x0f {
+        DW_EH_PE_absptr => "absptr",
+        DW_EH_PE_uleb128 => "uleb128",
+        DW_EH_PE_udata2 => "udata2",
+        DW_EH_PE_udata4 => "udata4",
+        DW_EH_PE_udata8 => "udata8",
+        DW_EH_PE_signed => "signed",
+        DW_EH_PE_sleb128 => "sleb128",
+        DW_EH_PE_sdata2 => "sdata2",
+        DW_EH_PE_sdata4 => "sdata4",
+        DW_EH_PE_sdata8 => "sdata8",
+        _ => "unknown format",
+    };
+    let mut s = format.to_string();
+    match encoding & 0x70 {
+        0 => {}
+        DW_EH_PE_pcrel => s.push_str(", pcrel"),
+        DW_EH_PE_textrel => s.push_str(", textrel"),
+        DW_EH_PE_datarel => s.push_str(", datarel"),
+        DW_EH_PE_funcrel => s.push_str(", funcrel"),
+        DW_EH_PE_aligned => s.push_str(", aligned"),
+        _ => s.push_str(", unknown application"),
+    }
+    if encoding & DW_EH_PE_indirect != 0 {
+        s.push_str(", indirect");
+    }
+    s
+}
+
+/// The size of pointers with `encoding`, or `None` for LEB128 ones.
+pub fn get_pointer_size(encoding: u8, address_size: u8) -> Option<u8> {
+    match encoding & 0x07 {
+        0 => Some(address_size),
+        2 => Some(2),
+        3 => Some(4),
+        4 => Some(8),
+        _ => None,
+    }
+}
+
+/// The addresses that pointers are relative to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointerBases {
+    /// The address of the start of the section being read, for
+    /// `DW_EH_PE_pcrel`.
+    pub section: u64,
+    /// For `DW_EH_PE_textrel`.
+    pub text: u64,
+    /// For `DW_EH_PE_datarel`, which `.eh_frame_hdr` makes relative to its
+    /// own start.
+    pub data: u64,
+}
+
+/// Reads a pointer with `encoding`. Indirect pointers are returned as the
+/// address they point at, as the memory of the process isn't at hand,
+/// and so are function-relative ones, which are only used for the
+/// addresses of FDE instructions.
+pub fn read_encoded_pointer(
+    reader: &mut DwarfReader, encoding: u8, address_size: u8, bases: &PointerBases)
+    -> Result<u64, DwarfError>
+{
+    if encoding == DW_EH_PE_omit {
+        return Err(DwarfError::Corrupt("omitted pointer"));
+    }
+    let address = bases.section.wrapping_add(reader.get_pos());
+    let value = match encoding & 0x0f {
+        DW_EH_PE_absptr => reader.uint(address_size)?,
+        DW_EH_PE_uleb128 => reader.uleb128()?,
+        DW_EH_PE_udata2 => reader.uint(2)?,
+        DW_EH_PE_udata4 => reader.uint(4)?,
+        DW_EH_PE_udata8 | DW_EH_PE_sdata8 => reader.uint(8)?,
+        DW_EH_PE_sleb128 => reader.sleb128()? as u64,
+        DW_EH_PE_sdata2 => reader.uint(2)? as u16 as i16 as u64,
+        DW_EH_PE_sdata4 => reader.uint(4)? as u32 as i32 as u64,
+        _ => return Err(DwarfError::Corrupt("unknown pointer encoding")),
+    };
+    let base = match encoding & 0x70 {
+        DW_EH_PE_absptr | DW_EH_PE_funcrel => 0,
+        DW_EH_PE_pcrel => address,
+        DW_EH_PE_textrel => bases.text,
+        DW_EH_PE_datarel => bases.data,
+        _ => return Err(DwarfError::Corrupt("unknown pointer application")),
+    };
+    Ok(base.wrapping_add(value))
+}
+
+/// A Common Information Entry, which holds what the FDEs referring to it
+/// share: how their instructions are encoded and the initial rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cie<'a> {
+    pub offset: u64,
+    pub length: u64,
+    pub format: DwarfFormat,
+    pub version: u8,
+    /// E.g. `zR`, which says what the augmentation data holds.
+    pub augmentation: &'a [u8],
+    pub address_size: u8,
+    pub segment_selector_size: u8,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u64,
+    /// Present if the augmentation starts with `z`.
+    pub augmentation_data: Option<&'a [u8]>,
+    /// The encoding of the addresses of the FDEs, from `R`.
+    pub fde_pointer_encoding: u8,
+    /// The encoding of the LSDA pointers of the FDEs, from `L`.
+    pub lsda_encoding: u8,
+    /// The personality routine, from `P`.
+    pub personality: Option<u64>,
+    /// Whether the FDEs are of signal handlers, from `S`.
+    pub is_signal_frame: bool,
+    pub initial_instructions: &'a [u8],
+}
+
+/// A Frame Description Entry: the instructions that describe how the
+/// rules change over the addresses of a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fde<'a> {
+    pub offset: u64,
+    pub length: u64,
+    pub format: DwarfFormat,
+    /// The offset of its CIE in the section.
+    pub cie_offset: u64,
+    pub segment_selector: u64,
+    pub initial_location: u64,
+    pub address_range: u64,
+    pub augmentation_data: Option<&'a [u8]>,
+    /// The language-specific data area, e.g. C++ exception tables.
+    pub lsda: Option<u64>,
+    pub instructions: &'a [u8],
+}
+
+/// An entry of a call frame section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfiEntry<'a> {
+    Cie(Cie<'a>),
+    Fde(Fde<'a>),
+    /// An entry of length 0, which ends `.eh_frame` at run time.
+    Terminator(u64),
+}
+
+/// Whether call frame information comes from `.eh_frame`, which the
+/// unwinder uses at run time, or from `.debug_frame`. They differ in how
+/// CIEs are told from FDEs and in the pointer encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfiKind {
+    EhFrame,
+    DebugFrame,
+}
+
+/// The CIEs and FDEs of `.eh_frame` or `.debug_frame`.
+#[derive(Debug, Clone)]
+pub struct CfiSection<'a> {
+    data: &'a [u8],
+    kind: CfiKind,
+    endianness: Endianness,
+    address_size: u8,
+    bases: PointerBases,
+}
+
+impl<'a> CfiSection<'a> {
+    /// `address` is where the section is loaded, which pc-relative
+    /// pointers are relative to, and `address_size` the size of addresses
+    /// of the file, which DWARF 4 CIEs may override.
+    pub fn new(data: &'a [u8], kind: CfiKind, endianness: Endianness, address_size: u8,
+               address: u64) -> CfiSection<'a>
+    {
+        let bases = PointerBases { section: address, ..PointerBases::default() };
+        CfiSection { data, kind, endianness, address_size, bases }
+    }
+
+    /// Returns the entries in the order of the section.
+    pub fn entries(&self) -> CfiEntries<'a, '_> {
+        CfiEntries { section: self, offset: 0 }
+    }
+
+    /// Reads the entry at `offset`. Returns it and the offset of the next
+    /// one.
+    pub fn entry_at(&self, offset: u64) -> Result<(CfiEntry<'a>, u64), DwarfError> {
+        let mut reader = DwarfReader::at(self.data, offset, self.endianness, "call frame entry")?;
+        let (length, format) = match reader.u32()? {
+            0 => return Ok((CfiEntry::Terminator(offset), reader.get_pos())),
+            0xffff_ffff => (reader.u64()?, DwarfFormat::Dwarf64),
+            length => (length as u64, DwarfFormat::Dwarf32),
+        };
+        let mut entry = reader.split(length)?;
+        let id_offset = entry.get_pos();
+        let id = entry.offset(format)?;
+        let is_cie = match (self.kind, format) {
+            (CfiKind::EhFrame, _) => id == 0,
+            (CfiKind::DebugFrame, DwarfFormat::Dwarf32) => id == DW_CIE_ID as u64,
+            (CfiKind::DebugFrame, DwarfFormat::Dwarf64) => id == DW64_CIE_ID,
+        };
+        let next = reader.get_pos();
+        if is_cie {
+            return Ok((CfiEntry::Cie(self.read_cie(&mut entry, offset, length, format)?), next));
+        }
+
+        // .eh_frame points back at the CIE from the pointer itself.
+        let cie_offset = match self.kind {
+            CfiKind::EhFrame => id_offset.wrapping_sub(id),
+            CfiKind::DebugFrame => id,
+        };
+        let cie = self.cie_at(cie_offset)?;
+        let bases = &self.bases;
+        let address_size = cie.address_size;
+        let segment_selector = entry.uint(cie.segment_selector_size)?;
+        let encoding = cie.fde_pointer_encoding;
+        let initial_location = read_encoded_pointer(&mut entry, encoding, address_size, bases)?;
+        // The range is a size, so only the format of the encoding applies.
+        let address_range = read_encoded_pointer(
+            &mut entry, encoding & 0x0f, address_size, bases)?;
+        let mut augmentation_data = None;
+        let mut lsda = None;
+        if cie.augmentation_data.is_some() {
+            let len = entry.uleb128()?;
+            let mut data = entry.split(len)?;
+            augmentation_data = Some(data.clone().bytes(len)?);
+            if cie.lsda_encoding != DW_EH_PE_omit && !data.is_empty() {
+                lsda = Some(read_encoded_pointer(&mut data, cie.lsda_encoding, address_size, bases)?);
+            }
+        }
+        Ok((CfiEntry::Fde(Fde {
+            offset,
+            length,
+            format,
+            cie_offset,
+            segment_selector,
+            initial_location,
+            address_range,
+            augmentation_data,
+            lsda,
+            instructions: entry.rest(),
+        }), next))
+    }
+
+    /// Reads the CIE at `offset`.
+    pub fn cie_at(&self, offset: u64) -> Result<Cie<'a>, DwarfError> {
+        match self.entry_at(offset)?.0 {
+            CfiEntry::Cie(cie) => Ok(cie),
+            _ => Err(DwarfError::BadOffset { what: "CIE", offset }),
+        }
+    }
+
+    /// Finds the FDE that covers `address` by reading all the entries.
+    /// `EhFrameHdr::lookup` finds the one of `.eh_frame` faster.
+    pub fn fde_for_address(&self, address: u64) -> Result<Option<Fde<'a>>, DwarfError> {
+        for entry in self.entries() {
+            if let CfiEntry::Fde(fde) = entry? {
+                if address.wrapping_sub(fde.initial_location) < fde.address_range {
+                    return Ok(Some(fde));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_cie(&self, entry: &mut DwarfReader<'a>, offset: u64, length: u64, format: DwarfFormat)
+        -> Result<Cie<'a>, DwarfError>
+    {
+        let version = entry.u8()?;
+        if !matches!(version, 1 | 3 | 4) {
+            return Err(DwarfError::UnsupportedVersion { what: "CIE", version: version as u16 });
+        }
+        let augmentation = entry.cstr()?;
+        // GCC once put the address of its exception table here.
+        if augmentation == b"eh" {
+            entry.skip(self.address_size as u64)?;
+        }
+        let (address_size, segment_selector_size) = if version >= 4 {
+            (entry.u8()?, entry.u8()?)
+        } else {
+            (self.address_size, 0)
+        };
+        let code_alignment_factor = entry.uleb128()?;
+        let data_alignment_factor = entry.sleb128()?;
+        let return_address_register = if version == 1 {
+            entry.u8()? as u64
+        } else {
+            entry.uleb128()?
+        };
+
+        let mut cie = Cie {
+            offset,
+            length,
+            format,
+            version,
+            augmentation,
+            address_size,
+            segment_selector_size,
+            code_alignment_factor,
+            data_alignment_factor,
+            return_address_register,
+            augmentation_data: None,
+            fde_pointer_encoding: DW_EH_PE_absptr,
+            lsda_encoding: DW_EH_PE_omit,
+            personality: None,
+            is_signal_frame: false,
+            initial_instructions: &[],
+        };
+        if augmentation.first() == Some(&b'z') {
+            let len = entry.uleb128()?;
+            let mut data = entry.split(len)?;
+            cie.augmentation_data = Some(data.clone().bytes(len)?);
+            for &letter in &augmentation[1..] {
+                match letter {
+                    b'L' => cie.lsda_encoding = data.u8()?,
+                    b'R' => cie.fde_pointer_encoding = data.u8()?,
+                    b'P' => {
+                        let encoding = data.u8()?;
+                        cie.personality = Some(read_encoded_pointer(
+                            &mut data, encoding, address_size, &self.bases)?);
+                    }
+                    b'S' => cie.is_signal_frame = true,
+                    // Others can't be skipped without knowing their size.
+                    _ => break,
+                }
+            }
+        }
+        cie.initial_instructions = entry.rest();
+        Ok(cie)
+    }
+}
+
+/// Iterates over the entries of a call frame section. Stops after the
+/// first one that doesn't read.
+pub struct CfiEntries<'a, 's> {
+    section: &'s CfiSection<'a>,
+    offset: u64,
+}
+
+impl<'a, 's> Iterator for CfiEntries<'a, 's> {
+    type Item = Result<CfiEntry<'a>, DwarfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.section.data.len() as u64 {
+            return None;
+        }
+        match self.section.entry_at(self.offset) {
+            Ok((entry, next)) => {
+                self.offset = next;
+                Some(Ok(entry))
+            }
+            Err(err) => {
+                self.offset = self.section.data.len() as u64;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// The header of `.eh_frame_hdr`, which `PT_GNU_EH_FRAME` points at, and
+/// its table of the FDEs of `.eh_frame` sorted by address.
+#[derive(Debug, Clone)]
+pub struct EhFrameHdr<'a> {
+    pub version: u8,
+    pub eh_frame_ptr_encoding: u8,
+    pub fde_count_encoding: u8,
+    pub table_encoding: u8,
+    /// The address of `.eh_frame`.
+    pub eh_frame_ptr: u64,
+    /// The number of entries of the table, 0 if there's none.
+    pub fde_count: u64,
+    data: &'a [u8],
+    table_offset: u64,
+    endianness: Endianness,
+    address_size: u8,
+    bases: PointerBases,
+}
+
+impl<'a> EhFrameHdr<'a> {
+    /// Reads the header of `data`, the contents of `.eh_frame_hdr` loaded
+    /// at `address`.
+    pub fn parse(data: &'a [u8], address: u64, endianness: Endianness, address_size: u8)
+        -> Result<EhFrameHdr<'a>, DwarfError>
+    {
+        let mut reader = DwarfReader::new(data, endianness, ".eh_frame_hdr");
+        let version = reader.u8()?;
+        if version != 1 {
+            return Err(DwarfError::UnsupportedVersion {
+                what: ".eh_frame_hdr", version: version as u16 });
+        }
+        let eh_frame_ptr_encoding = reader.u8()?;
+        let fde_count_encoding = reader.u8()?;
+        let table_encoding = reader.u8()?;
+        // Table entries are relative to the start of the section.
+        let bases = PointerBases { section: address, text: 0, data: address };
+        let eh_frame_ptr = read_encoded_pointer(
+            &mut reader, eh_frame_ptr_encoding, address_size, &bases)?;
+        let fde_count = if fde_count_encoding == DW_EH_PE_omit
+            || table_encoding == DW_EH_PE_omit
+        {
+            0
+        } else {
+            read_encoded_pointer(&mut reader, fde_count_encoding, address_size, &bases)?
+        };
+        Ok(EhFrameHdr {
+            version,
+            eh_frame_ptr_encoding,
+            fde_count_encoding,
+            table_encoding,
+            eh_frame_ptr,
+            fde_count,
+            data,
+            table_offset: reader.get_pos(),
+            endianness,
+            address_size,
+            bases,
+        })
+    }
+
+    /// Returns the entry at `index` of the table: the initial location of
+    /// an FDE and its address.
+    pub fn entry(&self, index: u64) -> Result<(u64, u64), DwarfError> {
+        let size = get_pointer_size(self.table_encoding, self.address_size)
+            .ok_or(DwarfError::Corrupt("table of variable-sized entries"))?;
+        let offset = index.checked_mul(2 * size as u64)
+            .and_then(|offset| offset.checked_add(self.table_offset))
+            .ok_or(DwarfError::BadOffset { what: ".eh_frame_hdr", offset: index })?;
+        let mut reader = DwarfReader::at(self.data, offset, self.endianness, ".eh_frame_hdr")?;
+        let location = read_encoded_pointer(
+            &mut reader, self.table_encoding, self.address_size, &self.bases)?;
+        let fde = read_encoded_pointer(
+            &mut reader, self.table_encoding, self.address_size, &self.bases)?;
+        Ok((location, fde))
+    }
+
+    /// Returns all the entries of the table.
+    pub fn table(&self) -> Result<Vec<(u64, u64)>, DwarfError> {
+        (0..self.fde_count).map(|index| self.entry(index)).collect()
+    }
+
+    /// Finds the address of the FDE of the last entry that starts at or
+    /// before `address` with a binary search, as the unwinder does. The
+    /// FDE may still end before `address`.
+    pub fn lookup(&self, address: u64) -> Result<Option<u64>, DwarfError> {
+        let (mut low, mut high) = (0, self.fde_count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.entry(middle)?.0 <= address {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        if low == 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.entry(low - 1)?.1))
+    }
+}
@@ -0,0 +1,189 @@
+#![allow(non_upper_case_globals)]
+
+use super::error::DwarfError;
+use super::reader::{DwarfReader, Encoding};
+
+dwarf_names! { u64, get_form_name;
+    DW_FORM_addr = 0x01,
+    DW_FORM_block2 = 0x03,
+    DW_FORM_block4 = 0x04,
+    DW_FORM_data2 = 0x05,
+    DW_FORM_data4 = 0x06,
+    DW_FORM_data8 = 0x07,
+    DW_FORM_string = 0x08,
+    DW_FORM_block = 0x09,
+    DW_FORM_block1 = 0x0a,
+    DW_FORM_data1 = 0x0b,
+    DW_FORM_flag = 0x0c,
+    DW_FORM_sdata = 0x0d,
+    DW_FORM_strp = 0x0e,
+    DW_FORM_udata = 0x0f,
+    DW_FORM_ref_addr = 0x10,
+    DW_FORM_ref1 = 0x11,
+    DW_FORM_ref2 = 0x12,
+    DW_FORM_ref4 = 0x13,
+    DW_FORM_ref8 = 0x14,
+    DW_FORM_ref_udata = 0x15,
+    DW_FORM_indirect = 0x16,
+    // DWARF 4.
+    DW_FORM_sec_offset = 0x17,
+    DW_FORM_exprloc = 0x18,
+    DW_FORM_flag_present = 0x19,
+    DW_FORM_ref_sig8 = 0x20,
+    // DWARF 5.
+    DW_FORM_strx = 0x1a,
+    DW_FORM_addrx = 0x1b,
+    DW_FORM_ref_sup4 = 0x1c,
+    DW_FORM_strp_sup = 0x1d,
+    DW_FORM_data16 = 0x1e,
+    DW_FORM_line_strp = 0x1f,
+    DW_FORM_implicit_const = 0x21,
+    DW_FORM_loclistx = 0x22,
+    DW_FORM_rnglistx = 0x23,
+    DW_FORM_ref_sup8 = 0x24,
+    DW_FORM_strx1 = 0x25,
+    DW_FORM_strx2 = 0x26,
+    DW_FORM_strx3 = 0x27,
+    DW_FORM_strx4 = 0x28,
+    DW_FORM_addrx1 = 0x29,
+    DW_FORM_addrx2 = 0x2a,
+    DW_FORM_addrx3 = 0x2b,
+    DW_FORM_addrx4 = 0x2c,
+    // GNU extensions for split DWARF and DWZ.
+    DW_FORM_GNU_addr_index = 0x1f01,
+    DW_FORM_GNU_str_index = 0x1f02,
+    DW_FORM_GNU_ref_alt = 0x1f20,
+    DW_FORM_GNU_strp_alt = 0x1f21,
+}
+
+/// The value of an attribute or of a field of a DWARF 5 file entry, by the
+/// class of its form. Offsets into other sections and indices into their
+/// tables are left unresolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrValue<'a> {
+    Addr(u64),
+    /// Index into `.debug_addr`.
+    Addrx(u64),
+    Block(&'a [u8]),
+    Exprloc(&'a [u8]),
+    /// `data1` to `data8` and `udata`. Whether they're signed depends on
+    /// the attribute.
+    Data(u64),
+    Data16(&'a [u8]),
+    /// `sdata` and `implicit_const`.
+    Sdata(i64),
+    Flag(bool),
+    String(&'a [u8]),
+    /// Offset into `.debug_str`.
+    Strp(u64),
+    /// Offset into `.debug_line_str`.
+    LineStrp(u64),
+    /// Index into `.debug_str_offsets`.
+    Strx(u64),
+    /// Offset into the supplementary or alternate `.debug_str`.
+    StrpSup(u64),
+    /// Offset from the start of the unit.
+    Ref(u64),
+    /// Offset into `.debug_info`.
+    RefAddr(u64),
+    /// Offset into the supplementary or alternate `.debug_info`.
+    RefSup(u64),
+    RefSig8(u64),
+    SecOffset(u64),
+    /// Index into the offsets of `.debug_loclists`.
+    Loclistx(u64),
+    /// Index into the offsets of `.debug_rnglists`.
+    Rnglistx(u64),
+}
+
+impl<'a> AttrValue<'a> {
+    /// The value as an unsigned integer, for the forms that hold one.
+    pub fn get_udata(&self) -> Option<u64> {
+        match *self {
+            AttrValue::Data(value) | AttrValue::SecOffset(value) => Some(value),
+            AttrValue::Sdata(value) if value >= 0 => Some(value as u64),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a value of `form`. `implicit_const` is the value that
+/// `DW_FORM_implicit_const` takes from the abbreviation.
+pub fn read_attr_value<'a>(
+    reader: &mut DwarfReader<'a>, form: u64, implicit_const: i64, encoding: Encoding)
+    -> Result<AttrValue<'a>, DwarfError>
+{
+    use self::AttrValue::*;
+
+    let offset_size = encoding.format.get_offset_size();
+    Ok(match form {
+        DW_FORM_addr => Addr(reader.uint(encoding.address_size)?),
+        DW_FORM_addrx | DW_FORM_GNU_addr_index => Addrx(reader.uleb128()?),
+        DW_FORM_addrx1 => Addrx(reader.uint(1)?),
+        DW_FORM_addrx2 => Addrx(reader.uint(2)?),
+        DW_FORM_addrx3 => Addrx(reader.uint(3)?),
+        DW_FORM_addrx4 => Addrx(reader.uint(4)?),
+        DW_FORM_block1 => {
+            let len = reader.u8()?;
+            Block(reader.bytes(len as u64)?)
+        }
+        DW_FORM_block2 => {
+            let len = reader.u16()?;
+            Block(reader.bytes(len as u64)?)
+        }
+        DW_FORM_block4 => {
+            let len = reader.u32()?;
+            Block(reader.bytes(len as u64)?)
+        }
+        DW_FORM_block => {
+            let len = reader.uleb128()?;
+            Block(reader.bytes(len)?)
+        }
+        DW_FORM_exprloc => {
+            let len = reader.uleb128()?;
+            Exprloc(reader.bytes(len)?)
+        }
+        DW_FORM_data1 => Data(reader.uint(1)?),
+        DW_FORM_data2 => Data(reader.uint(2)?),
+        DW_FORM_data4 => Data(reader.uint(4)?),
+        DW_FORM_data8 => Data(reader.uint(8)?),
+        DW_FORM_udata => Data(reader.uleb128()?),
+        DW_FORM_data16 => Data16(reader.bytes(16)?),
+        DW_FORM_sdata => Sdata(reader.sleb128()?),
+        DW_FORM_implicit_const => Sdata(implicit_const),
+        DW_FORM_flag => Flag(reader.u8()? != 0),
+        DW_FORM_flag_present => Flag(true),
+        DW_FORM_string => String(reader.cstr()?),
+        DW_FORM_strp => Strp(reader.uint(offset_size)?),
+        DW_FORM_line_strp => LineStrp(reader.uint(offset_size)?),
+        DW_FORM_strp_sup | DW_FORM_GNU_strp_alt => StrpSup(reader.uint(offset_size)?),
+        DW_FORM_strx | DW_FORM_GNU_str_index => Strx(reader.uleb128()?),
+        DW_FORM_strx1 => Strx(reader.uint(1)?),
+        DW_FORM_strx2 => Strx(reader.uint(2)?),
+        DW_FORM_strx3 => Strx(reader.uint(3)?),
+        DW_FORM_strx4 => Strx(reader.uint(4)?),
+        DW_FORM_ref1 => Ref(reader.uint(1)?),
+        DW_FORM_ref2 => Ref(reader.uint(2)?),
+        DW_FORM_ref4 => Ref(reader.uint(4)?),
+        DW_FORM_ref8 => Ref(reader.uint(8)?),
+        DW_FORM_ref_udata => Ref(reader.uleb128()?),
+        // DWARF 2 made references as wide as addresses.
+        DW_FORM_ref_addr if encoding.version == 2 => RefAddr(reader.uint(encoding.address_size)?),
+        DW_FORM_ref_addr => RefAddr(reader.uint(offset_size)?),
+        DW_FORM_ref_sup4 => RefSup(reader.uint(4)?),
+        DW_FORM_ref_sup8 => RefSup(reader.uint(8)?),
+        DW_FORM_GNU_ref_alt => RefSup(reader.uint(offset_size)?),
+        DW_FORM_ref_sig8 => RefSig8(reader.u64()?),
+        DW_FORM_sec_offset => SecOffset(reader.uint(offset_size)?),
+        DW_FORM_loclistx => Loclistx(reader.uleb128()?),
+        DW_FORM_rnglistx => Rnglistx(reader.uleb128()?),
+        DW_FORM_indirect => {
+            let form = reader.uleb128()?;
+            if form == DW_FORM_indirect {
+                return Err(DwarfError::Corrupt("indirect form of an indirect form"));
+            }
+            read_attr_value(reader, form, implicit_const, encoding)?
+        }
+        form => return Err(DwarfError::UnknownForm(form)),
+    })
+}
@@ -0,0 +1,442 @@
+//! DWARF expressions, the stack machine programs that compute locations
+//! and values, and their decoding as readelf shows them.
+
+#![allow(non_upper_case_globals)]
+
+use ::elf::elf_ehdr_machine::ElfEhdrMachine;
+use ::elf::helpers::format_c_hex;
+use ::to_host::Endianness;
+use super::reader::Encoding;
+use super::registers::format_register;
+
+pub const DW_OP_addr: u8 = 0x03;
+pub const DW_OP_deref: u8 = 0x06;
+pub const DW_OP_const1u: u8 = 0x08;
+pub const DW_OP_const1s: u8 = 0x09;
+pub const DW_OP_const2u: u8 = 0x0a;
+pub const DW_OP_const2s: u8 = 0x0b;
+pub const DW_OP_const4u: u8 = 0x0c;
+pub const DW_OP_const4s: u8 = 0x0d;
+pub const DW_OP_const8u: u8 = 0x0e;
+pub const DW_OP_const8s: u8 = 0x0f;
+pub const DW_OP_constu: u8 = 0x10;
+pub const DW_OP_consts: u8 = 0x11;
+pub const DW_OP_dup: u8 = 0x12;
+pub const DW_OP_drop: u8 = 0x13;
+pub const DW_OP_over: u8 = 0x14;
+pub const DW_OP_pick: u8 = 0x15;
+pub const DW_OP_swap: u8 = 0x16;
+pub const DW_OP_rot: u8 = 0x17;
+pub const DW_OP_xderef: u8 = 0x18;
+pub const DW_OP_abs: u8 = 0x19;
+pub const DW_OP_and: u8 = 0x1a;
+pub const DW_OP_div: u8 = 0x1b;
+pub const DW_OP_minus: u8 = 0x1c;
+pub const DW_OP_mod: u8 = 0x1d;
+pub const DW_OP_mul: u8 = 0x1e;
+pub const DW_OP_neg: u8 = 0x1f;
+pub const DW_OP_not: u8 = 0x20;
+pub const DW_OP_or: u8 = 0x21;
+pub const DW_OP_plus: u8 = 0x22;
+pub const DW_OP_plus_uconst: u8 = 0x23;
+pub const DW_OP_shl: u8 = 0x24;
+pub const DW_OP_shr: u8 = 0x25;
+pub const DW_OP_shra: u8 = 0x26;
+pub const DW_OP_xor: u8 = 0x27;
+pub const DW_OP_bra: u8 = 0x28;
+pub const DW_OP_eq: u8 = 0x29;
+pub const DW_OP_ge: u8 = 0x2a;
+pub const DW_OP_gt: u8 = 0x2b;
+pub const DW_OP_le: u8 = 0x2c;
+pub const DW_OP_lt: u8 = 0x2d;
+pub const DW_OP_ne: u8 = 0x2e;
+pub const DW_OP_skip: u8 = 0x2f;
+pub const DW_OP_lit0: u8 = 0x30;
+pub const DW_OP_lit31: u8 = 0x4f;
+pub const DW_OP_reg0: u8 = 0x50;
+pub const DW_OP_reg31: u8 = 0x6f;
+pub const DW_OP_breg0: u8 = 0x70;
+pub const DW_OP_breg31: u8 = 0x8f;
+pub const DW_OP_regx: u8 = 0x90;
+pub const DW_OP_fbreg: u8 = 0x91;
+pub const DW_OP_bregx: u8 = 0x92;
+pub const DW_OP_piece: u8 = 0x93;
+pub const DW_OP_deref_size: u8 = 0x94;
+pub const DW_OP_xderef_size: u8 = 0x95;
+pub const DW_OP_nop: u8 = 0x96;
+// DWARF 3.
+pub const DW_OP_push_object_address: u8 = 0x97;
+pub const DW_OP_call2: u8 = 0x98;
+pub const DW_OP_call4: u8 = 0x99;
+pub const DW_OP_call_ref: u8 = 0x9a;
+pub const DW_OP_form_tls_address: u8 = 0x9b;
+pub const DW_OP_call_frame_cfa: u8 = 0x9c;
+pub const DW_OP_bit_piece: u8 = 0x9d;
+// DWARF 4.
+pub const DW_OP_implicit_value: u8 = 0x9e;
+pub const DW_OP_stack_value: u8 = 0x9f;
+// DWARF 5.
+pub const DW_OP_implicit_pointer: u8 = 0xa0;
+pub const DW_OP_addrx: u8 = 0xa1;
+pub const DW_OP_constx: u8 = 0xa2;
+pub const DW_OP_entry_value: u8 = 0xa3;
+pub const DW_OP_const_type: u8 = 0xa4;
+pub const DW_OP_regval_type: u8 = 0xa5;
+pub const DW_OP_deref_type: u8 = 0xa6;
+pub const DW_OP_xderef_type: u8 = 0xa7;
+pub const DW_OP_convert: u8 = 0xa8;
+pub const DW_OP_reinterpret: u8 = 0xa9;
+pub const DW_OP_lo_user: u8 = 0xe0;
+pub const DW_OP_hi_user: u8 = 0xff;
+// GNU extensions.
+pub const DW_OP_GNU_push_tls_address: u8 = 0xe0;
+pub const DW_OP_GNU_uninit: u8 = 0xf0;
+pub const DW_OP_GNU_encoded_addr: u8 = 0xf1;
+pub const DW_OP_GNU_implicit_pointer: u8 = 0xf2;
+pub const DW_OP_GNU_entry_value: u8 = 0xf3;
+pub const DW_OP_GNU_const_type: u8 = 0xf4;
+pub const DW_OP_GNU_regval_type: u8 = 0xf5;
+pub const DW_OP_GNU_deref_type: u8 = 0xf6;
+pub const DW_OP_GNU_convert: u8 = 0xf7;
+pub const DW_OP_GNU_reinterpret: u8 = 0xf9;
+pub const DW_OP_GNU_parameter_ref: u8 = 0xfa;
+pub const DW_OP_GNU_addr_index: u8 = 0xfb;
+pub const DW_OP_GNU_const_index: u8 = 0xfc;
+pub const DW_OP_GNU_variable_value: u8 = 0xfd;
+// HP extensions.
+pub const DW_OP_HP_unknown: u8 = 0xe0;
+pub const DW_OP_HP_is_value: u8 = 0xe1;
+pub const DW_OP_HP_fltconst4: u8 = 0xe2;
+pub const DW_OP_HP_fltconst8: u8 = 0xe3;
+pub const DW_OP_HP_mod_range: u8 = 0xe4;
+pub const DW_OP_HP_unmod_range: u8 = 0xe5;
+pub const DW_OP_HP_tls: u8 = 0xe6;
+// PGI (STMicroelectronics) extensions.
+pub const DW_OP_PGI_omp_thread_num: u8 = 0xf8;
+
+/// What the operands of an expression depend on.
+#[derive(Debug, Clone, Copy)]
+pub struct ExprContext {
+    pub endianness: Endianness,
+    /// The encoding of the unit of the expression, which tells the size
+    /// of addresses and offsets.
+    pub encoding: Encoding,
+    /// The offset of the unit in `.debug_info`. The operands that refer to
+    /// DIEs, e.g. base types, are relative to it.
+    pub unit_offset: u64,
+    /// The machine whose registers the expression uses.
+    pub machine: ElfEhdrMachine,
+}
+
+/// Reads the operands of an expression as leniently as readelf: fixed
+/// size operands that don't fit read as what's there of them, which ends
+/// the expression.
+struct Operands<'a> {
+    data: &'a [u8],
+    pos: usize,
+    endianness: Endianness,
+}
+
+impl<'a> Operands<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn uint(&mut self, size: u8) -> u64 {
+        let start = self.pos.min(self.data.len());
+        self.pos = self.pos.saturating_add(size as usize).min(self.data.len());
+        let bytes = &self.data[start..self.pos];
+        let fold = |value, &byte| (value << 8) | byte as u64;
+        match self.endianness {
+            Endianness::LE => bytes.iter().rev().fold(0, fold),
+            Endianness::BE => bytes.iter().fold(0, fold),
+        }
+    }
+
+    fn int(&mut self, size: u8) -> i64 {
+        let start = self.pos.min(self.data.len());
+        let value = self.uint(size);
+        let read = self.pos - start;
+        if read == 0 {
+            return 0;
+        }
+        let shift = 64 - 8 * read as u32;
+        ((value << shift) as i64) >> shift
+    }
+
+    fn leb128(&mut self) -> (u64, u32, u8) {
+        let mut value = 0u64;
+        let mut shift = 0;
+        let mut byte = 0;
+        while let Some(&b) = self.data.get(self.pos) {
+            self.pos += 1;
+            byte = b;
+            if shift < 64 {
+                value |= ((b & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+        (value, shift, byte)
+    }
+
+    fn uleb128(&mut self) -> u64 {
+        self.leb128().0
+    }
+
+    fn sleb128(&mut self) -> i64 {
+        let (value, shift, byte) = self.leb128();
+        if shift < 64 && byte & 0x40 != 0 {
+            (value | (!0u64 << shift)) as i64
+        } else {
+            value as i64
+        }
+    }
+
+    /// Formats a block of `len` bytes like readelf's `display_block`, with
+    /// the length as given but only the bytes that are there.
+    fn block(&mut self, len: u64) -> String {
+        let start = self.pos.min(self.data.len());
+        let end = start + (len.min((self.data.len() - start) as u64) as usize);
+        self.pos = end;
+        let mut s = format!(" {} byte block: ", len);
+        for byte in &self.data[start..end] {
+            s.push_str(&format!("{:x} ", byte));
+        }
+        s
+    }
+}
+
+/// Decodes `expr` into the text readelf shows for it, e.g.
+/// `DW_OP_breg7 (rsp): 8; DW_OP_deref`. Also returns whether it uses
+/// `DW_OP_fbreg`, i.e. needs the frame base of its function.
+pub fn format_expression(expr: &[u8], ctx: &ExprContext) -> (String, bool) {
+    let mut s = String::new();
+    let needs_frame_base = format_expression_into(&mut s, expr, ctx);
+    (s, needs_frame_base)
+}
+
+fn format_expression_into(s: &mut String, expr: &[u8], ctx: &ExprContext) -> bool {
+    let address_size = ctx.encoding.address_size;
+    let offset_size = ctx.encoding.format.get_offset_size();
+    // DWARF 2 made DIE references as wide as addresses.
+    let ref_size = if ctx.encoding.version == 2 { address_size } else { offset_size };
+    let reg = |r: u64| format_register(ctx.machine, r);
+    let die = |offset: u64| format_c_hex(ctx.unit_offset.wrapping_add(offset));
+    let mut needs_frame_base = false;
+
+    let mut ops = Operands { data: expr, pos: 0, endianness: ctx.endianness };
+    while !ops.is_empty() {
+        let op = expr[ops.pos];
+        ops.pos += 1;
+        let text = match op {
+            DW_OP_addr => format!("DW_OP_addr: {:x}", ops.uint(address_size)),
+            DW_OP_deref => "DW_OP_deref".to_string(),
+            DW_OP_const1u => format!("DW_OP_const1u: {}", ops.uint(1)),
+            DW_OP_const1s => format!("DW_OP_const1s: {}", ops.int(1)),
+            DW_OP_const2u => format!("DW_OP_const2u: {}", ops.uint(2)),
+            DW_OP_const2s => format!("DW_OP_const2s: {}", ops.int(2)),
+            DW_OP_const4u => format!("DW_OP_const4u: {}", ops.uint(4)),
+            DW_OP_const4s => format!("DW_OP_const4s: {}", ops.int(4)),
+            DW_OP_const8u => format!("DW_OP_const8u: {}", ops.uint(8)),
+            DW_OP_const8s => format!("DW_OP_const8s: {}", ops.int(8)),
+            DW_OP_constu => format!("DW_OP_constu: {}", ops.uleb128()),
+            DW_OP_consts => format!("DW_OP_consts: {}", ops.sleb128()),
+            DW_OP_dup => "DW_OP_dup".to_string(),
+            DW_OP_drop => "DW_OP_drop".to_string(),
+            DW_OP_over => "DW_OP_over".to_string(),
+            DW_OP_pick => format!("DW_OP_pick: {}", ops.uint(1)),
+            DW_OP_swap => "DW_OP_swap".to_string(),
+            DW_OP_rot => "DW_OP_rot".to_string(),
+            DW_OP_xderef => "DW_OP_xderef".to_string(),
+            DW_OP_abs => "DW_OP_abs".to_string(),
+            DW_OP_and => "DW_OP_and".to_string(),
+            DW_OP_div => "DW_OP_div".to_string(),
+            DW_OP_minus => "DW_OP_minus".to_string(),
+            DW_OP_mod => "DW_OP_mod".to_string(),
+            DW_OP_mul => "DW_OP_mul".to_string(),
+            DW_OP_neg => "DW_OP_neg".to_string(),
+            DW_OP_not => "DW_OP_not".to_string(),
+            DW_OP_or => "DW_OP_or".to_string(),
+            DW_OP_plus => "DW_OP_plus".to_string(),
+            DW_OP_plus_uconst => format!("DW_OP_plus_uconst: {}", ops.uleb128()),
+            DW_OP_shl => "DW_OP_shl".to_string(),
+            DW_OP_shr => "DW_OP_shr".to_string(),
+            DW_OP_shra => "DW_OP_shra".to_string(),
+            DW_OP_xor => "DW_OP_xor".to_string(),
+            DW_OP_bra => format!("DW_OP_bra: {}", ops.int(2)),
+            DW_OP_eq => "DW_OP_eq".to_string(),
+            DW_OP_ge => "DW_OP_ge".to_string(),
+            DW_OP_gt => "DW_OP_gt".to_string(),
+            DW_OP_le => "DW_OP_le".to_string(),
+            DW_OP_lt => "DW_OP_lt".to_string(),
+            DW_OP_ne => "DW_OP_ne".to_string(),
+            DW_OP_skip => format!("DW_OP_skip: {}", ops.int(2)),
+            DW_OP_lit0..=DW_OP_lit31 => format!("DW_OP_lit{}", op - DW_OP_lit0),
+            DW_OP_reg0..=DW_OP_reg31 => {
+                let r = (op - DW_OP_reg0) as u64;
+                format!("DW_OP_reg{} ({})", r, reg(r))
+            }
+            DW_OP_breg0..=DW_OP_breg31 => {
+                let r = (op - DW_OP_breg0) as u64;
+                format!("DW_OP_breg{} ({}): {}", r, reg(r), ops.sleb128())
+            }
+            DW_OP_regx => {
+                let r = ops.uleb128();
+                format!("DW_OP_regx: {} ({})", r, reg(r))
+            }
+            DW_OP_fbreg => {
+                needs_frame_base = true;
+                format!("DW_OP_fbreg: {}", ops.sleb128())
+            }
+            DW_OP_bregx => {
+                let r = ops.uleb128();
+                format!("DW_OP_bregx: {} ({}) {}", r, reg(r), ops.sleb128())
+            }
+            DW_OP_piece => format!("DW_OP_piece: {}", ops.uleb128()),
+            DW_OP_deref_size => format!("DW_OP_deref_size: {}", ops.uint(1)),
+            DW_OP_xderef_size => format!("DW_OP_xderef_size: {}", ops.uint(1)),
+            DW_OP_nop => "DW_OP_nop".to_string(),
+            DW_OP_push_object_address => "DW_OP_push_object_address".to_string(),
+            DW_OP_call2 => format!("DW_OP_call2: <{}>", die(ops.int(2) as u64)),
+            DW_OP_call4 => format!("DW_OP_call4: <{}>", die(ops.int(4) as u64)),
+            DW_OP_call_ref => {
+                format!("DW_OP_call_ref: <{}>", format_c_hex(ops.uint(ref_size)))
+            }
+            DW_OP_form_tls_address => "DW_OP_form_tls_address".to_string(),
+            DW_OP_call_frame_cfa => "DW_OP_call_frame_cfa".to_string(),
+            DW_OP_bit_piece => {
+                let size = ops.uleb128();
+                format!("DW_OP_bit_piece: size: {} offset: {} ", size, ops.uleb128())
+            }
+            DW_OP_stack_value => "DW_OP_stack_value".to_string(),
+            DW_OP_implicit_value => {
+                let len = ops.uleb128();
+                format!("DW_OP_implicit_value{}", ops.block(len))
+            }
+            DW_OP_GNU_push_tls_address => {
+                "DW_OP_GNU_push_tls_address or DW_OP_HP_unknown".to_string()
+            }
+            DW_OP_GNU_uninit => "DW_OP_GNU_uninit".to_string(),
+            DW_OP_GNU_encoded_addr => {
+                let encoding = ops.uint(1) as u8;
+                let size = match encoding & 0x7 {
+                    0x2 => 2,
+                    0x3 => 4,
+                    0x4 => 8,
+                    _ => address_size,
+                };
+                let address = if encoding & 0x8 != 0 {
+                    ops.int(size) as u64
+                } else {
+                    ops.uint(size)
+                };
+                format!("DW_OP_GNU_encoded_addr: fmt:{:02x} addr:{:0width$x}",
+                        encoding, address, width = 2 * address_size as usize)
+            }
+            DW_OP_implicit_pointer | DW_OP_GNU_implicit_pointer => {
+                let name = if op == DW_OP_implicit_pointer {
+                    "DW_OP_implicit_pointer"
+                } else {
+                    "DW_OP_GNU_implicit_pointer"
+                };
+                let target = ops.uint(ref_size);
+                format!("{}: <{}> {}", name, format_c_hex(target), ops.sleb128())
+            }
+            DW_OP_entry_value | DW_OP_GNU_entry_value => {
+                let name = if op == DW_OP_entry_value {
+                    "DW_OP_entry_value"
+                } else {
+                    "DW_OP_GNU_entry_value"
+                };
+                let len = ops.uleb128();
+                let start = ops.pos.min(expr.len());
+                let end = start + len.min((expr.len() - start) as u64) as usize;
+                let mut inner = format!("{}: (", name);
+                needs_frame_base |= format_expression_into(&mut inner, &expr[start..end], ctx);
+                inner.push(')');
+                ops.pos = end;
+                inner
+            }
+            DW_OP_const_type | DW_OP_GNU_const_type => {
+                let name = if op == DW_OP_const_type {
+                    "DW_OP_const_type"
+                } else {
+                    "DW_OP_GNU_const_type"
+                };
+                let base_type = ops.uleb128();
+                let len = ops.uint(1);
+                format!("{}: <{}> {}", name, die(base_type), ops.block(len))
+            }
+            DW_OP_regval_type | DW_OP_GNU_regval_type => {
+                let name = if op == DW_OP_regval_type {
+                    "DW_OP_regval_type"
+                } else {
+                    "DW_OP_GNU_regval_type"
+                };
+                let r = ops.uleb128();
+                format!("{}: {} ({}) <{}>", name, r, reg(r), die(ops.uleb128()))
+            }
+            DW_OP_deref_type | DW_OP_GNU_deref_type => {
+                let name = if op == DW_OP_deref_type {
+                    "DW_OP_deref_type"
+                } else {
+                    "DW_OP_GNU_deref_type"
+                };
+                let size = ops.uint(1);
+                format!("{}: {} <{}>", name, size, die(ops.uleb128()))
+            }
+            DW_OP_convert | DW_OP_GNU_convert | DW_OP_reinterpret | DW_OP_GNU_reinterpret => {
+                let name = match op {
+                    DW_OP_convert => "DW_OP_convert",
+                    DW_OP_GNU_convert => "DW_OP_GNU_convert",
+                    DW_OP_reinterpret => "DW_OP_reinterpret",
+                    _ => "DW_OP_GNU_reinterpret",
+                };
+                // 0 stands for the generic type rather than an offset.
+                let base_type = match ops.uleb128() {
+                    0 => "0".to_string(),
+                    offset => die(offset),
+                };
+                format!("{} <{}>", name, base_type)
+            }
+            DW_OP_GNU_parameter_ref => {
+                format!("DW_OP_GNU_parameter_ref: <{}>", die(ops.uint(4)))
+            }
+            DW_OP_addrx => format!("DW_OP_addrx <{}>", format_c_hex(ops.uleb128())),
+            DW_OP_GNU_addr_index => {
+                format!("DW_OP_GNU_addr_index <{}>", format_c_hex(ops.uleb128()))
+            }
+            DW_OP_GNU_const_index => {
+                format!("DW_OP_GNU_const_index <{}>", format_c_hex(ops.uleb128()))
+            }
+            DW_OP_GNU_variable_value => {
+                format!("DW_OP_GNU_variable_value: <{}>", format_c_hex(ops.uint(ref_size)))
+            }
+            DW_OP_HP_is_value => "DW_OP_HP_is_value".to_string(),
+            DW_OP_HP_fltconst4 => "DW_OP_HP_fltconst4".to_string(),
+            DW_OP_HP_fltconst8 => "DW_OP_HP_fltconst8".to_string(),
+            DW_OP_HP_mod_range => "DW_OP_HP_mod_range".to_string(),
+            DW_OP_HP_unmod_range => "DW_OP_HP_unmod_range".to_string(),
+            DW_OP_HP_tls => "DW_OP_HP_tls".to_string(),
+            DW_OP_PGI_omp_thread_num => "DW_OP_PGI_omp_thread_num".to_string(),
+            _ => {
+                // Without knowing its operands there's no telling where
+                // the next operation starts.
+                if (DW_OP_lo_user..=DW_OP_hi_user).contains(&op) {
+                    s.push_str(&format!("(User defined location op {:#x})", op));
+                } else {
+                    s.push_str(&format!("(Unknown location op {})", format_c_hex(op)));
+                }
+                return needs_frame_base;
+            }
+        };
+        s.push_str(&text);
+        if !ops.is_empty() {
+            s.push_str("; ");
+        }
+    }
+    needs_frame_base
+}
@@ -0,0 +1,43 @@
+use ::std::fmt::{Display, Formatter};
+
+/// Everything that can go wrong while reading DWARF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DwarfError {
+    /// The data ends in the middle of what's named.
+    Truncated(&'static str),
+    /// A unit or a line program has a version we don't read.
+    UnsupportedVersion {
+        what: &'static str,
+        version: u16,
+    },
+    /// An attribute or a file entry has an unknown form.
+    UnknownForm(u64),
+    /// A DIE uses an abbreviation code its table doesn't have.
+    MissingAbbrev(u64),
+    /// An offset into another section, e.g. a string offset, is out of
+    /// range of that section.
+    BadOffset {
+        what: &'static str,
+        offset: u64,
+    },
+    /// Something else is malformed; says what.
+    Corrupt(&'static str),
+}
+
+impl Display for DwarfError {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        use self::DwarfError::*;
+        match *self {
+            Truncated(what) => write!(fmt, "{} is truncated", what),
+            UnsupportedVersion { what, version } => write!(
+                fmt, "Unsupported {} version: {}", what, version),
+            UnknownForm(form) => write!(fmt, "Unknown attribute form: {:#x}", form),
+            MissingAbbrev(code) => write!(fmt, "Missing abbreviation {}", code),
+            BadOffset { what, offset } => write!(
+                fmt, "Offset {:#x} is out of range of {}", offset, what),
+            Corrupt(what) => write!(fmt, "Corrupt DWARF: {}", what),
+        }
+    }
+}
+
+impl ::std::error::Error for DwarfError {}
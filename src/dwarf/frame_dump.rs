@@ -0,0 +1,1227 @@
+//! Printing of the call frame information of `.eh_frame` and
+//! `.debug_frame` the way `readelf -wf` and `readelf -wF` do: every CIE
+//! and FDE with its instructions, or with the table of rules they make
+//! for each address. Also prints the search table of `.eh_frame_hdr`,
+//! which readelf doesn't.
+
+#![allow(non_upper_case_globals)]
+
+use ::std::io::{self, Write};
+use ::elf::ElfFile;
+use ::elf::elf_ehdr_machine::ElfEhdrMachine;
+use ::elf::helpers::format_c_hex;
+use ::to_host::Endianness;
+use super::cfi::*;
+use super::dw_op::{ExprContext, format_expression};
+use super::reader::{DwarfFormat, Encoding};
+use super::registers::{format_register, get_register_count, get_register_name};
+
+/// Prints the CIEs and FDEs of `data`, the contents of the `.eh_frame` or
+/// `.debug_frame` section `name` of `elf` loaded at `address`, like
+/// `readelf -wf`, or like `readelf -wF` if `interp` is set.
+///
+/// Malformed data is warned about on stderr, after flushing `out` so that
+/// the warnings show up where they happen.
+pub fn print_debug_frames(
+    elf: &ElfFile, name: &str, data: &[u8], address: u64, interp: bool, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    let machine = elf.machine();
+    let mut printer = Printer {
+        name,
+        data,
+        address,
+        is_eh: name == ".eh_frame",
+        interp,
+        machine,
+        endianness: elf.endianness(),
+        address_size: if elf.is_32() { 4 } else { 8 },
+        register_count: get_register_count(machine),
+        out,
+        cies: Vec::new(),
+        forward_cies: Vec::new(),
+        remembered: Vec::new(),
+        max_regs: 0,
+    };
+    printer.print()
+}
+
+/// The rule of a column of the table, as readelf keeps it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule {
+    /// Not used by the instructions, so not shown.
+    Unreferenced,
+    Undefined,
+    SameValue,
+    Offset,
+    ValOffset,
+    Register,
+    Expression,
+    ValExpression,
+}
+
+/// The state of a CIE or an FDE while its instructions are run: what its
+/// header says and the current row of the table.
+#[derive(Debug, Clone)]
+struct Frame {
+    offset: u64,
+    augmentation: Vec<u8>,
+    ptr_size: u8,
+    segment_size: u8,
+    code_factor: u64,
+    data_factor: i64,
+    ra: u64,
+    fde_encoding: u8,
+    pc_begin: u64,
+    cfa_reg: u64,
+    cfa_offset: u64,
+    cfa_exp: bool,
+    col_type: Vec<Rule>,
+    col_offset: Vec<i64>,
+}
+
+impl Frame {
+    fn new(offset: u64, ptr_size: u8) -> Frame {
+        Frame {
+            offset,
+            augmentation: Vec::new(),
+            ptr_size,
+            segment_size: 0,
+            code_factor: 0,
+            data_factor: 0,
+            ra: 0,
+            fde_encoding: 0,
+            pc_begin: 0,
+            cfa_reg: 0,
+            cfa_offset: 0,
+            cfa_exp: false,
+            col_type: Vec::new(),
+            col_offset: Vec::new(),
+        }
+    }
+
+    fn ncols(&self) -> u64 {
+        self.col_type.len() as u64
+    }
+}
+
+/// What follows the fields of a CIE that readelf prints.
+struct CieHeader<'d> {
+    version: u8,
+    augmentation_data: &'d [u8],
+}
+
+/// Reads the fields of an entry as leniently as readelf: fields cut off
+/// by the end of the entry read as what's there of them, and reading
+/// stops at the end.
+struct Cursor<'d> {
+    data: &'d [u8],
+    pos: u64,
+    end: u64,
+    endianness: Endianness,
+}
+
+impl<'d> Cursor<'d> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.end
+    }
+
+    fn left(&self) -> u64 {
+        self.end.saturating_sub(self.pos)
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.uint(1) as u8
+    }
+
+    fn uint(&mut self, size: u8) -> u64 {
+        let start = self.pos.min(self.end) as usize;
+        let end = self.pos.saturating_add(size as u64).min(self.end) as usize;
+        self.pos = end as u64;
+        let fold = |value, &byte| (value << 8) | byte as u64;
+        match self.endianness {
+            Endianness::LE => self.data[start..end].iter().rev().fold(0, fold),
+            Endianness::BE => self.data[start..end].iter().fold(0, fold),
+        }
+    }
+
+    /// Reads a LEB128 number and its status like readelf's: bit 0 is set
+    /// if it runs past the end and bit 1 if it doesn't fit in 64 bits.
+    fn leb128(&mut self, signed: bool) -> (u64, u8) {
+        let mut value = 0u64;
+        let mut shift = 0;
+        let mut status = 1;
+        while !self.is_empty() {
+            let byte = self.data[self.pos as usize];
+            self.pos += 1;
+            if shift < 64 {
+                value |= ((byte & 0x7f) as u64) << shift;
+                if value >> shift != (byte & 0x7f) as u64 {
+                    status |= 2;
+                }
+                shift += 7;
+            } else if byte & 0x7f != 0 {
+                status |= 2;
+            }
+            if byte & 0x80 == 0 {
+                status &= !1;
+                if signed && shift < 64 && byte & 0x40 != 0 {
+                    value |= !0u64 << shift;
+                }
+                break;
+            }
+        }
+        (value, status)
+    }
+
+    fn uleb128(&mut self) -> u64 {
+        self.leb128(false).0
+    }
+
+    fn sleb128(&mut self) -> i64 {
+        self.leb128(true).0 as i64
+    }
+
+    fn skip(&mut self, len: u64) {
+        self.pos = self.pos.saturating_add(len).min(self.end);
+    }
+
+    fn bytes(&mut self, len: u64) -> &'d [u8] {
+        let start = self.pos.min(self.end) as usize;
+        self.skip(len);
+        &self.data[start..self.pos as usize]
+    }
+}
+
+struct Printer<'p> {
+    name: &'p str,
+    data: &'p [u8],
+    address: u64,
+    /// Whether this is `.eh_frame`, whose CIE pointers are relative.
+    is_eh: bool,
+    interp: bool,
+    machine: ElfEhdrMachine,
+    endianness: Endianness,
+    /// The size of addresses of the file, unless a CIE says otherwise.
+    address_size: u8,
+    /// The number of registers readelf knows of the machine, which it
+    /// takes as the highest register number that may be used.
+    register_count: usize,
+    out: &'p mut dyn Write,
+    /// The CIEs read so far, with the rules their instructions make.
+    cies: Vec<Frame>,
+    /// The CIEs that FDEs before them refer to, whose instructions
+    /// readelf doesn't run.
+    forward_cies: Vec<Frame>,
+    /// The rows of `DW_CFA_remember_state`, which readelf keeps across
+    /// entries.
+    remembered: Vec<Frame>,
+    /// The number of columns of the last row printed, which readelf
+    /// makes every later entry have at least.
+    max_regs: u64,
+}
+
+/// Formats register `reg` like readelf does in instructions, e.g.
+/// `r7 (rsp)`, or `r7` if it has no name.
+fn format_numbered_register(machine: ElfEhdrMachine, reg: u64) -> String {
+    let number = reg as u32 as i32;
+    match get_register_name(machine, reg as u32 as u64) {
+        Some(name) => format!("r{} ({})", number, name),
+        None => format!("r{}", number),
+    }
+}
+
+/// Formats `value` as `size` bytes of hex digits, dropping what doesn't
+/// fit.
+fn format_hex(value: u64, size: u8) -> String {
+    let size = if size == 0 { 2 } else { size.min(8) };
+    let mask = if size == 8 { !0 } else { (1u64 << (size * 8)) - 1 };
+    format!("{:0width$x}", value & mask, width = size as usize * 2)
+}
+
+impl<'p> Printer<'p> {
+    fn warn(&mut self, message: &str) -> io::Result<()> {
+        self.out.flush()?;
+        eprintln!("Warning: {}", message);
+        Ok(())
+    }
+
+    fn error(&mut self, message: &str) -> io::Result<()> {
+        self.out.flush()?;
+        eprintln!("Error: {}", message);
+        Ok(())
+    }
+
+    /// Reads an unsigned LEB128 number into a field of `bits` bits,
+    /// reporting what readelf does if it's cut off or doesn't fit.
+    fn uleb(&mut self, cursor: &mut Cursor, bits: u32) -> io::Result<u64> {
+        let (mut value, mut status) = cursor.leb128(false);
+        if bits < 64 && value >> bits != 0 {
+            value &= (1 << bits) - 1;
+            status |= 2;
+        }
+        self.report_leb(status)?;
+        Ok(value)
+    }
+
+    /// Reads a signed LEB128 number into a field of `bits` bits like
+    /// `uleb`.
+    fn sleb(&mut self, cursor: &mut Cursor, bits: u32) -> io::Result<i64> {
+        let (value, mut status) = cursor.leb128(true);
+        let mut value = value as i64;
+        if bits < 64 && (value << (64 - bits)) >> (64 - bits) != value {
+            value = (value << (64 - bits)) >> (64 - bits);
+            status |= 2;
+        }
+        self.report_leb(status)?;
+        Ok(value)
+    }
+
+    fn report_leb(&mut self, status: u8) -> io::Result<()> {
+        if status & 1 != 0 {
+            self.error("end of data encountered whilst reading LEB")
+        } else if status & 2 != 0 {
+            self.error("read LEB value is too large to store in destination variable")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn cursor(&self, pos: u64, end: u64) -> Cursor<'p> {
+        Cursor { data: self.data, pos, end, endianness: self.endianness }
+    }
+
+    /// The size of pointers with `encoding`, where readelf takes LEB128
+    /// ones to be as wide as addresses.
+    fn encoded_size(encoding: u8, address_size: u8) -> u8 {
+        get_pointer_size(encoding, address_size).unwrap_or(address_size)
+    }
+
+    /// Reads a pointer with `encoding` as readelf does: only pc-relative
+    /// ones are adjusted.
+    fn read_encoded(&mut self, cursor: &mut Cursor, encoding: u8, address_size: u8)
+        -> io::Result<u64>
+    {
+        let size = Printer::encoded_size(encoding, address_size);
+        if cursor.is_empty() || size as u64 > cursor.left() {
+            self.warn("Encoded value extends past end of section")?;
+            cursor.pos = cursor.end;
+            return Ok(0);
+        }
+        let field = cursor.pos;
+        let mut value = cursor.uint(size);
+        if encoding & DW_EH_PE_signed != 0 && size < 8 {
+            let shift = 64 - size as u32 * 8;
+            value = (((value << shift) as i64) >> shift) as u64;
+        }
+        if encoding & 0x70 == DW_EH_PE_pcrel {
+            value = value.wrapping_add(self.address).wrapping_add(field);
+        }
+        Ok(value)
+    }
+
+    /// Makes `frame` have a column for `reg`, like readelf's
+    /// `frame_need_space`. Returns `false` if the register is out of
+    /// range.
+    fn need_space(&mut self, frame: &mut Frame, reg: u64) -> io::Result<bool> {
+        if reg < frame.ncols() {
+            return Ok(true);
+        }
+        if self.register_count > 0 && reg > self.register_count as u64 {
+            return Ok(false);
+        }
+        if reg >= 1024 && self.register_count == 0 {
+            self.error(&format!("Unfeasibly large register number: {}", reg as u32))?;
+            frame.col_type.clear();
+            frame.col_offset.clear();
+            return Ok(false);
+        }
+        frame.col_type.resize(reg as usize + 1, Rule::Unreferenced);
+        frame.col_offset.resize(reg as usize + 1, 0);
+        Ok(true)
+    }
+
+    /// Whether `id`, the field after the length of an entry, says it's a
+    /// CIE rather than an FDE.
+    fn is_cie_id(&self, id: u64, offset_size: u8) -> bool {
+        if self.is_eh {
+            id == 0
+        } else if offset_size == 4 {
+            id == DW_CIE_ID as u64
+        } else {
+            id == DW64_CIE_ID
+        }
+    }
+
+    /// The column count readelf makes every entry have at least.
+    fn min_reg(&self) -> u64 {
+        self.max_regs.saturating_sub(1)
+    }
+
+    fn print(&mut self) -> io::Result<()> {
+        writeln!(self.out, "Contents of the {} section:\n", self.name)?;
+        let end = self.data.len() as u64;
+        let mut start = 0;
+        while start < end {
+            if !self.print_entry(&mut start, end)? {
+                break;
+            }
+        }
+        writeln!(self.out)
+    }
+
+    /// Prints the entry at `start` and moves it past the entry. Returns
+    /// `false` if the rest of the section can't be read.
+    fn print_entry(&mut self, start: &mut u64, end: u64) -> io::Result<bool> {
+        let saved_start = *start;
+        let mut cursor = self.cursor(saved_start, end);
+        let mut length = cursor.uint(4);
+        if length == 0 {
+            writeln!(self.out, "\n{:08x} ZERO terminator\n", saved_start)?;
+            *start = cursor.pos;
+            while *start < end && self.data[*start as usize] == 0 {
+                *start += 1;
+            }
+            return Ok(true);
+        }
+        let offset_size = if length == 0xffff_ffff {
+            length = cursor.uint(8);
+            8
+        } else {
+            4
+        };
+        let block_end = if length > cursor.left() {
+            self.warn(&format!(
+                "Invalid length {:#x} in FDE at {}", length, format_c_hex(saved_start)))?;
+            end
+        } else {
+            cursor.pos + length
+        };
+        cursor.end = block_end;
+        let cie_id = cursor.uint(offset_size);
+        let is_cie = self.is_cie_id(cie_id, offset_size);
+
+        let mut frame;
+        // The CIE of an FDE, or `None` if it's the frame itself: a CIE, or
+        // an FDE whose CIE wasn't found.
+        let cie;
+        let mut encoded_ptr_size = self.address_size;
+        if is_cie {
+            let (mut cie_frame, header) = match self.read_cie(&mut cursor, saved_start)? {
+                Some(cie) => cie,
+                None => return Ok(false),
+            };
+            let mreg = self.min_reg().max(cie_frame.ra);
+            if !self.need_space(&mut cie_frame, mreg)? {
+                return Ok(false);
+            }
+            if cie_frame.fde_encoding != 0 {
+                encoded_ptr_size = Printer::encoded_size(cie_frame.fde_encoding, cie_frame.ptr_size);
+            }
+            frame = cie_frame;
+            cie = None;
+            self.print_cie_header(&frame, &header, length, cie_id, offset_size)?;
+        } else {
+            let look_for = if self.is_eh {
+                let sign = 1u64 << (offset_size * 8 - 1);
+                let relative = (cie_id ^ sign).wrapping_sub(sign);
+                cursor.pos.wrapping_sub(4).wrapping_sub(relative)
+            } else {
+                cie_id
+            };
+            let found = if look_for <= saved_start {
+                self.cies.iter().rev().find(|c| c.offset == look_for).cloned()
+            } else if look_for >= end {
+                None
+            } else {
+                match self.forward_cies.iter().find(|c| c.offset == look_for).cloned() {
+                    Some(cie) => Some(cie),
+                    None => match self.read_forward_cie(look_for, end, &mut length)? {
+                        Ok(cie) => cie,
+                        Err(()) => return Ok(false),
+                    },
+                }
+            };
+
+            frame = match found {
+                Some(ref found) => Frame {
+                    offset: saved_start,
+                    cfa_exp: false,
+                    pc_begin: 0,
+                    ..found.clone()
+                },
+                None => Frame::new(saved_start, self.address_size),
+            };
+            let mreg = self.min_reg();
+            if !self.need_space(&mut frame, mreg)? {
+                self.warn("Invalid max register")?;
+                return Ok(false);
+            }
+            cie = found;
+            if frame.fde_encoding != 0 {
+                encoded_ptr_size = Printer::encoded_size(frame.fde_encoding, frame.ptr_size);
+            }
+
+            let mut segment_selector = 0;
+            if frame.segment_size != 0 {
+                if frame.segment_size > 8 {
+                    self.warn(&format!(
+                        "Probably corrupt segment size: {} - using 4 instead",
+                        frame.segment_size))?;
+                    frame.segment_size = 4;
+                }
+                segment_selector = cursor.uint(frame.segment_size);
+            }
+            let (encoding, ptr_size) = (frame.fde_encoding, frame.ptr_size);
+            frame.pc_begin = self.read_encoded(&mut cursor, encoding, ptr_size)?;
+            let pc_range = cursor.uint(encoded_ptr_size);
+            let mut augmentation_data: &[u8] = &[];
+            if frame.augmentation.first() == Some(&b'z') {
+                let len = self.uleb(&mut cursor, 64)?;
+                if len > cursor.left() {
+                    self.warn(&format!(
+                        "Augmentation data too long: {:#x}, expected at most {}",
+                        len, format_c_hex(cursor.left())))?;
+                    cursor.pos = cursor.end;
+                } else {
+                    augmentation_data = cursor.bytes(len);
+                }
+            }
+
+            let cie_offset = match cie {
+                Some(ref cie) => format!("{:08x}", cie.offset),
+                None => "invalid".to_string(),
+            };
+            write!(self.out, "\n{:08x} {} {} FDE cie={:<8} pc=",
+                   saved_start, format_hex(length, frame.ptr_size),
+                   format_hex(cie_id, offset_size), cie_offset)?;
+            if frame.segment_size != 0 {
+                write!(self.out, "{:04x}:", segment_selector)?;
+            }
+            writeln!(self.out, "{}..{}", format_hex(frame.pc_begin, frame.ptr_size),
+                     format_hex(frame.pc_begin.wrapping_add(pc_range), frame.ptr_size))?;
+            if !self.interp && !augmentation_data.is_empty() {
+                self.print_augmentation_data(augmentation_data)?;
+                writeln!(self.out)?;
+            }
+        }
+
+        self.note_registers(&mut frame, cie.as_ref(), cursor.pos, block_end, encoded_ptr_size)?;
+        self.print_instructions(&mut frame, cie.as_ref(), &mut cursor)?;
+        if is_cie {
+            self.cies.push(frame);
+        }
+        *start = block_end;
+        Ok(true)
+    }
+
+    /// Reads the CIE that an FDE before it refers to at `offset`, without
+    /// running its instructions. Returns `Err` if the section can't be
+    /// read on.
+    ///
+    /// Like readelf, this reads the length of the CIE into the `length`
+    /// of the FDE, which is then printed for it.
+    fn read_forward_cie(&mut self, offset: u64, end: u64, length: &mut u64)
+        -> io::Result<Result<Option<Frame>, ()>>
+    {
+        let mut cursor = self.cursor(offset, end);
+        *length = cursor.uint(4);
+        let mut offset_size = 4;
+        if *length == 0xffff_ffff {
+            *length = cursor.uint(8);
+            offset_size = 8;
+        }
+        if *length == 0 || *length > cursor.left() {
+            return Ok(Ok(None));
+        }
+        cursor.end = cursor.pos + *length;
+        let id = cursor.uint(offset_size);
+        if !self.is_cie_id(id, offset_size) {
+            return Ok(Ok(None));
+        }
+        let mut cie = match self.read_cie(&mut cursor, offset)? {
+            Some((cie, _)) => cie,
+            None => {
+                self.warn("Failed to read CIE information")?;
+                return Ok(Err(()));
+            }
+        };
+        let mreg = self.min_reg().max(cie.ra);
+        if !self.need_space(&mut cie, mreg)? {
+            self.warn("Invalid max register")?;
+            return Ok(Err(()));
+        }
+        self.forward_cies.push(cie.clone());
+        Ok(Ok(Some(cie)))
+    }
+
+    /// Reads the fields of the CIE at `offset` that follow its ID, like
+    /// readelf's `read_cie`. Returns `None` if they don't read.
+    fn read_cie<'d>(&mut self, cursor: &mut Cursor<'d>, offset: u64)
+        -> io::Result<Option<(Frame, CieHeader<'d>)>>
+    {
+        if cursor.is_empty() {
+            return Ok(None);
+        }
+        let mut frame = Frame::new(offset, self.address_size);
+        let version = cursor.u8();
+        let augmentation_start = cursor.pos as usize;
+        while !cursor.is_empty() {
+            if cursor.u8() == 0 {
+                break;
+            }
+        }
+        if cursor.is_empty() {
+            self.warn("No terminator for augmentation name")?;
+            return Ok(None);
+        }
+        frame.augmentation = self.data[augmentation_start..cursor.pos as usize - 1].to_vec();
+        if frame.augmentation == b"eh" {
+            if (self.address_size as u64) > cursor.left() {
+                return Ok(None);
+            }
+            cursor.skip(self.address_size as u64);
+        }
+        if version >= 4 {
+            if cursor.left() < 2 {
+                return Ok(None);
+            }
+            frame.ptr_size = cursor.u8();
+            if frame.ptr_size < 1 || frame.ptr_size > 8 {
+                self.warn(&format!("Invalid pointer size ({}) in CIE data", frame.ptr_size))?;
+                return Ok(None);
+            }
+            frame.segment_size = cursor.u8();
+            if frame.segment_size > 8 || frame.segment_size + frame.ptr_size > 8 {
+                self.warn(&format!(
+                    "Invalid segment size ({}) in CIE data", frame.segment_size))?;
+                return Ok(None);
+            }
+        }
+        frame.code_factor = self.uleb(cursor, 32)?;
+        frame.data_factor = self.sleb(cursor, 32)?;
+        if cursor.is_empty() {
+            return Ok(None);
+        }
+        frame.ra = if version == 1 {
+            cursor.u8() as u64
+        } else {
+            self.uleb(cursor, 32)?
+        };
+
+        let mut augmentation_data: &[u8] = &[];
+        if frame.augmentation.first() == Some(&b'z') {
+            if cursor.is_empty() {
+                return Ok(None);
+            }
+            let len = self.uleb(cursor, 64)?;
+            if len > cursor.left() {
+                self.warn(&format!(
+                    "Augmentation data too long: {:#x}, expected at most {}",
+                    len, format_c_hex(cursor.left())))?;
+                return Ok(None);
+            }
+            augmentation_data = cursor.bytes(len);
+        }
+        if !augmentation_data.is_empty() {
+            let mut i = 0;
+            for &letter in &frame.augmentation[1..] {
+                if i >= augmentation_data.len() {
+                    break;
+                }
+                match letter {
+                    b'L' => i += 1,
+                    b'P' => {
+                        let size = Printer::encoded_size(augmentation_data[i], frame.ptr_size);
+                        i += 1 + size as usize;
+                    }
+                    b'R' => {
+                        frame.fde_encoding = augmentation_data[i];
+                        i += 1;
+                    }
+                    b'S' | b'B' => {}
+                    _ => break,
+                }
+            }
+        }
+        Ok(Some((frame, CieHeader { version, augmentation_data })))
+    }
+
+    fn print_cie_header(
+        &mut self, frame: &Frame, header: &CieHeader, length: u64, cie_id: u64, offset_size: u8)
+        -> io::Result<()>
+    {
+        write!(self.out, "\n{:08x} {} {} ", frame.offset, format_hex(length, frame.ptr_size),
+               format_hex(cie_id, offset_size))?;
+        if self.interp {
+            write!(self.out, "CIE \"")?;
+            self.out.write_all(&frame.augmentation)?;
+            writeln!(self.out, "\" cf={} df={} ra={}", frame.code_factor as u32 as i32,
+                     frame.data_factor, frame.ra as u32 as i32)?;
+            return Ok(());
+        }
+        writeln!(self.out, "CIE")?;
+        writeln!(self.out, "  Version:               {}", header.version)?;
+        write!(self.out, "  Augmentation:          \"")?;
+        self.out.write_all(&frame.augmentation)?;
+        writeln!(self.out, "\"")?;
+        if header.version >= 4 {
+            writeln!(self.out, "  Pointer Size:          {}", frame.ptr_size)?;
+            writeln!(self.out, "  Segment Size:          {}", frame.segment_size)?;
+        }
+        writeln!(self.out, "  Code alignment factor: {}", frame.code_factor)?;
+        writeln!(self.out, "  Data alignment factor: {}", frame.data_factor)?;
+        writeln!(self.out, "  Return address column: {}", frame.ra as u32 as i32)?;
+        if !header.augmentation_data.is_empty() {
+            self.print_augmentation_data(header.augmentation_data)?;
+        }
+        writeln!(self.out)
+    }
+
+    /// Prints the augmentation data of an entry, without ending the line.
+    /// Data that doesn't fit in 80 columns goes on lines of its own.
+    fn print_augmentation_data(&mut self, data: &[u8]) -> io::Result<()> {
+        let label = "  Augmentation data:    ";
+        write!(self.out, "{}", label)?;
+        let wrap = data.len() >= (80 - label.len()) / 3;
+        for (i, byte) in data.iter().enumerate() {
+            if wrap && i % (80 / 3) == 0 {
+                writeln!(self.out)?;
+            }
+            write!(self.out, " {:02x}", byte)?;
+        }
+        Ok(())
+    }
+
+    /// Makes columns for the registers that the instructions from `pos`
+    /// to `end` use, with the rules of the CIE, so that the table shows
+    /// them all from the start.
+    fn note_registers(
+        &mut self, frame: &mut Frame, cie: Option<&Frame>, pos: u64, end: u64,
+        encoded_ptr_size: u8)
+        -> io::Result<()>
+    {
+        let mut cursor = self.cursor(pos, end);
+        while !cursor.is_empty() {
+            let mut op = cursor.u8();
+            let operand = (op & 0x3f) as u64;
+            if op & 0xc0 != 0 {
+                op &= 0xc0;
+            }
+            let reg = match op {
+                DW_CFA_advance_loc => None,
+                DW_CFA_offset => {
+                    cursor.uleb128();
+                    Some(operand)
+                }
+                DW_CFA_restore => Some(operand),
+                DW_CFA_set_loc => {
+                    cursor.skip(encoded_ptr_size as u64);
+                    None
+                }
+                DW_CFA_advance_loc1 => {
+                    cursor.skip(1);
+                    None
+                }
+                DW_CFA_advance_loc2 => {
+                    cursor.skip(2);
+                    None
+                }
+                DW_CFA_advance_loc4 => {
+                    cursor.skip(4);
+                    None
+                }
+                DW_CFA_offset_extended | DW_CFA_val_offset | DW_CFA_register
+                    | DW_CFA_GNU_negative_offset_extended => {
+                    let reg = self.uleb(&mut cursor, 32)?;
+                    cursor.uleb128();
+                    Some(reg)
+                }
+                DW_CFA_offset_extended_sf | DW_CFA_val_offset_sf => {
+                    let reg = self.uleb(&mut cursor, 32)?;
+                    cursor.sleb128();
+                    Some(reg)
+                }
+                DW_CFA_restore_extended | DW_CFA_undefined | DW_CFA_same_value => {
+                    Some(self.uleb(&mut cursor, 32)?)
+                }
+                DW_CFA_def_cfa | DW_CFA_def_cfa_sf => {
+                    cursor.uleb128();
+                    cursor.uleb128();
+                    None
+                }
+                DW_CFA_def_cfa_register | DW_CFA_def_cfa_offset | DW_CFA_def_cfa_offset_sf
+                    | DW_CFA_GNU_args_size => {
+                    cursor.uleb128();
+                    None
+                }
+                DW_CFA_def_cfa_expression => {
+                    let len = self.uleb(&mut cursor, 64)?;
+                    cursor.skip(len);
+                    None
+                }
+                DW_CFA_expression | DW_CFA_val_expression => {
+                    let reg = self.uleb(&mut cursor, 32)?;
+                    let len = self.uleb(&mut cursor, 64)?;
+                    cursor.skip(len);
+                    Some(reg)
+                }
+                DW_CFA_MIPS_advance_loc8 => {
+                    cursor.skip(8);
+                    None
+                }
+                _ => None,
+            };
+            // readelf keeps register numbers in 32 bits.
+            let reg = match reg {
+                Some(reg) if reg as u32 != u32::MAX => reg as u32 as u64,
+                _ => continue,
+            };
+            if self.need_space(frame, reg)? {
+                let cie = cie.unwrap_or(frame);
+                let rule = if reg >= cie.ncols() || cie.col_type[reg as usize] == Rule::Unreferenced {
+                    Rule::Undefined
+                } else {
+                    cie.col_type[reg as usize]
+                };
+                frame.col_type[reg as usize] = rule;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the instructions of `frame`, or with `interp` the rows of
+    /// the table they make, unless they're all `DW_CFA_nop`.
+    fn print_instructions(&mut self, frame: &mut Frame, cie: Option<&Frame>, cursor: &mut Cursor)
+        -> io::Result<()>
+    {
+        let interp = self.interp;
+        let machine = self.machine;
+        let reg_name = |reg: u64| format_numbered_register(machine, reg);
+        let mut need_col_headers = true;
+        let mut all_nops = true;
+
+        while !cursor.is_empty() {
+            let mut op = cursor.u8();
+            let operand = op & 0x3f;
+            if op & 0xc0 != 0 {
+                op &= 0xc0;
+            }
+            if op != DW_CFA_nop {
+                all_nops = false;
+            }
+            match op {
+                DW_CFA_advance_loc | DW_CFA_advance_loc1 | DW_CFA_advance_loc2
+                    | DW_CFA_advance_loc4 | DW_CFA_MIPS_advance_loc8 => {
+                    let delta = match op {
+                        DW_CFA_advance_loc => operand as u64,
+                        DW_CFA_advance_loc1 => cursor.uint(1),
+                        DW_CFA_advance_loc2 => cursor.uint(2),
+                        DW_CFA_advance_loc4 => cursor.uint(4),
+                        _ => cursor.uint(8),
+                    };
+                    let advance = delta.wrapping_mul(frame.code_factor);
+                    if interp {
+                        self.print_row(frame, &mut need_col_headers)?;
+                    } else {
+                        let name = get_cfa_name(op).unwrap_or("DW_CFA_MIPS_advance_loc8");
+                        // readelf does the small ones in 32 bits.
+                        let shown = if op == DW_CFA_advance_loc {
+                            advance as u32 as i32 as i64
+                        } else {
+                            advance as i64
+                        };
+                        writeln!(self.out, "  {}: {} to {}", name, shown,
+                                 format_hex(frame.pc_begin.wrapping_add(advance), frame.ptr_size))?;
+                    }
+                    frame.pc_begin = frame.pc_begin.wrapping_add(advance);
+                }
+                DW_CFA_set_loc => {
+                    let (encoding, ptr_size) = (frame.fde_encoding, frame.ptr_size);
+                    let address = self.read_encoded(cursor, encoding, ptr_size)?;
+                    if interp {
+                        self.print_row(frame, &mut need_col_headers)?;
+                    } else {
+                        writeln!(self.out, "  DW_CFA_set_loc: {}", format_hex(address, ptr_size))?;
+                    }
+                    frame.pc_begin = address;
+                }
+                DW_CFA_offset | DW_CFA_offset_extended | DW_CFA_offset_extended_sf
+                    | DW_CFA_val_offset | DW_CFA_val_offset_sf
+                    | DW_CFA_GNU_negative_offset_extended => {
+                    let reg = if op == DW_CFA_offset {
+                        operand as u64
+                    } else {
+                        self.uleb(cursor, 32)?
+                    };
+                    let factored = match op {
+                        DW_CFA_offset_extended_sf | DW_CFA_val_offset_sf => {
+                            self.sleb(cursor, 64)? as u64
+                        }
+                        DW_CFA_GNU_negative_offset_extended => {
+                            (self.sleb(cursor, 64)? as u64).wrapping_neg()
+                        }
+                        _ => self.uleb(cursor, 64)?,
+                    };
+                    let offset = factored.wrapping_mul(frame.data_factor as u64) as i64;
+                    let bad = reg >= frame.ncols();
+                    if !interp || bad {
+                        let (name, relation) = match op {
+                            DW_CFA_val_offset | DW_CFA_val_offset_sf => (get_cfa_name(op), "is"),
+                            _ => (get_cfa_name(op), "at"),
+                        };
+                        writeln!(self.out, "  {}: {}{} {} cfa{:+}", name.unwrap_or(""),
+                                 if bad { "bad register: " } else { "" }, reg_name(reg),
+                                 relation, offset)?;
+                    }
+                    if !bad {
+                        frame.col_type[reg as usize] = match op {
+                            DW_CFA_val_offset | DW_CFA_val_offset_sf => Rule::ValOffset,
+                            _ => Rule::Offset,
+                        };
+                        frame.col_offset[reg as usize] = offset;
+                    }
+                }
+                DW_CFA_restore | DW_CFA_restore_extended => {
+                    let reg = if op == DW_CFA_restore {
+                        operand as u64
+                    } else {
+                        self.uleb(cursor, 32)?
+                    };
+                    let bad = reg >= frame.ncols();
+                    if !interp || bad {
+                        writeln!(self.out, "  {}: {}{}", get_cfa_name(op).unwrap_or(""),
+                                 if bad { "bad register: " } else { "" }, reg_name(reg))?;
+                    }
+                    if bad {
+                        continue;
+                    }
+                    let (rule, offset) = {
+                        let cie = cie.unwrap_or(frame);
+                        match cie.col_type.get(reg as usize) {
+                            None | Some(&Rule::Unreferenced) => (Rule::Undefined, 0),
+                            Some(&rule) => (rule, cie.col_offset[reg as usize]),
+                        }
+                    };
+                    frame.col_type[reg as usize] = rule;
+                    frame.col_offset[reg as usize] = offset;
+                }
+                DW_CFA_undefined | DW_CFA_same_value => {
+                    let reg = self.uleb(cursor, 32)?;
+                    let bad = reg >= frame.ncols();
+                    if !interp || bad {
+                        writeln!(self.out, "  {}: {}{}", get_cfa_name(op).unwrap_or(""),
+                                 if bad { "bad register: " } else { "" }, reg_name(reg))?;
+                    }
+                    if !bad {
+                        frame.col_type[reg as usize] = if op == DW_CFA_undefined {
+                            Rule::Undefined
+                        } else {
+                            Rule::SameValue
+                        };
+                        frame.col_offset[reg as usize] = 0;
+                    }
+                }
+                DW_CFA_register => {
+                    let reg = self.uleb(cursor, 32)?;
+                    let other = self.uleb(cursor, 64)?;
+                    let bad = reg >= frame.ncols();
+                    if !interp || bad {
+                        writeln!(self.out, "  DW_CFA_register: {}{} in {}",
+                                 if bad { "bad register: " } else { "" }, reg_name(reg),
+                                 reg_name(other))?;
+                    }
+                    if !bad {
+                        frame.col_type[reg as usize] = Rule::Register;
+                        frame.col_offset[reg as usize] = other as i64;
+                    }
+                }
+                DW_CFA_remember_state => {
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_remember_state")?;
+                    }
+                    self.remembered.push(frame.clone());
+                }
+                DW_CFA_restore_state => {
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_restore_state")?;
+                    }
+                    match self.remembered.pop() {
+                        Some(state) => {
+                            frame.cfa_offset = state.cfa_offset;
+                            frame.cfa_reg = state.cfa_reg;
+                            frame.ra = state.ra;
+                            frame.cfa_exp = state.cfa_exp;
+                            if state.ncols() > 0 && !self.need_space(frame, state.ncols() - 1)? {
+                                self.warn("Invalid column number in saved frame state")?;
+                                frame.col_type.clear();
+                                frame.col_offset.clear();
+                                continue;
+                            }
+                            let ncols = state.col_type.len();
+                            frame.col_type[..ncols].copy_from_slice(&state.col_type);
+                            frame.col_offset[..ncols].copy_from_slice(&state.col_offset);
+                        }
+                        None if interp => writeln!(self.out, "Mismatched DW_CFA_restore_state")?,
+                        None => {}
+                    }
+                }
+                DW_CFA_def_cfa | DW_CFA_def_cfa_sf => {
+                    frame.cfa_reg = self.uleb(cursor, 32)?;
+                    if op == DW_CFA_def_cfa {
+                        frame.cfa_offset = self.uleb(cursor, 64)?;
+                    } else {
+                        frame.cfa_offset = (self.sleb(cursor, 64)? as u64)
+                            .wrapping_mul(frame.data_factor as u64);
+                    }
+                    frame.cfa_exp = false;
+                    if !interp {
+                        let offset = if op == DW_CFA_def_cfa {
+                            frame.cfa_offset as i32 as i64
+                        } else {
+                            frame.cfa_offset as i64
+                        };
+                        writeln!(self.out, "  {}: {} ofs {}", get_cfa_name(op).unwrap_or(""),
+                                 reg_name(frame.cfa_reg), offset)?;
+                    }
+                }
+                DW_CFA_def_cfa_register => {
+                    frame.cfa_reg = self.uleb(cursor, 32)?;
+                    frame.cfa_exp = false;
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_def_cfa_register: {}", reg_name(frame.cfa_reg))?;
+                    }
+                }
+                DW_CFA_def_cfa_offset => {
+                    frame.cfa_offset = self.uleb(cursor, 64)?;
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_def_cfa_offset: {}", frame.cfa_offset as i32)?;
+                    }
+                }
+                DW_CFA_def_cfa_offset_sf => {
+                    frame.cfa_offset = (self.sleb(cursor, 64)? as u64)
+                        .wrapping_mul(frame.data_factor as u64);
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_def_cfa_offset_sf: {}", frame.cfa_offset as i64)?;
+                    }
+                }
+                DW_CFA_nop => {
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_nop")?;
+                    }
+                }
+                DW_CFA_def_cfa_expression => {
+                    let len = self.uleb(cursor, 64)?;
+                    if len > cursor.left() {
+                        writeln!(self.out, "  DW_CFA_def_cfa_expression: <corrupt len {}>", len)?;
+                        continue;
+                    }
+                    let expr = cursor.bytes(len);
+                    if !interp {
+                        let text = self.format_expression(frame, expr);
+                        writeln!(self.out, "  DW_CFA_def_cfa_expression ({})", text)?;
+                    }
+                    frame.cfa_exp = true;
+                }
+                DW_CFA_expression | DW_CFA_val_expression => {
+                    let reg = self.uleb(cursor, 32)?;
+                    let len = self.uleb(cursor, 64)?;
+                    let bad = reg >= frame.ncols();
+                    let name = get_cfa_name(op).unwrap_or("");
+                    if len > cursor.left() {
+                        writeln!(self.out, "  {}: <corrupt len {}>", name, len)?;
+                        continue;
+                    }
+                    let expr = cursor.bytes(len);
+                    if !interp || bad {
+                        let text = self.format_expression(frame, expr);
+                        writeln!(self.out, "  {}: {}{} ({})", name,
+                                 if bad { "bad register: " } else { "" }, reg_name(reg), text)?;
+                    }
+                    if !bad {
+                        frame.col_type[reg as usize] = if op == DW_CFA_expression {
+                            Rule::Expression
+                        } else {
+                            Rule::ValExpression
+                        };
+                    }
+                }
+                DW_CFA_GNU_window_save => {
+                    if !interp {
+                        if self.machine == ElfEhdrMachine::EM_AARCH64 {
+                            writeln!(self.out, "  DW_CFA_AARCH64_negate_ra_state")?;
+                        } else {
+                            writeln!(self.out, "  DW_CFA_GNU_window_save")?;
+                        }
+                    }
+                }
+                DW_CFA_GNU_args_size => {
+                    let size = self.uleb(cursor, 64)?;
+                    if !interp {
+                        writeln!(self.out, "  DW_CFA_GNU_args_size: {}", size)?;
+                    }
+                }
+                DW_CFA_lo_user..=DW_CFA_hi_user => {
+                    writeln!(self.out, "  DW_CFA_??? (User defined call frame op: {:#x})", op)?;
+                    cursor.pos = cursor.end;
+                }
+                _ => {
+                    self.warn(&format!(
+                        "Unsupported or unknown Dwarf Call Frame Instruction number: {:#x}", op))?;
+                    cursor.pos = cursor.end;
+                }
+            }
+        }
+        if interp && !all_nops {
+            self.print_row(frame, &mut need_col_headers)?;
+        }
+        Ok(())
+    }
+
+    fn format_expression(&self, frame: &Frame, expr: &[u8]) -> String {
+        let ctx = ExprContext {
+            endianness: self.endianness,
+            encoding: Encoding {
+                format: DwarfFormat::Dwarf32,
+                version: 0,
+                address_size: frame.ptr_size,
+            },
+            unit_offset: 0,
+            machine: self.machine,
+        };
+        format_expression(expr, &ctx).0
+    }
+
+    /// Prints the current row of the table of `frame`, after the column
+    /// headers if they haven't been yet.
+    fn print_row(&mut self, frame: &mut Frame, need_col_headers: &mut bool) -> io::Result<()> {
+        self.max_regs = frame.ncols();
+        let machine = self.machine;
+        if *need_col_headers {
+            *need_col_headers = false;
+            write!(self.out, "{:<width$} CFA      ", "   LOC", width = frame.ptr_size as usize * 2)?;
+            for (reg, &rule) in frame.col_type.iter().enumerate() {
+                if rule == Rule::Unreferenced {
+                    continue;
+                }
+                if reg as u64 == frame.ra {
+                    write!(self.out, "ra    ")?;
+                } else {
+                    write!(self.out, "{:<5} ", format_register(machine, reg as u64))?;
+                }
+            }
+            writeln!(self.out)?;
+        }
+
+        let cfa = if frame.cfa_exp {
+            "exp".to_string()
+        } else {
+            format!("{}{:+}", format_register(machine, frame.cfa_reg),
+                    frame.cfa_offset as i32)
+        };
+        write!(self.out, "{} {:<8} ", format_hex(frame.pc_begin, frame.ptr_size), cfa)?;
+        for (&rule, &offset) in frame.col_type.iter().zip(&frame.col_offset) {
+            let text = match rule {
+                Rule::Unreferenced => continue,
+                Rule::Undefined => "u".to_string(),
+                Rule::SameValue => "s".to_string(),
+                Rule::Offset => format!("c{:+}", offset),
+                Rule::ValOffset => format!("v{:+}", offset),
+                Rule::Register => format_numbered_register(machine, offset as u64),
+                Rule::Expression => "exp".to_string(),
+                Rule::ValExpression => "vexp".to_string(),
+            };
+            write!(self.out, "{:<5} ", text)?;
+        }
+        writeln!(self.out)
+    }
+}
+
+/// Prints the header of `data`, the contents of the `.eh_frame_hdr`
+/// section `name` of `elf` loaded at `address`, and its table. Checks
+/// that the table is sorted and that its entries point at FDEs of
+/// `.eh_frame` that start where they say, which the unwinder relies on.
+pub fn print_eh_frame_hdr(
+    elf: &ElfFile, name: &str, data: &[u8], address: u64, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    let address_size = if elf.is_32() { 4 } else { 8 };
+    writeln!(out, "Contents of the {} section:\n", name)?;
+    let hdr = match EhFrameHdr::parse(data, address, elf.endianness(), address_size) {
+        Ok(hdr) => hdr,
+        Err(err) => {
+            out.flush()?;
+            eprintln!("Warning: {}", err);
+            return Ok(());
+        }
+    };
+    writeln!(out, "  Version:               {}", hdr.version)?;
+    writeln!(out, "  .eh_frame pointer:     {} ({})", format_c_hex(hdr.eh_frame_ptr),
+             format_pointer_encoding(hdr.eh_frame_ptr_encoding))?;
+    writeln!(out, "  FDE count:             {} ({})", hdr.fde_count,
+             format_pointer_encoding(hdr.fde_count_encoding))?;
+    writeln!(out, "  Table encoding:        {}", format_pointer_encoding(hdr.table_encoding))?;
+    writeln!(out)?;
+
+    let table = match hdr.table() {
+        Ok(table) => table,
+        Err(err) => {
+            out.flush()?;
+            eprintln!("Warning: {}", err);
+            return Ok(());
+        }
+    };
+    let width = address_size as usize * 2;
+    writeln!(out, "  {:<width$} FDE", "Initial location", width = width.max(16))?;
+    for &(location, fde) in &table {
+        writeln!(out, "  {:<width$} {}", format_hex(location, address_size),
+                 format_hex(fde, address_size), width = width.max(16))?;
+    }
+    writeln!(out)?;
+    out.flush()?;
+
+    for (index, pair) in table.windows(2).enumerate() {
+        if pair[1].0 < pair[0].0 {
+            eprintln!("Warning: {}: entry {} is out of order", name, index + 1);
+        }
+    }
+    let eh_frame = elf.sections().ok()
+        .and_then(|shdrs| shdrs.iter().position(|s| s.get_addr() == hdr.eh_frame_ptr
+                                                 && s.get_name() == ".eh_frame"));
+    let eh_frame = match eh_frame {
+        Some(index) => index,
+        None => {
+            eprintln!("Warning: {}: no .eh_frame section at {}", name,
+                      format_c_hex(hdr.eh_frame_ptr));
+            return Ok(());
+        }
+    };
+    let eh_frame_data = match elf.debug_section_data_at(eh_frame) {
+        Ok((data, _)) => data,
+        Err(err) => {
+            eprintln!("Warning: .eh_frame: {}", err);
+            return Ok(());
+        }
+    };
+    let section = CfiSection::new(
+        &eh_frame_data, CfiKind::EhFrame, elf.endianness(), address_size, hdr.eh_frame_ptr);
+    let fde_count = section.entries()
+        .filter(|entry| matches!(entry, Ok(CfiEntry::Fde(_))))
+        .count() as u64;
+    if fde_count != hdr.fde_count {
+        eprintln!("Warning: {}: lists {} FDEs but .eh_frame has {}", name, hdr.fde_count,
+                  fde_count);
+    }
+    for (index, &(location, fde)) in table.iter().enumerate() {
+        match section.entry_at(fde.wrapping_sub(hdr.eh_frame_ptr)) {
+            Ok((CfiEntry::Fde(entry), _)) if entry.initial_location == location => {}
+            Ok((CfiEntry::Fde(entry), _)) => eprintln!(
+                "Warning: {}: entry {} is for {} but its FDE starts at {}", name, index,
+                format_c_hex(location), format_c_hex(entry.initial_location)),
+            _ => eprintln!(
+                "Warning: {}: entry {} points at {}, which isn't an FDE", name, index,
+                format_c_hex(fde)),
+        }
+    }
+    Ok(())
+}
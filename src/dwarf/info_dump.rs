@@ -0,0 +1,984 @@
+//! Printing of the units of `.debug_info` and `.debug_types` the way
+//! `readelf -wi` does: the unit headers, then every DIE with its
+//! attributes, their values and what readelf makes of them.
+
+#![allow(non_upper_case_globals)]
+
+use ::std::io::{self, Write};
+use ::elf::elf_ehdr_machine::ElfEhdrMachine;
+use ::elf::helpers::format_c_hex;
+use ::to_host::Endianness;
+use super::Dwarf;
+use super::abbrev::Abbrevs;
+use super::dw_at::*;
+use super::dw_form::*;
+use super::dw_op::{ExprContext, format_expression, DW_OP_addrx};
+use super::dw_tag::{get_tag_name, DW_TAG_entry_point, DW_TAG_subprogram};
+use super::dw_tag::{DW_TAG_lo_user, DW_TAG_hi_user};
+use super::error::DwarfError;
+use super::reader::{DwarfReader, DwarfFormat, Encoding};
+use super::unit::*;
+
+/// Prints the units of `data`, the contents of the `.debug_info` or
+/// `.debug_types` section `name`, like `readelf -wi`. The sections they
+/// refer to, e.g. `.debug_abbrev` and `.debug_str`, come from `dwarf`,
+/// and `machine` names the registers of their expressions.
+///
+/// Malformed data is warned about on stderr, after flushing `out` so that
+/// the warnings show up where they happen.
+pub fn print_debug_info(
+    dwarf: &Dwarf, name: &str, data: &[u8], machine: ElfEhdrMachine, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    let mut printer = Printer {
+        dwarf,
+        name,
+        data,
+        dwo: name.ends_with(".dwo"),
+        machine,
+        out,
+        have_frame_base: false,
+        bogus_end_warnings: 0,
+    };
+    printer.print()
+}
+
+/// What the DIEs of a unit need to be printed.
+struct Unit {
+    offset: u64,
+    end: u64,
+    encoding: Encoding,
+    abbrevs: Abbrevs,
+    str_offsets_base: u64,
+    addr_base: u64,
+    rnglists_base: u64,
+    loclists_base: u64,
+}
+
+struct Printer<'p, 'a: 'p> {
+    dwarf: &'p Dwarf<'a>,
+    name: &'p str,
+    data: &'p [u8],
+    /// Whether this is a split DWARF section, whose strings are in the
+    /// `.dwo` string sections.
+    dwo: bool,
+    machine: ElfEhdrMachine,
+    out: &'p mut dyn Write,
+    /// Whether the last function seen has a `DW_AT_frame_base`, which
+    /// readelf tracks across units.
+    have_frame_base: bool,
+    bogus_end_warnings: u32,
+}
+
+/// Reads an unsigned integer of `size` bytes at `offset` of `data` as
+/// leniently as readelf: only the bytes that are there are read, and
+/// none at all read as 0.
+fn get_uint(data: &[u8], offset: u64, size: u8, endianness: Endianness) -> u64 {
+    let start = offset.min(data.len() as u64) as usize;
+    let end = offset.saturating_add(size as u64).min(data.len() as u64) as usize;
+    let fold = |value, &byte| (value << 8) | byte as u64;
+    match endianness {
+        Endianness::LE => data[start..end].iter().rev().fold(0, fold),
+        Endianness::BE => data[start..end].iter().fold(0, fold),
+    }
+}
+
+impl<'p, 'a> Printer<'p, 'a> {
+    fn warn(&mut self, message: &str) -> io::Result<()> {
+        self.out.flush()?;
+        eprintln!("Warning: {}", message);
+        Ok(())
+    }
+
+    /// Checks the lengths of the units before printing anything, like
+    /// readelf does.
+    fn check_unit_lengths(&mut self) -> io::Result<bool> {
+        let e = self.dwarf.endianness;
+        let len = self.data.len() as u64;
+        let mut pos = 0u64;
+        while pos < len {
+            let mut length = get_uint(self.data, pos, 4, e);
+            pos += 4;
+            if length == 0xffff_ffff {
+                length = get_uint(self.data, pos, 8, e);
+                pos += 8;
+            } else if length >= 0xffff_fff0 {
+                let message = format!(
+                    "Reserved length value ({:#x}) found in section {}", length, self.name);
+                self.warn(&message)?;
+                return Ok(false);
+            }
+            let rest = len.saturating_sub(pos);
+            pos = pos.saturating_add(length);
+            if length > rest {
+                let message = format!(
+                    "Corrupt unit length (got {:#x} expected at most {:#x}) in section {}",
+                    length, rest, self.name);
+                self.warn(&message)?;
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn print(&mut self) -> io::Result<()> {
+        if !self.check_unit_lengths()? {
+            return Ok(());
+        }
+        writeln!(self.out, "Contents of the {} section:", self.name)?;
+        writeln!(self.out)?;
+
+        let e = self.dwarf.endianness;
+        let data = self.data;
+        let len = data.len() as u64;
+        let is_types_section = self.name.contains("debug_types");
+        let mut start = 0u64;
+        while start < len {
+            let offset = start;
+            let mut pos = offset;
+            let mut get = |size: u8| {
+                let value = get_uint(data, pos, size, e);
+                pos = pos.saturating_add(size as u64);
+                value
+            };
+            let mut length = get(4);
+            let format = if length == 0xffff_ffff {
+                length = get(8);
+                DwarfFormat::Dwarf64
+            } else {
+                DwarfFormat::Dwarf32
+            };
+            let offset_size = format.get_offset_size();
+            let version = get(2);
+            let mut is_types = is_types_section;
+            let (unit_type, mut address_size, abbrev_offset) = if version >= 5 {
+                let unit_type = get(1) as u8;
+                is_types = unit_type == DW_UT_type;
+                let address_size = get(1);
+                (unit_type, address_size, get(offset_size))
+            } else {
+                let abbrev_offset = get(offset_size);
+                (DW_UT_compile, get(1), abbrev_offset)
+            };
+            let type_signature = if is_types {
+                Some((get(8), get(offset_size)))
+            } else {
+                None
+            };
+            let dwo_id = match unit_type {
+                DW_UT_split_compile | DW_UT_skeleton => Some(get(8)),
+                _ => None,
+            };
+            let dies_offset = pos;
+
+            if !(2..=8).contains(&address_size) {
+                let message = format!(
+                    "Invalid pointer size ({}) in compunit header, using {} instead",
+                    address_size, offset_size);
+                self.warn(&message)?;
+                address_size = offset_size as u64;
+            }
+            let length_size = match format {
+                DwarfFormat::Dwarf32 => 4,
+                DwarfFormat::Dwarf64 => 12,
+            };
+            let end = offset.saturating_add(length_size).saturating_add(length);
+            if end > len {
+                let message = format!(
+                    concat!("Debug info is corrupted, length of CU at {:#x} extends beyond",
+                            " end of section (length = {:#x})"),
+                    offset, length);
+                self.warn(&message)?;
+                break;
+            }
+            start = end;
+            if !(2..=5).contains(&version) {
+                let message = format!(
+                    "CU at offset {:#x} contains corrupt or unsupported version number: {}.",
+                    offset, version);
+                self.warn(&message)?;
+                continue;
+            }
+            match unit_type {
+                DW_UT_compile | DW_UT_partial | DW_UT_type | DW_UT_split_compile
+                    | DW_UT_skeleton => {}
+                _ => {
+                    let message = format!(
+                        "CU at offset {:#x} contains corrupt or unsupported unit type: {}.",
+                        offset, unit_type);
+                    self.warn(&message)?;
+                    continue;
+                }
+            }
+            let abbrev_size = self.dwarf.debug_abbrev.len() as u64;
+            if abbrev_offset >= abbrev_size {
+                let message = format!(
+                    concat!("Debug info is corrupted, abbrev offset ({:#x}) is larger than",
+                            " abbrev section size ({:#x})"),
+                    abbrev_offset, abbrev_size);
+                self.warn(&message)?;
+                continue;
+            }
+
+            writeln!(self.out, "  Compilation Unit @ offset {}:", format_c_hex(offset))?;
+            writeln!(self.out, "   Length:        {} ({})", format_c_hex(length),
+                     if offset_size == 8 { "64-bit" } else { "32-bit" })?;
+            writeln!(self.out, "   Version:       {}", version)?;
+            if version >= 5 {
+                writeln!(self.out, "   Unit Type:     {} ({:x})",
+                         get_unit_type_name(unit_type).unwrap_or("unknown"), unit_type)?;
+            }
+            writeln!(self.out, "   Abbrev Offset: {}", format_c_hex(abbrev_offset))?;
+            writeln!(self.out, "   Pointer Size:  {}", address_size)?;
+            if let Some((signature, type_offset)) = type_signature {
+                writeln!(self.out, "   Signature:     {}", format_c_hex(signature))?;
+                writeln!(self.out, "   Type Offset:   {}", format_c_hex(type_offset))?;
+            }
+            if let Some(dwo_id) = dwo_id {
+                writeln!(self.out, "   DWO ID:        {}", format_c_hex(dwo_id))?;
+            }
+
+            let abbrevs = Abbrevs::read(&self.dwarf.debug_abbrev, abbrev_offset, e)
+                .unwrap_or_default();
+            let encoding = Encoding { format, version: version as u16, address_size: address_size as u8 };
+            let mut unit = Unit {
+                offset,
+                end,
+                encoding,
+                abbrevs,
+                str_offsets_base: 0,
+                addr_base: 0,
+                rnglists_base: 0,
+                loclists_base: 0,
+            };
+            self.read_unit_bases(&mut unit, dies_offset);
+            if !self.print_dies(&unit, dies_offset)? {
+                return Ok(());
+            }
+        }
+        writeln!(self.out)
+    }
+
+    /// Finds the attributes that tell where the unit's entries of the
+    /// string offsets, address and list sections start. readelf knows
+    /// them before it prints the DIEs, so they apply to the attributes
+    /// that come before them too.
+    fn read_unit_bases(&self, unit: &mut Unit, dies_offset: u64) {
+        let data = &self.data[..unit.end as usize];
+        let mut reader = match DwarfReader::at(data, dies_offset, self.dwarf.endianness, "unit") {
+            Ok(reader) => reader,
+            Err(_) => return,
+        };
+        while !reader.is_empty() {
+            let die = match Die::read(&mut reader, unit.encoding, &unit.abbrevs) {
+                Ok(Some(die)) => die,
+                Ok(None) => continue,
+                Err(_) => break,
+            };
+            for attr in &die.attrs {
+                let value = match attr.value.get_udata() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match attr.name {
+                    DW_AT_str_offsets_base => unit.str_offsets_base = value,
+                    DW_AT_addr_base | DW_AT_GNU_addr_base => unit.addr_base = value,
+                    DW_AT_rnglists_base => unit.rnglists_base = value,
+                    DW_AT_loclists_base => unit.loclists_base = value,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Prints the DIEs of `unit`. Returns `false` if one of them refers to
+    /// a missing abbreviation, which ends the dump of the section.
+    fn print_dies(&mut self, unit: &Unit, dies_offset: u64) -> io::Result<bool> {
+        let data = self.data;
+        let section_end = data.len() as u64;
+        let unit_data = &data[..unit.end as usize];
+        let mut reader = DwarfReader::at(unit_data, dies_offset, self.dwarf.endianness, "unit")
+            .expect("the DIEs start within the unit");
+        let mut level: i64 = 0;
+        while !reader.is_empty() {
+            let die_offset = reader.get_pos();
+            let code = match reader.uleb128() {
+                Ok(code) => code,
+                Err(_) => break,
+            };
+            if code == 0 {
+                // The last unit may be padded with zeros up to the end of
+                // the section.
+                if level == 0 && unit.end == section_end
+                    && unit_data[reader.get_pos() as usize..].iter().all(|&b| b == 0)
+                {
+                    break;
+                }
+                writeln!(self.out, " <{}><{:x}>: Abbrev Number: 0", level, die_offset)?;
+                level -= 1;
+                if level < 0 && self.bogus_end_warnings < 3 {
+                    let message = format!(
+                        "Bogus end-of-siblings marker detected at offset {:x} in {} section",
+                        die_offset, self.name);
+                    self.warn(&message)?;
+                    self.bogus_end_warnings += 1;
+                    if self.bogus_end_warnings == 3 {
+                        self.warn("Further warnings about bogus end-of-sibling markers suppressed")?;
+                    }
+                }
+                continue;
+            }
+
+            write!(self.out, " <{}><{:x}>: Abbrev Number: {}", level, die_offset, code)?;
+            let abbrev = match unit.abbrevs.get(code) {
+                Some(abbrev) => abbrev,
+                None => {
+                    writeln!(self.out)?;
+                    let message = format!(
+                        "DIE at offset {:#x} refers to abbreviation number {} which does not exist",
+                        die_offset, code);
+                    self.warn(&message)?;
+                    return Ok(false);
+                }
+            };
+            writeln!(self.out, " ({})", format_tag(abbrev.tag))?;
+            if abbrev.tag == DW_TAG_subprogram || abbrev.tag == DW_TAG_entry_point {
+                self.have_frame_base = false;
+            }
+
+            for spec in &abbrev.attrs {
+                write!(self.out, "    <{:x}>", reader.get_pos())?;
+                write!(self.out, "   {:<18}:", format_attr_name(spec.name))?;
+                let ok = self.print_attr(unit, spec.name, spec.form, spec.implicit_const,
+                                         &mut reader)?;
+                writeln!(self.out)?;
+                if !ok {
+                    return Ok(true);
+                }
+            }
+            if abbrev.has_children {
+                level += 1;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Prints the value of an attribute `name` of `form` at the position of
+    /// `reader`, and what readelf says about it. Returns `false` if it
+    /// couldn't be read, which ends the unit.
+    fn print_attr(
+        &mut self, unit: &Unit, name: u64, mut form: u64, mut implicit_const: i64,
+        reader: &mut DwarfReader)
+        -> io::Result<bool>
+    {
+        let value_offset = reader.get_pos();
+        while form == DW_FORM_indirect {
+            form = match reader.uleb128() {
+                Ok(form) => form,
+                Err(err) => {
+                    self.warn(&err.to_string())?;
+                    return Ok(false);
+                }
+            };
+            write!(self.out, " {}", format_form_name(form))?;
+            if form == DW_FORM_implicit_const {
+                implicit_const = match reader.sleb128() {
+                    Ok(value) => value,
+                    Err(err) => {
+                        self.warn(&err.to_string())?;
+                        return Ok(false);
+                    }
+                };
+            }
+        }
+
+        let value = match read_attr_value(reader, form, implicit_const, unit.encoding) {
+            Ok(value) => value,
+            Err(DwarfError::UnknownForm(form)) => {
+                self.warn(&format!("Unrecognized form: {:#x}", form))?;
+                // What to do? readelf consumes a byte.
+                let _ = reader.skip(1);
+                return Ok(true);
+            }
+            Err(err) => {
+                self.warn(&err.to_string())?;
+                return Ok(false);
+            }
+        };
+
+        let mut block = None;
+        let uvalue = match value {
+            AttrValue::Addr(value) | AttrValue::Addrx(value) | AttrValue::Data(value)
+                | AttrValue::Strp(value) | AttrValue::LineStrp(value) | AttrValue::Strx(value)
+                | AttrValue::StrpSup(value) | AttrValue::Ref(value) | AttrValue::RefAddr(value)
+                | AttrValue::RefSup(value) | AttrValue::RefSig8(value)
+                | AttrValue::SecOffset(value) | AttrValue::Loclistx(value)
+                | AttrValue::Rnglistx(value) => value,
+            AttrValue::Sdata(value) => value as u64,
+            AttrValue::Flag(value) => value as u64,
+            AttrValue::Block(bytes) | AttrValue::Exprloc(bytes) => {
+                block = Some(bytes);
+                bytes.len() as u64
+            }
+            AttrValue::Data16(_) | AttrValue::String(_) => 0,
+        };
+        self.print_value(unit, form, value)?;
+        self.print_annotation(unit, name, form, uvalue, block, value_offset)?;
+        Ok(true)
+    }
+
+    fn print_value(&mut self, unit: &Unit, form: u64, value: AttrValue) -> io::Result<()> {
+        let e = self.dwarf.endianness;
+        let hex = format_c_hex;
+        match value {
+            AttrValue::Addr(address) => write!(self.out, " {}", hex(address)),
+            AttrValue::Addrx(index) => {
+                let size = unit.encoding.address_size;
+                let offset = unit.addr_base.wrapping_add(index.wrapping_mul(size as u64));
+                let debug_addr = &self.dwarf.debug_addr;
+                let address = if debug_addr.is_empty() {
+                    self.warn("Cannot fetch indexed address: the .debug_addr section is missing")?;
+                    0
+                } else if offset.checked_add(size as u64).is_none_or(|end| end > debug_addr.len() as u64) {
+                    let message = format!(
+                        "Offset into section .debug_addr too big: {}", hex(offset));
+                    self.warn(&message)?;
+                    0
+                } else {
+                    get_uint(debug_addr, offset, size, e)
+                };
+                write!(self.out, " (index: {}): {}", hex(index), hex(address))
+            }
+            // readelf leaves the bytes out when the annotation shows the
+            // address an expression of `DW_OP_addrx` fetches.
+            AttrValue::Exprloc(bytes) if bytes.first() == Some(&DW_OP_addrx) => Ok(()),
+            AttrValue::Block(bytes) | AttrValue::Exprloc(bytes) => {
+                write!(self.out, " {} byte block: ", bytes.len())?;
+                for byte in bytes {
+                    write!(self.out, "{:x} ", byte)?;
+                }
+                Ok(())
+            }
+            AttrValue::Data(value) => match form {
+                DW_FORM_data4 | DW_FORM_data8 => write!(self.out, " {}", hex(value)),
+                _ => write!(self.out, " {}", value),
+            },
+            AttrValue::Data16(bytes) => {
+                let (low, high) = match e {
+                    Endianness::LE => (get_uint(bytes, 0, 8, e), get_uint(bytes, 8, 8, e)),
+                    Endianness::BE => (get_uint(bytes, 8, 8, e), get_uint(bytes, 0, 8, e)),
+                };
+                if high != 0 {
+                    write!(self.out, " {}{:016x}", hex(high), low)
+                } else {
+                    write!(self.out, " {}", hex(low))
+                }
+            }
+            AttrValue::Sdata(value) => write!(self.out, " {}", value),
+            AttrValue::Flag(value) => write!(self.out, " {}", value as u8),
+            AttrValue::String(string) => {
+                self.out.write_all(b" ")?;
+                self.out.write_all(string)
+            }
+            AttrValue::Strp(offset) => {
+                write!(self.out, " (indirect string, offset: {}): ", hex(offset))?;
+                let string = self.fetch_string(offset, false)?;
+                self.out.write_all(&string)
+            }
+            AttrValue::LineStrp(offset) => {
+                write!(self.out, " (indirect line string, offset: {}): ", hex(offset))?;
+                let string = self.fetch_string(offset, true)?;
+                self.out.write_all(&string)
+            }
+            AttrValue::Strx(index) => {
+                write!(self.out, " (indexed string: {}): ", hex(index))?;
+                let string = self.fetch_indexed_string(unit, index)?;
+                self.out.write_all(&string)
+            }
+            AttrValue::StrpSup(offset) => {
+                if form == DW_FORM_GNU_strp_alt {
+                    write!(self.out, " (alt indirect string, offset: {}) <no links available>",
+                           hex(offset))
+                } else {
+                    write!(self.out, " <{}>", hex(offset.wrapping_add(unit.offset)))
+                }
+            }
+            AttrValue::Ref(offset) => {
+                let offset = offset.wrapping_add(unit.offset);
+                if form == DW_FORM_ref8 {
+                    write!(self.out, " {}", hex(offset))
+                } else {
+                    write!(self.out, " <{}>", hex(offset))
+                }
+            }
+            AttrValue::RefAddr(offset) => write!(self.out, " <{}>", hex(offset)),
+            AttrValue::RefSup(offset) => match form {
+                DW_FORM_ref_sup4 => write!(self.out, " <{}>", hex(offset.wrapping_add(unit.offset))),
+                DW_FORM_GNU_ref_alt => write!(self.out, " <alt {}>", hex(offset)),
+                _ => write!(self.out, " {}", hex(offset)),
+            },
+            AttrValue::RefSig8(signature) => write!(self.out, " signature: {}", hex(signature)),
+            AttrValue::SecOffset(offset) => write!(self.out, " {}", hex(offset)),
+            AttrValue::Loclistx(index) => {
+                self.print_list_index(unit, ".debug_loclists", index, unit.loclists_base)
+            }
+            AttrValue::Rnglistx(index) => {
+                self.print_list_index(unit, ".debug_rnglists", index, unit.rnglists_base)
+            }
+        }
+    }
+
+    /// Prints the offset of the list at `index` of `.debug_loclists` or
+    /// `.debug_rnglists`. Split units usually have no base attribute, and
+    /// then their offsets follow the section header.
+    fn print_list_index(&mut self, unit: &Unit, name: &str, index: u64, base: u64)
+        -> io::Result<()>
+    {
+        let base = if self.dwo && base == 0 {
+            match unit.encoding.format {
+                DwarfFormat::Dwarf32 => 12,
+                DwarfFormat::Dwarf64 => 20,
+            }
+        } else {
+            base
+        };
+        if let Some(offset) = self.fetch_list_offset(unit, name, index, base)? {
+            write!(self.out, " (index: {}): {}", format_c_hex(index), format_c_hex(offset))?;
+            if self.dwo {
+                // readelf goes on to look for the list in the section of
+                // the skeleton unit, which isn't in the file.
+                self.warn(&format!("Unable to locate {} section", name))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the string at `offset` of `.debug_str` or of
+    /// `.debug_line_str`, or what readelf shows instead if it's not there.
+    fn fetch_string(&mut self, offset: u64, line_str: bool) -> io::Result<Vec<u8>> {
+        let (section, name, form) = if line_str {
+            (&self.dwarf.debug_line_str, ".debug_line_str", "DW_FORM_line_strp")
+        } else {
+            (&self.dwarf.debug_str, ".debug_str", "DW_FORM_strp")
+        };
+        if section.is_empty() {
+            return Ok(format!("<no {} section>", name).into_bytes());
+        }
+        if offset >= section.len() as u64 {
+            let message = format!("{} offset too big: {}", form, format_c_hex(offset));
+            self.warn(&message)?;
+            return Ok(b"<offset is too big>".to_vec());
+        }
+        let rest = &section[offset as usize..];
+        Ok(match rest.iter().position(|&b| b == 0) {
+            Some(len) => rest[..len].to_vec(),
+            None => format!("<no NUL byte at end of {} section>", name).into_bytes(),
+        })
+    }
+
+    /// Returns the string at `index` of the unit's string offsets, or what
+    /// readelf shows instead if it's not there.
+    fn fetch_indexed_string(&mut self, unit: &Unit, index: u64) -> io::Result<Vec<u8>> {
+        let suffix = if self.dwo { ".dwo" } else { "" };
+        let offsets = &self.dwarf.debug_str_offsets;
+        let strings = &self.dwarf.debug_str;
+        if offsets.is_empty() {
+            return Ok(format!("<no .debug_str_offsets{} section>", suffix).into_bytes());
+        }
+        if strings.is_empty() {
+            return Ok(format!("<no .debug_str{} section>", suffix).into_bytes());
+        }
+        let offset_size = unit.encoding.format.get_offset_size();
+        let index_offset = index.checked_mul(offset_size as u64)
+            .and_then(|offset| offset.checked_add(unit.str_offsets_base));
+        let index_offset = match index_offset {
+            Some(offset) if offset.saturating_add(offset_size as u64) <= offsets.len() as u64 => {
+                offset
+            }
+            offset => {
+                // readelf leaves the newline out of this one.
+                self.out.flush()?;
+                eprint!(
+                    "Warning: string index of {} converts to an offset of {} which is too big for section .debug_str{}",
+                    index, format_c_hex(offset.unwrap_or(0)), suffix);
+                return Ok(b"<string index too big>".to_vec());
+            }
+        };
+        let str_offset = get_uint(offsets, index_offset, offset_size, self.dwarf.endianness);
+        if str_offset >= strings.len() as u64 {
+            let message = format!("indirect offset too big: {}", format_c_hex(str_offset));
+            self.warn(&message)?;
+            return Ok(b"<indirect index offset is too big>".to_vec());
+        }
+        let rest = &strings[str_offset as usize..];
+        Ok(match rest.iter().position(|&b| b == 0) {
+            Some(len) => rest[..len].to_vec(),
+            None => b"<no NUL byte at end of section>".to_vec(),
+        })
+    }
+
+    /// Returns the offset of the list at `index` of the offsets at `base`
+    /// of `.debug_loclists` or `.debug_rnglists`, or `None` if it's not
+    /// there.
+    fn fetch_list_offset(&mut self, unit: &Unit, name: &str, index: u64, base: u64)
+        -> io::Result<Option<u64>>
+    {
+        let section = if name == ".debug_loclists" {
+            &self.dwarf.debug_loclists
+        } else {
+            &self.dwarf.debug_rnglists
+        };
+        let suffix = if self.dwo { ".dwo" } else { "" };
+        if section.is_empty() {
+            self.warn(&format!("Unable to locate {}{} section", name, suffix))?;
+            return Ok(None);
+        }
+        let offset_size = unit.encoding.format.get_offset_size();
+        let offset = base.wrapping_add(index.wrapping_mul(offset_size as u64));
+        if offset.checked_add(offset_size as u64).is_none_or(|end| end > section.len() as u64) {
+            let message = format!(
+                "Offset into section {}{} too big: {}", name, suffix, format_c_hex(offset));
+            self.warn(&message)?;
+            return Ok(None);
+        }
+        let list_offset = get_uint(section, offset, offset_size, self.dwarf.endianness);
+        Ok(Some(base.wrapping_add(list_offset)))
+    }
+
+    /// Prints what readelf says about the value of some attributes: what
+    /// their constants stand for, and their location expressions.
+    fn print_annotation(
+        &mut self, unit: &Unit, name: u64, form: u64, uvalue: u64, block: Option<&[u8]>,
+        value_offset: u64)
+        -> io::Result<()>
+    {
+        match name {
+            DW_AT_inline => {
+                write!(self.out, "\t")?;
+                match uvalue {
+                    0 => write!(self.out, "(not inlined)"),
+                    1 => write!(self.out, "(inlined)"),
+                    2 => write!(self.out, "(declared as inline but ignored)"),
+                    3 => write!(self.out, "(declared as inline and inlined)"),
+                    _ => write!(self.out, "  (Unknown inline attribute value: {})",
+                                format_c_hex(uvalue)),
+                }
+            }
+            DW_AT_language => write!(self.out, "\t{}", format_language(uvalue)),
+            DW_AT_encoding => write!(self.out, "\t{}", format_encoding(uvalue)),
+            DW_AT_accessibility => write!(self.out, "\t{}", match uvalue {
+                1 => "(public)",
+                2 => "(protected)",
+                3 => "(private)",
+                _ => "(unknown accessibility)",
+            }),
+            DW_AT_visibility => write!(self.out, "\t{}", match uvalue {
+                1 => "(local)",
+                2 => "(exported)",
+                3 => "(qualified)",
+                _ => "(unknown visibility)",
+            }),
+            DW_AT_endianity => write!(self.out, "\t{}", match uvalue {
+                0 => "(default)",
+                1 => "(big)",
+                2 => "(little)",
+                0x40..=0xff => "(user specified)",
+                _ => "(unknown endianity)",
+            }),
+            DW_AT_virtuality => write!(self.out, "\t{}", match uvalue {
+                0 => "(none)",
+                1 => "(virtual)",
+                2 => "(pure_virtual)",
+                _ => "(unknown virtuality)",
+            }),
+            DW_AT_identifier_case => write!(self.out, "\t{}", match uvalue {
+                0 => "(case_sensitive)",
+                1 => "(up_case)",
+                2 => "(down_case)",
+                3 => "(case_insensitive)",
+                _ => "(unknown case)",
+            }),
+            DW_AT_calling_convention => write!(self.out, "\t{}", match uvalue {
+                1 => "(normal)",
+                2 => "(program)",
+                3 => "(nocall)",
+                4 => "(pass by ref)",
+                5 => "(pass by value)",
+                0x40 => "(Rensas SH)",
+                0x41 => "(Borland fastcall i386)",
+                0x42..=0xff => "(user defined)",
+                _ => "(unknown convention)",
+            }),
+            DW_AT_ordering => write!(self.out, "\t{}", match uvalue {
+                0xff | 0xffff_ffff_ffff_ffff => "(undefined)",
+                0 => "(row major)",
+                1 => "(column major)",
+                _ => "",
+            }),
+            DW_AT_decimal_sign => write!(self.out, "\t{}", match uvalue {
+                1 => "(unsigned)",
+                2 => "(leading overpunch)",
+                3 => "(trailing overpunch)",
+                4 => "(leading separate)",
+                5 => "(trailing separate)",
+                _ => "(unrecognised)",
+            }),
+            DW_AT_defaulted => write!(self.out, "\t{}", match uvalue {
+                0 => "(no)",
+                1 => "(in class)",
+                2 => "(out of class)",
+                _ => "(unrecognised)",
+            }),
+            DW_AT_discr_list => {
+                write!(self.out, "\t")?;
+                self.print_discr_list(form, uvalue, block)
+            }
+            DW_AT_frame_base | DW_AT_location | DW_AT_loclists_base | DW_AT_rnglists_base
+                | DW_AT_str_offsets_base | DW_AT_string_length | DW_AT_return_addr
+                | DW_AT_data_member_location | DW_AT_vtable_elem_location | DW_AT_segment
+                | DW_AT_static_link | DW_AT_use_location | DW_AT_call_value
+                | DW_AT_GNU_call_site_value | DW_AT_call_data_value
+                | DW_AT_GNU_call_site_data_value | DW_AT_call_target
+                | DW_AT_GNU_call_site_target | DW_AT_call_target_clobbered
+                | DW_AT_GNU_call_site_target_clobbered => {
+                if name == DW_AT_frame_base {
+                    self.have_frame_base = true;
+                }
+                let is_list = (unit.encoding.version < 4
+                               && (form == DW_FORM_data4 || form == DW_FORM_data8))
+                    || form == DW_FORM_sec_offset || form == DW_FORM_loclistx;
+                if is_list && name != DW_AT_rnglists_base && name != DW_AT_str_offsets_base {
+                    write!(self.out, " (location list)")?;
+                }
+                self.print_expression(unit, block, true)
+            }
+            DW_AT_allocated | DW_AT_associated | DW_AT_data_location | DW_AT_byte_stride
+                | DW_AT_upper_bound | DW_AT_lower_bound | DW_AT_rank => {
+                self.print_expression(unit, block, true)
+            }
+            DW_AT_data_bit_offset | DW_AT_byte_size | DW_AT_bit_size
+                | DW_AT_string_length_byte_size | DW_AT_string_length_bit_size
+                | DW_AT_bit_stride if form == DW_FORM_exprloc => {
+                self.print_expression(unit, block, false)
+            }
+            DW_AT_import => self.print_import(unit, form, uvalue, value_offset),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes the location expression of a block value.
+    fn print_expression(&mut self, unit: &Unit, block: Option<&[u8]>, check_frame_base: bool)
+        -> io::Result<()>
+    {
+        let expr = match block {
+            Some(expr) => expr,
+            None => return Ok(()),
+        };
+        let ctx = ExprContext {
+            endianness: self.dwarf.endianness,
+            encoding: unit.encoding,
+            unit_offset: unit.offset,
+            machine: self.machine,
+        };
+        let (text, needs_frame_base) = format_expression(expr, &ctx);
+        write!(self.out, "\t({})", text)?;
+        if check_frame_base && needs_frame_base && !self.have_frame_base {
+            write!(self.out, " [without DW_AT_frame_base]")?;
+        }
+        Ok(())
+    }
+
+    /// Prints the labels and ranges of the values of a variant.
+    fn print_discr_list(&mut self, form: u64, uvalue: u64, block: Option<&[u8]>)
+        -> io::Result<()>
+    {
+        if uvalue == 0 {
+            return write!(self.out, "[default]");
+        }
+        let list = match (form, block) {
+            (DW_FORM_block | DW_FORM_block1 | DW_FORM_block2 | DW_FORM_block4, Some(list)) => list,
+            _ => {
+                writeln!(self.out, "<corrupt>")?;
+                return self.warn("corrupt discr_list - not using a block form");
+            }
+        };
+        let mut reader = DwarfReader::new(list, self.dwarf.endianness, "discr_list");
+        write!(self.out, "(")?;
+        while !reader.is_empty() {
+            let discriminant = reader.u8().unwrap_or(0);
+            match discriminant {
+                0 => write!(self.out, "label {}", reader.uleb128().unwrap_or(0))?,
+                1 => {
+                    let low = reader.uleb128().unwrap_or(0);
+                    write!(self.out, "range {}..{}", low, reader.uleb128().unwrap_or(0))?;
+                }
+                _ => {
+                    writeln!(self.out, "<corrupt>")?;
+                    let message = format!(
+                        "corrupt discr_list - unrecognized discriminant byte {:#x}",
+                        discriminant);
+                    return self.warn(&message);
+                }
+            }
+            if !reader.is_empty() {
+                write!(self.out, ", ")?;
+            }
+        }
+        write!(self.out, ")(unsigned)")
+    }
+
+    /// Prints the abbreviation and tag of the DIE that a `DW_AT_import`
+    /// refers to.
+    fn print_import(&mut self, unit: &Unit, form: u64, uvalue: u64, value_offset: u64)
+        -> io::Result<()>
+    {
+        let e = self.dwarf.endianness;
+        let target = match form {
+            DW_FORM_ref1 | DW_FORM_ref2 | DW_FORM_ref4 | DW_FORM_ref8 | DW_FORM_ref_udata => {
+                let offset = unit.offset.wrapping_add(uvalue);
+                let mut reader = DwarfReader::at(self.data, offset, e, "unit").ok();
+                reader.as_mut().and_then(|r| r.uleb128().ok())
+                    .and_then(|code| unit.abbrevs.get(code))
+                    .map(|abbrev| (abbrev.code, abbrev.tag))
+            }
+            DW_FORM_ref_addr => {
+                let debug_info = &self.dwarf.debug_info;
+                let header = UnitHeader::read_all(debug_info, e).ok()
+                    .and_then(|units| units.into_iter()
+                              .find(|u| u.get_offset() <= uvalue && uvalue < u.get_end()));
+                header.and_then(|header| {
+                    let abbrevs = self.dwarf.abbrevs(&header).ok()?;
+                    let code = DwarfReader::at(debug_info, uvalue, e, ".debug_info").ok()?
+                        .uleb128().ok()?;
+                    abbrevs.get(code).map(|abbrev| (abbrev.code, abbrev.tag))
+                })
+            }
+            DW_FORM_GNU_ref_alt => return Ok(()),
+            _ => None,
+        };
+        match target {
+            Some((code, tag)) => {
+                write!(self.out, "\t[Abbrev Number: {} ({})]", code, format_tag(tag))
+            }
+            None => {
+                let message = format!(
+                    "Offset {} used as value for DW_AT_import attribute of DIE at offset {:#x} is too big.",
+                    format_c_hex(uvalue), value_offset);
+                self.warn(&message)
+            }
+        }
+    }
+}
+
+fn format_tag(tag: u64) -> String {
+    match get_tag_name(tag) {
+        Some(name) => name.to_string(),
+        None if (DW_TAG_lo_user..=DW_TAG_hi_user).contains(&tag) => {
+            format!("User TAG value: {:#x}", tag)
+        }
+        None => format!("Unknown TAG value: {:#x}", tag),
+    }
+}
+
+fn format_attr_name(name: u64) -> String {
+    match name {
+        0 => "DW_AT value: 0".to_string(),
+        // The MIPS and HP extensions share this one.
+        DW_AT_MIPS_fde => "DW_AT_MIPS_fde or DW_AT_HP_unmodifiable".to_string(),
+        _ => match get_at_name(name) {
+            Some(name) => name.to_string(),
+            None => format!("Unknown AT value: {:x}", name),
+        },
+    }
+}
+
+fn format_form_name(form: u64) -> String {
+    match form {
+        0 => "DW_FORM value: 0".to_string(),
+        _ => match get_form_name(form) {
+            Some(name) => name.to_string(),
+            None => format!("Unknown FORM value: {:x}", form),
+        },
+    }
+}
+
+/// Describes a `DW_LANG_*` value like readelf.
+fn format_language(lang: u64) -> String {
+    let name = match lang {
+        0x0001 => "ANSI C",
+        0x0002 => "non-ANSI C",
+        0x0003 => "Ada",
+        0x0004 => "C++",
+        0x0005 => "Cobol 74",
+        0x0006 => "Cobol 85",
+        0x0007 => "FORTRAN 77",
+        0x0008 => "Fortran 90",
+        0x0009 => "ANSI Pascal",
+        0x000a => "Modula 2",
+        0x000b => "Java",
+        0x000c => "ANSI C99",
+        0x000d => "ADA 95",
+        0x000e => "Fortran 95",
+        0x000f => "PLI",
+        0x0010 => "Objective C",
+        0x0011 => "Objective C++",
+        0x0012 => "Unified Parallel C",
+        0x0013 => "D",
+        0x0014 => "Python",
+        0x0015 => "OpenCL",
+        0x0016 => "Go",
+        0x0017 => "Modula 3",
+        0x0018 => "Haskell",
+        0x0019 => "C++03",
+        0x001a => "C++11",
+        0x001b => "OCaml",
+        0x001c => "Rust",
+        0x001d => "C11",
+        0x001e => "Swift",
+        0x001f => "Julia",
+        0x0020 => "Dylan",
+        0x0021 => "C++14",
+        0x0022 => "Fortran 03",
+        0x0023 => "Fortran 08",
+        0x0024 => "RenderScript",
+        0x8001 => "MIPS assembler",
+        0x8765 => "Unified Parallel C",
+        0x8000..=0xffff => return format!("(implementation defined: {:#x})", lang),
+        _ => return format!("(unknown: {})", format_c_hex(lang)),
+    };
+    format!("({})", name)
+}
+
+/// Describes a `DW_ATE_*` value like readelf.
+fn format_encoding(encoding: u64) -> &'static str {
+    match encoding {
+        0x00 => "(void)",
+        0x01 => "(machine address)",
+        0x02 => "(boolean)",
+        0x03 => "(complex float)",
+        0x04 => "(float)",
+        0x05 => "(signed)",
+        0x06 => "(signed char)",
+        0x07 => "(unsigned)",
+        0x08 => "(unsigned char)",
+        0x09 => "(imaginary float)",
+        0x0a => "(packed_decimal)",
+        0x0b => "(numeric_string)",
+        0x0c => "(edited)",
+        0x0d => "(signed_fixed)",
+        0x0e => "(unsigned_fixed)",
+        0x0f => "(decimal float)",
+        0x10 => "(unicode string)",
+        0x11 => "(UCS)",
+        0x12 => "(ASCII)",
+        0x80 => "(HP_float80)",
+        0x81 => "(HP_complex_float80)",
+        0x82 => "(HP_float128)",
+        0x83 => "(HP_complex_float128)",
+        0x84 => "(HP_floathpintel)",
+        0x85 => "(HP_imaginary_float80)",
+        0x86 => "(HP_imaginary_float128)",
+        0x87..=0xff => "(user defined type)",
+        _ => "(unknown type)",
+    }
+}
@@ -0,0 +1,375 @@
+//! Line number programs of `.debug_line`, which map addresses to source
+//! lines.
+
+#![allow(non_upper_case_globals)]
+
+use ::to_host::Endianness;
+use super::dw_form::{AttrValue, read_attr_value};
+use super::error::DwarfError;
+use super::reader::{DwarfReader, DwarfFormat, Encoding, get_str_at};
+
+pub const DW_LNS_copy: u8 = 0x01;
+pub const DW_LNS_advance_pc: u8 = 0x02;
+pub const DW_LNS_advance_line: u8 = 0x03;
+pub const DW_LNS_set_file: u8 = 0x04;
+pub const DW_LNS_set_column: u8 = 0x05;
+pub const DW_LNS_negate_stmt: u8 = 0x06;
+pub const DW_LNS_set_basic_block: u8 = 0x07;
+pub const DW_LNS_const_add_pc: u8 = 0x08;
+pub const DW_LNS_fixed_advance_pc: u8 = 0x09;
+pub const DW_LNS_set_prologue_end: u8 = 0x0a;
+pub const DW_LNS_set_epilogue_begin: u8 = 0x0b;
+pub const DW_LNS_set_isa: u8 = 0x0c;
+
+pub const DW_LNE_end_sequence: u8 = 0x01;
+pub const DW_LNE_set_address: u8 = 0x02;
+pub const DW_LNE_define_file: u8 = 0x03;
+pub const DW_LNE_set_discriminator: u8 = 0x04;
+
+pub const DW_LNCT_path: u64 = 0x1;
+pub const DW_LNCT_directory_index: u64 = 0x2;
+pub const DW_LNCT_timestamp: u64 = 0x3;
+pub const DW_LNCT_size: u64 = 0x4;
+pub const DW_LNCT_MD5: u64 = 0x5;
+
+/// An entry of the file name table of a line program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry<'a> {
+    pub path: &'a [u8],
+    pub directory_index: u64,
+    pub timestamp: u64,
+    pub size: u64,
+    pub md5: Option<&'a [u8]>,
+}
+
+/// The header of a line number program.
+#[derive(Debug, Clone)]
+pub struct LineProgramHeader<'a> {
+    pub offset: u64,
+    pub unit_length: u64,
+    pub encoding: Encoding,
+    pub segment_selector_size: u8,
+    pub header_length: u64,
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    /// The number of arguments of the standard opcodes from 1 up.
+    pub standard_opcode_lengths: Vec<u8>,
+    /// Directory 0 is the compilation directory from DWARF 5 on; before,
+    /// it isn't listed and the first directory is numbered 1.
+    pub include_directories: Vec<&'a [u8]>,
+    /// File 0 is the primary source file from DWARF 5 on; before, it isn't
+    /// listed and the first file is numbered 1.
+    pub file_names: Vec<FileEntry<'a>>,
+}
+
+/// A line number program: its header and the opcodes that follow it.
+#[derive(Debug, Clone)]
+pub struct LineProgram<'a> {
+    header: LineProgramHeader<'a>,
+    program: DwarfReader<'a>,
+}
+
+/// A row of the line number matrix, i.e. the state of the line program
+/// registers when a row is emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+    pub op_index: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    pub basic_block: bool,
+    pub end_sequence: bool,
+    pub prologue_end: bool,
+    pub epilogue_begin: bool,
+    pub isa: u64,
+    pub discriminator: u64,
+}
+
+impl LineRow {
+    fn new(default_is_stmt: bool) -> LineRow {
+        LineRow {
+            address: 0,
+            op_index: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+            basic_block: false,
+            end_sequence: false,
+            prologue_end: false,
+            epilogue_begin: false,
+            isa: 0,
+            discriminator: 0,
+        }
+    }
+}
+
+impl<'a> LineProgram<'a> {
+    /// Reads the line program at `offset` of `.debug_line`. DWARF 5 names
+    /// files and directories by offsets into `.debug_str` and
+    /// `.debug_line_str`, which are given as `debug_str` and
+    /// `debug_line_str`.
+    pub fn read(
+        data: &'a [u8], offset: u64, endianness: Endianness,
+        debug_str: &'a [u8], debug_line_str: &'a [u8])
+        -> Result<LineProgram<'a>, DwarfError>
+    {
+        let mut reader = DwarfReader::at(data, offset, endianness, ".debug_line")?;
+        let (unit_length, format) = reader.initial_length()?;
+        let mut unit = reader.split(unit_length)?;
+
+        let version = unit.u16()?;
+        if !(2..=5).contains(&version) {
+            return Err(DwarfError::UnsupportedVersion { what: "line program", version });
+        }
+        let (address_size, segment_selector_size) = if version >= 5 {
+            (unit.u8()?, unit.u8()?)
+        } else {
+            // Only DW_LNE_set_address tells it, by its length.
+            (0, 0)
+        };
+        let header_length = unit.offset(format)?;
+        let mut program = unit.clone();
+        program.skip(header_length)?;
+
+        let minimum_instruction_length = unit.u8()?;
+        let maximum_operations_per_instruction = if version >= 4 { unit.u8()? } else { 1 };
+        let default_is_stmt = unit.u8()? != 0;
+        let line_base = unit.i8()?;
+        let line_range = unit.u8()?;
+        let opcode_base = unit.u8()?;
+        let mut standard_opcode_lengths = Vec::new();
+        for _ in 1..opcode_base {
+            standard_opcode_lengths.push(unit.u8()?);
+        }
+
+        let encoding = Encoding { format, version, address_size };
+        let (include_directories, file_names) = if version >= 5 {
+            let strings = (debug_str, debug_line_str);
+            let directories = read_entries(&mut unit, encoding, strings)?
+                .into_iter()
+                .map(|entry| entry.path)
+                .collect();
+            (directories, read_entries(&mut unit, encoding, strings)?)
+        } else {
+            let mut directories = Vec::new();
+            loop {
+                let directory = unit.cstr()?;
+                if directory.is_empty() {
+                    break;
+                }
+                directories.push(directory);
+            }
+            let mut files = Vec::new();
+            while let Some(file) = read_file_entry(&mut unit)? {
+                files.push(file);
+            }
+            (directories, files)
+        };
+
+        Ok(LineProgram {
+            header: LineProgramHeader {
+                offset,
+                unit_length,
+                encoding,
+                segment_selector_size,
+                header_length,
+                minimum_instruction_length,
+                maximum_operations_per_instruction,
+                default_is_stmt,
+                line_base,
+                line_range,
+                opcode_base,
+                standard_opcode_lengths,
+                include_directories,
+                file_names,
+            },
+            program,
+        })
+    }
+
+    pub fn get_header(&self) -> &LineProgramHeader<'a> {
+        &self.header
+    }
+
+    /// The offset just past the end of the program in `.debug_line`.
+    pub fn get_end(&self) -> u64 {
+        let length_size = match self.header.encoding.format {
+            DwarfFormat::Dwarf32 => 4,
+            DwarfFormat::Dwarf64 => 12,
+        };
+        self.header.offset + length_size + self.header.unit_length
+    }
+
+    /// Runs the program and returns the rows of the line number matrix,
+    /// in the order they're emitted. `DW_LNE_define_file` is ignored, as
+    /// compilers don't use it.
+    pub fn rows(&self) -> Result<Vec<LineRow>, DwarfError> {
+        let header = &self.header;
+        if header.line_range == 0 {
+            return Err(DwarfError::Corrupt("line range of 0"));
+        }
+        let min_length = header.minimum_instruction_length as u64;
+        let max_ops = header.maximum_operations_per_instruction.max(1) as u64;
+        // Moves the address and op_index forward by `advance` operations.
+        let advance = |row: &mut LineRow, advance: u64| {
+            let ops = row.op_index.wrapping_add(advance);
+            row.address = row.address.wrapping_add(min_length.wrapping_mul(ops / max_ops));
+            row.op_index = ops % max_ops;
+        };
+
+        let mut reader = self.program.clone();
+        let mut rows = Vec::new();
+        let mut row = LineRow::new(header.default_is_stmt);
+        let emit = |rows: &mut Vec<LineRow>, row: &mut LineRow| {
+            rows.push(*row);
+            row.basic_block = false;
+            row.prologue_end = false;
+            row.epilogue_begin = false;
+            row.discriminator = 0;
+        };
+
+        while !reader.is_empty() {
+            let opcode = reader.u8()?;
+            if opcode >= header.opcode_base {
+                let adjusted = (opcode - header.opcode_base) as u64;
+                advance(&mut row, adjusted / header.line_range as u64);
+                let line_advance = header.line_base as i64 + (adjusted % header.line_range as u64) as i64;
+                row.line = row.line.wrapping_add(line_advance as u64);
+                emit(&mut rows, &mut row);
+                continue;
+            }
+
+            match opcode {
+                0 => {
+                    let len = reader.uleb128()?;
+                    let mut ext = reader.split(len)?;
+                    if len == 0 {
+                        continue;
+                    }
+                    match ext.u8()? {
+                        DW_LNE_end_sequence => {
+                            row.end_sequence = true;
+                            emit(&mut rows, &mut row);
+                            row = LineRow::new(header.default_is_stmt);
+                        }
+                        DW_LNE_set_address => {
+                            let size = (len - 1).min(8) as u8;
+                            row.address = ext.uint(size)?;
+                            row.op_index = 0;
+                        }
+                        DW_LNE_set_discriminator => row.discriminator = ext.uleb128()?,
+                        _ => {}
+                    }
+                }
+                DW_LNS_copy => emit(&mut rows, &mut row),
+                DW_LNS_advance_pc => {
+                    let operations = reader.uleb128()?;
+                    advance(&mut row, operations);
+                }
+                DW_LNS_advance_line => {
+                    let delta = reader.sleb128()?;
+                    row.line = row.line.wrapping_add(delta as u64);
+                }
+                DW_LNS_set_file => row.file = reader.uleb128()?,
+                DW_LNS_set_column => row.column = reader.uleb128()?,
+                DW_LNS_negate_stmt => row.is_stmt = !row.is_stmt,
+                DW_LNS_set_basic_block => row.basic_block = true,
+                DW_LNS_const_add_pc => {
+                    let adjusted = (255 - header.opcode_base) as u64;
+                    advance(&mut row, adjusted / header.line_range as u64);
+                }
+                DW_LNS_fixed_advance_pc => {
+                    row.address = row.address.wrapping_add(reader.u16()? as u64);
+                    row.op_index = 0;
+                }
+                DW_LNS_set_prologue_end => row.prologue_end = true,
+                DW_LNS_set_epilogue_begin => row.epilogue_begin = true,
+                DW_LNS_set_isa => row.isa = reader.uleb128()?,
+                _ => {
+                    // Opcodes we don't know are skipped by their number of
+                    // LEB128 arguments.
+                    let args = header.standard_opcode_lengths[opcode as usize - 1];
+                    for _ in 0..args {
+                        reader.uleb128()?;
+                    }
+                }
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// Reads a file entry of a line program before DWARF 5, or `None` for the
+/// empty name that ends the table.
+fn read_file_entry<'a>(reader: &mut DwarfReader<'a>) -> Result<Option<FileEntry<'a>>, DwarfError> {
+    let path = reader.cstr()?;
+    if path.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(FileEntry {
+        path,
+        directory_index: reader.uleb128()?,
+        timestamp: reader.uleb128()?,
+        size: reader.uleb128()?,
+        md5: None,
+    }))
+}
+
+/// Reads a DWARF 5 directory or file name table: the format of its
+/// entries, then the entries.
+fn read_entries<'a>(
+    reader: &mut DwarfReader<'a>, encoding: Encoding, strings: (&'a [u8], &'a [u8]))
+    -> Result<Vec<FileEntry<'a>>, DwarfError>
+{
+    let format_count = reader.u8()?;
+    let mut format = Vec::new();
+    for _ in 0..format_count {
+        format.push((reader.uleb128()?, reader.uleb128()?));
+    }
+
+    let count = reader.uleb128()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let mut entry = FileEntry {
+            path: &[],
+            directory_index: 0,
+            timestamp: 0,
+            size: 0,
+            md5: None,
+        };
+        for &(content_type, form) in &format {
+            let value = read_attr_value(reader, form, 0, encoding)?;
+            match content_type {
+                DW_LNCT_path => entry.path = match value {
+                    AttrValue::String(s) => s,
+                    AttrValue::Strp(offset) => get_str_at(strings.0, offset, ".debug_str")?,
+                    AttrValue::LineStrp(offset) => {
+                        get_str_at(strings.1, offset, ".debug_line_str")?
+                    }
+                    _ => return Err(DwarfError::UnknownForm(form)),
+                },
+                DW_LNCT_directory_index => entry.directory_index = value.get_udata().unwrap_or(0),
+                DW_LNCT_timestamp => entry.timestamp = match value {
+                    AttrValue::Block(_) => 0,
+                    value => value.get_udata().unwrap_or(0),
+                },
+                DW_LNCT_size => entry.size = value.get_udata().unwrap_or(0),
+                DW_LNCT_MD5 => {
+                    if let AttrValue::Data16(md5) = value {
+                        entry.md5 = Some(md5);
+                    }
+                }
+                _ => {}
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
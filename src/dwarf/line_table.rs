@@ -0,0 +1,158 @@
+//! The address to source line mapping of all the line programs of a file,
+//! as GNU addr2line looks addresses up in it.
+
+use super::Dwarf;
+use super::dw_at::{DW_AT_stmt_list, DW_AT_comp_dir};
+use super::error::DwarfError;
+use super::line::LineProgramHeader;
+
+/// The source position of an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location<'t> {
+    /// The path of the source file, made absolute with the directories of
+    /// the line program and the compilation directory where it can be.
+    pub file: &'t str,
+    /// The line, or 0 if the address isn't attributed to any.
+    pub line: u64,
+    pub column: u64,
+    pub discriminator: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    address: u64,
+    file: usize,
+    line: u64,
+    column: u64,
+    discriminator: u64,
+}
+
+/// The rows of a sequence of a line program, which covers the addresses
+/// from `start` up to but not including `end`.
+#[derive(Debug, Clone)]
+struct Sequence {
+    start: u64,
+    end: u64,
+    rows: Vec<Row>,
+}
+
+/// The line number information of a file.
+#[derive(Debug, Clone, Default)]
+pub struct LineTable {
+    files: Vec<String>,
+    sequences: Vec<Sequence>,
+}
+
+impl LineTable {
+    /// Runs the line programs of all the compilation units of `dwarf`.
+    pub fn new(dwarf: &Dwarf) -> Result<LineTable, DwarfError> {
+        let mut table = LineTable::default();
+        for header in dwarf.units()? {
+            let abbrevs = dwarf.abbrevs(&header)?;
+            let root = match dwarf.root_die(&header, &abbrevs)? {
+                Some(root) => root,
+                None => continue,
+            };
+            let stmt_list = match root.get(DW_AT_stmt_list).and_then(|v| v.get_udata()) {
+                Some(offset) => offset,
+                None => continue,
+            };
+            let comp_dir = match root.get(DW_AT_comp_dir) {
+                Some(value) => dwarf.attr_string(&header, &root, value)?
+                    .map(|dir| String::from_utf8_lossy(dir).into_owned()),
+                None => None,
+            };
+
+            let program = dwarf.line_program(stmt_list)?;
+            let first_file = table.files.len();
+            let program_header = program.get_header();
+            for i in 0..program_header.file_names.len() {
+                table.files.push(file_name(program_header, i, comp_dir.as_deref()));
+            }
+            let unknown = table.files.len();
+            table.files.push(String::from("<unknown>"));
+            // Files are numbered from 1 before DWARF 5, with 0 left unknown.
+            let file_index = |file: u64| {
+                let index = if program_header.encoding.version >= 5 {
+                    file
+                } else {
+                    file.wrapping_sub(1)
+                };
+                if index < program_header.file_names.len() as u64 {
+                    first_file + index as usize
+                } else {
+                    unknown
+                }
+            };
+
+            let mut rows: Vec<Row> = Vec::new();
+            for row in program.rows()? {
+                if row.end_sequence {
+                    if let Some(first) = rows.first() {
+                        let start = first.address;
+                        table.sequences.push(Sequence {
+                            start,
+                            end: row.address,
+                            rows: ::std::mem::take(&mut rows),
+                        });
+                    }
+                    continue;
+                }
+                rows.push(Row {
+                    address: row.address,
+                    file: file_index(row.file),
+                    line: row.line,
+                    column: row.column,
+                    discriminator: row.discriminator,
+                });
+            }
+        }
+        Ok(table)
+    }
+
+    /// Finds the source position of `address`: in the first sequence that
+    /// covers it, the last row at or before it.
+    pub fn find(&self, address: u64) -> Option<Location<'_>> {
+        let sequence = self.sequences.iter()
+            .find(|s| s.start <= address && address < s.end)?;
+        let index = sequence.rows.partition_point(|r| r.address <= address);
+        let row = &sequence.rows[index.checked_sub(1)?];
+        Some(Location {
+            file: &self.files[row.file],
+            line: row.line,
+            column: row.column,
+            discriminator: row.discriminator,
+        })
+    }
+}
+
+/// Builds the path of the file at `index` of the file name table, like
+/// binutils does: relative names are put under their directory and
+/// relative directories under the compilation directory.
+fn file_name(header: &LineProgramHeader, index: usize, comp_dir: Option<&str>) -> String {
+    let lossy = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+    let entry = &header.file_names[index];
+    let name = lossy(entry.path);
+    if name.starts_with('/') {
+        return name;
+    }
+
+    let dir_index = if header.encoding.version >= 5 {
+        Some(entry.directory_index)
+    } else {
+        entry.directory_index.checked_sub(1)
+    };
+    let subdir = dir_index
+        .and_then(|i| header.include_directories.get(i as usize))
+        .map(|dir| lossy(dir));
+    let dir = match subdir {
+        Some(ref subdir) if subdir.starts_with('/') => None,
+        _ => comp_dir,
+    };
+    match (dir, subdir) {
+        (Some(dir), Some(subdir)) => format!("{}/{}/{}", dir, subdir, name),
+        (Some(dir), None) => format!("{}/{}", dir, name),
+        (None, Some(dir)) => format!("{}/{}", dir, name),
+        (None, None) => name,
+    }
+}
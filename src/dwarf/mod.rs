@@ -0,0 +1,161 @@
+//! Reading of DWARF debugging information.
+//!
+//! [`Dwarf`] gathers the debugging sections of an [`ElfFile`],
+//! decompressed and relocated as needed, and reads the units of
+//! `.debug_info` and the line programs of `.debug_line` from them.
+//! [`line_table::LineTable`] maps addresses to source lines the way
+//! GNU addr2line does, and [`info_dump`] prints the DIEs of units the way
+//! `readelf -wi` does.
+
+use ::std::borrow::Cow;
+use ::elf::ElfFile;
+use ::elf::elf_error::ElfError;
+use ::to_host::Endianness;
+
+/// Defines a constant for each of a set of DWARF names, and `$get_name`
+/// to look the name of a value up. Values that other names share, like
+/// vendor extensions that clash, are defined apart from it.
+macro_rules! dwarf_names {
+    ($ty:ty, $get_name:ident; $($name:ident = $value:expr,)*) => {
+        $(pub const $name: $ty = $value;)*
+
+        /// The name of `value`, if it has one.
+        pub fn $get_name(value: $ty) -> Option<&'static str> {
+            match value {
+                $($name => Some(stringify!($name)),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+pub mod error;
+pub mod reader;
+pub mod dw_form;
+pub mod dw_at;
+pub mod dw_tag;
+pub mod dw_op;
+pub mod registers;
+pub mod cfi;
+pub mod abbrev;
+pub mod unit;
+pub mod line;
+pub mod line_table;
+pub mod info_dump;
+pub mod frame_dump;
+
+pub use self::error::DwarfError;
+
+use self::abbrev::Abbrevs;
+use self::dw_at::DW_AT_str_offsets_base;
+use self::dw_form::AttrValue;
+use self::line::LineProgram;
+use self::reader::{DwarfReader, get_str_at};
+use self::unit::{Die, UnitHeader};
+
+/// The debugging sections of an ELF file. Missing sections read as empty.
+pub struct Dwarf<'a> {
+    endianness: Endianness,
+    debug_info: Cow<'a, [u8]>,
+    debug_abbrev: Cow<'a, [u8]>,
+    debug_str: Cow<'a, [u8]>,
+    debug_line_str: Cow<'a, [u8]>,
+    debug_str_offsets: Cow<'a, [u8]>,
+    debug_line: Cow<'a, [u8]>,
+    debug_addr: Cow<'a, [u8]>,
+    debug_rnglists: Cow<'a, [u8]>,
+    debug_loclists: Cow<'a, [u8]>,
+}
+
+impl<'a> Dwarf<'a> {
+    /// Loads the debugging sections of `elf`.
+    pub fn load(elf: &'a ElfFile) -> Result<Dwarf<'a>, ElfError> {
+        Dwarf::load_with_suffix(elf, "")
+    }
+
+    /// Loads the `.dwo` debugging sections that split DWARF leaves in
+    /// object files or `.dwo` files, e.g. `.debug_info.dwo`. There's no
+    /// `.debug_addr.dwo`: split units take their addresses from the
+    /// skeleton's file.
+    pub fn load_dwo(elf: &'a ElfFile) -> Result<Dwarf<'a>, ElfError> {
+        Dwarf::load_with_suffix(elf, ".dwo")
+    }
+
+    fn load_with_suffix(elf: &'a ElfFile, suffix: &str) -> Result<Dwarf<'a>, ElfError> {
+        let load = |name: &str| -> Result<Cow<'a, [u8]>, ElfError> {
+            let name = format!("{}{}", name, suffix);
+            Ok(elf.debug_section_data(&name)?.unwrap_or(Cow::Borrowed(&[])))
+        };
+        Ok(Dwarf {
+            endianness: elf.endianness(),
+            debug_info: load(".debug_info")?,
+            debug_abbrev: load(".debug_abbrev")?,
+            debug_str: load(".debug_str")?,
+            debug_line_str: load(".debug_line_str")?,
+            debug_str_offsets: load(".debug_str_offsets")?,
+            debug_line: load(".debug_line")?,
+            debug_addr: if suffix.is_empty() { load(".debug_addr")? } else { Cow::Borrowed(&[]) },
+            debug_rnglists: load(".debug_rnglists")?,
+            debug_loclists: load(".debug_loclists")?,
+        })
+    }
+
+    pub fn get_endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Reads the headers of the units of `.debug_info`.
+    pub fn units(&self) -> Result<Vec<UnitHeader>, DwarfError> {
+        UnitHeader::read_all(&self.debug_info, self.endianness)
+    }
+
+    /// Reads the abbreviations used by the unit of `header`.
+    pub fn abbrevs(&self, header: &UnitHeader) -> Result<Abbrevs, DwarfError> {
+        Abbrevs::read(&self.debug_abbrev, header.get_abbrev_offset(), self.endianness)
+    }
+
+    /// Reads the first DIE of the unit of `header`, e.g. its
+    /// `DW_TAG_compile_unit`.
+    pub fn root_die(&self, header: &UnitHeader, abbrevs: &Abbrevs)
+        -> Result<Option<Die<'_>>, DwarfError>
+    {
+        let unit = &self.debug_info[..header.get_end() as usize];
+        let mut reader = DwarfReader::at(
+            unit, header.get_dies_offset(), self.endianness, ".debug_info")?;
+        Die::read(&mut reader, header.get_encoding(), abbrevs)
+    }
+
+    /// Resolves a string attribute of a DIE of the unit of `header`, whose
+    /// root DIE is `root`. Returns `None` for values that aren't strings,
+    /// and for strings in supplementary files.
+    pub fn attr_string<'s>(&'s self, header: &UnitHeader, root: &Die, value: AttrValue<'s>)
+        -> Result<Option<&'s [u8]>, DwarfError>
+    {
+        Ok(Some(match value {
+            AttrValue::String(string) => string,
+            AttrValue::Strp(offset) => get_str_at(&self.debug_str, offset, ".debug_str")?,
+            AttrValue::LineStrp(offset) => {
+                get_str_at(&self.debug_line_str, offset, ".debug_line_str")?
+            }
+            AttrValue::Strx(index) => {
+                let base = root.get(DW_AT_str_offsets_base)
+                    .and_then(|v| v.get_udata())
+                    .unwrap_or(0);
+                let format = header.get_encoding().format;
+                let size = format.get_offset_size() as u64;
+                let mut reader = DwarfReader::at(
+                    &self.debug_str_offsets, base + index * size,
+                    self.endianness, ".debug_str_offsets")?;
+                let offset = reader.offset(format)?;
+                get_str_at(&self.debug_str, offset, ".debug_str")?
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Reads the line program at `offset` of `.debug_line`.
+    pub fn line_program(&self, offset: u64) -> Result<LineProgram<'_>, DwarfError> {
+        LineProgram::read(
+            &self.debug_line, offset, self.endianness, &self.debug_str, &self.debug_line_str)
+    }
+}
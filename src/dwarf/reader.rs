@@ -0,0 +1,205 @@
+use ::to_host::Endianness;
+use super::error::DwarfError;
+
+/// Whether offsets and lengths in a unit are 4 or 8 bytes long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DwarfFormat {
+    Dwarf32,
+    Dwarf64,
+}
+
+impl DwarfFormat {
+    /// The size of a section offset.
+    pub fn get_offset_size(&self) -> u8 {
+        match *self {
+            DwarfFormat::Dwarf32 => 4,
+            DwarfFormat::Dwarf64 => 8,
+        }
+    }
+}
+
+/// What the fields of a unit or line program depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub format: DwarfFormat,
+    pub version: u16,
+    pub address_size: u8,
+}
+
+/// Reads the fields of DWARF data in the byte order of the file. `what`
+/// names the data in `DwarfError::Truncated`.
+#[derive(Debug, Clone)]
+pub struct DwarfReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    endianness: Endianness,
+    what: &'static str,
+}
+
+impl<'a> DwarfReader<'a> {
+    pub fn new(data: &'a [u8], endianness: Endianness, what: &'static str) -> DwarfReader<'a> {
+        DwarfReader { data, pos: 0, endianness, what }
+    }
+
+    /// A reader of `data` starting at `offset`, which must lie within it.
+    pub fn at(data: &'a [u8], offset: u64, endianness: Endianness, what: &'static str)
+        -> Result<DwarfReader<'a>, DwarfError>
+    {
+        if offset > data.len() as u64 {
+            return Err(DwarfError::BadOffset { what, offset });
+        }
+        Ok(DwarfReader { data, pos: offset as usize, endianness, what })
+    }
+
+    pub fn get_endianness(&self) -> Endianness {
+        self.endianness
+    }
+    /// The offset of the next byte to read.
+    pub fn get_pos(&self) -> u64 {
+        self.pos as u64
+    }
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// A reader of the next `len` bytes, which are skipped in this one.
+    /// Offsets in it stay relative to the start of the data.
+    pub fn split(&mut self, len: u64) -> Result<DwarfReader<'a>, DwarfError> {
+        let end = self.check(len)?;
+        let mut reader = self.clone();
+        reader.data = &self.data[..end];
+        self.pos = end;
+        Ok(reader)
+    }
+
+    /// Returns the end of the next `len` bytes, if they're there.
+    fn check(&self, len: u64) -> Result<usize, DwarfError> {
+        let left = (self.data.len() - self.pos.min(self.data.len())) as u64;
+        if len > left {
+            return Err(DwarfError::Truncated(self.what));
+        }
+        Ok(self.pos + len as usize)
+    }
+
+    pub fn skip(&mut self, len: u64) -> Result<(), DwarfError> {
+        self.pos = self.check(len)?;
+        Ok(())
+    }
+
+    pub fn bytes(&mut self, len: u64) -> Result<&'a [u8], DwarfError> {
+        let end = self.check(len)?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Returns the bytes that are left, which are skipped.
+    pub fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        self.pos = self.data.len();
+        rest
+    }
+
+    pub fn u8(&mut self) -> Result<u8, DwarfError> {
+        Ok(self.bytes(1)?[0])
+    }
+    pub fn i8(&mut self) -> Result<i8, DwarfError> {
+        Ok(self.u8()? as i8)
+    }
+    pub fn u16(&mut self) -> Result<u16, DwarfError> {
+        Ok(self.uint(2)? as u16)
+    }
+    pub fn u32(&mut self) -> Result<u32, DwarfError> {
+        Ok(self.uint(4)? as u32)
+    }
+    pub fn u64(&mut self) -> Result<u64, DwarfError> {
+        self.uint(8)
+    }
+
+    /// Reads an unsigned integer of `size` bytes, up to 8, e.g. an address.
+    pub fn uint(&mut self, size: u8) -> Result<u64, DwarfError> {
+        if size > 8 {
+            return Err(DwarfError::Corrupt("integer wider than 8 bytes"));
+        }
+        let bytes = self.bytes(size as u64)?;
+        let fold = |value, &byte| (value << 8) | byte as u64;
+        Ok(match self.endianness {
+            Endianness::LE => bytes.iter().rev().fold(0, fold),
+            Endianness::BE => bytes.iter().fold(0, fold),
+        })
+    }
+
+    /// Reads a section offset of the size `format` tells.
+    pub fn offset(&mut self, format: DwarfFormat) -> Result<u64, DwarfError> {
+        self.uint(format.get_offset_size())
+    }
+
+    /// Reads the length that starts units and line programs, which tells
+    /// their format too.
+    pub fn initial_length(&mut self) -> Result<(u64, DwarfFormat), DwarfError> {
+        match self.u32()? {
+            0xffff_ffff => Ok((self.u64()?, DwarfFormat::Dwarf64)),
+            len if len >= 0xffff_fff0 => Err(DwarfError::Corrupt("reserved initial length")),
+            len => Ok((len as u64, DwarfFormat::Dwarf32)),
+        }
+    }
+
+    /// Reads an unsigned LEB128 number. Bits past the 64th are dropped.
+    pub fn uleb128(&mut self) -> Result<u64, DwarfError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= ((byte & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Reads a signed LEB128 number.
+    pub fn sleb128(&mut self) -> Result<i64, DwarfError> {
+        let mut value = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= ((byte & 0x7f) as i64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Reads a NUL-terminated string, without the terminator.
+    pub fn cstr(&mut self) -> Result<&'a [u8], DwarfError> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let len = rest.iter().position(|&b| b == 0)
+            .ok_or(DwarfError::Truncated(self.what))?;
+        self.pos += len + 1;
+        Ok(&rest[..len])
+    }
+}
+
+/// Returns the NUL-terminated string at `offset` of a string section such
+/// as `.debug_str`. `what` names the section in errors.
+pub fn get_str_at<'a>(data: &'a [u8], offset: u64, what: &'static str)
+    -> Result<&'a [u8], DwarfError>
+{
+    if offset > data.len() as u64 {
+        return Err(DwarfError::BadOffset { what, offset });
+    }
+    let rest = &data[offset as usize..];
+    match rest.iter().position(|&b| b == 0) {
+        Some(len) => Ok(&rest[..len]),
+        None => Err(DwarfError::Truncated(what)),
+    }
+}
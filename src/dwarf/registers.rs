@@ -0,0 +1,138 @@
+//! The names of DWARF register numbers, for the machines readelf knows
+//! them of.
+
+use ::elf::elf_ehdr_machine::ElfEhdrMachine;
+
+const I386_REGISTERS: &[&str] = &[
+    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi",
+    "eip", "eflags", "",
+    "st0", "st1", "st2", "st3", "st4", "st5", "st6", "st7",
+    "", "",
+    "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7",
+    "mm0", "mm1", "mm2", "mm3", "mm4", "mm5", "mm6", "mm7",
+    "fcw", "fsw", "mxcsr",
+    "es", "cs", "ss", "ds", "fs", "gs", "", "",
+    "tr", "ldtr",
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "", "", "", "",
+    "k0", "k1", "k2", "k3", "k4", "k5", "k6", "k7",
+];
+
+const X86_64_REGISTERS: &[&str] = &[
+    "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp",
+    "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+    "rip",
+    "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7",
+    "xmm8", "xmm9", "xmm10", "xmm11", "xmm12", "xmm13", "xmm14", "xmm15",
+    "st0", "st1", "st2", "st3", "st4", "st5", "st6", "st7",
+    "mm0", "mm1", "mm2", "mm3", "mm4", "mm5", "mm6", "mm7",
+    "rflags",
+    "es", "cs", "ss", "ds", "fs", "gs", "", "",
+    "fs.base", "gs.base", "", "",
+    "tr", "ldtr",
+    "mxcsr", "fcw", "fsw",
+    "xmm16", "xmm17", "xmm18", "xmm19", "xmm20", "xmm21", "xmm22", "xmm23",
+    "xmm24", "xmm25", "xmm26", "xmm27", "xmm28", "xmm29", "xmm30", "xmm31",
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "", "", "",
+    "k0", "k1", "k2", "k3", "k4", "k5", "k6", "k7",
+];
+
+const AARCH64_REGISTERS: &[&str] = &[
+    "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7",
+    "x8", "x9", "x10", "x11", "x12", "x13", "x14", "x15",
+    "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23",
+    "x24", "x25", "x26", "x27", "x28", "x29", "x30", "sp",
+    "", "elr", "", "", "", "", "", "",
+    "", "", "", "", "", "", "vg", "ffr",
+    "p0", "p1", "p2", "p3", "p4", "p5", "p6", "p7",
+    "p8", "p9", "p10", "p11", "p12", "p13", "p14", "p15",
+    "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7",
+    "v8", "v9", "v10", "v11", "v12", "v13", "v14", "v15",
+    "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23",
+    "v24", "v25", "v26", "v27", "v28", "v29", "v30", "v31",
+    "z0", "z1", "z2", "z3", "z4", "z5", "z6", "z7",
+    "z8", "z9", "z10", "z11", "z12", "z13", "z14", "z15",
+    "z16", "z17", "z18", "z19", "z20", "z21", "z22", "z23",
+    "z24", "z25", "z26", "z27", "z28", "z29", "z30", "z31",
+];
+
+// The general registers are left unnamed so they don't show as
+// "r5 (r5)".
+const S390_REGISTERS: &[&str] = &[
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "f0", "f2", "f4", "f6", "f1", "f3", "f5", "f7",
+    "f8", "f10", "f12", "f14", "f9", "f11", "f13", "f15",
+    "cr0", "cr1", "cr2", "cr3", "cr4", "cr5", "cr6", "cr7",
+    "cr8", "cr9", "cr10", "cr11", "cr12", "cr13", "cr14", "cr15",
+    "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7",
+    "a8", "a9", "a10", "a11", "a12", "a13", "a14", "a15",
+    "pswm", "pswa",
+    "", "",
+    "v16", "v18", "v20", "v22", "v17", "v19", "v21", "v23",
+    "v24", "v26", "v28", "v30", "v25", "v27", "v29", "v31",
+];
+
+const RISCV_REGISTERS: &[&str] = &[
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2",
+    "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+    "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7",
+    "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7",
+    "fs0", "fs1",
+    "fa0", "fa1", "fa2", "fa3", "fa4", "fa5", "fa6", "fa7",
+    "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11",
+    "ft8", "ft9", "ft10", "ft11",
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "", "", "", "", "", "", "", "", "",
+    "v0", "v1", "v2", "v3", "v4", "v5", "v6", "v7",
+    "v8", "v9", "v10", "v11", "v12", "v13", "v14", "v15",
+    "v16", "v17", "v18", "v19", "v20", "v21", "v22", "v23",
+    "v24", "v25", "v26", "v27", "v28", "v29", "v30", "v31",
+];
+
+/// The names of the DWARF registers of `machine`, by number.
+fn get_register_names(machine: ElfEhdrMachine) -> &'static [&'static str] {
+    match machine {
+        ElfEhdrMachine::EM_386 | ElfEhdrMachine::EM_IAMCU => I386_REGISTERS,
+        ElfEhdrMachine::EM_X86_64 | ElfEhdrMachine::EM_L10M | ElfEhdrMachine::EM_K10M => {
+            X86_64_REGISTERS
+        }
+        ElfEhdrMachine::EM_AARCH64 => AARCH64_REGISTERS,
+        ElfEhdrMachine::EM_S390 => S390_REGISTERS,
+        ElfEhdrMachine::EM_RISCV => RISCV_REGISTERS,
+        _ => &[],
+    }
+}
+
+/// Returns the name of DWARF register `reg` of `machine`, if it has one.
+/// RISC-V control and status registers are numbered from 4096 and named
+/// by their number.
+pub fn get_register_name(machine: ElfEhdrMachine, reg: u64) -> Option<String> {
+    if machine == ElfEhdrMachine::EM_RISCV && (4096..8192).contains(&reg) {
+        return Some(format!("csr{}", reg - 4096));
+    }
+    match get_register_names(machine).get(reg as usize) {
+        Some(name) if !name.is_empty() => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+/// The number of registers readelf has names for on `machine`, which it
+/// takes as the highest register number call frame information may use.
+/// 0 if it has none.
+pub fn get_register_count(machine: ElfEhdrMachine) -> usize {
+    if machine == ElfEhdrMachine::EM_RISCV {
+        return 8192;
+    }
+    get_register_names(machine).len()
+}
+
+/// Formats a register of an expression like readelf does: by its name,
+/// or as `r` and its number if it has none.
+pub fn format_register(machine: ElfEhdrMachine, reg: u64) -> String {
+    get_register_name(machine, reg).unwrap_or_else(|| format!("r{}", reg as u32 as i32))
+}
@@ -0,0 +1,175 @@
+#![allow(non_upper_case_globals)]
+
+use ::to_host::Endianness;
+use super::abbrev::Abbrevs;
+use super::dw_form::{AttrValue, read_attr_value};
+use super::error::DwarfError;
+use super::reader::{DwarfReader, DwarfFormat, Encoding};
+
+dwarf_names! { u8, get_unit_type_name;
+    DW_UT_compile = 0x01,
+    DW_UT_type = 0x02,
+    DW_UT_partial = 0x03,
+    DW_UT_skeleton = 0x04,
+    DW_UT_split_compile = 0x05,
+    DW_UT_split_type = 0x06,
+}
+
+/// The header of a unit of `.debug_info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitHeader {
+    offset: u64,
+    length: u64,
+    encoding: Encoding,
+    unit_type: u8,
+    abbrev_offset: u64,
+    signature: Option<u64>,
+    type_offset: Option<u64>,
+    dies_offset: u64,
+}
+
+impl UnitHeader {
+    /// Reads the header of the unit at the position of `reader` and skips
+    /// the rest of the unit.
+    pub fn read(reader: &mut DwarfReader) -> Result<UnitHeader, DwarfError> {
+        let offset = reader.get_pos();
+        let (length, format) = reader.initial_length()?;
+        let mut unit = reader.split(length)?;
+
+        let version = unit.u16()?;
+        if !(2..=5).contains(&version) {
+            return Err(DwarfError::UnsupportedVersion { what: "unit", version });
+        }
+        let (unit_type, abbrev_offset, address_size) = if version >= 5 {
+            let unit_type = unit.u8()?;
+            let address_size = unit.u8()?;
+            (unit_type, unit.offset(format)?, address_size)
+        } else {
+            let abbrev_offset = unit.offset(format)?;
+            (DW_UT_compile, abbrev_offset, unit.u8()?)
+        };
+
+        let (signature, type_offset) = match unit_type {
+            DW_UT_skeleton | DW_UT_split_compile => (Some(unit.u64()?), None),
+            DW_UT_type | DW_UT_split_type => (Some(unit.u64()?), Some(unit.offset(format)?)),
+            _ => (None, None),
+        };
+
+        Ok(UnitHeader {
+            offset,
+            length,
+            encoding: Encoding { format, version, address_size },
+            unit_type,
+            abbrev_offset,
+            signature,
+            type_offset,
+            dies_offset: unit.get_pos(),
+        })
+    }
+
+    /// Reads the headers of all the units of `.debug_info`.
+    pub fn read_all(data: &[u8], endianness: Endianness)
+        -> Result<Vec<UnitHeader>, DwarfError>
+    {
+        let mut reader = DwarfReader::new(data, endianness, ".debug_info");
+        let mut units = Vec::new();
+        while !reader.is_empty() {
+            units.push(UnitHeader::read(&mut reader)?);
+        }
+        Ok(units)
+    }
+
+    /// The offset of the unit in `.debug_info`.
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+    /// The length of the unit after the length field.
+    pub fn get_length(&self) -> u64 {
+        self.length
+    }
+    pub fn get_encoding(&self) -> Encoding {
+        self.encoding
+    }
+    pub fn get_version(&self) -> u16 {
+        self.encoding.version
+    }
+    /// `DW_UT_compile` for units before DWARF 5.
+    pub fn get_unit_type(&self) -> u8 {
+        self.unit_type
+    }
+    pub fn get_abbrev_offset(&self) -> u64 {
+        self.abbrev_offset
+    }
+    /// The DWO id of skeleton and split units, or the type signature of
+    /// type units.
+    pub fn get_signature(&self) -> Option<u64> {
+        self.signature
+    }
+    /// The offset of the type DIE of type units.
+    pub fn get_type_offset(&self) -> Option<u64> {
+        self.type_offset
+    }
+    /// The offset of the first DIE in `.debug_info`.
+    pub fn get_dies_offset(&self) -> u64 {
+        self.dies_offset
+    }
+    /// The offset just past the end of the unit in `.debug_info`.
+    pub fn get_end(&self) -> u64 {
+        let length_size = match self.encoding.format {
+            DwarfFormat::Dwarf32 => 4,
+            DwarfFormat::Dwarf64 => 12,
+        };
+        self.offset + length_size + self.length
+    }
+}
+
+/// An attribute of a DIE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attr<'a> {
+    pub name: u64,
+    pub form: u64,
+    pub value: AttrValue<'a>,
+}
+
+/// A debugging information entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Die<'a> {
+    pub offset: u64,
+    pub code: u64,
+    pub tag: u64,
+    pub has_children: bool,
+    pub attrs: Vec<Attr<'a>>,
+}
+
+impl<'a> Die<'a> {
+    /// The value of the attribute `name`, if the DIE has it.
+    pub fn get(&self, name: u64) -> Option<AttrValue<'a>> {
+        self.attrs.iter().find(|a| a.name == name).map(|a| a.value)
+    }
+
+    /// Reads the DIE at the position of `reader`, which must be within a
+    /// unit of `encoding`. Returns `None` for the null entries that end
+    /// lists of children.
+    pub fn read(reader: &mut DwarfReader<'a>, encoding: Encoding, abbrevs: &Abbrevs)
+        -> Result<Option<Die<'a>>, DwarfError>
+    {
+        let offset = reader.get_pos();
+        let code = reader.uleb128()?;
+        if code == 0 {
+            return Ok(None);
+        }
+        let abbrev = abbrevs.get(code).ok_or(DwarfError::MissingAbbrev(code))?;
+        let mut attrs = Vec::with_capacity(abbrev.attrs.len());
+        for spec in &abbrev.attrs {
+            let value = read_attr_value(reader, spec.form, spec.implicit_const, encoding)?;
+            attrs.push(Attr { name: spec.name, form: spec.form, value });
+        }
+        Ok(Some(Die {
+            offset,
+            code,
+            tag: abbrev.tag,
+            has_children: abbrev.has_children,
+            attrs,
+        }))
+    }
+}
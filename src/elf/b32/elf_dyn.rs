@@ -0,0 +1 @@
+elf_dyn!(Elf32_Word);
@@ -1 +1 @@
-elf_ehdr!(Elf32_Half, Elf32_Word, Elf32_Addr, Elf32_Off);
+elf_ehdr!(Elf32_Addr: U32, Elf32_Off: U32);
@@ -0,0 +1 @@
+elf_shdr!(Elf32_Word, Elf32_Word, Elf32_Addr, Elf32_Off);
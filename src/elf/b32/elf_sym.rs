@@ -0,0 +1 @@
+elf_sym!(b32);
@@ -1,7 +1,19 @@
 use super::elf_ehdr::Elf_Ehdr as Elf32_Ehdr;
 use super::elf_phdr::Elf_Phdr as Elf32_Phdr;
-use super::super::elf_ident_named::EI_MAGIC_SIZE;
-use ::std::io::{Read, Seek};
+use super::elf_shdr::Elf_Shdr as Elf32_Shdr;
+use super::elf_sym::Elf_Sym as Elf32_Sym;
+use super::elf_dyn::Elf_Dyn as Elf32_Dyn;
+use super::elf_rel::Elf_Rel as Elf32_Rel;
+use super::elf_rel::Elf_Rela as Elf32_Rela;
+use super::elf_chdr::Elf_Chdr as Elf32_Chdr;
+use super::primitive::Elf32_Word;
 
 read_ehdr!(Elf32_Ehdr);
-read_phdrs!(Elf32_Phdr, Elf32_Ehdr);
+read_phdrs!(Elf32_Phdr);
+read_shdrs!(Elf32_Shdr);
+read_syms!(Elf32_Sym);
+read_dyns!(Elf32_Dyn);
+read_rels!(Elf32_Rel, Elf32_Rela, Elf32_Word);
+read_chdr!(Elf32_Chdr);
+write_headers!(Elf32_Ehdr, Elf32_Phdr, Elf32_Shdr);
+write_dyns!(Elf32_Dyn);
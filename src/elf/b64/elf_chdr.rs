@@ -0,0 +1 @@
+elf_chdr!(b64);
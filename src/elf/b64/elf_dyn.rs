@@ -0,0 +1 @@
+elf_dyn!(Elf64_Xword);
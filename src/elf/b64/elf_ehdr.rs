@@ -1 +1 @@
-elf_ehdr!(Elf64_Half, Elf64_Word, Elf64_Addr, Elf64_Off);
+elf_ehdr!(Elf64_Addr: U64, Elf64_Off: U64);
@@ -0,0 +1 @@
+elf_rel!(b64);
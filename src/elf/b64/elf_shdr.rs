@@ -0,0 +1 @@
+elf_shdr!(Elf64_Word, Elf64_Xword, Elf64_Addr, Elf64_Off);
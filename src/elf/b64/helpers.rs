@@ -1,7 +1,19 @@
 use super::elf_ehdr::Elf_Ehdr as Elf64_Ehdr;
 use super::elf_phdr::Elf_Phdr as Elf64_Phdr;
-use super::super::elf_ident_named::EI_MAGIC_SIZE;
-use ::std::io::{Read, Seek};
+use super::elf_shdr::Elf_Shdr as Elf64_Shdr;
+use super::elf_sym::Elf_Sym as Elf64_Sym;
+use super::elf_dyn::Elf_Dyn as Elf64_Dyn;
+use super::elf_rel::Elf_Rel as Elf64_Rel;
+use super::elf_rel::Elf_Rela as Elf64_Rela;
+use super::elf_chdr::Elf_Chdr as Elf64_Chdr;
+use super::primitive::Elf64_Xword;
 
 read_ehdr!(Elf64_Ehdr);
-read_phdrs!(Elf64_Phdr, Elf64_Ehdr);
+read_phdrs!(Elf64_Phdr);
+read_shdrs!(Elf64_Shdr);
+read_syms!(Elf64_Sym);
+read_dyns!(Elf64_Dyn);
+read_rels!(Elf64_Rel, Elf64_Rela, Elf64_Xword);
+read_chdr!(Elf64_Chdr);
+write_headers!(Elf64_Ehdr, Elf64_Phdr, Elf64_Shdr);
+write_dyns!(Elf64_Dyn);
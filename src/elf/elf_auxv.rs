@@ -0,0 +1,116 @@
+use ::to_host::Endianness;
+use super::elf_note::read_addr;
+
+// Types of the entries of the auxiliary vector, in an `NT_AUXV` note.
+pub const AT_NULL: u64 = 0;
+pub const AT_IGNORE: u64 = 1;
+pub const AT_EXECFD: u64 = 2;
+pub const AT_PHDR: u64 = 3;
+pub const AT_PHENT: u64 = 4;
+pub const AT_PHNUM: u64 = 5;
+pub const AT_PAGESZ: u64 = 6;
+pub const AT_BASE: u64 = 7;
+pub const AT_FLAGS: u64 = 8;
+pub const AT_ENTRY: u64 = 9;
+pub const AT_NOTELF: u64 = 10;
+pub const AT_UID: u64 = 11;
+pub const AT_EUID: u64 = 12;
+pub const AT_GID: u64 = 13;
+pub const AT_EGID: u64 = 14;
+pub const AT_PLATFORM: u64 = 15;
+pub const AT_HWCAP: u64 = 16;
+pub const AT_CLKTCK: u64 = 17;
+pub const AT_FPUCW: u64 = 18;
+pub const AT_DCACHEBSIZE: u64 = 19;
+pub const AT_ICACHEBSIZE: u64 = 20;
+pub const AT_UCACHEBSIZE: u64 = 21;
+pub const AT_IGNOREPPC: u64 = 22;
+pub const AT_SECURE: u64 = 23;
+pub const AT_BASE_PLATFORM: u64 = 24;
+pub const AT_RANDOM: u64 = 25;
+pub const AT_HWCAP2: u64 = 26;
+pub const AT_RSEQ_FEATURE_SIZE: u64 = 27;
+pub const AT_RSEQ_ALIGN: u64 = 28;
+pub const AT_HWCAP3: u64 = 29;
+pub const AT_HWCAP4: u64 = 30;
+pub const AT_EXECFN: u64 = 31;
+pub const AT_SYSINFO: u64 = 32;
+pub const AT_SYSINFO_EHDR: u64 = 33;
+pub const AT_MINSIGSTKSZ: u64 = 51;
+
+/// How the value of an auxiliary vector entry is best shown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuxvFormat {
+    /// An address or a bit mask.
+    Hex,
+    /// A number or an ID.
+    Dec,
+    /// The address of a NUL-terminated string in the process memory.
+    Str,
+}
+
+/// Returns the name of an entry type, a description like GDB's
+/// `info auxv` gives and the way its value is shown, or `None` for
+/// unknown types.
+pub fn get_auxv_type_info(a_type: u64) -> Option<(&'static str, &'static str, AuxvFormat)> {
+    use self::AuxvFormat::*;
+    Some(match a_type {
+        AT_NULL => ("AT_NULL", "End of vector", Hex),
+        AT_IGNORE => ("AT_IGNORE", "Entry should be ignored", Hex),
+        AT_EXECFD => ("AT_EXECFD", "File descriptor of program", Dec),
+        AT_PHDR => ("AT_PHDR", "Program headers for program", Hex),
+        AT_PHENT => ("AT_PHENT", "Size of program header entry", Dec),
+        AT_PHNUM => ("AT_PHNUM", "Number of program headers", Dec),
+        AT_PAGESZ => ("AT_PAGESZ", "System page size", Dec),
+        AT_BASE => ("AT_BASE", "Base address of interpreter", Hex),
+        AT_FLAGS => ("AT_FLAGS", "Flags", Hex),
+        AT_ENTRY => ("AT_ENTRY", "Entry point of program", Hex),
+        AT_NOTELF => ("AT_NOTELF", "Program is not ELF", Dec),
+        AT_UID => ("AT_UID", "Real user ID", Dec),
+        AT_EUID => ("AT_EUID", "Effective user ID", Dec),
+        AT_GID => ("AT_GID", "Real group ID", Dec),
+        AT_EGID => ("AT_EGID", "Effective group ID", Dec),
+        AT_PLATFORM => ("AT_PLATFORM", "String identifying platform", Str),
+        AT_HWCAP => ("AT_HWCAP", "Machine-dependent CPU capability hints", Hex),
+        AT_CLKTCK => ("AT_CLKTCK", "Frequency of times()", Dec),
+        AT_FPUCW => ("AT_FPUCW", "Used FPU control word", Hex),
+        AT_DCACHEBSIZE => ("AT_DCACHEBSIZE", "Data cache block size", Dec),
+        AT_ICACHEBSIZE => ("AT_ICACHEBSIZE", "Instruction cache block size", Dec),
+        AT_UCACHEBSIZE => ("AT_UCACHEBSIZE", "Unified cache block size", Dec),
+        AT_IGNOREPPC => ("AT_IGNOREPPC", "Entry should be ignored", Hex),
+        AT_SECURE => ("AT_SECURE", "Boolean, was exec setuid-like?", Dec),
+        AT_BASE_PLATFORM => ("AT_BASE_PLATFORM", "String identifying base platform", Str),
+        AT_RANDOM => ("AT_RANDOM", "Address of 16 random bytes", Hex),
+        AT_HWCAP2 => ("AT_HWCAP2", "Extension of AT_HWCAP", Hex),
+        AT_RSEQ_FEATURE_SIZE => ("AT_RSEQ_FEATURE_SIZE", "rseq supported feature size", Dec),
+        AT_RSEQ_ALIGN => ("AT_RSEQ_ALIGN", "rseq allocation alignment", Dec),
+        AT_HWCAP3 => ("AT_HWCAP3", "Extension of AT_HWCAP", Hex),
+        AT_HWCAP4 => ("AT_HWCAP4", "Extension of AT_HWCAP", Hex),
+        AT_EXECFN => ("AT_EXECFN", "File name of executable", Str),
+        AT_SYSINFO => ("AT_SYSINFO", "Special system info/entry points", Hex),
+        AT_SYSINFO_EHDR => ("AT_SYSINFO_EHDR", "System-supplied DSO's ELF header", Hex),
+        AT_MINSIGSTKSZ => ("AT_MINSIGSTKSZ", "Minimal stack size for signal delivery", Dec),
+        _ => return None,
+    })
+}
+
+/// An entry of the auxiliary vector the kernel gave the process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuxvEntry {
+    pub a_type: u64,
+    pub value: u64,
+}
+
+/// Reads the entries of an `NT_AUXV` note up to `AT_NULL`, which isn't
+/// included, or up to the end of the note if it's missing.
+pub fn read_auxv(desc: &[u8], endianness: Endianness, is_32: bool) -> Vec<AuxvEntry> {
+    let word = if is_32 { 4 } else { 8 };
+    desc.chunks(2 * word)
+        .filter(|pair| pair.len() == 2 * word)
+        .map(|pair| AuxvEntry {
+            a_type: read_addr(pair, &endianness, is_32),
+            value: read_addr(&pair[word..], &endianness, is_32),
+        })
+        .take_while(|entry| entry.a_type != AT_NULL)
+        .collect()
+}
@@ -0,0 +1,105 @@
+macro_rules! elf_chdr {
+    // Elf64_Chdr pads ch_type to align the 64-bit fields after it.
+    (b32) => {
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Chdr {
+            ch_type: Elf32_Word,
+            ch_size: Elf32_Word,
+            ch_addralign: Elf32_Word,
+        }
+
+        elf_chdr!(impl Elf32_Word, {});
+    };
+    (b64) => {
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Chdr {
+            ch_type: Elf64_Word,
+            ch_reserved: Elf64_Word,
+            ch_size: Elf64_Xword,
+            ch_addralign: Elf64_Xword,
+        }
+
+        elf_chdr!(impl Elf64_Xword, { ch_reserved: 0, });
+    };
+    (impl $xword:ty, { $($reserved:tt)* }) => {
+        use ::to_host::Endianness;
+        use ::to_host::to_host_copy::ToHostCopy;
+        use ::to_host::ToHostCopyStruct;
+
+        impl Elf_Chdr {
+            pub fn get_type(&self) -> u32 {
+                self.ch_type
+            }
+            pub fn get_size(&self) -> $xword {
+                self.ch_size
+            }
+            pub fn get_addralign(&self) -> $xword {
+                self.ch_addralign
+            }
+        }
+
+        impl ToHostCopyStruct for Elf_Chdr {
+            fn to_host_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Chdr {
+                    ch_type: self.ch_type.to_host_copy(e),
+                    $($reserved)*
+                    ch_size: self.ch_size.to_host_copy(e),
+                    ch_addralign: self.ch_addralign.to_host_copy(e),
+                }
+            }
+        }
+    }
+}
+
+use super::{Elf32_Chdr, Elf64_Chdr};
+
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+/// The compression header at the start of a `SHF_COMPRESSED` section, of a
+/// file of either class, in host byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfChdr {
+    ch_type: u32,
+    ch_size: u64,
+    ch_addralign: u64,
+}
+
+macro_rules! elf_chdr_from {
+    ($t:ty) => {
+        /// `chdr` must already be converted to host byte order.
+        impl<'a> From<&'a $t> for ElfChdr {
+            fn from(chdr: &'a $t) -> ElfChdr {
+                ElfChdr {
+                    ch_type: chdr.get_type(),
+                    ch_size: chdr.get_size() as u64,
+                    ch_addralign: chdr.get_addralign() as u64,
+                }
+            }
+        }
+    }
+}
+
+elf_chdr_from!(Elf32_Chdr);
+elf_chdr_from!(Elf64_Chdr);
+
+impl ElfChdr {
+    pub fn get_type(&self) -> u32 {
+        self.ch_type
+    }
+    /// The size of the section once decompressed.
+    pub fn get_size(&self) -> u64 {
+        self.ch_size
+    }
+    /// The alignment of the section once decompressed.
+    pub fn get_addralign(&self) -> u64 {
+        self.ch_addralign
+    }
+}
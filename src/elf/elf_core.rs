@@ -0,0 +1,757 @@
+use ::std::io::{self, Write};
+use ::to_host::Endianness;
+use super::elf_auxv::{AuxvEntry, AuxvFormat, get_auxv_type_info, read_auxv};
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_error::ElfError;
+use super::elf_file::ElfFile;
+use super::elf_note::{ElfNote, ElfNoteIter, read_word, read_addr};
+use super::elf_note::{NT_PRSTATUS, NT_FPREGSET, NT_PRPSINFO, NT_AUXV, NT_SIGINFO, NT_FILE};
+use super::elf_phdr_type::ElfPhdrType;
+use super::elf_version::read_half;
+
+// Linux signal numbers, as on x86 and ARM.
+pub const SIGHUP: i32 = 1;
+pub const SIGINT: i32 = 2;
+pub const SIGQUIT: i32 = 3;
+pub const SIGILL: i32 = 4;
+pub const SIGTRAP: i32 = 5;
+pub const SIGABRT: i32 = 6;
+pub const SIGBUS: i32 = 7;
+pub const SIGFPE: i32 = 8;
+pub const SIGKILL: i32 = 9;
+pub const SIGUSR1: i32 = 10;
+pub const SIGSEGV: i32 = 11;
+pub const SIGUSR2: i32 = 12;
+pub const SIGPIPE: i32 = 13;
+pub const SIGALRM: i32 = 14;
+pub const SIGTERM: i32 = 15;
+pub const SIGSTKFLT: i32 = 16;
+pub const SIGCHLD: i32 = 17;
+pub const SIGCONT: i32 = 18;
+pub const SIGSTOP: i32 = 19;
+pub const SIGTSTP: i32 = 20;
+pub const SIGTTIN: i32 = 21;
+pub const SIGTTOU: i32 = 22;
+pub const SIGURG: i32 = 23;
+pub const SIGXCPU: i32 = 24;
+pub const SIGXFSZ: i32 = 25;
+pub const SIGVTALRM: i32 = 26;
+pub const SIGPROF: i32 = 27;
+pub const SIGWINCH: i32 = 28;
+pub const SIGIO: i32 = 29;
+pub const SIGPWR: i32 = 30;
+pub const SIGSYS: i32 = 31;
+
+// `si_code` values any signal may have.
+pub const SI_USER: i32 = 0;
+pub const SI_KERNEL: i32 = 0x80;
+pub const SI_QUEUE: i32 = -1;
+pub const SI_TIMER: i32 = -2;
+pub const SI_MESGQ: i32 = -3;
+pub const SI_ASYNCIO: i32 = -4;
+pub const SI_SIGIO: i32 = -5;
+pub const SI_TKILL: i32 = -6;
+
+/// Returns the name of a Linux signal number.
+pub fn get_signal_name(signo: i32) -> Option<&'static str> {
+    Some(match signo {
+        SIGHUP => "SIGHUP",
+        SIGINT => "SIGINT",
+        SIGQUIT => "SIGQUIT",
+        SIGILL => "SIGILL",
+        SIGTRAP => "SIGTRAP",
+        SIGABRT => "SIGABRT",
+        SIGBUS => "SIGBUS",
+        SIGFPE => "SIGFPE",
+        SIGKILL => "SIGKILL",
+        SIGUSR1 => "SIGUSR1",
+        SIGSEGV => "SIGSEGV",
+        SIGUSR2 => "SIGUSR2",
+        SIGPIPE => "SIGPIPE",
+        SIGALRM => "SIGALRM",
+        SIGTERM => "SIGTERM",
+        SIGSTKFLT => "SIGSTKFLT",
+        SIGCHLD => "SIGCHLD",
+        SIGCONT => "SIGCONT",
+        SIGSTOP => "SIGSTOP",
+        SIGTSTP => "SIGTSTP",
+        SIGTTIN => "SIGTTIN",
+        SIGTTOU => "SIGTTOU",
+        SIGURG => "SIGURG",
+        SIGXCPU => "SIGXCPU",
+        SIGXFSZ => "SIGXFSZ",
+        SIGVTALRM => "SIGVTALRM",
+        SIGPROF => "SIGPROF",
+        SIGWINCH => "SIGWINCH",
+        SIGIO => "SIGIO",
+        SIGPWR => "SIGPWR",
+        SIGSYS => "SIGSYS",
+        _ => return None,
+    })
+}
+
+/// Returns the name of the `si_code` of a signal: why the kernel sent a
+/// fault signal, or who sent any other.
+pub fn get_si_code_name(signo: i32, code: i32) -> Option<&'static str> {
+    let name = match (signo, code) {
+        (SIGILL, 1) => "ILL_ILLOPC",
+        (SIGILL, 2) => "ILL_ILLOPN",
+        (SIGILL, 3) => "ILL_ILLADR",
+        (SIGILL, 4) => "ILL_ILLTRP",
+        (SIGILL, 5) => "ILL_PRVOPC",
+        (SIGILL, 6) => "ILL_PRVREG",
+        (SIGILL, 7) => "ILL_COPROC",
+        (SIGILL, 8) => "ILL_BADSTK",
+        (SIGFPE, 1) => "FPE_INTDIV",
+        (SIGFPE, 2) => "FPE_INTOVF",
+        (SIGFPE, 3) => "FPE_FLTDIV",
+        (SIGFPE, 4) => "FPE_FLTOVF",
+        (SIGFPE, 5) => "FPE_FLTUND",
+        (SIGFPE, 6) => "FPE_FLTRES",
+        (SIGFPE, 7) => "FPE_FLTINV",
+        (SIGFPE, 8) => "FPE_FLTSUB",
+        (SIGSEGV, 1) => "SEGV_MAPERR",
+        (SIGSEGV, 2) => "SEGV_ACCERR",
+        (SIGSEGV, 3) => "SEGV_BNDERR",
+        (SIGSEGV, 4) => "SEGV_PKUERR",
+        (SIGBUS, 1) => "BUS_ADRALN",
+        (SIGBUS, 2) => "BUS_ADRERR",
+        (SIGBUS, 3) => "BUS_OBJERR",
+        (SIGBUS, 4) => "BUS_MCEERR_AR",
+        (SIGBUS, 5) => "BUS_MCEERR_AO",
+        (SIGTRAP, 1) => "TRAP_BRKPT",
+        (SIGTRAP, 2) => "TRAP_TRACE",
+        (SIGTRAP, 3) => "TRAP_BRANCH",
+        (SIGTRAP, 4) => "TRAP_HWBKPT",
+        (_, SI_USER) => "SI_USER",
+        (_, SI_KERNEL) => "SI_KERNEL",
+        (_, SI_QUEUE) => "SI_QUEUE",
+        (_, SI_TIMER) => "SI_TIMER",
+        (_, SI_MESGQ) => "SI_MESGQ",
+        (_, SI_ASYNCIO) => "SI_ASYNCIO",
+        (_, SI_SIGIO) => "SI_SIGIO",
+        (_, SI_TKILL) => "SI_TKILL",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Formats a signal number with its name, e.g. `SIGSEGV (11)`.
+fn format_signal(signo: i32) -> String {
+    match get_signal_name(signo) {
+        Some(name) => format!("{} ({})", name, signo),
+        None => format!("{}", signo),
+    }
+}
+
+/// Names of the general purpose registers in the order `elf_gregset_t`
+/// holds them, or `None` for machines whose layout isn't known.
+pub fn get_register_names(machine: ElfEhdrMachine) -> Option<&'static [&'static str]> {
+    const X86_64: &[&str] = &[
+        "r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8",
+        "rax", "rcx", "rdx", "rsi", "rdi", "orig_rax", "rip", "cs", "eflags",
+        "rsp", "ss", "fs_base", "gs_base", "ds", "es", "fs", "gs",
+    ];
+    const I386: &[&str] = &[
+        "ebx", "ecx", "edx", "esi", "edi", "ebp", "eax", "ds", "es", "fs", "gs",
+        "orig_eax", "eip", "cs", "eflags", "esp", "ss",
+    ];
+    const AARCH64: &[&str] = &[
+        "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10",
+        "x11", "x12", "x13", "x14", "x15", "x16", "x17", "x18", "x19", "x20",
+        "x21", "x22", "x23", "x24", "x25", "x26", "x27", "x28", "x29", "x30",
+        "sp", "pc", "pstate",
+    ];
+    match machine {
+        ElfEhdrMachine::EM_X86_64 => Some(X86_64),
+        ElfEhdrMachine::EM_386 => Some(I386),
+        ElfEhdrMachine::EM_AARCH64 => Some(AARCH64),
+        _ => None,
+    }
+}
+
+/// A time in seconds and microseconds, like `struct timeval`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeVal {
+    pub sec: u64,
+    pub usec: u64,
+}
+
+/// The status of a thread, from an `NT_PRSTATUS` note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrStatus {
+    pub signo: i32,
+    pub code: i32,
+    pub errno: i32,
+    /// The signal the thread was handling.
+    pub cursig: u16,
+    pub sigpend: u64,
+    pub sighold: u64,
+    pub pid: i32,
+    pub ppid: i32,
+    pub pgrp: i32,
+    pub sid: i32,
+    pub utime: TimeVal,
+    pub stime: TimeVal,
+    pub cutime: TimeVal,
+    pub cstime: TimeVal,
+    /// The general purpose registers, see `get_register_names`.
+    pub regs: Vec<u64>,
+}
+
+impl PrStatus {
+    /// Reads the note of a thread of a `machine` process. Returns `None`
+    /// if it's too short for the registers.
+    pub fn parse(desc: &[u8], e: Endianness, is_32: bool, machine: ElfEhdrMachine)
+        -> Option<PrStatus>
+    {
+        // Everything after the three ints of `pr_info` and `pr_cursig`
+        // is word-sized or word-aligned.
+        let w = if is_32 { 4 } else { 8 };
+        let regs_offset = 32 + 10 * w;
+        let nregs = match get_register_names(machine) {
+            Some(names) => names.len(),
+            // The registers are followed by the int `pr_fpvalid`, padded
+            // to a word.
+            None => desc.len().checked_sub(regs_offset + w)? / w,
+        };
+        if desc.len() < regs_offset + nregs * w {
+            return None;
+        }
+
+        let word = |offset: usize| read_addr(&desc[offset..], &e, is_32);
+        let int = |offset: usize| read_word(&desc[offset..], &e) as i32;
+        let timeval = |offset: usize| TimeVal { sec: word(offset), usec: word(offset + w) };
+        let times = 32 + 2 * w;
+        Some(PrStatus {
+            signo: int(0),
+            code: int(4),
+            errno: int(8),
+            cursig: read_half(&desc[12..], &e),
+            sigpend: word(16),
+            sighold: word(16 + w),
+            pid: int(16 + 2 * w),
+            ppid: int(20 + 2 * w),
+            pgrp: int(24 + 2 * w),
+            sid: int(28 + 2 * w),
+            utime: timeval(times),
+            stime: timeval(times + 2 * w),
+            cutime: timeval(times + 4 * w),
+            cstime: timeval(times + 6 * w),
+            regs: (0..nregs).map(|i| word(regs_offset + i * w)).collect(),
+        })
+    }
+}
+
+/// The process information of an `NT_PRPSINFO` note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrPsInfo {
+    pub state: u8,
+    /// The state as a letter, as `ps` shows it.
+    pub sname: char,
+    pub zomb: u8,
+    pub nice: i8,
+    pub flag: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub pid: i32,
+    pub ppid: i32,
+    pub pgrp: i32,
+    pub sid: i32,
+    /// The file name of the executable, truncated to 15 bytes.
+    pub fname: String,
+    /// The start of the command line, truncated to 79 bytes.
+    pub psargs: String,
+}
+
+impl PrPsInfo {
+    /// Reads the note. Returns `None` if it's too short.
+    pub fn parse(desc: &[u8], e: Endianness, is_32: bool) -> Option<PrPsInfo> {
+        // The IDs are 16-bit on some 32-bit machines, i386 and ARM among
+        // them, which only shows in the size of the note.
+        let (w, id_size) = match (is_32, desc.len()) {
+            (false, _) => (8, 4),
+            (true, len) if len < 128 => (4, 2),
+            (true, _) => (4, 4),
+        };
+        let ids = 2 * w + 2 * id_size;
+        let fname = ids + 16;
+        let psargs = fname + 16;
+        if desc.len() < psargs + 80 {
+            return None;
+        }
+
+        let id = |offset: usize| if id_size == 2 {
+            read_half(&desc[offset..], &e) as u32
+        } else {
+            read_word(&desc[offset..], &e)
+        };
+        let int = |offset: usize| read_word(&desc[offset..], &e) as i32;
+        let string = |bytes: &[u8]| {
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..end]).into_owned()
+        };
+        Some(PrPsInfo {
+            state: desc[0],
+            sname: desc[1] as char,
+            zomb: desc[2],
+            nice: desc[3] as i8,
+            flag: read_addr(&desc[w..], &e, is_32),
+            uid: id(w + w),
+            gid: id(w + w + id_size),
+            pid: int(ids),
+            ppid: int(ids + 4),
+            pgrp: int(ids + 8),
+            sid: int(ids + 12),
+            fname: string(&desc[fname..fname + 16]),
+            psargs: string(&desc[psargs..psargs + 80]),
+        })
+    }
+}
+
+/// The signal that killed the process, from an `NT_SIGINFO` note.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SigInfo {
+    pub signo: i32,
+    pub errno: i32,
+    pub code: i32,
+    /// The faulting address, for faults the kernel found.
+    pub addr: Option<u64>,
+    /// The PID and UID of the sender, for signals sent by a process.
+    pub sender: Option<(i32, u32)>,
+}
+
+impl SigInfo {
+    /// Reads the note. Returns `None` if it's too short.
+    pub fn parse(desc: &[u8], e: Endianness, is_32: bool) -> Option<SigInfo> {
+        // The union is aligned to a pointer.
+        let union = if is_32 { 12 } else { 16 };
+        if desc.len() < union + 8 {
+            return None;
+        }
+
+        let int = |offset: usize| read_word(&desc[offset..], &e) as i32;
+        let (signo, code) = (int(0), int(8));
+        let is_fault = match signo {
+            SIGILL | SIGFPE | SIGSEGV | SIGBUS | SIGTRAP => code > 0 && code != SI_KERNEL,
+            _ => false,
+        };
+        let is_sent = code <= 0 && code != SI_TIMER;
+        Some(SigInfo {
+            signo,
+            errno: int(4),
+            code,
+            addr: if is_fault { Some(read_addr(&desc[union..], &e, is_32)) } else { None },
+            sender: if is_sent {
+                Some((int(union), read_word(&desc[union + 4..], &e)))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// A file mapped into the process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappedFile {
+    pub start: u64,
+    pub end: u64,
+    /// The offset in the file, in pages.
+    pub page_offset: u64,
+    pub name: String,
+}
+
+/// The files mapped into the process, from an `NT_FILE` note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappedFiles {
+    pub page_size: u64,
+    pub files: Vec<MappedFile>,
+}
+
+/// Reads an `NT_FILE` note: the number of files and the page size, the
+/// address range and page offset of each file, then their names. Errors
+/// are worded like readelf's.
+pub fn read_mapped_files(desc: &[u8], e: Endianness, is_32: bool)
+    -> Result<MappedFiles, &'static str>
+{
+    let w = if is_32 { 4 } else { 8 };
+    if desc.len() < 2 * w {
+        return Err("too short for header");
+    }
+    if desc.last() != Some(&0) {
+        return Err("does not end with \\0");
+    }
+    let count = read_addr(desc, &e, is_32);
+    let page_size = read_addr(&desc[w..], &e, is_32);
+    let names_offset = count.checked_mul(3 * w as u64)
+        .and_then(|size| size.checked_add(2 * w as u64))
+        .filter(|&offset| offset <= desc.len() as u64)
+        .ok_or("too short for supplied file count")? as usize;
+
+    let mut name_offset = names_offset;
+    let mut files = Vec::new();
+    for i in 0..count as usize {
+        let entry = &desc[2 * w + i * 3 * w..];
+        if name_offset >= desc.len() {
+            return Err("filenames end too early");
+        }
+        // The note ends with a NUL, so every name is terminated.
+        let len = desc[name_offset..].iter().position(|&b| b == 0).unwrap();
+        let name = &desc[name_offset..name_offset + len];
+        name_offset += len + 1;
+        files.push(MappedFile {
+            start: read_addr(entry, &e, is_32),
+            end: read_addr(&entry[w..], &e, is_32),
+            page_offset: read_addr(&entry[2 * w..], &e, is_32),
+            name: String::from_utf8_lossy(name).into_owned(),
+        });
+    }
+    Ok(MappedFiles { page_size, files })
+}
+
+/// The floating point state of a thread, from an `NT_FPREGSET` note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FpRegisters {
+    /// The control and status registers, by name.
+    pub control: Vec<(&'static str, u64)>,
+    /// The x87 stack, `st0` to `st7`, converted to `f64`.
+    pub x87: Vec<f64>,
+    /// The name prefix of the vector registers: `xmm` or `v`.
+    pub vector_prefix: &'static str,
+    pub vectors: Vec<u128>,
+}
+
+impl FpRegisters {
+    /// Reads the note of a thread of a `machine` process. Returns `None`
+    /// for machines whose layout isn't known and for short notes.
+    pub fn parse(desc: &[u8], e: Endianness, machine: ElfEhdrMachine)
+        -> Option<FpRegisters>
+    {
+        let half = |offset: usize| read_half(&desc[offset..], &e) as u64;
+        let word = |offset: usize| read_word(&desc[offset..], &e) as u64;
+        let xword = |offset: usize| read_addr(&desc[offset..], &e, false);
+        let vector = |offset: usize| {
+            let (first, second) = (xword(offset) as u128, xword(offset + 8) as u128);
+            if e == Endianness::LE { second << 64 | first } else { first << 64 | second }
+        };
+        let x87 = |offset: usize| {
+            x87_to_f64(xword(offset), read_half(&desc[offset + 8..], &e))
+        };
+
+        match machine {
+            // The FXSAVE area.
+            ElfEhdrMachine::EM_X86_64 if desc.len() >= 416 => Some(FpRegisters {
+                control: vec![
+                    ("fcw", half(0)), ("fsw", half(2)), ("ftw", desc[4] as u64),
+                    ("fop", half(6)), ("rip", xword(8)), ("rdp", xword(16)),
+                    ("mxcsr", word(24)), ("mxcsr_mask", word(28)),
+                ],
+                x87: (0..8).map(|i| x87(32 + 16 * i)).collect(),
+                vector_prefix: "xmm",
+                vectors: (0..16).map(|i| vector(160 + 16 * i)).collect(),
+            }),
+            // The FSAVE area, `user_i387_struct`.
+            ElfEhdrMachine::EM_386 if desc.len() >= 108 => Some(FpRegisters {
+                // The selectors and the 16-bit registers are stored in
+                // words whose upper halves are reserved.
+                control: vec![
+                    ("cwd", half(0)), ("swd", half(4)), ("twd", half(8)),
+                    ("fip", word(12)), ("fcs", half(16)), ("foo", word(20)),
+                    ("fos", half(24)),
+                ],
+                x87: (0..8).map(|i| x87(28 + 10 * i)).collect(),
+                vector_prefix: "xmm",
+                vectors: Vec::new(),
+            }),
+            // `user_fpsimd_struct`.
+            ElfEhdrMachine::EM_AARCH64 if desc.len() >= 520 => Some(FpRegisters {
+                control: vec![("fpsr", word(512)), ("fpcr", word(516))],
+                x87: Vec::new(),
+                vector_prefix: "v",
+                vectors: (0..32).map(|i| vector(16 * i)).collect(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Prints the registers, indented under the ones of the thread.
+    pub fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "  Floating point registers:")?;
+        for row in self.control.chunks(4) {
+            write!(out, "   ")?;
+            for &(name, value) in row {
+                write!(out, " {:<10} {:#010x}", name, value)?;
+            }
+            writeln!(out)?;
+        }
+        for (i, value) in self.x87.iter().enumerate() {
+            writeln!(out, "    st{:<8} {}", i, value)?;
+        }
+        for (i, value) in self.vectors.iter().enumerate() {
+            let name = format!("{}{}", self.vector_prefix, i);
+            writeln!(out, "    {:<10} 0x{:032x}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts an x87 extended precision number, given as its 64-bit
+/// significand with the explicit integer bit and its sign and exponent.
+fn x87_to_f64(significand: u64, sign_exponent: u16) -> f64 {
+    let sign = if sign_exponent & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (sign_exponent & 0x7fff) as i32;
+    if exponent == 0x7fff {
+        return if significand << 1 == 0 { sign * f64::INFINITY } else { f64::NAN };
+    }
+    // Denormals have the exponent of 1 instead of 0. Scale in two steps so
+    // that they don't underflow before the significand is applied.
+    let exponent = if exponent == 0 { 1 } else { exponent } - 16383;
+    let value = significand as f64 / 2f64.powi(63);
+    sign * value * 2f64.powi(exponent / 2) * 2f64.powi(exponent - exponent / 2)
+}
+
+/// A thread of the process: its status and floating point state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreThread {
+    pub status: PrStatus,
+    pub fpregs: Option<FpRegisters>,
+}
+
+/// The process state a core dump records in its notes.
+pub struct CoreFile<'a, 'data: 'a> {
+    elf: &'a ElfFile<'data>,
+    pub process: Option<PrPsInfo>,
+    pub signal: Option<SigInfo>,
+    /// The threads, the one that got the signal first, as Linux writes
+    /// them.
+    pub threads: Vec<CoreThread>,
+    pub files: Option<MappedFiles>,
+    pub auxv: Vec<AuxvEntry>,
+    /// What was wrong with the notes, for the caller to report.
+    pub warnings: Vec<String>,
+}
+
+impl<'a, 'data> CoreFile<'a, 'data> {
+    /// Decodes the notes in the `PT_NOTE` segments of a core dump. Notes
+    /// too short for their structure are skipped with a warning in
+    /// `warnings`.
+    pub fn new(elf: &'a ElfFile<'data>) -> Result<CoreFile<'a, 'data>, ElfError> {
+        let mut core = CoreFile {
+            elf,
+            process: None,
+            signal: None,
+            threads: Vec::new(),
+            files: None,
+            auxv: Vec::new(),
+            warnings: Vec::new(),
+        };
+        for phdr in elf.program_headers()? {
+            if phdr.get_type() != ElfPhdrType::PT_NOTE {
+                continue;
+            }
+            let data = elf.read_bytes(phdr.get_offset(), phdr.get_filesz(), "notes")?;
+            match ElfNoteIter::new(data, elf.endianness(), phdr.get_align(), true) {
+                Some(mut notes) => {
+                    for note in notes.by_ref() {
+                        core.add_note(&note);
+                    }
+                    if notes.is_truncated() {
+                        core.warnings.push(String::from(
+                            "note with invalid namesz and/or descsz found"));
+                    }
+                }
+                None => core.warnings.push(format!(
+                    "Corrupt note: alignment {}, expecting 4 or 8", phdr.get_align())),
+            }
+        }
+        Ok(core)
+    }
+
+    fn add_note(&mut self, note: &ElfNote) {
+        if note.get_name() != "CORE" {
+            return;
+        }
+        let (desc, e, is_32) = (note.get_desc(), self.elf.endianness(), self.elf.is_32());
+        let machine = self.elf.machine();
+        let short = |what: &str| format!("{} note is too short: {} bytes", what, desc.len());
+        let mut warning = None;
+        match note.get_type() {
+            NT_PRSTATUS => match PrStatus::parse(desc, e, is_32, machine) {
+                Some(status) => self.threads.push(CoreThread { status, fpregs: None }),
+                None => warning = Some(short("NT_PRSTATUS")),
+            },
+            NT_FPREGSET => {
+                let fpregs = FpRegisters::parse(desc, e, machine);
+                match self.threads.last_mut() {
+                    Some(thread) => thread.fpregs = fpregs,
+                    None => {
+                        warning = Some("NT_FPREGSET note comes before NT_PRSTATUS".to_string());
+                    }
+                }
+            }
+            NT_PRPSINFO => match PrPsInfo::parse(desc, e, is_32) {
+                Some(process) => self.process = Some(process),
+                None => warning = Some(short("NT_PRPSINFO")),
+            },
+            NT_SIGINFO => match SigInfo::parse(desc, e, is_32) {
+                Some(signal) => self.signal = Some(signal),
+                None => warning = Some(short("NT_SIGINFO")),
+            },
+            NT_FILE => match read_mapped_files(desc, e, is_32) {
+                Ok(files) => self.files = Some(files),
+                Err(err) => warning = Some(format!("Malformed NT_FILE note - {}", err)),
+            },
+            NT_AUXV => self.auxv = read_auxv(desc, e, is_32),
+            _ => {}
+        }
+        self.warnings.extend(warning);
+    }
+
+    /// Reads the NUL-terminated string at `addr` in the memory of the
+    /// process, if it's in the dump.
+    pub fn read_string(&self, addr: u64) -> Option<String> {
+        let phdr = self.elf.program_headers().ok()?.iter()
+            .find(|p| p.get_type() == ElfPhdrType::PT_LOAD && p.contains_vaddr(addr))?;
+        let skip = addr - phdr.get_vaddr();
+        let bytes = self.elf.read_bytes(
+            phdr.get_offset() + skip, phdr.get_filesz() - skip, "memory").ok()?;
+        let end = bytes.iter().position(|&b| b == 0)?;
+        Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+
+    /// Prints the process, the signal that killed it, its threads, mapped
+    /// files and auxiliary vector.
+    pub fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        if let Some(ref process) = self.process {
+            self.print_process(process, out)?;
+        }
+        self.print_signal(out)?;
+        for (i, thread) in self.threads.iter().enumerate() {
+            self.print_thread(thread, i == 0, out)?;
+        }
+        if let Some(ref files) = self.files {
+            self.print_files(files, out)?;
+        }
+        if !self.auxv.is_empty() {
+            self.print_auxv(out)?;
+        }
+        Ok(())
+    }
+
+    fn print_process(&self, process: &PrPsInfo, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Process {} ({})", process.pid, process.fname)?;
+        writeln!(out, "  Command line: {}", process.psargs.trim_end())?;
+        writeln!(out, "  Parent: {}, process group: {}, session: {}",
+                 process.ppid, process.pgrp, process.sid)?;
+        writeln!(out, "  UID: {}, GID: {}, state: {}, nice: {}, flags: {:#x}",
+                 process.uid, process.gid, process.sname, process.nice, process.flag)?;
+        writeln!(out)
+    }
+
+    /// Prints the signal from `NT_SIGINFO`, or the one the first thread
+    /// was handling if there's no such note.
+    fn print_signal(&self, out: &mut dyn Write) -> io::Result<()> {
+        let signal = match self.signal {
+            Some(signal) => signal,
+            None => {
+                match self.threads.first() {
+                    Some(thread) if thread.status.cursig != 0 => writeln!(
+                        out,
+                        "Signal: {}", format_signal(thread.status.cursig as i32))?,
+                    _ => writeln!(out, "Signal: none")?,
+                }
+                return writeln!(out);
+            }
+        };
+        write!(out, "Signal: {}", format_signal(signal.signo))?;
+        match get_si_code_name(signal.signo, signal.code) {
+            Some(name) => write!(out, ", code {} ({})", name, signal.code)?,
+            None => write!(out, ", code {}", signal.code)?,
+        }
+        if signal.errno != 0 {
+            write!(out, ", errno {}", signal.errno)?;
+        }
+        if let Some(addr) = signal.addr {
+            write!(out, ", fault address {:#x}", addr)?;
+        }
+        if let Some((pid, uid)) = signal.sender {
+            write!(out, ", sent by PID {} UID {}", pid, uid)?;
+        }
+        writeln!(out)?;
+        writeln!(out)
+    }
+
+    fn print_thread(&self, thread: &CoreThread, is_crashing: bool, out: &mut dyn Write)
+        -> io::Result<()>
+    {
+        let status = &thread.status;
+        if is_crashing {
+            writeln!(out, "Thread {} (crashing):", status.pid)?;
+        } else {
+            writeln!(out, "Thread {}:", status.pid)?;
+        }
+        writeln!(out, "  User time: {}.{:06}, system time: {}.{:06}",
+                 status.utime.sec, status.utime.usec, status.stime.sec, status.stime.usec)?;
+        writeln!(out, "  Pending signals: {:#x}, blocked signals: {:#x}",
+                 status.sigpend, status.sighold)?;
+
+        let names = get_register_names(self.elf.machine());
+        let (width, per_row) = if self.elf.is_32() { (8, 4) } else { (16, 3) };
+        let regs: Vec<(String, u64)> = status.regs.iter().enumerate()
+            .map(|(i, &value)| {
+                let name = match names {
+                    Some(names) => String::from(names[i]),
+                    None => format!("r{}", i),
+                };
+                (name, value)
+            })
+            .collect();
+        let name_width = regs.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for row in regs.chunks(per_row) {
+            write!(out, " ")?;
+            for (name, value) in row {
+                write!(out, " {:<nw$} 0x{:0w$x}", name, value, nw = name_width, w = width)?;
+            }
+            writeln!(out)?;
+        }
+        if let Some(ref fpregs) = thread.fpregs {
+            fpregs.print(out)?;
+        }
+        writeln!(out)
+    }
+
+    fn print_files(&self, files: &MappedFiles, out: &mut dyn Write) -> io::Result<()> {
+        let width = if self.elf.is_32() { 8 } else { 16 };
+        writeln!(out, "Mapped files (page size {}):", files.page_size)?;
+        writeln!(out, "  {:<w$} {:<w$} {:<w$} File", "Start", "End", "Offset", w = width + 2)?;
+        for file in &files.files {
+            writeln!(out, "  0x{:0w$x} 0x{:0w$x} 0x{:0w$x} {}",
+                     file.start, file.end, file.page_offset.wrapping_mul(files.page_size),
+                     file.name, w = width)?;
+        }
+        writeln!(out)
+    }
+
+    fn print_auxv(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Auxiliary vector:")?;
+        for entry in &self.auxv {
+            let (name, description, format) = match get_auxv_type_info(entry.a_type) {
+                Some(info) => info,
+                None => {
+                    writeln!(out, "  {:<20} {:<40} {:#x}", entry.a_type, "", entry.value)?;
+                    continue;
+                }
+            };
+            write!(out, "  {:<20} {:<40} ", name, description)?;
+            match format {
+                AuxvFormat::Hex => writeln!(out, "{:#x}", entry.value)?,
+                AuxvFormat::Dec => writeln!(out, "{}", entry.value)?,
+                AuxvFormat::Str => match self.read_string(entry.value) {
+                    Some(s) => writeln!(out, "{:#x} \"{}\"", entry.value, s)?,
+                    None => writeln!(out, "{:#x}", entry.value)?,
+                },
+            }
+        }
+        Ok(())
+    }
+}
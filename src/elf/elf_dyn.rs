@@ -0,0 +1,147 @@
+macro_rules! elf_dyn {
+    ($xword:ty) => {
+        use ::to_host::Endianness;
+        use ::to_host::to_host_copy::ToHostCopy;
+        use ::to_host::ToHostCopyStruct;
+        use ::to_host::to_target_copy::ToTargetCopy;
+        use ::to_host::ToTargetCopyStruct;
+        use super::super::elf_dyn::ElfDyn;
+        use super::super::elf_dyn_tag::ElfDynTag;
+        use super::primitive::*;
+
+        // d_tag is signed in the specification; see ElfDynTag.
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Dyn {
+            d_tag: $xword,
+            d_val: $xword,
+        }
+
+        impl Elf_Dyn {
+            pub fn get_tag(&self) -> ElfDynTag {
+                ElfDynTag::from(self.d_tag as u64)
+            }
+            pub fn get_val(&self) -> $xword {
+                self.d_val
+            }
+        }
+
+        impl ToHostCopyStruct for Elf_Dyn {
+            fn to_host_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Dyn {
+                    d_tag: self.d_tag.to_host_copy(e),
+                    d_val: self.d_val.to_host_copy(e),
+                }
+            }
+        }
+
+        impl ToTargetCopyStruct for Elf_Dyn {
+            fn to_target_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Dyn {
+                    d_tag: self.d_tag.to_target_copy(e),
+                    d_val: self.d_val.to_target_copy(e),
+                }
+            }
+        }
+
+        /// Returns `d` in host byte order, to be converted with
+        /// `to_target_copy`. The value is truncated to the class.
+        impl<'a> From<&'a ElfDyn> for Elf_Dyn {
+            fn from(d: &'a ElfDyn) -> Elf_Dyn {
+                Elf_Dyn {
+                    d_tag: d.get_tag().get() as $xword,
+                    d_val: d.get_val() as $xword,
+                }
+            }
+        }
+    }
+}
+
+use ::std::io::{self, Write};
+use ::json::Json;
+use super::elf_dyn_tag::ElfDynTag;
+use super::{Elf32_Dyn, Elf64_Dyn};
+
+/// A dynamic section entry of a file of either class, in host byte order.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfDyn {
+    d_tag: u64,
+    d_val: u64,
+}
+
+macro_rules! elf_dyn_from {
+    ($t:ty) => {
+        /// `d` must already be converted to host byte order.
+        impl<'a> From<&'a $t> for ElfDyn {
+            fn from(d: &'a $t) -> ElfDyn {
+                ElfDyn {
+                    d_tag: d.get_tag().get(),
+                    d_val: d.get_val() as u64,
+                }
+            }
+        }
+    }
+}
+
+elf_dyn_from!(Elf32_Dyn);
+elf_dyn_from!(Elf64_Dyn);
+
+impl ElfDyn {
+    pub fn new(d_tag: u64, d_val: u64) -> ElfDyn {
+        ElfDyn { d_tag, d_val }
+    }
+    pub fn get_tag(&self) -> ElfDynTag {
+        ElfDynTag::from(self.d_tag)
+    }
+    pub fn get_val(&self) -> u64 {
+        self.d_val
+    }
+    pub fn set_val(&mut self, d_val: u64) {
+        self.d_val = d_val;
+    }
+
+    /// Describes the entry for `--output=json`. `text` is the value the
+    /// way `print_row` shows it.
+    pub fn to_json(&self, strtab: &[u8]) -> Json {
+        let tag = self.get_tag();
+        Json::object(vec![
+            ("tag", Json::named(self.d_tag, tag.to_string())),
+            ("value", self.d_val.into()),
+            ("text", tag.format_value(self.d_val, strtab).into()),
+        ])
+    }
+
+    /// Prints a row of readelf's `-d` table.
+    /// `strtab` is the dynamic string table, used for library names
+    /// and paths.
+    pub fn print_row(&self, strtab: &[u8], is_32: bool, out: &mut dyn Write) -> io::Result<()> {
+        let tag = self.get_tag();
+        let tag_name = format!("{}", tag);
+        let tag_width = if is_32 { 8 } else { 16 };
+        // readelf aligns values by padding the name to a fixed
+        // column, but always leaves at least one space.
+        let column = if is_32 { 27 } else { 19 };
+        let padding = (column as isize - tag_name.len() as isize)
+            .abs()
+            .max(1) as usize;
+
+        writeln!(
+            out,
+            " {:#0width$x} ({}){:padding$}{}",
+            self.d_tag,
+            tag_name,
+            "",
+            tag.format_value(self.d_val, strtab),
+            width = tag_width + 2,
+            padding = padding)
+    }
+}
+
+/// Returns the value of the first dynamic entry with `tag`.
+pub fn get_dyn_val(dyns: &[ElfDyn], tag: u64) -> Option<u64> {
+    dyns.iter()
+        .find(|d| d.d_tag == tag)
+        .map(|d| d.d_val)
+}
@@ -0,0 +1,47 @@
+use ::std::fmt::{Display, Formatter};
+
+pub const DF_ORIGIN: u64 = 0x1;
+pub const DF_SYMBOLIC: u64 = 0x2;
+pub const DF_TEXTREL: u64 = 0x4;
+pub const DF_BIND_NOW: u64 = 0x8;
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+/// Value of the `DT_FLAGS` dynamic entry.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfDynFlags {
+    flags: u64,
+}
+
+impl From<u64> for ElfDynFlags {
+    fn from(flags: u64) -> ElfDynFlags {
+        ElfDynFlags { flags }
+    }
+}
+
+impl ElfDynFlags {
+    pub fn contains(&self, flag: u64) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+impl Display for ElfDynFlags {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let mut names = Vec::new();
+        for bit in 0..64 {
+            let flag = 1u64 << bit;
+            if self.flags & flag == 0 {
+                continue;
+            }
+            names.push(match flag {
+                DF_ORIGIN => "ORIGIN",
+                DF_SYMBOLIC => "SYMBOLIC",
+                DF_TEXTREL => "TEXTREL",
+                DF_BIND_NOW => "BIND_NOW",
+                DF_STATIC_TLS => "STATIC_TLS",
+                _ => "unknown",
+            });
+        }
+        fmt.pad(&names.join(" "))
+    }
+}
@@ -0,0 +1,111 @@
+use ::std::fmt::{Display, Formatter};
+
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_GLOBAL: u64 = 0x2;
+pub const DF_1_GROUP: u64 = 0x4;
+pub const DF_1_NODELETE: u64 = 0x8;
+pub const DF_1_LOADFLTR: u64 = 0x10;
+pub const DF_1_INITFIRST: u64 = 0x20;
+pub const DF_1_NOOPEN: u64 = 0x40;
+pub const DF_1_ORIGIN: u64 = 0x80;
+pub const DF_1_DIRECT: u64 = 0x100;
+pub const DF_1_TRANS: u64 = 0x200;
+pub const DF_1_INTERPOSE: u64 = 0x400;
+pub const DF_1_NODEFLIB: u64 = 0x800;
+pub const DF_1_NODUMP: u64 = 0x1000;
+pub const DF_1_CONFALT: u64 = 0x2000;
+pub const DF_1_ENDFILTEE: u64 = 0x4000;
+pub const DF_1_DISPRELDNE: u64 = 0x8000;
+pub const DF_1_DISPRELPND: u64 = 0x10000;
+pub const DF_1_NODIRECT: u64 = 0x20000;
+pub const DF_1_IGNMULDEF: u64 = 0x40000;
+pub const DF_1_NOKSYMS: u64 = 0x80000;
+pub const DF_1_NOHDR: u64 = 0x100000;
+pub const DF_1_EDITED: u64 = 0x200000;
+pub const DF_1_NORELOC: u64 = 0x400000;
+pub const DF_1_SYMINTPOSE: u64 = 0x800000;
+pub const DF_1_GLOBAUDIT: u64 = 0x1000000;
+pub const DF_1_SINGLETON: u64 = 0x2000000;
+pub const DF_1_STUB: u64 = 0x4000000;
+pub const DF_1_PIE: u64 = 0x8000000;
+pub const DF_1_KMOD: u64 = 0x10000000;
+pub const DF_1_WEAKFILTER: u64 = 0x20000000;
+pub const DF_1_NOCOMMON: u64 = 0x40000000;
+
+/// Value of the `DT_FLAGS_1` dynamic entry.
+///
+/// Displayed as a list of names, each preceded by a space, so it can follow
+/// readelf's `Flags:` label directly.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfDynFlags1 {
+    flags: u64,
+}
+
+impl From<u64> for ElfDynFlags1 {
+    fn from(flags: u64) -> ElfDynFlags1 {
+        ElfDynFlags1 { flags }
+    }
+}
+
+impl ElfDynFlags1 {
+    pub fn contains(&self, flag: u64) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+impl Display for ElfDynFlags1 {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let mut s = String::new();
+        let mut unknown = 0;
+        for bit in 0..64 {
+            let flag = 1u64 << bit;
+            if self.flags & flag == 0 {
+                continue;
+            }
+            let name = match flag {
+                DF_1_NOW => "NOW",
+                DF_1_GLOBAL => "GLOBAL",
+                DF_1_GROUP => "GROUP",
+                DF_1_NODELETE => "NODELETE",
+                DF_1_LOADFLTR => "LOADFLTR",
+                DF_1_INITFIRST => "INITFIRST",
+                DF_1_NOOPEN => "NOOPEN",
+                DF_1_ORIGIN => "ORIGIN",
+                DF_1_DIRECT => "DIRECT",
+                DF_1_TRANS => "TRANS",
+                DF_1_INTERPOSE => "INTERPOSE",
+                DF_1_NODEFLIB => "NODEFLIB",
+                DF_1_NODUMP => "NODUMP",
+                DF_1_CONFALT => "CONFALT",
+                DF_1_ENDFILTEE => "ENDFILTEE",
+                DF_1_DISPRELDNE => "DISPRELDNE",
+                DF_1_DISPRELPND => "DISPRELPND",
+                DF_1_NODIRECT => "NODIRECT",
+                DF_1_IGNMULDEF => "IGNMULDEF",
+                DF_1_NOKSYMS => "NOKSYMS",
+                DF_1_NOHDR => "NOHDR",
+                DF_1_EDITED => "EDITED",
+                DF_1_NORELOC => "NORELOC",
+                DF_1_SYMINTPOSE => "SYMINTPOSE",
+                DF_1_GLOBAUDIT => "GLOBAUDIT",
+                DF_1_SINGLETON => "SINGLETON",
+                DF_1_STUB => "STUB",
+                DF_1_PIE => "PIE",
+                DF_1_KMOD => "KMOD",
+                DF_1_WEAKFILTER => "WEAKFILTER",
+                DF_1_NOCOMMON => "NOCOMMON",
+                _ => {
+                    unknown |= flag;
+                    continue;
+                }
+            };
+            s.push(' ');
+            s.push_str(name);
+        }
+        if unknown != 0 {
+            s.push_str(&format!(" {:x}", unknown));
+        }
+        fmt.pad(&s)
+    }
+}
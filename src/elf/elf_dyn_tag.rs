@@ -0,0 +1,229 @@
+use ::std::fmt::{Display, Formatter};
+
+use super::elf_strtab::get_string;
+use super::elf_dyn_flags::ElfDynFlags;
+use super::elf_dyn_flags_1::ElfDynFlags1;
+
+pub const DT_NULL: u64 = 0;
+pub const DT_NEEDED: u64 = 1;
+pub const DT_PLTRELSZ: u64 = 2;
+pub const DT_PLTGOT: u64 = 3;
+pub const DT_HASH: u64 = 4;
+pub const DT_STRTAB: u64 = 5;
+pub const DT_SYMTAB: u64 = 6;
+pub const DT_RELA: u64 = 7;
+pub const DT_RELASZ: u64 = 8;
+pub const DT_RELAENT: u64 = 9;
+pub const DT_STRSZ: u64 = 10;
+pub const DT_SYMENT: u64 = 11;
+pub const DT_INIT: u64 = 12;
+pub const DT_FINI: u64 = 13;
+pub const DT_SONAME: u64 = 14;
+pub const DT_RPATH: u64 = 15;
+pub const DT_SYMBOLIC: u64 = 16;
+pub const DT_REL: u64 = 17;
+pub const DT_RELSZ: u64 = 18;
+pub const DT_RELENT: u64 = 19;
+pub const DT_PLTREL: u64 = 20;
+pub const DT_DEBUG: u64 = 21;
+pub const DT_TEXTREL: u64 = 22;
+pub const DT_JMPREL: u64 = 23;
+pub const DT_BIND_NOW: u64 = 24;
+pub const DT_INIT_ARRAY: u64 = 25;
+pub const DT_FINI_ARRAY: u64 = 26;
+pub const DT_INIT_ARRAYSZ: u64 = 27;
+pub const DT_FINI_ARRAYSZ: u64 = 28;
+pub const DT_RUNPATH: u64 = 29;
+pub const DT_FLAGS: u64 = 30;
+pub const DT_PREINIT_ARRAY: u64 = 32;
+pub const DT_PREINIT_ARRAYSZ: u64 = 33;
+pub const DT_SYMTAB_SHNDX: u64 = 34;
+pub const DT_RELRSZ: u64 = 35;
+pub const DT_RELR: u64 = 36;
+pub const DT_RELRENT: u64 = 37;
+pub const DT_LOOS: u64 = 0x6000000d;
+pub const DT_HIOS: u64 = 0x6ffff000;
+pub const DT_GNU_FLAGS_1: u64 = 0x6ffffdf4;
+pub const DT_GNU_PRELINKED: u64 = 0x6ffffdf5;
+pub const DT_GNU_CONFLICTSZ: u64 = 0x6ffffdf6;
+pub const DT_GNU_LIBLISTSZ: u64 = 0x6ffffdf7;
+pub const DT_CHECKSUM: u64 = 0x6ffffdf8;
+pub const DT_PLTPADSZ: u64 = 0x6ffffdf9;
+pub const DT_MOVEENT: u64 = 0x6ffffdfa;
+pub const DT_MOVESZ: u64 = 0x6ffffdfb;
+pub const DT_FEATURE: u64 = 0x6ffffdfc;
+pub const DT_POSFLAG_1: u64 = 0x6ffffdfd;
+pub const DT_SYMINSZ: u64 = 0x6ffffdfe;
+pub const DT_SYMINENT: u64 = 0x6ffffdff;
+pub const DT_GNU_HASH: u64 = 0x6ffffef5;
+pub const DT_TLSDESC_PLT: u64 = 0x6ffffef6;
+pub const DT_TLSDESC_GOT: u64 = 0x6ffffef7;
+pub const DT_GNU_CONFLICT: u64 = 0x6ffffef8;
+pub const DT_GNU_LIBLIST: u64 = 0x6ffffef9;
+pub const DT_CONFIG: u64 = 0x6ffffefa;
+pub const DT_DEPAUDIT: u64 = 0x6ffffefb;
+pub const DT_AUDIT: u64 = 0x6ffffefc;
+pub const DT_PLTPAD: u64 = 0x6ffffefd;
+pub const DT_MOVETAB: u64 = 0x6ffffefe;
+pub const DT_SYMINFO: u64 = 0x6ffffeff;
+pub const DT_VERSYM: u64 = 0x6ffffff0;
+pub const DT_RELACOUNT: u64 = 0x6ffffff9;
+pub const DT_RELCOUNT: u64 = 0x6ffffffa;
+pub const DT_FLAGS_1: u64 = 0x6ffffffb;
+pub const DT_VERDEF: u64 = 0x6ffffffc;
+pub const DT_VERDEFNUM: u64 = 0x6ffffffd;
+pub const DT_VERNEED: u64 = 0x6ffffffe;
+pub const DT_VERNEEDNUM: u64 = 0x6fffffff;
+pub const DT_LOPROC: u64 = 0x70000000;
+pub const DT_AUXILIARY: u64 = 0x7ffffffd;
+pub const DT_USED: u64 = 0x7ffffffe;
+pub const DT_FILTER: u64 = 0x7fffffff;
+pub const DT_HIPROC: u64 = 0x7fffffff;
+
+/// Tag of a dynamic section entry.
+///
+/// `d_tag` is signed in the specification, but no defined tag is negative,
+/// so it's kept as an unsigned value widened to 64 bits.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfDynTag {
+    data: u64,
+}
+
+impl From<u64> for ElfDynTag {
+    fn from(data: u64) -> ElfDynTag {
+        ElfDynTag { data }
+    }
+}
+
+impl ElfDynTag {
+    pub fn get(&self) -> u64 {
+        self.data
+    }
+
+    /// Formats `val` the way readelf's `-d` shows values of this tag.
+    /// String values are looked up in `strtab`, the `DT_STRTAB` table.
+    pub fn format_value(&self, val: u64, strtab: &[u8]) -> String {
+        let string = || if strtab.is_empty() {
+            format!("<string table offset {:#x}>", val)
+        } else {
+            get_string(strtab, val as usize)
+        };
+
+        match self.data {
+            DT_NEEDED => format!("Shared library: [{}]", string()),
+            DT_SONAME => format!("Library soname: [{}]", string()),
+            DT_RPATH => format!("Library rpath: [{}]", string()),
+            DT_RUNPATH => format!("Library runpath: [{}]", string()),
+            DT_AUXILIARY => format!("Auxiliary library: [{}]", string()),
+            DT_FILTER => format!("Filter library: [{}]", string()),
+            DT_CONFIG => format!("Configuration file: {}", string()),
+            DT_DEPAUDIT => format!("Dependency audit library: {}", string()),
+            DT_AUDIT => format!("Audit library: {}", string()),
+            DT_FLAGS => format!("{}", ElfDynFlags::from(val)),
+            DT_FLAGS_1 => format!("Flags:{}", ElfDynFlags1::from(val)),
+            DT_PLTREL => format!("{}", ElfDynTag::from(val)),
+            DT_PLTRELSZ | DT_RELASZ | DT_RELAENT | DT_STRSZ | DT_SYMENT
+            | DT_RELSZ | DT_RELENT | DT_RELRSZ | DT_RELRENT
+            | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ
+            | DT_SYMINSZ | DT_SYMINENT | DT_MOVEENT | DT_MOVESZ
+            | DT_PLTPADSZ | DT_GNU_CONFLICTSZ | DT_GNU_LIBLISTSZ => {
+                format!("{} (bytes)", val)
+            }
+            // readelf prints nothing for BIND_NOW, whatever its value.
+            DT_BIND_NOW => String::new(),
+            DT_VERDEFNUM | DT_VERNEEDNUM | DT_RELACOUNT | DT_RELCOUNT => {
+                format!("{}", val)
+            }
+            _ => format!("{:#x}", val),
+        }
+    }
+}
+
+impl Display for ElfDynTag {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let s = match self.data {
+            DT_NULL => "NULL",
+            DT_NEEDED => "NEEDED",
+            DT_PLTRELSZ => "PLTRELSZ",
+            DT_PLTGOT => "PLTGOT",
+            DT_HASH => "HASH",
+            DT_STRTAB => "STRTAB",
+            DT_SYMTAB => "SYMTAB",
+            DT_RELA => "RELA",
+            DT_RELASZ => "RELASZ",
+            DT_RELAENT => "RELAENT",
+            DT_STRSZ => "STRSZ",
+            DT_SYMENT => "SYMENT",
+            DT_INIT => "INIT",
+            DT_FINI => "FINI",
+            DT_SONAME => "SONAME",
+            DT_RPATH => "RPATH",
+            DT_SYMBOLIC => "SYMBOLIC",
+            DT_REL => "REL",
+            DT_RELSZ => "RELSZ",
+            DT_RELENT => "RELENT",
+            DT_PLTREL => "PLTREL",
+            DT_DEBUG => "DEBUG",
+            DT_TEXTREL => "TEXTREL",
+            DT_JMPREL => "JMPREL",
+            DT_BIND_NOW => "BIND_NOW",
+            DT_INIT_ARRAY => "INIT_ARRAY",
+            DT_FINI_ARRAY => "FINI_ARRAY",
+            DT_INIT_ARRAYSZ => "INIT_ARRAYSZ",
+            DT_FINI_ARRAYSZ => "FINI_ARRAYSZ",
+            DT_RUNPATH => "RUNPATH",
+            DT_FLAGS => "FLAGS",
+            DT_PREINIT_ARRAY => "PREINIT_ARRAY",
+            DT_PREINIT_ARRAYSZ => "PREINIT_ARRAYSZ",
+            DT_SYMTAB_SHNDX => "SYMTAB_SHNDX",
+            DT_RELRSZ => "RELRSZ",
+            DT_RELR => "RELR",
+            DT_RELRENT => "RELRENT",
+            DT_GNU_FLAGS_1 => "GNU_FLAGS_1",
+            DT_GNU_PRELINKED => "GNU_PRELINKED",
+            DT_GNU_CONFLICTSZ => "GNU_CONFLICTSZ",
+            DT_GNU_LIBLISTSZ => "GNU_LIBLISTSZ",
+            DT_CHECKSUM => "CHECKSUM",
+            DT_PLTPADSZ => "PLTPADSZ",
+            DT_MOVEENT => "MOVEENT",
+            DT_MOVESZ => "MOVESZ",
+            DT_FEATURE => "FEATURE",
+            DT_POSFLAG_1 => "POSFLAG_1",
+            DT_SYMINSZ => "SYMINSZ",
+            DT_SYMINENT => "SYMINENT",
+            DT_GNU_HASH => "GNU_HASH",
+            DT_TLSDESC_PLT => "TLSDESC_PLT",
+            DT_TLSDESC_GOT => "TLSDESC_GOT",
+            DT_GNU_CONFLICT => "GNU_CONFLICT",
+            DT_GNU_LIBLIST => "GNU_LIBLIST",
+            DT_CONFIG => "CONFIG",
+            DT_DEPAUDIT => "DEPAUDIT",
+            DT_AUDIT => "AUDIT",
+            DT_PLTPAD => "PLTPAD",
+            DT_MOVETAB => "MOVETAB",
+            DT_SYMINFO => "SYMINFO",
+            DT_VERSYM => "VERSYM",
+            DT_RELACOUNT => "RELACOUNT",
+            DT_RELCOUNT => "RELCOUNT",
+            DT_FLAGS_1 => "FLAGS_1",
+            DT_VERDEF => "VERDEF",
+            DT_VERDEFNUM => "VERDEFNUM",
+            DT_VERNEED => "VERNEED",
+            DT_VERNEEDNUM => "VERNEEDNUM",
+            DT_AUXILIARY => "AUXILIARY",
+            DT_USED => "USED",
+            DT_FILTER => "FILTER",
+            x @ DT_LOPROC..=DT_HIPROC => {
+                return fmt.pad(&format!("Processor Specific: {:x}", x));
+            }
+            x @ DT_LOOS..=DT_HIOS => {
+                return fmt.pad(&format!("Operating System specific: {:x}", x));
+            }
+            x => {
+                return fmt.pad(&format!("<unknown>: {:x}", x));
+            }
+        };
+        fmt.pad(s)
+    }
+}
@@ -1,213 +1,391 @@
-#[macro_export]
 macro_rules! elf_ehdr {
-    ($half:ty, $word:ty, $addr:ty, $off:ty) => {
-        use ::std::fmt::{Display, Formatter};
-        use ::to_host::to_host_copy::ToHostCopy;
-        use ::to_host::to_host_in_place::ToHostInPlace;
-        use ::to_host::{Endianness, ToHostInPlaceStruct, ToHostCopyStruct};
+    ($addr:ty: $addr_s:ident, $off:ty: $off_s:ident) => {
+        use ::to_host::Endianness;
+        use ::to_host::endian::*;
         use super::super::elf_ident::ElfIdent;
         use super::super::elf_ident_named::ElfIdentNamed;
         use super::super::elf_ehdr_type::ElfEhdrType;
         use super::super::elf_ehdr_machine::ElfEhdrMachine;
         use super::primitive::*;
 
+        /// The ELF header as laid out in the file. The fields after
+        /// `e_ident` are stored in byte order `E`.
         #[repr(C)]
         #[derive(Debug, Clone, Copy)]
-        pub struct Elf_Ehdr {
+        pub struct Elf_Ehdr<E: ByteOrder = Endianness> {
             e_ident: ElfIdent,
-            e_type: ElfEhdrType,
-            e_machine: ElfEhdrMachine,
-            e_version: $word,
-            e_entry: $addr,
-            e_phoff: $off,
-            e_shoff: $off,
-            e_flags: $word,
-            e_ehsize: $half,
-            e_phentsize: $half,
-            e_phnum: $half,
-            e_shentsize: $half,
-            e_shnum: $half,
-            e_shstrndx: $half
+            e_type: U16<E>,
+            e_machine: U16<E>,
+            e_version: U32<E>,
+            e_entry: $addr_s<E>,
+            e_phoff: $off_s<E>,
+            e_shoff: $off_s<E>,
+            e_flags: U32<E>,
+            e_ehsize: U16<E>,
+            e_phentsize: U16<E>,
+            e_phnum: U16<E>,
+            e_shentsize: U16<E>,
+            e_shnum: U16<E>,
+            e_shstrndx: U16<E>,
         }
 
-        impl Display for Elf_Ehdr {
-            fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-                let ehdr_ident: &ElfIdentNamed = unsafe {
-                    ::std::mem::transmute(&self.e_ident)
-                };
-
-                let e = self.get_endianness();
-
-                write!(
-                    fmt,
-                    concat!(
-                        "ELF Header:\n",
-                        "  Magic:   {}\n",
-                        "{}",
-                        "  Type:                              {}\n",
-                        "  Machine:                           {}\n",
-                        "  Version:                           {:#x}\n",
-                        "  Entry point address:               {:#x}\n",
-                        "  Start of program headers:          {} (bytes into file)\n",
-                        "  Start of section headers:          {} (bytes into file)\n",
-                        "  Flags:                             {:#x}\n",
-                        "  Size of this header:               {} (bytes)\n",
-                        "  Size of program headers:           {} (bytes)\n",
-                        "  Number of program headers:         {}\n",
-                        "  Size of section headers:           {} (bytes)\n",
-                        "  Number of section headers:         {}\n",
-                        "  Section header string table index: {}\n",
-                    ),
-                    self.e_ident,
-                    ehdr_ident,
-                    self.e_type.to_host_copy(&e),
-                    self.e_machine.to_host_copy(&e),
-                    self.e_version.to_host_copy(&e),
-                    self.e_entry.to_host_copy(&e),
-                    self.e_phoff.to_host_copy(&e),
-                    self.e_shoff.to_host_copy(&e),
-                    self.e_flags.to_host_copy(&e),
-                    self.e_ehsize.to_host_copy(&e),
-                    self.e_phentsize.to_host_copy(&e),
-                    self.e_phnum.to_host_copy(&e),
-                    self.e_shentsize.to_host_copy(&e),
-                    self.e_shnum.to_host_copy(&e),
-                    self.e_shstrndx.to_host_copy(&e))
-            }
-        }
-
-        impl Elf_Ehdr {
+        impl<E: ByteOrder> Elf_Ehdr<E> {
             #[allow(dead_code)]
-            fn from_slice(buffer: &[u8]) -> Result<&Elf_Ehdr, ()> {
-                let proper_magic = &[0x7f, b'E', b'L', b'F'];
-                let magic_ptr: *const [u8; 4] = unsafe {
-                    ::std::mem::transmute(buffer.as_ptr())
-                };
-                let magic = unsafe { &*magic_ptr };
-                if proper_magic != magic {
-                    return Err(())
-                }
-
-                let ehdr_ptr: *const Elf_Ehdr = unsafe {
-                    ::std::mem::transmute(buffer.as_ptr())
-                };
-                let ehdr: &Elf_Ehdr = unsafe { &*ehdr_ptr };
-
-                Ok(ehdr)
+            pub fn get_phentsize(&self, e: E) -> u16 {
+                self.e_phentsize.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_phentsize(&self) -> u16 {
-                self.e_phentsize
+            pub fn get_phnum(&self, e: E) -> u16 {
+                self.e_phnum.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_phnum(&self) -> u16 {
-                self.e_phnum
+            pub fn get_phoff(&self, e: E) -> $off {
+                self.e_phoff.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_phoff(&self) -> $off {
-                self.e_phoff
+            pub fn get_entry(&self, e: E) -> $addr {
+                self.e_entry.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_entry(&self) -> $addr {
-                self.e_entry
+            pub fn get_raw_ident(&self) -> ElfIdent {
+                self.e_ident
             }
             #[allow(dead_code)]
             pub fn get_ident(&self) -> ElfIdentNamed {
-                unsafe { 
-                    ::std::mem::transmute(self.e_ident)
-                }
+                ElfIdentNamed::from(&self.e_ident)
             }
             #[allow(dead_code)]
-            pub fn get_type(&self) -> ElfEhdrType {
-                self.e_type
+            pub fn get_type(&self, e: E) -> ElfEhdrType {
+                ElfEhdrType::from(self.e_type.get(e))
             }
             #[allow(dead_code)]
-            pub fn get_machine(&self) -> ElfEhdrMachine {
-                self.e_machine
+            pub fn get_machine(&self, e: E) -> ElfEhdrMachine {
+                ElfEhdrMachine::from(self.e_machine.get(e))
             }
             #[allow(dead_code)]
-            pub fn get_version(&self) -> u32 {
-                self.e_version
+            pub fn get_version(&self, e: E) -> u32 {
+                self.e_version.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_shoff(&self) -> $off {
-                self.e_shoff
+            pub fn get_shoff(&self, e: E) -> $off {
+                self.e_shoff.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_flags(&self) -> u32 {
-                self.e_flags
+            pub fn get_flags(&self, e: E) -> u32 {
+                self.e_flags.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_ehsize(&self) -> u16 {
-                self.e_ehsize
+            pub fn get_ehsize(&self, e: E) -> u16 {
+                self.e_ehsize.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_shentsize(&self) -> u16 {
-                self.e_shentsize
+            pub fn get_shentsize(&self, e: E) -> u16 {
+                self.e_shentsize.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_shnum(&self) -> u16 {
-                self.e_shnum
+            pub fn get_shnum(&self, e: E) -> u16 {
+                self.e_shnum.get(e)
             }
             #[allow(dead_code)]
-            pub fn get_shstrndx(&self) -> u16 {
-                self.e_shstrndx
+            pub fn get_shstrndx(&self, e: E) -> u16 {
+                self.e_shstrndx.get(e)
             }
 
+            /// The byte order told by `e_ident`. `read_ehdr` rejects headers
+            /// with an unknown data encoding, so it's always known here.
             #[allow(dead_code)]
             pub fn get_endianness(&self) -> Endianness {
-                let ehdr_ptr: *mut Elf_Ehdr = unsafe {
-                    ::std::mem::transmute(self)
-                };
-                let ehdr: &mut Elf_Ehdr = unsafe { &mut *ehdr_ptr };
-                let ehdr_ident: &ElfIdentNamed = unsafe {
-                    ::std::mem::transmute(&ehdr.e_ident)
-                };
+                self.get_ident().get_endianness().unwrap_or(Endianness::LE)
+            }
+        }
 
-                ehdr_ident.get_endianness()
+        /// Stores the fields of `ehdr` in byte order `e`. Addresses and
+        /// offsets are truncated to the class, so they must fit.
+        impl<'a, E: ByteOrder> From<(&'a super::super::elf_ehdr::ElfEhdr, E)> for Elf_Ehdr<E> {
+            fn from((ehdr, e): (&'a super::super::elf_ehdr::ElfEhdr, E)) -> Elf_Ehdr<E> {
+                Elf_Ehdr {
+                    e_ident: ehdr.get_raw_ident(),
+                    e_type: U16::new(e, ehdr.get_type().get()),
+                    e_machine: U16::new(e, ehdr.get_machine().get()),
+                    e_version: U32::new(e, ehdr.get_version()),
+                    e_entry: $addr_s::new(e, ehdr.get_entry() as $addr),
+                    e_phoff: $off_s::new(e, ehdr.get_phoff() as $off),
+                    e_shoff: $off_s::new(e, ehdr.get_shoff() as $off),
+                    e_flags: U32::new(e, ehdr.get_flags().get()),
+                    e_ehsize: U16::new(e, ehdr.get_ehsize()),
+                    e_phentsize: U16::new(e, ehdr.get_phentsize()),
+                    e_phnum: U16::new(e, ehdr.get_phnum()),
+                    e_shentsize: U16::new(e, ehdr.get_shentsize()),
+                    e_shnum: U16::new(e, ehdr.get_shnum()),
+                    e_shstrndx: U16::new(e, ehdr.get_shstrndx()),
+                }
             }
         }
+    }
+}
 
+use ::std::fmt::{Display, Formatter, Write};
+use ::json::Json;
+use ::to_host::Endianness;
+use ::to_host::endian::ByteOrder;
+use super::elf_ident::ElfIdent;
+use super::elf_ident_named::ElfIdentNamed;
+use super::elf_ei_class::ElfEiClass;
+use super::elf_ehdr_type::ElfEhdrType;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_ehdr_flags::ElfEhdrFlags;
+use super::elf_shdr::ElfShdr;
+use super::elf_sym_shndx::SHN_XINDEX;
+use super::{Elf32_Ehdr, Elf64_Ehdr};
 
-        impl ToHostInPlaceStruct for Elf_Ehdr {
-            fn to_host_in_place(&mut self, endianness: &Endianness) {
-                let e = endianness;
-                self.e_type.to_host_in_place(e);
-                self.e_machine.to_host_in_place(e);
-                self.e_version.to_host_in_place(e);
-                self.e_entry.to_host_in_place(e);
-                self.e_phoff.to_host_in_place(e);
-                self.e_shoff.to_host_in_place(e);
-                self.e_flags.to_host_in_place(e);
-                self.e_ehsize.to_host_in_place(e);
-                self.e_phentsize.to_host_in_place(e);
-                self.e_phnum.to_host_in_place(e);
-                self.e_shentsize.to_host_in_place(e);
-                self.e_shnum.to_host_in_place(e);
-                self.e_shstrndx.to_host_in_place(e);
+/// The ELF header of a file of either class, in host byte order.
+/// Addresses and offsets are widened to 64 bits.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfEhdr {
+    e_ident: ElfIdent,
+    e_type: ElfEhdrType,
+    e_machine: ElfEhdrMachine,
+    e_version: u32,
+    e_entry: u64,
+    e_phoff: u64,
+    e_shoff: u64,
+    e_flags: u32,
+    e_ehsize: u16,
+    e_phentsize: u16,
+    e_phnum: u16,
+    e_shentsize: u16,
+    e_shnum: u16,
+    e_shstrndx: u16,
+}
+
+macro_rules! elf_ehdr_from {
+    ($t:ty) => {
+        /// Reads the fields of `ehdr` in byte order `e`.
+        impl<'a, E: ByteOrder> From<(&'a $t, E)> for ElfEhdr {
+            fn from((ehdr, e): (&'a $t, E)) -> ElfEhdr {
+                ElfEhdr {
+                    e_ident: ehdr.get_raw_ident(),
+                    e_type: ehdr.get_type(e),
+                    e_machine: ehdr.get_machine(e),
+                    e_version: ehdr.get_version(e),
+                    e_entry: ehdr.get_entry(e) as u64,
+                    e_phoff: ehdr.get_phoff(e) as u64,
+                    e_shoff: ehdr.get_shoff(e) as u64,
+                    e_flags: ehdr.get_flags(e),
+                    e_ehsize: ehdr.get_ehsize(e),
+                    e_phentsize: ehdr.get_phentsize(e),
+                    e_phnum: ehdr.get_phnum(e),
+                    e_shentsize: ehdr.get_shentsize(e),
+                    e_shnum: ehdr.get_shnum(e),
+                    e_shstrndx: ehdr.get_shstrndx(e),
+                }
             }
         }
+    }
+}
 
-        impl ToHostCopyStruct for Elf_Ehdr {
-            fn to_host_copy(&self, endianness: &Endianness) -> Self {
-                let e = endianness;
-                Elf_Ehdr {
-                    e_ident: self.e_ident,
-                    e_type: self.e_type.to_host_copy(e),
-                    e_machine: self.e_machine.to_host_copy(e),
-                    e_version: self.e_version.to_host_copy(e),
-                    e_entry: self.e_entry.to_host_copy(e),
-                    e_phoff: self.e_phoff.to_host_copy(e),
-                    e_shoff: self.e_shoff.to_host_copy(e),
-                    e_flags: self.e_flags.to_host_copy(e),
-                    e_ehsize: self.e_ehsize.to_host_copy(e),
-                    e_phentsize: self.e_phentsize.to_host_copy(e),
-                    e_phnum: self.e_phnum.to_host_copy(e),
-                    e_shentsize: self.e_shentsize.to_host_copy(e),
-                    e_shnum: self.e_shnum.to_host_copy(e),
-                    e_shstrndx: self.e_shstrndx.to_host_copy(e),
-                }
+elf_ehdr_from!(Elf32_Ehdr<E>);
+elf_ehdr_from!(Elf64_Ehdr<E>);
+
+impl Display for ElfEhdr {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        self.write_to(fmt, false, None)
+    }
+}
+
+impl ElfEhdr {
+    /// Returns the text of readelf's `-h`. `is_pie` selects the name
+    /// readelf gives to position-independent executables. With extended
+    /// numbering, the section count and the index of the section name
+    /// string table are followed by their values in `first_section`.
+    pub fn format(&self, is_pie: bool, first_section: Option<&ElfShdr>) -> String {
+        let mut s = String::new();
+        let _ = self.write_to(&mut s, is_pie, first_section);
+        s
+    }
+
+    /// Describes the header for `--output=json`. The type is named like
+    /// in `Display`, whatever the text output calls it.
+    pub fn to_json(&self) -> Json {
+        let ident = self.get_ident();
+        let flags = self.get_flags();
+        Json::object(vec![
+            ("ident", Json::object(vec![
+                ("class", Json::named(ident.get_class().get(), ident.get_class().to_string())),
+                ("data", Json::named(ident.get_data().get(), ident.get_data().to_string())),
+                ("version", Json::named(
+                    ident.get_version().get(), ident.get_version().to_string())),
+                ("osabi", Json::named(
+                    ident.get_osabi().get(), ident.get_osabi().display_for(self.e_machine))),
+                ("abi_version", ident.get_abi_version().get().into()),
+            ])),
+            ("type", Json::named(self.e_type.get(), self.e_type.to_string())),
+            ("machine", Json::named(self.e_machine.get(), self.e_machine.to_string())),
+            ("version", self.e_version.into()),
+            ("entry", self.e_entry.into()),
+            ("phoff", self.e_phoff.into()),
+            ("shoff", self.e_shoff.into()),
+            ("flags", Json::object(vec![
+                ("value", flags.get().into()),
+                ("names", flags.get_names(self.e_machine).into()),
+            ])),
+            ("ehsize", self.e_ehsize.into()),
+            ("phentsize", self.e_phentsize.into()),
+            ("phnum", self.e_phnum.into()),
+            ("shentsize", self.e_shentsize.into()),
+            ("shnum", self.e_shnum.into()),
+            ("shstrndx", self.e_shstrndx.into()),
+        ])
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W, is_pie: bool, first_section: Option<&ElfShdr>)
+                          -> ::std::fmt::Result {
+        let mut shnum = self.e_shnum.to_string();
+        let mut shstrndx = self.e_shstrndx.to_string();
+        if let Some(shdr) = first_section {
+            if self.e_shnum == 0 {
+                shnum += &format!(" ({})", shdr.get_size());
+            }
+            if self.e_shstrndx as u32 == SHN_XINDEX {
+                shstrndx += &format!(" ({})", shdr.get_link());
             }
         }
+
+        write!(
+            out,
+            concat!(
+                "ELF Header:\n",
+                "  Magic:   {}\n",
+                "{}",
+                "  Type:                              {}\n",
+                "  Machine:                           {}\n",
+                "  Version:                           {:#x}\n",
+                "  Entry point address:               {:#x}\n",
+                "  Start of program headers:          {} (bytes into file)\n",
+                "  Start of section headers:          {} (bytes into file)\n",
+                "  Flags:                             {}\n",
+                "  Size of this header:               {} (bytes)\n",
+                "  Size of program headers:           {} (bytes)\n",
+                "  Number of program headers:         {}\n",
+                "  Size of section headers:           {} (bytes)\n",
+                "  Number of section headers:         {}\n",
+                "  Section header string table index: {}\n",
+            ),
+            self.e_ident,
+            self.get_ident().format(self.e_machine),
+            self.e_type.get_name(is_pie),
+            self.e_machine,
+            self.e_version,
+            self.e_entry,
+            self.e_phoff,
+            self.e_shoff,
+            self.get_flags().format(self.e_machine),
+            self.e_ehsize,
+            self.e_phentsize,
+            self.e_phnum,
+            self.e_shentsize,
+            shnum,
+            shstrndx)
+    }
+}
+
+impl ElfEhdr {
+    pub fn get_raw_ident(&self) -> ElfIdent {
+        self.e_ident
+    }
+    pub fn get_ident(&self) -> ElfIdentNamed {
+        ElfIdentNamed::from(&self.e_ident)
+    }
+    pub fn get_type(&self) -> ElfEhdrType {
+        self.e_type
+    }
+    pub fn get_machine(&self) -> ElfEhdrMachine {
+        self.e_machine
+    }
+    pub fn get_version(&self) -> u32 {
+        self.e_version
+    }
+    pub fn get_entry(&self) -> u64 {
+        self.e_entry
+    }
+    pub fn get_phoff(&self) -> u64 {
+        self.e_phoff
+    }
+    pub fn get_shoff(&self) -> u64 {
+        self.e_shoff
+    }
+    pub fn get_flags(&self) -> ElfEhdrFlags {
+        ElfEhdrFlags::from(self.e_flags)
+    }
+    pub fn get_ehsize(&self) -> u16 {
+        self.e_ehsize
+    }
+    pub fn get_phentsize(&self) -> u16 {
+        self.e_phentsize
+    }
+    pub fn get_phnum(&self) -> u16 {
+        self.e_phnum
+    }
+    pub fn get_shentsize(&self) -> u16 {
+        self.e_shentsize
+    }
+    pub fn get_shnum(&self) -> u16 {
+        self.e_shnum
+    }
+    pub fn get_shstrndx(&self) -> u16 {
+        self.e_shstrndx
+    }
+
+    pub fn get_class(&self) -> ElfEiClass {
+        self.get_ident().get_class()
+    }
+    pub fn is_32(&self) -> bool {
+        self.get_class() == ElfEiClass::ELFCLASS32
+    }
+
+    /// `ElfFile::open` rejects headers with an unknown data encoding,
+    /// so the endianness is always known here.
+    pub fn get_endianness(&self) -> Endianness {
+        self.get_ident().get_endianness().unwrap_or(Endianness::LE)
+    }
+}
+
+/// Setters for editing the header with `ElfWriter`. The locations and
+/// sizes of the header tables are set by the writer itself, except
+/// `e_phoff`, which can move the program header table to space reserved
+/// for it.
+impl ElfEhdr {
+    pub fn set_type(&mut self, e_type: ElfEhdrType) {
+        self.e_type = e_type;
+    }
+    pub fn set_machine(&mut self, e_machine: ElfEhdrMachine) {
+        self.e_machine = e_machine;
+    }
+    pub fn set_entry(&mut self, e_entry: u64) {
+        self.e_entry = e_entry;
+    }
+    pub fn set_flags(&mut self, e_flags: ElfEhdrFlags) {
+        self.e_flags = e_flags.get();
+    }
+    pub fn set_phoff(&mut self, e_phoff: u64) {
+        self.e_phoff = e_phoff;
+    }
+    pub(crate) fn set_shoff(&mut self, e_shoff: u64) {
+        self.e_shoff = e_shoff;
+    }
+    pub(crate) fn set_phentsize(&mut self, e_phentsize: u16) {
+        self.e_phentsize = e_phentsize;
+    }
+    pub(crate) fn set_phnum(&mut self, e_phnum: u16) {
+        self.e_phnum = e_phnum;
+    }
+    pub(crate) fn set_shentsize(&mut self, e_shentsize: u16) {
+        self.e_shentsize = e_shentsize;
+    }
+    pub(crate) fn set_shnum(&mut self, e_shnum: u16) {
+        self.e_shnum = e_shnum;
+    }
+    pub(crate) fn set_shstrndx(&mut self, e_shstrndx: u16) {
+        self.e_shstrndx = e_shstrndx;
     }
 }
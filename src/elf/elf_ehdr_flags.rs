@@ -0,0 +1,407 @@
+use ::std::fmt::{Display, Formatter};
+
+use super::elf_ehdr_machine::ElfEhdrMachine;
+
+pub const EF_PPC_EMB: u32 = 0x80000000;
+pub const EF_PPC_RELOCATABLE: u32 = 0x00010000;
+pub const EF_PPC_RELOCATABLE_LIB: u32 = 0x00008000;
+
+pub const EF_PPC64_ABI: u32 = 0x3;
+
+pub const EF_ARM_RELEXEC: u32 = 0x01;
+pub const EF_ARM_INTERWORK: u32 = 0x04;
+pub const EF_ARM_SYMSARESORTED: u32 = 0x04;
+pub const EF_ARM_APCS_26: u32 = 0x08;
+pub const EF_ARM_DYNSYMSUSESEGIDX: u32 = 0x08;
+pub const EF_ARM_APCS_FLOAT: u32 = 0x10;
+pub const EF_ARM_MAPSYMSFIRST: u32 = 0x10;
+pub const EF_ARM_PIC: u32 = 0x20;
+pub const EF_ARM_ALIGN8: u32 = 0x40;
+pub const EF_ARM_NEW_ABI: u32 = 0x80;
+pub const EF_ARM_OLD_ABI: u32 = 0x100;
+pub const EF_ARM_SOFT_FLOAT: u32 = 0x200;
+pub const EF_ARM_ABI_FLOAT_SOFT: u32 = 0x200;
+pub const EF_ARM_VFP_FLOAT: u32 = 0x400;
+pub const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400;
+pub const EF_ARM_MAVERICK_FLOAT: u32 = 0x800;
+pub const EF_ARM_LE8: u32 = 0x00400000;
+pub const EF_ARM_BE8: u32 = 0x00800000;
+pub const EF_ARM_EABIMASK: u32 = 0xff000000;
+pub const EF_ARM_EABI_UNKNOWN: u32 = 0x00000000;
+pub const EF_ARM_EABI_VER1: u32 = 0x01000000;
+pub const EF_ARM_EABI_VER2: u32 = 0x02000000;
+pub const EF_ARM_EABI_VER3: u32 = 0x03000000;
+pub const EF_ARM_EABI_VER4: u32 = 0x04000000;
+pub const EF_ARM_EABI_VER5: u32 = 0x05000000;
+
+pub const EF_MIPS_NOREORDER: u32 = 0x1;
+pub const EF_MIPS_PIC: u32 = 0x2;
+pub const EF_MIPS_CPIC: u32 = 0x4;
+pub const EF_MIPS_UCODE: u32 = 0x10;
+pub const EF_MIPS_ABI2: u32 = 0x20;
+pub const EF_MIPS_OPTIONS_FIRST: u32 = 0x80;
+pub const EF_MIPS_32BITMODE: u32 = 0x100;
+pub const EF_MIPS_FP64: u32 = 0x200;
+pub const EF_MIPS_NAN2008: u32 = 0x400;
+pub const EF_MIPS_ABI: u32 = 0x0000f000;
+pub const EF_MIPS_MACH: u32 = 0x00ff0000;
+pub const EF_MIPS_ARCH_ASE_MICROMIPS: u32 = 0x02000000;
+pub const EF_MIPS_ARCH_ASE_M16: u32 = 0x04000000;
+pub const EF_MIPS_ARCH_ASE_MDMX: u32 = 0x08000000;
+pub const EF_MIPS_ARCH: u32 = 0xf0000000;
+
+pub const EF_RISCV_RVC: u32 = 0x1;
+pub const EF_RISCV_FLOAT_ABI: u32 = 0x6;
+pub const EF_RISCV_FLOAT_ABI_SOFT: u32 = 0x0;
+pub const EF_RISCV_FLOAT_ABI_SINGLE: u32 = 0x2;
+pub const EF_RISCV_FLOAT_ABI_DOUBLE: u32 = 0x4;
+pub const EF_RISCV_FLOAT_ABI_QUAD: u32 = 0x6;
+pub const EF_RISCV_RVE: u32 = 0x8;
+pub const EF_RISCV_TSO: u32 = 0x10;
+
+pub const EF_LOONGARCH_ABI_MODIFIER_MASK: u32 = 0x7;
+pub const EF_LOONGARCH_ABI_SOFT_FLOAT: u32 = 0x1;
+pub const EF_LOONGARCH_ABI_SINGLE_FLOAT: u32 = 0x2;
+pub const EF_LOONGARCH_ABI_DOUBLE_FLOAT: u32 = 0x3;
+pub const EF_LOONGARCH_OBJABI_MASK: u32 = 0xc0;
+pub const EF_LOONGARCH_OBJABI_V0: u32 = 0x00;
+pub const EF_LOONGARCH_OBJABI_V1: u32 = 0x40;
+
+pub const EF_SPARCV9_MM: u32 = 0x3;
+pub const EF_SPARCV9_TSO: u32 = 0x0;
+pub const EF_SPARCV9_PSO: u32 = 0x1;
+pub const EF_SPARCV9_RMO: u32 = 0x2;
+pub const EF_SPARC_32PLUS: u32 = 0x100;
+pub const EF_SPARC_SUN_US1: u32 = 0x200;
+pub const EF_SPARC_HAL_R1: u32 = 0x400;
+pub const EF_SPARC_SUN_US3: u32 = 0x800;
+pub const EF_SPARC_LEDATA: u32 = 0x800000;
+
+/// The calling convention for floating point arguments, as far as
+/// `e_flags` tell it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ElfFloatAbi {
+    /// Floating point values are passed in integer registers.
+    Soft,
+    /// Floating point values are passed in floating point registers.
+    Hard,
+    /// Like `Hard`, for values up to single precision.
+    Single,
+    /// Like `Hard`, for values up to double precision.
+    Double,
+    /// Like `Hard`, for values up to quad precision.
+    Quad,
+}
+
+impl Display for ElfFloatAbi {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let s = match *self {
+            ElfFloatAbi::Soft => "soft",
+            ElfFloatAbi::Hard => "hard",
+            ElfFloatAbi::Single => "single",
+            ElfFloatAbi::Double => "double",
+            ElfFloatAbi::Quad => "quad",
+        };
+        write!(fmt, "{}", s)
+    }
+}
+
+/// `e_flags` of the ELF header. Their meaning depends on the machine.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfEhdrFlags {
+    flags: u32,
+}
+
+impl From<u32> for ElfEhdrFlags {
+    fn from(flags: u32) -> ElfEhdrFlags {
+        ElfEhdrFlags { flags }
+    }
+}
+
+impl ElfEhdrFlags {
+    pub fn get(&self) -> u32 {
+        self.flags
+    }
+    pub fn contains(&self, flag: u32) -> bool {
+        self.flags & flag == flag
+    }
+
+    /// Returns the float ABI the flags select on `machine`, or `None` if
+    /// the flags don't tell it.
+    pub fn get_float_abi(&self, machine: ElfEhdrMachine) -> Option<ElfFloatAbi> {
+        let flags = self.flags;
+        match machine {
+            ElfEhdrMachine::EM_ARM => match flags & EF_ARM_EABIMASK {
+                EF_ARM_EABI_VER5 if flags & EF_ARM_ABI_FLOAT_HARD != 0 =>
+                    Some(ElfFloatAbi::Hard),
+                EF_ARM_EABI_VER5 if flags & EF_ARM_ABI_FLOAT_SOFT != 0 =>
+                    Some(ElfFloatAbi::Soft),
+                EF_ARM_EABI_UNKNOWN if flags & EF_ARM_VFP_FLOAT != 0 =>
+                    Some(ElfFloatAbi::Hard),
+                EF_ARM_EABI_UNKNOWN if flags & EF_ARM_SOFT_FLOAT != 0 =>
+                    Some(ElfFloatAbi::Soft),
+                _ => None,
+            },
+            ElfEhdrMachine::EM_RISCV => Some(match flags & EF_RISCV_FLOAT_ABI {
+                EF_RISCV_FLOAT_ABI_SOFT => ElfFloatAbi::Soft,
+                EF_RISCV_FLOAT_ABI_SINGLE => ElfFloatAbi::Single,
+                EF_RISCV_FLOAT_ABI_DOUBLE => ElfFloatAbi::Double,
+                _ => ElfFloatAbi::Quad,
+            }),
+            ElfEhdrMachine::EM_LOONGARCH => match flags & EF_LOONGARCH_ABI_MODIFIER_MASK {
+                EF_LOONGARCH_ABI_SOFT_FLOAT => Some(ElfFloatAbi::Soft),
+                EF_LOONGARCH_ABI_SINGLE_FLOAT => Some(ElfFloatAbi::Single),
+                EF_LOONGARCH_ABI_DOUBLE_FLOAT => Some(ElfFloatAbi::Double),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the descriptions of the flags readelf's `-h` lists after
+    /// their value, in its order.
+    pub fn get_names(&self, machine: ElfEhdrMachine) -> Vec<&'static str> {
+        let flags = self.flags;
+        let mut names = Vec::new();
+        if flags == 0 {
+            return names;
+        }
+
+        match machine {
+            ElfEhdrMachine::EM_ARM => arm_names(flags, &mut names),
+            ElfEhdrMachine::EM_PPC => {
+                if flags & EF_PPC_EMB != 0 {
+                    names.push("emb");
+                }
+                if flags & EF_PPC_RELOCATABLE != 0 {
+                    names.push("relocatable");
+                }
+                if flags & EF_PPC_RELOCATABLE_LIB != 0 {
+                    names.push("relocatable-lib");
+                }
+            }
+            ElfEhdrMachine::EM_PPC64 => match flags & EF_PPC64_ABI {
+                1 => names.push("abiv1"),
+                2 => names.push("abiv2"),
+                3 => names.push("abiv3"),
+                _ => (),
+            },
+            ElfEhdrMachine::EM_MIPS | ElfEhdrMachine::EM_MIPS_RS3_LE =>
+                mips_names(flags, &mut names),
+            ElfEhdrMachine::EM_RISCV => {
+                if flags & EF_RISCV_RVC != 0 {
+                    names.push("RVC");
+                }
+                if flags & EF_RISCV_RVE != 0 {
+                    names.push("RVE");
+                }
+                if flags & EF_RISCV_TSO != 0 {
+                    names.push("TSO");
+                }
+                names.push(match flags & EF_RISCV_FLOAT_ABI {
+                    EF_RISCV_FLOAT_ABI_SOFT => "soft-float ABI",
+                    EF_RISCV_FLOAT_ABI_SINGLE => "single-float ABI",
+                    EF_RISCV_FLOAT_ABI_DOUBLE => "double-float ABI",
+                    _ => "quad-float ABI",
+                });
+            }
+            ElfEhdrMachine::EM_LOONGARCH => {
+                match flags & EF_LOONGARCH_ABI_MODIFIER_MASK {
+                    EF_LOONGARCH_ABI_SOFT_FLOAT => names.push("SOFT-FLOAT"),
+                    EF_LOONGARCH_ABI_SINGLE_FLOAT => names.push("SINGLE-FLOAT"),
+                    EF_LOONGARCH_ABI_DOUBLE_FLOAT => names.push("DOUBLE-FLOAT"),
+                    _ => (),
+                }
+                match flags & EF_LOONGARCH_OBJABI_MASK {
+                    EF_LOONGARCH_OBJABI_V0 => names.push("OBJ-v0"),
+                    EF_LOONGARCH_OBJABI_V1 => names.push("OBJ-v1"),
+                    _ => (),
+                }
+            }
+            ElfEhdrMachine::EM_SPARCV9 => {
+                if flags & EF_SPARC_32PLUS != 0 {
+                    names.push("v8+");
+                }
+                if flags & EF_SPARC_SUN_US1 != 0 {
+                    names.push("ultrasparcI");
+                }
+                if flags & EF_SPARC_SUN_US3 != 0 {
+                    names.push("ultrasparcIII");
+                }
+                if flags & EF_SPARC_HAL_R1 != 0 {
+                    names.push("halr1");
+                }
+                if flags & EF_SPARC_LEDATA != 0 {
+                    names.push("ledata");
+                }
+                match flags & EF_SPARCV9_MM {
+                    EF_SPARCV9_TSO => names.push("tso"),
+                    EF_SPARCV9_PSO => names.push("pso"),
+                    EF_SPARCV9_RMO => names.push("rmo"),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        names
+    }
+
+    /// Formats the flags like readelf's `-h`: the value followed by the
+    /// descriptions of the flags.
+    pub fn format(&self, machine: ElfEhdrMachine) -> String {
+        let mut s = format!("{:#x}", self.flags);
+        for name in self.get_names(machine) {
+            s.push_str(", ");
+            s.push_str(name);
+        }
+        s
+    }
+}
+
+/// The flags of each ARM EABI version share bits, so they're looked at one
+/// bit at a time. Leftover bits add `<unknown>` at the end.
+fn arm_names(flags: u32, names: &mut Vec<&'static str>) {
+    let eabi = flags & EF_ARM_EABIMASK;
+    let mut flags = flags & !EF_ARM_EABIMASK;
+    let mut unknown = false;
+
+    if flags & EF_ARM_RELEXEC != 0 {
+        names.push("relocatable executable");
+        flags &= !EF_ARM_RELEXEC;
+    }
+    if flags & EF_ARM_PIC != 0 {
+        names.push("position independent");
+        flags &= !EF_ARM_PIC;
+    }
+
+    let version = match eabi {
+        EF_ARM_EABI_VER1 => "Version1 EABI",
+        EF_ARM_EABI_VER2 => "Version2 EABI",
+        EF_ARM_EABI_VER3 => "Version3 EABI",
+        EF_ARM_EABI_VER4 => "Version4 EABI",
+        EF_ARM_EABI_VER5 => "Version5 EABI",
+        EF_ARM_EABI_UNKNOWN => "GNU EABI",
+        _ => "<unrecognized EABI>",
+    };
+    names.push(version);
+
+    if eabi == EF_ARM_EABI_VER3 {
+        flags = 0;
+    }
+    while flags != 0 {
+        let flag = flags & flags.wrapping_neg();
+        flags &= !flag;
+
+        let name = match (eabi, flag) {
+            (EF_ARM_EABI_VER1, EF_ARM_SYMSARESORTED) => "sorted symbol tables",
+            (EF_ARM_EABI_VER2, EF_ARM_SYMSARESORTED) => "sorted symbol tables",
+            (EF_ARM_EABI_VER2, EF_ARM_DYNSYMSUSESEGIDX) =>
+                "dynamic symbols use segment index",
+            (EF_ARM_EABI_VER2, EF_ARM_MAPSYMSFIRST) => "mapping symbols precede others",
+            (EF_ARM_EABI_VER4, EF_ARM_BE8) | (EF_ARM_EABI_VER5, EF_ARM_BE8) => "BE8",
+            (EF_ARM_EABI_VER4, EF_ARM_LE8) | (EF_ARM_EABI_VER5, EF_ARM_LE8) => "LE8",
+            (EF_ARM_EABI_VER5, EF_ARM_ABI_FLOAT_SOFT) => "soft-float ABI",
+            (EF_ARM_EABI_VER5, EF_ARM_ABI_FLOAT_HARD) => "hard-float ABI",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_INTERWORK) => "interworking enabled",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_APCS_26) => "uses APCS/26",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_APCS_FLOAT) => "uses APCS/float",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_ALIGN8) => "8 bit structure alignment",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_NEW_ABI) => "uses new ABI",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_OLD_ABI) => "uses old ABI",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_SOFT_FLOAT) => "software FP",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_VFP_FLOAT) => "VFP",
+            (EF_ARM_EABI_UNKNOWN, EF_ARM_MAVERICK_FLOAT) => "Maverick FP",
+            _ => {
+                unknown = true;
+                continue;
+            }
+        };
+        names.push(name);
+    }
+
+    if unknown {
+        names.push("<unknown>");
+    }
+}
+
+fn mips_names(flags: u32, names: &mut Vec<&'static str>) {
+    let bits = [
+        (EF_MIPS_NOREORDER, "noreorder"),
+        (EF_MIPS_PIC, "pic"),
+        (EF_MIPS_CPIC, "cpic"),
+        (EF_MIPS_UCODE, "ugen_reserved"),
+        (EF_MIPS_ABI2, "abi2"),
+        (EF_MIPS_OPTIONS_FIRST, "odk first"),
+        (EF_MIPS_32BITMODE, "32bitmode"),
+        (EF_MIPS_NAN2008, "nan2008"),
+        (EF_MIPS_FP64, "fp64"),
+    ];
+    for &(bit, name) in bits.iter() {
+        if flags & bit != 0 {
+            names.push(name);
+        }
+    }
+
+    // Neither the CPU nor the ABI field is in the MIPS ELF spec, they're
+    // GNU extensions, so readelf doesn't mention them if they're zero.
+    match flags & EF_MIPS_MACH {
+        0 => (),
+        0x00810000 => names.push("3900"),
+        0x00820000 => names.push("4010"),
+        0x00830000 => names.push("4100"),
+        0x00850000 => names.push("4650"),
+        0x00870000 => names.push("4120"),
+        0x00880000 => names.push("4111"),
+        0x008a0000 => names.push("sb1"),
+        0x008b0000 => names.push("octeon"),
+        0x008c0000 => names.push("xlr"),
+        0x008d0000 => names.push("octeon2"),
+        0x008e0000 => names.push("octeon3"),
+        0x00910000 => names.push("5400"),
+        0x00920000 => names.push("5900"),
+        0x00930000 => names.push("interaptiv-mr2"),
+        0x00980000 => names.push("5500"),
+        0x00990000 => names.push("9000"),
+        0x00a00000 => names.push("loongson-2e"),
+        0x00a10000 => names.push("loongson-2f"),
+        0x00a20000 => names.push("gs464"),
+        0x00a30000 => names.push("gs464e"),
+        0x00a40000 => names.push("gs264e"),
+        _ => names.push("unknown CPU"),
+    }
+
+    match flags & EF_MIPS_ABI {
+        0 => (),
+        0x1000 => names.push("o32"),
+        0x2000 => names.push("o64"),
+        0x3000 => names.push("eabi32"),
+        0x4000 => names.push("eabi64"),
+        _ => names.push("unknown ABI"),
+    }
+
+    if flags & EF_MIPS_ARCH_ASE_MDMX != 0 {
+        names.push("mdmx");
+    }
+    if flags & EF_MIPS_ARCH_ASE_M16 != 0 {
+        names.push("mips16");
+    }
+    if flags & EF_MIPS_ARCH_ASE_MICROMIPS != 0 {
+        names.push("micromips");
+    }
+
+    names.push(match flags & EF_MIPS_ARCH {
+        0x00000000 => "mips1",
+        0x10000000 => "mips2",
+        0x20000000 => "mips3",
+        0x30000000 => "mips4",
+        0x40000000 => "mips5",
+        0x50000000 => "mips32",
+        0x60000000 => "mips64",
+        0x70000000 => "mips32r2",
+        0x80000000 => "mips64r2",
+        0x90000000 => "mips32r6",
+        0xa0000000 => "mips64r6",
+        _ => "unknown ISA",
+    });
+}
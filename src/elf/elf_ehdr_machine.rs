@@ -1,183 +1,427 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u16)]
-#[derive(Debug,Clone,Copy)]
-#[allow(dead_code)]
-pub enum ElfEhdrMachine {
-    EM_NONE,
-    EM_M32,
-    EM_SPARC,
-    EM_386,
-    EM_68K,
-    EM_88K,
-    EM_860,
-    EM_MIPS,
-    EM_S370,
-    EM_MIPS_RS3_LE,
-
-    EM_PARISC = 15,
-
-    EM_VPP500 = 17,
-    EM_SPARC32PLUS,
-    EM_960,
-    EM_PPC,
-    EM_PPC64,
-    EM_S390,
+/// `e_machine`. Any value can be represented; the constants below are the
+/// ones readelf knows a name for.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfEhdrMachine {
+    data: u16,
+}
 
-    EM_V800 = 36,
-    EM_FR20,
-    EM_RH32,
-    EM_RCE,
-    EM_ARM,
-    EM_FAKE_ALPHA,
-    EM_SH,
-    EM_SPARCV9,
-    EM_TRICORE,
-    EM_ARC,
-    EM_H8_300,
-    EM_H8_300H,
-    EM_H8S,
-    EM_H8_500,
-    EM_IA_64,
-    EM_MIPS_X,
-    EM_COLDFIRE,
-    EM_68HC12,
-    EM_MMA,
-    EM_PCP,
-    EM_NCPU,
-    EM_NDR1,
-    EM_STARCORE,
-    EM_ME16,
-    EM_ST100,
-    EM_TINYJ,
-    EM_X86_64,
-    EM_PDSP,
+impl ElfEhdrMachine {
+    pub const EM_NONE: ElfEhdrMachine = ElfEhdrMachine { data: 0 };
+    pub const EM_M32: ElfEhdrMachine = ElfEhdrMachine { data: 1 };
+    pub const EM_SPARC: ElfEhdrMachine = ElfEhdrMachine { data: 2 };
+    pub const EM_386: ElfEhdrMachine = ElfEhdrMachine { data: 3 };
+    pub const EM_68K: ElfEhdrMachine = ElfEhdrMachine { data: 4 };
+    pub const EM_88K: ElfEhdrMachine = ElfEhdrMachine { data: 5 };
+    pub const EM_IAMCU: ElfEhdrMachine = ElfEhdrMachine { data: 6 };
+    pub const EM_860: ElfEhdrMachine = ElfEhdrMachine { data: 7 };
+    pub const EM_MIPS: ElfEhdrMachine = ElfEhdrMachine { data: 8 };
+    pub const EM_S370: ElfEhdrMachine = ElfEhdrMachine { data: 9 };
+    pub const EM_MIPS_RS3_LE: ElfEhdrMachine = ElfEhdrMachine { data: 10 };
+    pub const EM_OLD_SPARCV9: ElfEhdrMachine = ElfEhdrMachine { data: 11 };
+    pub const EM_PARISC: ElfEhdrMachine = ElfEhdrMachine { data: 15 };
+    pub const EM_VPP500: ElfEhdrMachine = ElfEhdrMachine { data: 17 };
+    pub const EM_SPARC32PLUS: ElfEhdrMachine = ElfEhdrMachine { data: 18 };
+    pub const EM_960: ElfEhdrMachine = ElfEhdrMachine { data: 19 };
+    pub const EM_PPC: ElfEhdrMachine = ElfEhdrMachine { data: 20 };
+    pub const EM_PPC64: ElfEhdrMachine = ElfEhdrMachine { data: 21 };
+    pub const EM_S390: ElfEhdrMachine = ElfEhdrMachine { data: 22 };
+    pub const EM_SPU: ElfEhdrMachine = ElfEhdrMachine { data: 23 };
+    pub const EM_V800: ElfEhdrMachine = ElfEhdrMachine { data: 36 };
+    pub const EM_FR20: ElfEhdrMachine = ElfEhdrMachine { data: 37 };
+    pub const EM_RH32: ElfEhdrMachine = ElfEhdrMachine { data: 38 };
+    pub const EM_RCE: ElfEhdrMachine = ElfEhdrMachine { data: 39 };
+    pub const EM_ARM: ElfEhdrMachine = ElfEhdrMachine { data: 40 };
+    pub const EM_FAKE_ALPHA: ElfEhdrMachine = ElfEhdrMachine { data: 41 };
+    pub const EM_SH: ElfEhdrMachine = ElfEhdrMachine { data: 42 };
+    pub const EM_SPARCV9: ElfEhdrMachine = ElfEhdrMachine { data: 43 };
+    pub const EM_TRICORE: ElfEhdrMachine = ElfEhdrMachine { data: 44 };
+    pub const EM_ARC: ElfEhdrMachine = ElfEhdrMachine { data: 45 };
+    pub const EM_H8_300: ElfEhdrMachine = ElfEhdrMachine { data: 46 };
+    pub const EM_H8_300H: ElfEhdrMachine = ElfEhdrMachine { data: 47 };
+    pub const EM_H8S: ElfEhdrMachine = ElfEhdrMachine { data: 48 };
+    pub const EM_H8_500: ElfEhdrMachine = ElfEhdrMachine { data: 49 };
+    pub const EM_IA_64: ElfEhdrMachine = ElfEhdrMachine { data: 50 };
+    pub const EM_MIPS_X: ElfEhdrMachine = ElfEhdrMachine { data: 51 };
+    pub const EM_COLDFIRE: ElfEhdrMachine = ElfEhdrMachine { data: 52 };
+    pub const EM_68HC12: ElfEhdrMachine = ElfEhdrMachine { data: 53 };
+    pub const EM_MMA: ElfEhdrMachine = ElfEhdrMachine { data: 54 };
+    pub const EM_PCP: ElfEhdrMachine = ElfEhdrMachine { data: 55 };
+    pub const EM_NCPU: ElfEhdrMachine = ElfEhdrMachine { data: 56 };
+    pub const EM_NDR1: ElfEhdrMachine = ElfEhdrMachine { data: 57 };
+    pub const EM_STARCORE: ElfEhdrMachine = ElfEhdrMachine { data: 58 };
+    pub const EM_ME16: ElfEhdrMachine = ElfEhdrMachine { data: 59 };
+    pub const EM_ST100: ElfEhdrMachine = ElfEhdrMachine { data: 60 };
+    pub const EM_TINYJ: ElfEhdrMachine = ElfEhdrMachine { data: 61 };
+    pub const EM_X86_64: ElfEhdrMachine = ElfEhdrMachine { data: 62 };
+    pub const EM_PDSP: ElfEhdrMachine = ElfEhdrMachine { data: 63 };
+    pub const EM_PDP10: ElfEhdrMachine = ElfEhdrMachine { data: 64 };
+    pub const EM_PDP11: ElfEhdrMachine = ElfEhdrMachine { data: 65 };
+    pub const EM_FX66: ElfEhdrMachine = ElfEhdrMachine { data: 66 };
+    pub const EM_ST9PLUS: ElfEhdrMachine = ElfEhdrMachine { data: 67 };
+    pub const EM_ST7: ElfEhdrMachine = ElfEhdrMachine { data: 68 };
+    pub const EM_68HC16: ElfEhdrMachine = ElfEhdrMachine { data: 69 };
+    pub const EM_68HC11: ElfEhdrMachine = ElfEhdrMachine { data: 70 };
+    pub const EM_68HC08: ElfEhdrMachine = ElfEhdrMachine { data: 71 };
+    pub const EM_68HC05: ElfEhdrMachine = ElfEhdrMachine { data: 72 };
+    pub const EM_SVX: ElfEhdrMachine = ElfEhdrMachine { data: 73 };
+    pub const EM_ST19: ElfEhdrMachine = ElfEhdrMachine { data: 74 };
+    pub const EM_VAX: ElfEhdrMachine = ElfEhdrMachine { data: 75 };
+    pub const EM_CRIS: ElfEhdrMachine = ElfEhdrMachine { data: 76 };
+    pub const EM_JAVELIN: ElfEhdrMachine = ElfEhdrMachine { data: 77 };
+    pub const EM_FIREPATH: ElfEhdrMachine = ElfEhdrMachine { data: 78 };
+    pub const EM_ZSP: ElfEhdrMachine = ElfEhdrMachine { data: 79 };
+    pub const EM_MMIX: ElfEhdrMachine = ElfEhdrMachine { data: 80 };
+    pub const EM_HUANY: ElfEhdrMachine = ElfEhdrMachine { data: 81 };
+    pub const EM_PRISM: ElfEhdrMachine = ElfEhdrMachine { data: 82 };
+    pub const EM_AVR: ElfEhdrMachine = ElfEhdrMachine { data: 83 };
+    pub const EM_FR30: ElfEhdrMachine = ElfEhdrMachine { data: 84 };
+    pub const EM_D10V: ElfEhdrMachine = ElfEhdrMachine { data: 85 };
+    pub const EM_D30V: ElfEhdrMachine = ElfEhdrMachine { data: 86 };
+    pub const EM_V850: ElfEhdrMachine = ElfEhdrMachine { data: 87 };
+    pub const EM_M32R: ElfEhdrMachine = ElfEhdrMachine { data: 88 };
+    pub const EM_MN10300: ElfEhdrMachine = ElfEhdrMachine { data: 89 };
+    pub const EM_MN10200: ElfEhdrMachine = ElfEhdrMachine { data: 90 };
+    pub const EM_PJ: ElfEhdrMachine = ElfEhdrMachine { data: 91 };
+    pub const EM_OPENRISC: ElfEhdrMachine = ElfEhdrMachine { data: 92 };
+    pub const EM_ARC_COMPACT: ElfEhdrMachine = ElfEhdrMachine { data: 93 };
+    pub const EM_ARC_A5: ElfEhdrMachine = ElfEhdrMachine::EM_ARC_COMPACT;
+    pub const EM_XTENSA: ElfEhdrMachine = ElfEhdrMachine { data: 94 };
+    pub const EM_VIDEOCORE: ElfEhdrMachine = ElfEhdrMachine { data: 95 };
+    pub const EM_TMM_GPP: ElfEhdrMachine = ElfEhdrMachine { data: 96 };
+    pub const EM_NS32K: ElfEhdrMachine = ElfEhdrMachine { data: 97 };
+    pub const EM_TPC: ElfEhdrMachine = ElfEhdrMachine { data: 98 };
+    pub const EM_SNP1K: ElfEhdrMachine = ElfEhdrMachine { data: 99 };
+    pub const EM_ST200: ElfEhdrMachine = ElfEhdrMachine { data: 100 };
+    pub const EM_IP2K: ElfEhdrMachine = ElfEhdrMachine { data: 101 };
+    pub const EM_MAX: ElfEhdrMachine = ElfEhdrMachine { data: 102 };
+    pub const EM_CR: ElfEhdrMachine = ElfEhdrMachine { data: 103 };
+    pub const EM_F2MC16: ElfEhdrMachine = ElfEhdrMachine { data: 104 };
+    pub const EM_MSP430: ElfEhdrMachine = ElfEhdrMachine { data: 105 };
+    pub const EM_BLACKFIN: ElfEhdrMachine = ElfEhdrMachine { data: 106 };
+    pub const EM_SE_C33: ElfEhdrMachine = ElfEhdrMachine { data: 107 };
+    pub const EM_SEP: ElfEhdrMachine = ElfEhdrMachine { data: 108 };
+    pub const EM_ARCA: ElfEhdrMachine = ElfEhdrMachine { data: 109 };
+    pub const EM_UNICORE: ElfEhdrMachine = ElfEhdrMachine { data: 110 };
+    pub const EM_EXCESS: ElfEhdrMachine = ElfEhdrMachine { data: 111 };
+    pub const EM_DXP: ElfEhdrMachine = ElfEhdrMachine { data: 112 };
+    pub const EM_ALTERA_NIOS2: ElfEhdrMachine = ElfEhdrMachine { data: 113 };
+    pub const EM_CRX: ElfEhdrMachine = ElfEhdrMachine { data: 114 };
+    pub const EM_XGATE: ElfEhdrMachine = ElfEhdrMachine { data: 115 };
+    pub const EM_C166: ElfEhdrMachine = ElfEhdrMachine { data: 116 };
+    pub const EM_M16C: ElfEhdrMachine = ElfEhdrMachine { data: 117 };
+    pub const EM_DSPIC30F: ElfEhdrMachine = ElfEhdrMachine { data: 118 };
+    pub const EM_CE: ElfEhdrMachine = ElfEhdrMachine { data: 119 };
+    pub const EM_M32C: ElfEhdrMachine = ElfEhdrMachine { data: 120 };
+    pub const EM_TSK3000: ElfEhdrMachine = ElfEhdrMachine { data: 131 };
+    pub const EM_RS08: ElfEhdrMachine = ElfEhdrMachine { data: 132 };
+    pub const EM_SHARC: ElfEhdrMachine = ElfEhdrMachine { data: 133 };
+    pub const EM_ECOG2: ElfEhdrMachine = ElfEhdrMachine { data: 134 };
+    pub const EM_SCORE7: ElfEhdrMachine = ElfEhdrMachine { data: 135 };
+    pub const EM_DSP24: ElfEhdrMachine = ElfEhdrMachine { data: 136 };
+    pub const EM_VIDEOCORE3: ElfEhdrMachine = ElfEhdrMachine { data: 137 };
+    pub const EM_LATTICEMICO32: ElfEhdrMachine = ElfEhdrMachine { data: 138 };
+    pub const EM_SE_C17: ElfEhdrMachine = ElfEhdrMachine { data: 139 };
+    pub const EM_TI_C6000: ElfEhdrMachine = ElfEhdrMachine { data: 140 };
+    pub const EM_TI_C2000: ElfEhdrMachine = ElfEhdrMachine { data: 141 };
+    pub const EM_TI_C5500: ElfEhdrMachine = ElfEhdrMachine { data: 142 };
+    pub const EM_TI_ARP32: ElfEhdrMachine = ElfEhdrMachine { data: 143 };
+    pub const EM_TI_PRU: ElfEhdrMachine = ElfEhdrMachine { data: 144 };
+    pub const EM_MMDSP_PLUS: ElfEhdrMachine = ElfEhdrMachine { data: 160 };
+    pub const EM_CYPRESS_M8C: ElfEhdrMachine = ElfEhdrMachine { data: 161 };
+    pub const EM_R32C: ElfEhdrMachine = ElfEhdrMachine { data: 162 };
+    pub const EM_TRIMEDIA: ElfEhdrMachine = ElfEhdrMachine { data: 163 };
+    pub const EM_QDSP6: ElfEhdrMachine = ElfEhdrMachine { data: 164 };
+    pub const EM_8051: ElfEhdrMachine = ElfEhdrMachine { data: 165 };
+    pub const EM_STXP7X: ElfEhdrMachine = ElfEhdrMachine { data: 166 };
+    pub const EM_NDS32: ElfEhdrMachine = ElfEhdrMachine { data: 167 };
+    pub const EM_ECOG1X: ElfEhdrMachine = ElfEhdrMachine { data: 168 };
+    pub const EM_MAXQ30: ElfEhdrMachine = ElfEhdrMachine { data: 169 };
+    pub const EM_XIMO16: ElfEhdrMachine = ElfEhdrMachine { data: 170 };
+    pub const EM_MANIK: ElfEhdrMachine = ElfEhdrMachine { data: 171 };
+    pub const EM_CRAYNV2: ElfEhdrMachine = ElfEhdrMachine { data: 172 };
+    pub const EM_RX: ElfEhdrMachine = ElfEhdrMachine { data: 173 };
+    pub const EM_METAG: ElfEhdrMachine = ElfEhdrMachine { data: 174 };
+    pub const EM_MCST_ELBRUS: ElfEhdrMachine = ElfEhdrMachine { data: 175 };
+    pub const EM_ECOG16: ElfEhdrMachine = ElfEhdrMachine { data: 176 };
+    pub const EM_CR16: ElfEhdrMachine = ElfEhdrMachine { data: 177 };
+    pub const EM_ETPU: ElfEhdrMachine = ElfEhdrMachine { data: 178 };
+    pub const EM_SLE9X: ElfEhdrMachine = ElfEhdrMachine { data: 179 };
+    pub const EM_L10M: ElfEhdrMachine = ElfEhdrMachine { data: 180 };
+    pub const EM_K10M: ElfEhdrMachine = ElfEhdrMachine { data: 181 };
+    pub const EM_INTEL182: ElfEhdrMachine = ElfEhdrMachine { data: 182 };
+    pub const EM_AARCH64: ElfEhdrMachine = ElfEhdrMachine { data: 183 };
+    pub const EM_ARM184: ElfEhdrMachine = ElfEhdrMachine { data: 184 };
+    pub const EM_AVR32: ElfEhdrMachine = ElfEhdrMachine { data: 185 };
+    pub const EM_STM8: ElfEhdrMachine = ElfEhdrMachine { data: 186 };
+    pub const EM_TILE64: ElfEhdrMachine = ElfEhdrMachine { data: 187 };
+    pub const EM_TILEPRO: ElfEhdrMachine = ElfEhdrMachine { data: 188 };
+    pub const EM_MICROBLAZE: ElfEhdrMachine = ElfEhdrMachine { data: 189 };
+    pub const EM_CUDA: ElfEhdrMachine = ElfEhdrMachine { data: 190 };
+    pub const EM_TILEGX: ElfEhdrMachine = ElfEhdrMachine { data: 191 };
+    pub const EM_CLOUDSHIELD: ElfEhdrMachine = ElfEhdrMachine { data: 192 };
+    pub const EM_COREA_1ST: ElfEhdrMachine = ElfEhdrMachine { data: 193 };
+    pub const EM_COREA_2ND: ElfEhdrMachine = ElfEhdrMachine { data: 194 };
+    pub const EM_ARCV2: ElfEhdrMachine = ElfEhdrMachine { data: 195 };
+    pub const EM_OPEN8: ElfEhdrMachine = ElfEhdrMachine { data: 196 };
+    pub const EM_RL78: ElfEhdrMachine = ElfEhdrMachine { data: 197 };
+    pub const EM_VIDEOCORE5: ElfEhdrMachine = ElfEhdrMachine { data: 198 };
+    pub const EM_78KOR: ElfEhdrMachine = ElfEhdrMachine { data: 199 };
+    pub const EM_56800EX: ElfEhdrMachine = ElfEhdrMachine { data: 200 };
+    pub const EM_BA1: ElfEhdrMachine = ElfEhdrMachine { data: 201 };
+    pub const EM_BA2: ElfEhdrMachine = ElfEhdrMachine { data: 202 };
+    pub const EM_XCORE: ElfEhdrMachine = ElfEhdrMachine { data: 203 };
+    pub const EM_MCHP_PIC: ElfEhdrMachine = ElfEhdrMachine { data: 204 };
+    pub const EM_INTELGT: ElfEhdrMachine = ElfEhdrMachine { data: 205 };
+    pub const EM_KM32: ElfEhdrMachine = ElfEhdrMachine { data: 210 };
+    pub const EM_KMX32: ElfEhdrMachine = ElfEhdrMachine { data: 211 };
+    pub const EM_EMX16: ElfEhdrMachine = ElfEhdrMachine { data: 212 };
+    pub const EM_EMX8: ElfEhdrMachine = ElfEhdrMachine { data: 213 };
+    pub const EM_KVARC: ElfEhdrMachine = ElfEhdrMachine { data: 214 };
+    pub const EM_CDP: ElfEhdrMachine = ElfEhdrMachine { data: 215 };
+    pub const EM_COGE: ElfEhdrMachine = ElfEhdrMachine { data: 216 };
+    pub const EM_COOL: ElfEhdrMachine = ElfEhdrMachine { data: 217 };
+    pub const EM_NORC: ElfEhdrMachine = ElfEhdrMachine { data: 218 };
+    pub const EM_CSR_KALIMBA: ElfEhdrMachine = ElfEhdrMachine { data: 219 };
+    pub const EM_Z80: ElfEhdrMachine = ElfEhdrMachine { data: 220 };
+    pub const EM_VISIUM: ElfEhdrMachine = ElfEhdrMachine { data: 221 };
+    pub const EM_FT32: ElfEhdrMachine = ElfEhdrMachine { data: 222 };
+    pub const EM_MOXIE: ElfEhdrMachine = ElfEhdrMachine { data: 223 };
+    pub const EM_AMDGPU: ElfEhdrMachine = ElfEhdrMachine { data: 224 };
+    pub const EM_RISCV: ElfEhdrMachine = ElfEhdrMachine { data: 243 };
+    pub const EM_LANAI: ElfEhdrMachine = ElfEhdrMachine { data: 244 };
+    pub const EM_CEVA: ElfEhdrMachine = ElfEhdrMachine { data: 245 };
+    pub const EM_CEVA_X2: ElfEhdrMachine = ElfEhdrMachine { data: 246 };
+    pub const EM_BPF: ElfEhdrMachine = ElfEhdrMachine { data: 247 };
+    pub const EM_GRAPHCORE: ElfEhdrMachine = ElfEhdrMachine { data: 248 };
+    pub const EM_IMG1: ElfEhdrMachine = ElfEhdrMachine { data: 249 };
+    pub const EM_NFP: ElfEhdrMachine = ElfEhdrMachine { data: 250 };
+    pub const EM_VE: ElfEhdrMachine = ElfEhdrMachine { data: 251 };
+    pub const EM_CSKY: ElfEhdrMachine = ElfEhdrMachine { data: 252 };
+    pub const EM_ARC_COMPACT3_64: ElfEhdrMachine = ElfEhdrMachine { data: 253 };
+    pub const EM_MCS6502: ElfEhdrMachine = ElfEhdrMachine { data: 254 };
+    pub const EM_ARC_COMPACT3: ElfEhdrMachine = ElfEhdrMachine { data: 255 };
+    pub const EM_KVX: ElfEhdrMachine = ElfEhdrMachine { data: 256 };
+    pub const EM_65816: ElfEhdrMachine = ElfEhdrMachine { data: 257 };
+    pub const EM_LOONGARCH: ElfEhdrMachine = ElfEhdrMachine { data: 258 };
+    pub const EM_ALPHA: ElfEhdrMachine = ElfEhdrMachine { data: 0x9026 };
+}
 
-    EM_FX66 = 66,
-    EM_ST9PLUS,
-    EM_ST7,
-    EM_68HC16,
-    EM_68HC11,
-    EM_68HC08,
-    EM_68HC05,
-    EM_SVX,
-    EM_ST19,
-    EM_VAX,
-    EM_CRIS,
-    EM_JAVELIN,
-    EM_FIREPATH,
-    EM_ZSP,
-    EM_MMIX,
-    EM_HUANY,
-    EM_PRISM,
-    EM_AVR,
-    EM_FR30,
-    EM_D10V,
-    EM_D30V,
-    EM_V850,
-    EM_M32R,
-    EM_MN10300,
-    EM_MN10200,
-    EM_PJ,
-    EM_OPENRISC,
-    EM_ARC_A5,
-    EM_XTENSA,
-    EM_AARCH64,
-    EM_TILEPRO,
-    EM_MICROBLAZE,
-    EM_TILEGX,
-    EM_NUM,
+impl From<u16> for ElfEhdrMachine {
+    fn from(data: u16) -> ElfEhdrMachine {
+        ElfEhdrMachine { data }
+    }
+}
 
-    EM_ALPHA = 0x9026,
+impl ElfEhdrMachine {
+    pub fn get(&self) -> u16 {
+        self.data
+    }
 }
 
 impl Display for ElfEhdrMachine {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfEhdrMachine::*;
         let s = match *self {
-            EM_NONE => "No machine",
-            EM_M32 => "AT&T WE 32100",
-            EM_SPARC => "SUN SPARC",
-            EM_386 => "Intel 80386",
-            EM_68K => "Motorola m68k family",
-            EM_88K => "Motorola m88k family",
-            EM_860 => "Intel 80860",
-            EM_MIPS => "MIPS R3000 big-endian",
-            EM_S370 => "IBM System/370",
-            EM_MIPS_RS3_LE => "MIPS R3000 little-endian",
-
-            EM_PARISC => "HPPA",
-            EM_VPP500 => "Fujitsu VPP500",
-            EM_SPARC32PLUS => "Sun's \"v8plus\"",
-            EM_960 => "Intel 80960",
-            EM_PPC => "PowerPC",
-            EM_PPC64 => "PowerPC 64-bit",
-            EM_S390 => "IBM S390",
-
-            EM_V800 => "NEC V800 series",
-            EM_FR20 => "Fujitsu FR20",
-            EM_RH32 => "TRW RH-32",
-            EM_RCE => "Motorola RCE",
-            EM_ARM => "ARM",
-            EM_FAKE_ALPHA => "Digital Alpha",
-            EM_SH => "Hitachi SH",
-            EM_SPARCV9 => "SPARC v9 64-bit",
-            EM_TRICORE => "Siemens Tricore",
-            EM_ARC => "Argonaut RISC Core",
-            EM_H8_300 => "Hitachi H8/300",
-            EM_H8_300H => "Hitachi H8/300H",
-            EM_H8S => "Hitachi H8S",
-            EM_H8_500 => "Hitachi H8/500",
-            EM_IA_64 => "Intel Merced",
-            EM_MIPS_X => "Stanford MIPS-X",
-            EM_COLDFIRE => "Motorola Coldfire",
-            EM_68HC12 => "Motorola M68HC12",
-            EM_MMA => "Fujitsu MMA Multimedia Accelerato",
-            EM_PCP => "Siemens PCP",
-            EM_NCPU => "Sony nCPU embeeded RISC",
-            EM_NDR1 => "Denso NDR1 microprocessor",
-            EM_STARCORE => "Motorola Start*Core processor",
-            EM_ME16 => "Toyota ME16 processor",
-            EM_ST100 => "STMicroelectronic ST100 processor",
-            EM_TINYJ => "Advanced Logic Corp. Tinyj emb.fa",
-            EM_X86_64 => "Advanced Micro Devices x86-64",
-            EM_PDSP => "Sony DSP Processor",
-
-            EM_FX66 => "Siemens FX66 microcontroller",
-            EM_ST9PLUS => "STMicroelectronics ST9+ 8/16 mc",
-            EM_ST7 => "STmicroelectronics ST7 8 bit mc",
-            EM_68HC16 => "Motorola MC68HC16 microcontroller",
-            EM_68HC11 => "Motorola MC68HC11 microcontroller",
-            EM_68HC08 => "Motorola MC68HC08 microcontroller",
-            EM_68HC05 => "Motorola MC68HC05 microcontroller",
-            EM_SVX => "Silicon Graphics SVx",
-            EM_ST19 => "STMicroelectronics ST19 8 bit mc",
-            EM_VAX => "Digital VAX",
-            EM_CRIS => "Axis Communications 32-bit embedded processor",
-            EM_JAVELIN => "Infineon Technologies 32-bit embedded processor",
-            EM_FIREPATH => "Element 14 64-bit DSP Processor",
-            EM_ZSP => "LSI Logic 16-bit DSP Processor",
-            EM_MMIX => "Donald Knuth's educational 64-bit processor",
-            EM_HUANY => "Harvard University machine-independent object files",
-            EM_PRISM => "SiTera Prism",
-            EM_AVR => "Atmel AVR 8-bit microcontroller",
-            EM_FR30 => "Fujitsu FR30",
-            EM_D10V => "Mitsubishi D10V",
-            EM_D30V => "Mitsubishi D30V",
-            EM_V850 => "NEC v850",
-            EM_M32R => "Mitsubishi M32R",
-            EM_MN10300 => "Matsushita MN10300",
-            EM_MN10200 => "Matsushita MN10200",
-            EM_PJ => "picoJava",
-            EM_OPENRISC => "OpenRISC 32-bit embedded processor",
-            EM_ARC_A5 => "ARC Cores Tangent-A5",
-            EM_XTENSA => "Tensilica Xtensa Architecture",
-            EM_AARCH64 => "ARM AARCH64",
-            EM_TILEPRO => "Tilera TILEPro",
-            EM_MICROBLAZE => "Xilinx MicroBlaze",
-            EM_TILEGX => "Tilera TILE-Gx",
-            EM_ALPHA => "Alpha",
-            _ => "Unknown machine",
+            ElfEhdrMachine::EM_NONE => "None",
+            ElfEhdrMachine::EM_M32 => "WE32100",
+            ElfEhdrMachine::EM_SPARC => "Sparc",
+            ElfEhdrMachine::EM_386 => "Intel 80386",
+            ElfEhdrMachine::EM_68K => "MC68000",
+            ElfEhdrMachine::EM_88K => "MC88000",
+            ElfEhdrMachine::EM_IAMCU => "Intel MCU",
+            ElfEhdrMachine::EM_860 => "Intel 80860",
+            ElfEhdrMachine::EM_MIPS => "MIPS R3000",
+            ElfEhdrMachine::EM_S370 => "IBM System/370",
+            ElfEhdrMachine::EM_MIPS_RS3_LE => "MIPS R4000 big-endian",
+            ElfEhdrMachine::EM_OLD_SPARCV9 => "Sparc v9 (old)",
+            ElfEhdrMachine::EM_PARISC => "HPPA",
+            ElfEhdrMachine::EM_VPP500 => "Fujitsu VPP500",
+            ElfEhdrMachine::EM_SPARC32PLUS => "Sparc v8+",
+            ElfEhdrMachine::EM_960 => "Intel 80960",
+            ElfEhdrMachine::EM_PPC => "PowerPC",
+            ElfEhdrMachine::EM_PPC64 => "PowerPC64",
+            ElfEhdrMachine::EM_S390 => "IBM S/390",
+            ElfEhdrMachine::EM_SPU => "SPU",
+            ElfEhdrMachine::EM_V800 => "Renesas V850 (using RH850 ABI)",
+            ElfEhdrMachine::EM_FR20 => "Fujitsu FR20",
+            ElfEhdrMachine::EM_RH32 => "TRW RH32",
+            ElfEhdrMachine::EM_RCE => "MCORE",
+            ElfEhdrMachine::EM_ARM => "ARM",
+            ElfEhdrMachine::EM_FAKE_ALPHA => "Digital Alpha (old)",
+            ElfEhdrMachine::EM_SH => "Renesas / SuperH SH",
+            ElfEhdrMachine::EM_SPARCV9 => "Sparc v9",
+            ElfEhdrMachine::EM_TRICORE => "Siemens Tricore",
+            ElfEhdrMachine::EM_ARC => "ARC",
+            ElfEhdrMachine::EM_H8_300 => "Renesas H8/300",
+            ElfEhdrMachine::EM_H8_300H => "Renesas H8/300H",
+            ElfEhdrMachine::EM_H8S => "Renesas H8S",
+            ElfEhdrMachine::EM_H8_500 => "Renesas H8/500",
+            ElfEhdrMachine::EM_IA_64 => "Intel IA-64",
+            ElfEhdrMachine::EM_MIPS_X => "Stanford MIPS-X",
+            ElfEhdrMachine::EM_COLDFIRE => "Motorola Coldfire",
+            ElfEhdrMachine::EM_68HC12 => "Motorola MC68HC12 Microcontroller",
+            ElfEhdrMachine::EM_MMA => "Fujitsu Multimedia Accelerator",
+            ElfEhdrMachine::EM_PCP => "Siemens PCP",
+            ElfEhdrMachine::EM_NCPU => "Sony nCPU embedded RISC processor",
+            ElfEhdrMachine::EM_NDR1 => "Denso NDR1 microprocesspr",
+            ElfEhdrMachine::EM_STARCORE => "Motorola Star*Core processor",
+            ElfEhdrMachine::EM_ME16 => "Toyota ME16 processor",
+            ElfEhdrMachine::EM_ST100 => "STMicroelectronics ST100 processor",
+            ElfEhdrMachine::EM_TINYJ => "Advanced Logic Corp. TinyJ embedded processor",
+            ElfEhdrMachine::EM_X86_64 => "Advanced Micro Devices X86-64",
+            ElfEhdrMachine::EM_PDSP => "Sony DSP processor",
+            ElfEhdrMachine::EM_PDP10 => "Digital Equipment Corp. PDP-10",
+            ElfEhdrMachine::EM_PDP11 => "Digital Equipment Corp. PDP-11",
+            ElfEhdrMachine::EM_FX66 => "Siemens FX66 microcontroller",
+            ElfEhdrMachine::EM_ST9PLUS => "STMicroelectronics ST9+ 8/16 bit microcontroller",
+            ElfEhdrMachine::EM_ST7 => "STMicroelectronics ST7 8-bit microcontroller",
+            ElfEhdrMachine::EM_68HC16 => "Motorola MC68HC16 Microcontroller",
+            ElfEhdrMachine::EM_68HC11 => "Motorola MC68HC11 Microcontroller",
+            ElfEhdrMachine::EM_68HC08 => "Motorola MC68HC08 Microcontroller",
+            ElfEhdrMachine::EM_68HC05 => "Motorola MC68HC05 Microcontroller",
+            ElfEhdrMachine::EM_SVX => "Silicon Graphics SVx",
+            ElfEhdrMachine::EM_ST19 => "STMicroelectronics ST19 8-bit microcontroller",
+            ElfEhdrMachine::EM_VAX => "Digital VAX",
+            ElfEhdrMachine::EM_CRIS => "Axis Communications 32-bit embedded processor",
+            ElfEhdrMachine::EM_JAVELIN => "Infineon Technologies 32-bit embedded cpu",
+            ElfEhdrMachine::EM_FIREPATH => "Element 14 64-bit DSP processor",
+            ElfEhdrMachine::EM_ZSP => "LSI Logic's 16-bit DSP processor",
+            ElfEhdrMachine::EM_MMIX => "Donald Knuth's educational 64-bit processor",
+            ElfEhdrMachine::EM_HUANY => "Harvard Universitys's machine-independent object format",
+            ElfEhdrMachine::EM_PRISM => "Vitesse Prism",
+            ElfEhdrMachine::EM_AVR => "Atmel AVR 8-bit microcontroller",
+            ElfEhdrMachine::EM_FR30 => "Fujitsu FR30",
+            ElfEhdrMachine::EM_D10V => "d10v",
+            ElfEhdrMachine::EM_D30V => "d30v",
+            ElfEhdrMachine::EM_V850 => "Renesas V850",
+            ElfEhdrMachine::EM_M32R => "Renesas M32R (formerly Mitsubishi M32r)",
+            ElfEhdrMachine::EM_MN10300 => "mn10300",
+            ElfEhdrMachine::EM_MN10200 => "mn10200",
+            ElfEhdrMachine::EM_PJ => "picoJava",
+            ElfEhdrMachine::EM_OPENRISC => "OpenRISC 1000",
+            ElfEhdrMachine::EM_ARC_COMPACT => "ARCompact",
+            ElfEhdrMachine::EM_XTENSA => "Tensilica Xtensa Processor",
+            ElfEhdrMachine::EM_VIDEOCORE => "Alphamosaic VideoCore processor",
+            ElfEhdrMachine::EM_TMM_GPP => "Thompson Multimedia General Purpose Processor",
+            ElfEhdrMachine::EM_NS32K => "National Semiconductor 32000 series",
+            ElfEhdrMachine::EM_TPC => "Tenor Network TPC processor",
+            ElfEhdrMachine::EM_SNP1K => "Trebia SNP 1000 processor",
+            ElfEhdrMachine::EM_ST200 => "STMicroelectronics ST200 microcontroller",
+            ElfEhdrMachine::EM_IP2K => "Ubicom IP2xxx 8-bit microcontrollers",
+            ElfEhdrMachine::EM_MAX => "MAX Processor",
+            ElfEhdrMachine::EM_CR => "National Semiconductor CompactRISC",
+            ElfEhdrMachine::EM_F2MC16 => "Fujitsu F2MC16",
+            ElfEhdrMachine::EM_MSP430 => "Texas Instruments msp430 microcontroller",
+            ElfEhdrMachine::EM_BLACKFIN => "Analog Devices Blackfin",
+            ElfEhdrMachine::EM_SE_C33 => "S1C33 Family of Seiko Epson processors",
+            ElfEhdrMachine::EM_SEP => "Sharp embedded microprocessor",
+            ElfEhdrMachine::EM_ARCA => "Arca RISC microprocessor",
+            ElfEhdrMachine::EM_UNICORE => "Unicore",
+            ElfEhdrMachine::EM_EXCESS => "eXcess 16/32/64-bit configurable embedded CPU",
+            ElfEhdrMachine::EM_DXP => "Icera Semiconductor Inc. Deep Execution Processor",
+            ElfEhdrMachine::EM_ALTERA_NIOS2 => "Altera Nios II",
+            ElfEhdrMachine::EM_CRX => "National Semiconductor CRX microprocessor",
+            ElfEhdrMachine::EM_XGATE => "Motorola XGATE embedded processor",
+            ElfEhdrMachine::EM_C166 => "Infineon Technologies xc16x",
+            ElfEhdrMachine::EM_M16C => "Renesas M16C series microprocessors",
+            ElfEhdrMachine::EM_DSPIC30F => "Microchip Technology dsPIC30F Digital Signal Controller",
+            ElfEhdrMachine::EM_CE => "Freescale Communication Engine RISC core",
+            ElfEhdrMachine::EM_M32C => "Renesas M32c",
+            ElfEhdrMachine::EM_TSK3000 => "Altium TSK3000 core",
+            ElfEhdrMachine::EM_RS08 => "Freescale RS08 embedded processor",
+            ElfEhdrMachine::EM_ECOG2 => "Cyan Technology eCOG2 microprocessor",
+            ElfEhdrMachine::EM_SCORE7 => "SUNPLUS S+Core",
+            ElfEhdrMachine::EM_DSP24 => "New Japan Radio (NJR) 24-bit DSP Processor",
+            ElfEhdrMachine::EM_VIDEOCORE3 => "Broadcom VideoCore III processor",
+            ElfEhdrMachine::EM_LATTICEMICO32 => "Lattice Mico32",
+            ElfEhdrMachine::EM_SE_C17 => "Seiko Epson C17 family",
+            ElfEhdrMachine::EM_TI_C6000 => "Texas Instruments TMS320C6000 DSP family",
+            ElfEhdrMachine::EM_TI_C2000 => "Texas Instruments TMS320C2000 DSP family",
+            ElfEhdrMachine::EM_TI_C5500 => "Texas Instruments TMS320C55x DSP family",
+            ElfEhdrMachine::EM_TI_PRU => "TI PRU I/O processor",
+            ElfEhdrMachine::EM_MMDSP_PLUS => "STMicroelectronics 64bit VLIW Data Signal Processor",
+            ElfEhdrMachine::EM_CYPRESS_M8C => "Cypress M8C microprocessor",
+            ElfEhdrMachine::EM_R32C => "Renesas R32C series microprocessors",
+            ElfEhdrMachine::EM_TRIMEDIA => "NXP Semiconductors TriMedia architecture family",
+            ElfEhdrMachine::EM_QDSP6 => "QUALCOMM DSP6 Processor",
+            ElfEhdrMachine::EM_8051 => "Intel 8051 and variants",
+            ElfEhdrMachine::EM_STXP7X => "STMicroelectronics STxP7x family",
+            ElfEhdrMachine::EM_NDS32 => "Andes Technology compact code size embedded RISC processor family",
+            ElfEhdrMachine::EM_ECOG1X => "Cyan Technology eCOG1X family",
+            ElfEhdrMachine::EM_MAXQ30 => "Dallas Semiconductor MAXQ30 Core microcontrollers",
+            ElfEhdrMachine::EM_XIMO16 => "New Japan Radio (NJR) 16-bit DSP Processor",
+            ElfEhdrMachine::EM_MANIK => "M2000 Reconfigurable RISC Microprocessor",
+            ElfEhdrMachine::EM_CRAYNV2 => "Cray Inc. NV2 vector architecture",
+            ElfEhdrMachine::EM_RX => "Renesas RX",
+            ElfEhdrMachine::EM_METAG => "Imagination Technologies Meta processor architecture",
+            ElfEhdrMachine::EM_MCST_ELBRUS => "MCST Elbrus general purpose hardware architecture",
+            ElfEhdrMachine::EM_ECOG16 => "Cyan Technology eCOG16 family",
+            ElfEhdrMachine::EM_CR16 => "Xilinx MicroBlaze",
+            ElfEhdrMachine::EM_ETPU => "Freescale Extended Time Processing Unit",
+            ElfEhdrMachine::EM_SLE9X => "Infineon Technologies SLE9X core",
+            ElfEhdrMachine::EM_L10M => "Intel L1OM",
+            ElfEhdrMachine::EM_K10M => "Intel K1OM",
+            ElfEhdrMachine::EM_INTEL182 => "Intel (reserved)",
+            ElfEhdrMachine::EM_AARCH64 => "AArch64",
+            ElfEhdrMachine::EM_ARM184 => "ARM (reserved)",
+            ElfEhdrMachine::EM_AVR32 => "Atmel Corporation 32-bit microprocessor",
+            ElfEhdrMachine::EM_STM8 => "STMicroeletronics STM8 8-bit microcontroller",
+            ElfEhdrMachine::EM_TILE64 => "Tilera TILE64 multicore architecture family",
+            ElfEhdrMachine::EM_TILEPRO => "Tilera TILEPro multicore architecture family",
+            ElfEhdrMachine::EM_MICROBLAZE => "Xilinx MicroBlaze",
+            ElfEhdrMachine::EM_CUDA => "NVIDIA CUDA architecture",
+            ElfEhdrMachine::EM_TILEGX => "Tilera TILE-Gx multicore architecture family",
+            ElfEhdrMachine::EM_CLOUDSHIELD => "CloudShield architecture family",
+            ElfEhdrMachine::EM_COREA_1ST => "KIPO-KAIST Core-A 1st generation processor family",
+            ElfEhdrMachine::EM_COREA_2ND => "KIPO-KAIST Core-A 2nd generation processor family",
+            ElfEhdrMachine::EM_ARCV2 => "ARCv2",
+            ElfEhdrMachine::EM_OPEN8 => "Open8 8-bit RISC soft processor core",
+            ElfEhdrMachine::EM_RL78 => "Renesas RL78",
+            ElfEhdrMachine::EM_VIDEOCORE5 => "Broadcom VideoCore V processor",
+            ElfEhdrMachine::EM_78KOR => "Renesas 78K0R",
+            ElfEhdrMachine::EM_56800EX => "Freescale 56800EX Digital Signal Controller (DSC)",
+            ElfEhdrMachine::EM_BA1 => "Beyond BA1 CPU architecture",
+            ElfEhdrMachine::EM_BA2 => "Beyond BA2 CPU architecture",
+            ElfEhdrMachine::EM_XCORE => "XMOS xCORE processor family",
+            ElfEhdrMachine::EM_MCHP_PIC => "Microchip 8-bit PIC(r) family",
+            ElfEhdrMachine::EM_INTELGT => "Intel Graphics Technology",
+            ElfEhdrMachine::EM_KM32 => "KM211 KM32 32-bit processor",
+            ElfEhdrMachine::EM_KMX32 => "KM211 KMX32 32-bit processor",
+            ElfEhdrMachine::EM_EMX16 => "KM211 KMX16 16-bit processor",
+            ElfEhdrMachine::EM_EMX8 => "KM211 KMX8 8-bit processor",
+            ElfEhdrMachine::EM_KVARC => "KM211 KVARC processor",
+            ElfEhdrMachine::EM_CDP => "Paneve CDP architecture family",
+            ElfEhdrMachine::EM_COGE => "Cognitive Smart Memory Processor",
+            ElfEhdrMachine::EM_COOL => "Bluechip Systems CoolEngine",
+            ElfEhdrMachine::EM_NORC => "Nanoradio Optimized RISC",
+            ElfEhdrMachine::EM_CSR_KALIMBA => "CSR Kalimba architecture family",
+            ElfEhdrMachine::EM_Z80 => "Zilog Z80",
+            ElfEhdrMachine::EM_VISIUM => "CDS VISIUMcore processor",
+            ElfEhdrMachine::EM_FT32 => "FTDI Chip FT32",
+            ElfEhdrMachine::EM_MOXIE => "Moxie",
+            ElfEhdrMachine::EM_AMDGPU => "AMD GPU",
+            ElfEhdrMachine::EM_RISCV => "RISC-V",
+            ElfEhdrMachine::EM_LANAI => "Lanai 32-bit processor",
+            ElfEhdrMachine::EM_CEVA => "CEVA Processor Architecture Family",
+            ElfEhdrMachine::EM_CEVA_X2 => "CEVA X2 Processor Family",
+            ElfEhdrMachine::EM_BPF => "Linux BPF",
+            ElfEhdrMachine::EM_GRAPHCORE => "Graphcore Intelligent Processing Unit",
+            ElfEhdrMachine::EM_IMG1 => "Imagination Technologies",
+            ElfEhdrMachine::EM_NFP => "Netronome Flow Processor",
+            ElfEhdrMachine::EM_VE => "NEC Vector Engine",
+            ElfEhdrMachine::EM_CSKY => "C-SKY",
+            ElfEhdrMachine::EM_ARC_COMPACT3_64 => "Synopsys ARCv2.3 64-bit",
+            ElfEhdrMachine::EM_MCS6502 => "MOS Technology MCS 6502 processor",
+            ElfEhdrMachine::EM_ARC_COMPACT3 => "Synopsys ARCv2.3 32-bit",
+            ElfEhdrMachine::EM_KVX => "Kalray VLIW core of the MPPA processor family",
+            ElfEhdrMachine::EM_65816 => "WDC 65816/65C816",
+            ElfEhdrMachine::EM_LOONGARCH => "LoongArch",
+            ElfEhdrMachine::EM_ALPHA => "Alpha",
+            ElfEhdrMachine { data } => {
+                return write!(fmt, "<unknown>: {:#x}", data);
+            }
         };
         write!(fmt, "{}", s)
     }
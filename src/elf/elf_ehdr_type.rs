@@ -1,29 +1,61 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u16)]
-#[derive(Debug,PartialEq,PartialOrd,Eq,Ord,Clone,Copy)]
+#[repr(C)]
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Copy)]
+pub struct ElfEhdrType {
+    data: u16,
+}
+
 #[allow(dead_code)]
-pub enum ElfEhdrType {
-    ET_NONE,
-    ET_REL,
-    ET_EXEC,
-    ET_DYN,
-    ET_CORE,
-    ET_LOPROC = 0xff00,
-    ET_HIPROC = 0xffff,
+impl ElfEhdrType {
+    pub const ET_NONE: ElfEhdrType = ElfEhdrType { data: 0 };
+    pub const ET_REL: ElfEhdrType = ElfEhdrType { data: 1 };
+    pub const ET_EXEC: ElfEhdrType = ElfEhdrType { data: 2 };
+    pub const ET_DYN: ElfEhdrType = ElfEhdrType { data: 3 };
+    pub const ET_CORE: ElfEhdrType = ElfEhdrType { data: 4 };
+    pub const ET_LOOS: ElfEhdrType = ElfEhdrType { data: 0xfe00 };
+    pub const ET_HIOS: ElfEhdrType = ElfEhdrType { data: 0xfeff };
+    pub const ET_LOPROC: ElfEhdrType = ElfEhdrType { data: 0xff00 };
+    pub const ET_HIPROC: ElfEhdrType = ElfEhdrType { data: 0xffff };
+}
+
+impl From<u16> for ElfEhdrType {
+    fn from(data: u16) -> ElfEhdrType {
+        ElfEhdrType { data }
+    }
+}
+
+impl ElfEhdrType {
+    pub fn get(&self) -> u16 {
+        self.data
+    }
+
+    /// Like `to_string`, but names `ET_DYN` files that are
+    /// position-independent executables the way readelf does.
+    pub fn get_name(&self, is_pie: bool) -> String {
+        if *self == ElfEhdrType::ET_DYN && is_pie {
+            String::from("DYN (Position-Independent Executable file)")
+        } else {
+            self.to_string()
+        }
+    }
 }
 
 impl Display for ElfEhdrType {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfEhdrType::*;
         let s = match *self {
-            ET_NONE => "NONE (No file type)",
-            ET_REL => "REL (Relocatable file)",
-            ET_EXEC => "EXEC (Executable file)",
-            ET_DYN => "DYN (Shared object file)",
-            ET_CORE => "CORE (Core file)",
-            ref x if *x >= ET_LOPROC && *x <= ET_HIPROC => "Processor-specific",
-            _ => "Unknown file type",
+            ElfEhdrType::ET_NONE => "NONE (None)",
+            ElfEhdrType::ET_REL => "REL (Relocatable file)",
+            ElfEhdrType::ET_EXEC => "EXEC (Executable file)",
+            ElfEhdrType::ET_DYN => "DYN (Shared object file)",
+            ElfEhdrType::ET_CORE => "CORE (Core file)",
+            x if x >= ElfEhdrType::ET_LOPROC => {
+                return write!(fmt, "Processor Specific: ({:x})", x.data);
+            }
+            x if x >= ElfEhdrType::ET_LOOS => {
+                return write!(fmt, "OS Specific: ({:x})", x.data);
+            }
+            x => return write!(fmt, "<unknown>: {:x}", x.data),
         };
         write!(fmt, "{}", s)
     }
@@ -32,4 +64,4 @@ impl Display for ElfEhdrType {
 swap_in_place_wrapper!(ElfEhdrType, u16);
 to_host_in_place_wrapper!(ElfEhdrType, u16);
 swap_copy_wrapper!(ElfEhdrType, u16);
-to_host_copy_wrapper!(ElfEhdrType, u16);
\ No newline at end of file
+to_host_copy_wrapper!(ElfEhdrType, u16);
@@ -1,12 +1,23 @@
 use ::std::fmt::{Display, Formatter};
 
 #[repr(C)]
-#[derive(Debug)]
-#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct ElfEiAbiVersion {
     data: u8,
 }
 
+impl From<u8> for ElfEiAbiVersion {
+    fn from(data: u8) -> ElfEiAbiVersion {
+        ElfEiAbiVersion { data }
+    }
+}
+
+impl ElfEiAbiVersion {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
+}
+
 impl Display for ElfEiAbiVersion {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
         write!(fmt, "{}", self.data)
@@ -1,21 +1,37 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u8)]
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfEiClass {
+    data: u8,
+}
+
 #[allow(dead_code)]
-pub enum ElfEiClass {
-    ELFCLASSNONE,
-    ELFCLASS32,
-    ELFCLASS64,
+impl ElfEiClass {
+    pub const ELFCLASSNONE: ElfEiClass = ElfEiClass { data: 0 };
+    pub const ELFCLASS32: ElfEiClass = ElfEiClass { data: 1 };
+    pub const ELFCLASS64: ElfEiClass = ElfEiClass { data: 2 };
+}
+
+impl From<u8> for ElfEiClass {
+    fn from(data: u8) -> ElfEiClass {
+        ElfEiClass { data }
+    }
+}
+
+impl ElfEiClass {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
 }
 
 impl Display for ElfEiClass {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfEiClass::*;
         let s = match *self {
-            ELFCLASSNONE => "None",
-            ELFCLASS32 => "ELF32",
-            ELFCLASS64 => "ELF64",
+            ElfEiClass::ELFCLASSNONE => "none",
+            ElfEiClass::ELFCLASS32 => "ELF32",
+            ElfEiClass::ELFCLASS64 => "ELF64",
+            ElfEiClass { data } => return write!(fmt, "<unknown: {:x}>", data),
         };
         write!(fmt, "{}", s)
     }
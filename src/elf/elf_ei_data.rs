@@ -1,35 +1,52 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u8)]
-#[derive(Debug)]
+use super::elf_error::ElfError;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfEiData {
+    data: u8,
+}
+
 #[allow(dead_code)]
-pub enum ElfEiData {
-    ELFDATANONE,
-    ELFDATA2LSB,
-    ELFDATA2MSB,
+impl ElfEiData {
+    pub const ELFDATANONE: ElfEiData = ElfEiData { data: 0 };
+    pub const ELFDATA2LSB: ElfEiData = ElfEiData { data: 1 };
+    pub const ELFDATA2MSB: ElfEiData = ElfEiData { data: 2 };
+}
+
+impl From<u8> for ElfEiData {
+    fn from(data: u8) -> ElfEiData {
+        ElfEiData { data }
+    }
+}
+
+impl ElfEiData {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
 }
 
 impl Display for ElfEiData {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfEiData::*;
         let s = match *self {
-            ELFDATANONE => "None",
-            ELFDATA2LSB => "2's complement, little endian",
-            ELFDATA2MSB => "2's complement, big endian",
+            ElfEiData::ELFDATANONE => "none",
+            ElfEiData::ELFDATA2LSB => "2's complement, little endian",
+            ElfEiData::ELFDATA2MSB => "2's complement, big endian",
+            ElfEiData { data } => return write!(fmt, "<unknown: {:x}>", data),
         };
         write!(fmt, "{}", s)
     }
 }
 
 impl ElfEiData {
-    pub fn get_endianness(&self) -> ::to_host::Endianness {
-        use self::ElfEiData::*;
+    pub fn get_endianness(&self) -> Result<::to_host::Endianness, ElfError> {
         use ::to_host::Endianness::*;
 
         match *self {
-            ELFDATA2MSB => BE,
-            ELFDATA2LSB => LE,
-            ELFDATANONE => panic!("Unknown data format"),
-        } 
+            ElfEiData::ELFDATA2MSB => Ok(BE),
+            ElfEiData::ELFDATA2LSB => Ok(LE),
+            ElfEiData { data } => Err(ElfError::UnsupportedData(data)),
+        }
     }
-}
\ No newline at end of file
+}
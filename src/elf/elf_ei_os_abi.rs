@@ -1,48 +1,102 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u8)]
-#[derive(Debug)]
-#[allow(dead_code)]
-pub enum ElfEiOsAbi {
-    ELFOSABI_NONE = 0,
-    ELFOSABI_HPUX = 1,
-    ELFOSABI_NETBSD = 2,
-    ELFOSABI_GNU = 3,
-    ELFOSABI_SOLARIS = 6,
-    ELFOSABI_AIX = 7,
-    ELFOSABI_IRIX = 8,
-    ELFOSABI_FREEBSD = 9,
-    ELFOSABI_TRU64 = 10,
-    ELFOSABI_MODESTO = 11,
-    ELFOSABI_OPENBSD = 12,
-    ELFOSABI_ARM_AEABI = 64,
-    ELFOSABI_ARM = 97,
-    ELFOSABI_STANDALONE = 255
+use super::elf_ehdr_machine::ElfEhdrMachine;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfEiOsAbi {
+    data: u8,
 }
 
 #[allow(dead_code)]
-const ELFOSABI_SYSV: u8 = ElfEiOsAbi::ELFOSABI_NONE as u8;
-#[allow(dead_code)]
-const ELFOSABI_LINUX: u8 = ElfEiOsAbi::ELFOSABI_GNU as u8;
+impl ElfEiOsAbi {
+    pub const ELFOSABI_NONE: ElfEiOsAbi = ElfEiOsAbi { data: 0 };
+    pub const ELFOSABI_SYSV: ElfEiOsAbi = ElfEiOsAbi::ELFOSABI_NONE;
+    pub const ELFOSABI_HPUX: ElfEiOsAbi = ElfEiOsAbi { data: 1 };
+    pub const ELFOSABI_NETBSD: ElfEiOsAbi = ElfEiOsAbi { data: 2 };
+    pub const ELFOSABI_GNU: ElfEiOsAbi = ElfEiOsAbi { data: 3 };
+    pub const ELFOSABI_LINUX: ElfEiOsAbi = ElfEiOsAbi::ELFOSABI_GNU;
+    pub const ELFOSABI_SOLARIS: ElfEiOsAbi = ElfEiOsAbi { data: 6 };
+    pub const ELFOSABI_AIX: ElfEiOsAbi = ElfEiOsAbi { data: 7 };
+    pub const ELFOSABI_IRIX: ElfEiOsAbi = ElfEiOsAbi { data: 8 };
+    pub const ELFOSABI_FREEBSD: ElfEiOsAbi = ElfEiOsAbi { data: 9 };
+    pub const ELFOSABI_TRU64: ElfEiOsAbi = ElfEiOsAbi { data: 10 };
+    pub const ELFOSABI_MODESTO: ElfEiOsAbi = ElfEiOsAbi { data: 11 };
+    pub const ELFOSABI_OPENBSD: ElfEiOsAbi = ElfEiOsAbi { data: 12 };
+    pub const ELFOSABI_OPENVMS: ElfEiOsAbi = ElfEiOsAbi { data: 13 };
+    pub const ELFOSABI_NSK: ElfEiOsAbi = ElfEiOsAbi { data: 14 };
+    pub const ELFOSABI_AROS: ElfEiOsAbi = ElfEiOsAbi { data: 15 };
+    pub const ELFOSABI_FENIXOS: ElfEiOsAbi = ElfEiOsAbi { data: 16 };
+    pub const ELFOSABI_CLOUDABI: ElfEiOsAbi = ElfEiOsAbi { data: 17 };
+    pub const ELFOSABI_OPENVOS: ElfEiOsAbi = ElfEiOsAbi { data: 18 };
+    pub const ELFOSABI_ARM_AEABI: ElfEiOsAbi = ElfEiOsAbi { data: 64 };
+    pub const ELFOSABI_ARM_FDPIC: ElfEiOsAbi = ElfEiOsAbi { data: 65 };
+    pub const ELFOSABI_C6000_ELFABI: ElfEiOsAbi = ElfEiOsAbi { data: 64 };
+    pub const ELFOSABI_C6000_LINUX: ElfEiOsAbi = ElfEiOsAbi { data: 65 };
+    pub const ELFOSABI_AMDGPU_HSA: ElfEiOsAbi = ElfEiOsAbi { data: 64 };
+    pub const ELFOSABI_AMDGPU_PAL: ElfEiOsAbi = ElfEiOsAbi { data: 65 };
+    pub const ELFOSABI_AMDGPU_MESA3D: ElfEiOsAbi = ElfEiOsAbi { data: 66 };
+    pub const ELFOSABI_ARM: ElfEiOsAbi = ElfEiOsAbi { data: 97 };
+    pub const ELFOSABI_STANDALONE: ElfEiOsAbi = ElfEiOsAbi { data: 255 };
+}
+
+impl From<u8> for ElfEiOsAbi {
+    fn from(data: u8) -> ElfEiOsAbi {
+        ElfEiOsAbi { data }
+    }
+}
+
+impl ElfEiOsAbi {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
+
+    /// Names the OS/ABI like readelf does for files of `machine`. Values
+    /// from 64 up are processor-specific; the ones readelf doesn't know
+    /// for `machine` are shown as unknown, like in `Display`.
+    pub fn display_for(&self, machine: ElfEhdrMachine) -> String {
+        if self.data < 64 {
+            return self.to_string();
+        }
+        let s = match (machine, *self) {
+            (ElfEhdrMachine::EM_AMDGPU, ElfEiOsAbi::ELFOSABI_AMDGPU_HSA) => "AMDGPU HSA",
+            (ElfEhdrMachine::EM_AMDGPU, ElfEiOsAbi::ELFOSABI_AMDGPU_PAL) => "AMDGPU PAL",
+            (ElfEhdrMachine::EM_AMDGPU, ElfEiOsAbi::ELFOSABI_AMDGPU_MESA3D) => "AMDGPU Mesa3D",
+            (ElfEhdrMachine::EM_ARM, ElfEiOsAbi::ELFOSABI_ARM) => "ARM",
+            (ElfEhdrMachine::EM_ARM, ElfEiOsAbi::ELFOSABI_ARM_FDPIC) => "ARM FDPIC",
+            (ElfEhdrMachine::EM_MSP430, ElfEiOsAbi::ELFOSABI_STANDALONE)
+            | (ElfEhdrMachine::EM_VISIUM, ElfEiOsAbi::ELFOSABI_STANDALONE) => "Standalone App",
+            (ElfEhdrMachine::EM_TI_C6000, ElfEiOsAbi::ELFOSABI_C6000_ELFABI) => "Bare-metal C6000",
+            (ElfEhdrMachine::EM_TI_C6000, ElfEiOsAbi::ELFOSABI_C6000_LINUX) => "Linux C6000",
+            _ => return self.to_string(),
+        };
+        s.to_string()
+    }
+}
 
+/// Values from 64 up are processor-specific and shown as unknown; see
+/// `display_for` for the names readelf gives them on some machines.
 impl Display for ElfEiOsAbi {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfEiOsAbi::*;
         let s = match *self {
-            ELFOSABI_NONE => "UNIX - System V",
-            ELFOSABI_HPUX => "HP-UX",
-            ELFOSABI_NETBSD => "NetBSD",
-            ELFOSABI_GNU => "GNU ELF",
-            ELFOSABI_SOLARIS => "Sun Solaris",
-            ELFOSABI_AIX => "IBM AIX",
-            ELFOSABI_IRIX => "SGI Irix",
-            ELFOSABI_FREEBSD => "FreeBSD",
-            ELFOSABI_TRU64 => "Compaq TRU64 UNIX",
-            ELFOSABI_MODESTO => "Novell Modesto",
-            ELFOSABI_OPENBSD => "OpenBSD",
-            ELFOSABI_ARM_AEABI => "ARM EABI",
-            ELFOSABI_ARM => "ARM",
-            ELFOSABI_STANDALONE => "Standalone (embedded) application",
+            ElfEiOsAbi::ELFOSABI_NONE => "UNIX - System V",
+            ElfEiOsAbi::ELFOSABI_HPUX => "UNIX - HP-UX",
+            ElfEiOsAbi::ELFOSABI_NETBSD => "UNIX - NetBSD",
+            ElfEiOsAbi::ELFOSABI_GNU => "UNIX - GNU",
+            ElfEiOsAbi::ELFOSABI_SOLARIS => "UNIX - Solaris",
+            ElfEiOsAbi::ELFOSABI_AIX => "UNIX - AIX",
+            ElfEiOsAbi::ELFOSABI_IRIX => "UNIX - IRIX",
+            ElfEiOsAbi::ELFOSABI_FREEBSD => "UNIX - FreeBSD",
+            ElfEiOsAbi::ELFOSABI_TRU64 => "UNIX - TRU64",
+            ElfEiOsAbi::ELFOSABI_MODESTO => "Novell - Modesto",
+            ElfEiOsAbi::ELFOSABI_OPENBSD => "UNIX - OpenBSD",
+            ElfEiOsAbi::ELFOSABI_OPENVMS => "VMS - OpenVMS",
+            ElfEiOsAbi::ELFOSABI_NSK => "HP - Non-Stop Kernel",
+            ElfEiOsAbi::ELFOSABI_AROS => "AROS",
+            ElfEiOsAbi::ELFOSABI_FENIXOS => "FenixOS",
+            ElfEiOsAbi::ELFOSABI_CLOUDABI => "Nuxi CloudABI",
+            ElfEiOsAbi::ELFOSABI_OPENVOS => "Stratus Technologies OpenVOS",
+            ElfEiOsAbi { data } => return write!(fmt, "<unknown: {:x}>", data),
         };
         write!(fmt, "{}", s)
     }
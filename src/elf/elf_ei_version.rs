@@ -1,20 +1,36 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u8)]
-#[derive(Debug)]
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfEiVersion {
+    data: u8,
+}
+
 #[allow(dead_code)]
-pub enum ElfEiVersion {
-    EV_NONE,
-    EV_CURRENT,
+impl ElfEiVersion {
+    pub const EV_NONE: ElfEiVersion = ElfEiVersion { data: 0 };
+    pub const EV_CURRENT: ElfEiVersion = ElfEiVersion { data: 1 };
+}
+
+impl From<u8> for ElfEiVersion {
+    fn from(data: u8) -> ElfEiVersion {
+        ElfEiVersion { data }
+    }
+}
+
+impl ElfEiVersion {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
 }
 
 impl Display for ElfEiVersion {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfEiVersion::*;
         let s = match *self {
-            EV_NONE => "None",
-            EV_CURRENT => "1 (current)",
+            ElfEiVersion::EV_NONE => "",
+            ElfEiVersion::EV_CURRENT => " (current)",
+            _ => " <unknown>",
         };
-        write!(fmt, "{}", s)
+        write!(fmt, "{}{}", self.data, s)
     }
 }
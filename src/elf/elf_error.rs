@@ -0,0 +1,98 @@
+use ::std::fmt::{Display, Formatter};
+use ::compress::DecompressError;
+use ::dwarf::DwarfError;
+use ::archive::ArchiveError;
+
+/// Everything that can go wrong while reading or writing an ELF file.
+#[derive(Debug)]
+pub enum ElfError {
+    /// The file doesn't start with `\x7fELF`.
+    BadMagic,
+    /// The file is too short to hold an ELF header.
+    TruncatedHeader,
+    /// `EI_CLASS` is neither `ELFCLASS32` nor `ELFCLASS64`.
+    UnsupportedClass(u8),
+    /// `EI_DATA` is neither `ELFDATA2LSB` nor `ELFDATA2MSB`.
+    UnsupportedData(u8),
+    /// A table or the contents of a section extend past the end of the file.
+    OutOfRange {
+        what: &'static str,
+        offset: u64,
+        size: u64,
+    },
+    /// The entries of a table are smaller than the structure they hold.
+    BadEntsize {
+        what: &'static str,
+        entsize: u64,
+    },
+    /// A compressed section uses a compression type other than
+    /// `ELFCOMPRESS_ZLIB` and `ELFCOMPRESS_ZSTD`.
+    UnsupportedCompression(u32),
+    /// The data of a compressed section doesn't decompress.
+    Decompress(DecompressError),
+    /// The DWARF debugging information is malformed.
+    Dwarf(DwarfError),
+    /// The file is an archive that can't be read.
+    Archive(ArchiveError),
+    /// The file can't be edited as asked, e.g. it has no dynamic section
+    /// for `elf_patch` to edit or its segments end past the address space.
+    CannotPatch(&'static str),
+    /// A value set with `ElfWriter` is too large for the class of the file.
+    DoesNotFit {
+        what: &'static str,
+        value: u64,
+    },
+    Io(::std::io::Error),
+}
+
+impl Display for ElfError {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        use self::ElfError::*;
+        match *self {
+            BadMagic => write!(
+                fmt, "Not an ELF file - it has the wrong magic bytes at the start"),
+            TruncatedHeader => write!(fmt, "Failed to read file header"),
+            UnsupportedClass(class) => write!(fmt, "Unsupported ELF class: {}", class),
+            UnsupportedData(data) => write!(fmt, "Unsupported ELF data encoding: {}", data),
+            OutOfRange { what, size, .. } => write!(
+                fmt, "Reading {} bytes extends past end of file for {}", size, what),
+            BadEntsize { what, entsize } => write!(
+                fmt, "Invalid entry size {:#x} for {}", entsize, what),
+            UnsupportedCompression(ch_type) => write!(
+                fmt, "Unsupported compression type: {}", ch_type),
+            Decompress(ref err) => write!(fmt, "Unable to decompress section: {}", err),
+            Dwarf(ref err) => write!(fmt, "{}", err),
+            Archive(ref err) => write!(fmt, "{}", err),
+            CannotPatch(why) => write!(fmt, "Cannot patch: {}", why),
+            DoesNotFit { what, value } => write!(
+                fmt, "Value {:#x} of {} doesn't fit in a 32-bit file", value, what),
+            Io(ref err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl ::std::error::Error for ElfError {}
+
+impl From<::std::io::Error> for ElfError {
+    fn from(err: ::std::io::Error) -> ElfError {
+        ElfError::Io(err)
+    }
+}
+
+impl From<DecompressError> for ElfError {
+    fn from(err: DecompressError) -> ElfError {
+        ElfError::Decompress(err)
+    }
+}
+
+impl From<DwarfError> for ElfError {
+    fn from(err: DwarfError) -> ElfError {
+        ElfError::Dwarf(err)
+    }
+}
+
+impl From<ArchiveError> for ElfError {
+    fn from(err: ArchiveError) -> ElfError {
+        ElfError::Archive(err)
+    }
+}
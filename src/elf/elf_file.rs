@@ -0,0 +1,680 @@
+use ::std::borrow::Cow;
+use ::std::cell::OnceCell;
+use ::std::io::Read;
+use ::to_host::Endianness;
+use ::to_host::to_host_copy::ToHostCopy;
+
+use super::b32::helpers as b32;
+use super::b64::helpers as b64;
+use super::helpers::{read_ident, get_bytes_at};
+use super::elf_error::ElfError;
+use super::elf_ident_named::ElfIdentNamed;
+use super::elf_ei_class::ElfEiClass;
+use super::elf_ei_os_abi::ElfEiOsAbi;
+use super::elf_ehdr::ElfEhdr;
+use super::elf_ehdr_type::ElfEhdrType;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_ehdr_flags::{ElfEhdrFlags, ElfFloatAbi};
+use super::elf_phdr::ElfPhdr;
+use super::elf_phdr_type::ElfPhdrType;
+use super::elf_shdr::ElfShdr;
+use super::elf_shdr_type::{SHT_NOTE, SHT_SYMTAB_SHNDX, SHT_REL, SHT_RELA, SHT_SYMTAB, SHT_DYNSYM};
+use super::elf_sym::ElfSym;
+use super::elf_sym_shndx::{ElfSymShndx, SHN_XINDEX};
+use super::elf_sym_type::{STT_SECTION, STT_COMMON};
+use super::elf_dyn::{ElfDyn, get_dyn_val};
+use super::elf_dyn_tag::{DT_STRTAB, DT_STRSZ, DT_SYMTAB, DT_SYMENT, DT_FLAGS_1};
+use super::elf_dyn_flags_1::DF_1_PIE;
+use super::elf_dyn_tag::{DT_VERSYM, DT_VERDEF, DT_VERDEFNUM, DT_VERNEED, DT_VERNEEDNUM};
+use super::elf_rel::ElfRela;
+use super::elf_rel_type::{RelocOp, get_reloc_op};
+use super::elf_note::ElfNoteIter;
+use super::elf_strtab::get_string;
+use super::elf_version::{self, SymbolVersions};
+use super::elf_chdr::{ElfChdr, ELFCOMPRESS_ZLIB, ELFCOMPRESS_ZSTD};
+use super::elf_shdr_flags::SHF_COMPRESSED;
+use ::compress::{zlib_decompress, zstd_decompress};
+
+const SHN_XINDEX_HALF: u16 = 0xffff;
+
+/// An ELF file of either class and data encoding.
+///
+/// Everything read through it is converted to host byte order and widened
+/// to the sizes of the 64-bit structures, so callers don't need to branch
+/// on the class of the file. Section and segment contents are borrowed
+/// from the underlying buffer.
+///
+/// Program headers, section headers and the dynamic section are parsed
+/// on first use, so e.g. the file header can still be shown when the
+/// section header table is corrupt.
+pub struct ElfFile<'data> {
+    data: Cow<'data, [u8]>,
+    ehdr: ElfEhdr,
+    phdrs: OnceCell<Vec<ElfPhdr>>,
+    shdrs: OnceCell<Vec<ElfShdr>>,
+    dynamic: OnceCell<Option<(u64, Vec<ElfDyn>)>>,
+}
+
+impl<'data> ElfFile<'data> {
+    /// Parses the ELF header of the file in `data`, detecting the class
+    /// and the data encoding from `e_ident`. `data` can be e.g. a
+    /// memory-mapped file; nothing is copied out of it except the headers.
+    pub fn parse(data: &'data [u8]) -> Result<ElfFile<'data>, ElfError> {
+        ElfFile::from_data(Cow::Borrowed(data))
+    }
+
+    fn from_data(data: Cow<'data, [u8]>) -> Result<ElfFile<'data>, ElfError> {
+        let ident = read_ident(&data)?;
+        let ehdr = if ElfIdentNamed::from(&ident).get_class() == ElfEiClass::ELFCLASS32 {
+            b32::read_ehdr(&data)?
+        } else {
+            b64::read_ehdr(&data)?
+        };
+
+        Ok(ElfFile {
+            data,
+            ehdr,
+            phdrs: OnceCell::new(),
+            shdrs: OnceCell::new(),
+            dynamic: OnceCell::new(),
+        })
+    }
+
+    pub fn header(&self) -> &ElfEhdr {
+        &self.ehdr
+    }
+    pub fn class(&self) -> ElfEiClass {
+        self.ehdr.get_class()
+    }
+    pub fn is_32(&self) -> bool {
+        self.ehdr.is_32()
+    }
+    pub fn endianness(&self) -> Endianness {
+        self.ehdr.get_endianness()
+    }
+    pub fn file_type(&self) -> ElfEhdrType {
+        self.ehdr.get_type()
+    }
+    pub fn machine(&self) -> ElfEhdrMachine {
+        self.ehdr.get_machine()
+    }
+    pub fn osabi(&self) -> ElfEiOsAbi {
+        self.ehdr.get_ident().get_osabi()
+    }
+    pub fn flags(&self) -> ElfEhdrFlags {
+        self.ehdr.get_flags()
+    }
+    /// The float ABI told by `e_flags`, if the machine records it there.
+    pub fn float_abi(&self) -> Option<ElfFloatAbi> {
+        self.flags().get_float_abi(self.machine())
+    }
+    pub fn entry(&self) -> u64 {
+        self.ehdr.get_entry()
+    }
+
+    /// The whole file.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the `size` bytes at `offset`. `what` names the data in
+    /// errors.
+    pub fn read_bytes(&self, offset: u64, size: u64, what: &'static str)
+        -> Result<&[u8], ElfError>
+    {
+        get_bytes_at(&self.data, offset, size, what)
+    }
+
+    pub fn program_headers(&self) -> Result<&[ElfPhdr], ElfError> {
+        if let Some(phdrs) = self.phdrs.get() {
+            return Ok(phdrs);
+        }
+        let phdrs = if self.is_32() {
+            b32::read_phdrs(&self.ehdr, &self.data)?
+        } else {
+            b64::read_phdrs(&self.ehdr, &self.data)?
+        };
+        Ok(self.phdrs.get_or_init(|| phdrs))
+    }
+
+    /// Returns the section headers with their names resolved through
+    /// `.shstrtab`.
+    pub fn sections(&self) -> Result<&[ElfShdr], ElfError> {
+        if let Some(shdrs) = self.shdrs.get() {
+            return Ok(shdrs);
+        }
+        let shdrs = if self.is_32() {
+            b32::read_shdrs(&self.ehdr, &self.data)?
+        } else {
+            b64::read_shdrs(&self.ehdr, &self.data)?
+        };
+
+        let shstrndx = self.get_shstrndx(shdrs.first());
+        let shstrtab = match shdrs.get(shstrndx) {
+            Some(shdr) if shstrndx != 0 => self.section_data(shdr)?,
+            _ => &[],
+        };
+
+        let shdrs = shdrs.into_iter()
+            .map(|shdr| {
+                let name = if shstrtab.is_empty() {
+                    String::from("<no-strings>")
+                } else {
+                    get_string(shstrtab, shdr.get_name_offset() as usize)
+                };
+                shdr.with_name(name)
+            })
+            .collect();
+        Ok(self.shdrs.get_or_init(|| shdrs))
+    }
+
+    /// Returns the header of the section name string table, or `None`
+    /// if the file has none.
+    pub fn section_name_table(&self) -> Result<Option<&ElfShdr>, ElfError> {
+        let shdrs = self.sections()?;
+        let shstrndx = self.get_shstrndx(shdrs.first());
+        Ok(shdrs.get(shstrndx).filter(|_| shstrndx != 0))
+    }
+
+    /// With extended numbering, the real index of the section name string
+    /// table lives in `sh_link` of the first section header.
+    fn get_shstrndx(&self, first: Option<&ElfShdr>) -> usize {
+        if self.ehdr.get_shstrndx() == SHN_XINDEX_HALF {
+            first.map_or(0, |s| s.get_link() as usize)
+        } else {
+            self.ehdr.get_shstrndx() as usize
+        }
+    }
+
+    /// Returns the contents of a section.
+    /// `SHT_NOBITS` sections occupy no space in the file and read as empty.
+    pub fn section_data(&self, shdr: &ElfShdr) -> Result<&[u8], ElfError> {
+        use super::elf_shdr_type::SHT_NOBITS;
+
+        if shdr.get_type().get() == SHT_NOBITS {
+            return Ok(&[]);
+        }
+        self.read_bytes(shdr.get_offset(), shdr.get_size(), "section data")
+    }
+
+    /// Reads the compression header of a `SHF_COMPRESSED` section.
+    /// Returns `None` for other sections.
+    pub fn compression_header(&self, shdr: &ElfShdr) -> Result<Option<ElfChdr>, ElfError> {
+        if !shdr.get_flags().contains(SHF_COMPRESSED) {
+            return Ok(None);
+        }
+        let (chdr, _) = self.read_chdr(self.section_data(shdr)?)?;
+        Ok(Some(chdr))
+    }
+
+    /// Reads the compression header at the start of `data` and returns it
+    /// with its size.
+    fn read_chdr(&self, data: &[u8]) -> Result<(ElfChdr, u64), ElfError> {
+        if self.is_32() {
+            b32::read_chdr(&self.ehdr, data)
+        } else {
+            b64::read_chdr(&self.ehdr, data)
+        }
+    }
+
+    /// Returns the contents of a section, decompressed if it's compressed,
+    /// like readelf's `-z` shows them. Sections with `SHF_COMPRESSED` can
+    /// be compressed with zlib or zstd. Like readelf, sections that start
+    /// with `ZLIB` followed by the big-endian 64-bit size are taken to be
+    /// compressed with zlib the GNU way, as `.zdebug_*` sections are,
+    /// whatever their names. Other sections are returned as they are.
+    pub fn decompressed_section_data(&self, shdr: &ElfShdr)
+        -> Result<Cow<'_, [u8]>, ElfError>
+    {
+        let data = self.section_data(shdr)?;
+        if shdr.get_flags().contains(SHF_COMPRESSED) {
+            let (chdr, chdr_size) = self.read_chdr(data)?;
+            let compressed = &data[chdr_size as usize..];
+            let decompressed = match chdr.get_type() {
+                ELFCOMPRESS_ZLIB => zlib_decompress(compressed, chdr.get_size())?,
+                ELFCOMPRESS_ZSTD => zstd_decompress(compressed, chdr.get_size())?,
+                ch_type => return Err(ElfError::UnsupportedCompression(ch_type)),
+            };
+            return Ok(Cow::Owned(decompressed));
+        }
+
+        if data.len() > 12 && data.starts_with(b"ZLIB\0") {
+            let size = data[4..12].iter().fold(0u64, |size, &b| (size << 8) | b as u64);
+            return Ok(Cow::Owned(zlib_decompress(&data[12..], size)?));
+        }
+        Ok(Cow::Borrowed(data))
+    }
+
+    /// Returns the contents of the debugging section `name`, e.g.
+    /// `.debug_line`, or of its `.zdebug` counterpart, decompressed and,
+    /// in relocatable files, relocated. Relocations that can't be applied
+    /// are left out silently. Returns `None` if there's no such section.
+    pub fn debug_section_data(&self, name: &str) -> Result<Option<Cow<'_, [u8]>>, ElfError> {
+        let zname = format!(".z{}", &name[1..]);
+        let shdrs = self.sections()?;
+        match shdrs.iter().position(|s| s.get_name() == name)
+            .or_else(|| shdrs.iter().position(|s| s.get_name() == zname))
+        {
+            Some(index) => Ok(Some(self.debug_section_data_at(index)?.0)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the contents of the debugging section at `index` like
+    /// `debug_section_data`, along with warnings about the relocations
+    /// that couldn't be applied.
+    pub fn debug_section_data_at(&self, index: usize)
+        -> Result<(Cow<'_, [u8]>, Vec<String>), ElfError>
+    {
+        let data = self.decompressed_section_data(&self.sections()?[index])?;
+        if self.file_type() != ElfEhdrType::ET_REL
+            || self.relocation_sections_for(index)?.is_empty()
+        {
+            return Ok((data, Vec::new()));
+        }
+        let mut data = data.into_owned();
+        let warnings = self.apply_relocations(index, &mut data)?;
+        Ok((Cow::Owned(data), warnings))
+    }
+
+    /// Reads the symbols of the `SHT_SYMTAB` or `SHT_DYNSYM` section at
+    /// `index`, resolving their names and extended section indices.
+    /// Unnamed section symbols get the name of their section, like readelf
+    /// shows them.
+    pub fn symbols(&self, index: usize) -> Result<Vec<ElfSym>, ElfError> {
+        let shdrs = self.sections()?;
+        let symtab = &shdrs[index];
+        let syms = if self.is_32() {
+            b32::read_syms(&self.ehdr, symtab, &self.data)?
+        } else {
+            b64::read_syms(&self.ehdr, symtab, &self.data)?
+        };
+
+        let e = self.endianness();
+        let shndx_table: Vec<u32> = match shdrs.iter().find(|s| {
+            s.get_type().get() == SHT_SYMTAB_SHNDX && s.get_link() as usize == index
+        }) {
+            Some(shdr) => self.section_data(shdr)?
+                .chunks_exact(4)
+                .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]).to_host_copy(&e))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let strtab_index = symtab.get_link() as usize;
+        let strtab = match shdrs.get(strtab_index) {
+            Some(shdr) if strtab_index != 0 => self.section_data(shdr)?,
+            _ => &[],
+        };
+
+        Ok(syms.into_iter().enumerate()
+            .map(|(i, sym)| {
+                let shndx = if sym.get_raw_shndx() as u32 == SHN_XINDEX {
+                    ElfSymShndx::from_extended(shndx_table.get(i).cloned().unwrap_or(0))
+                } else {
+                    sym.get_shndx()
+                };
+                let name = if sym.get_type().get() == STT_SECTION && sym.get_name_offset() == 0 {
+                    match shndx {
+                        ElfSymShndx::Section(index) => shdrs.get(index as usize)
+                            .map(|s| s.get_name().to_string())
+                            .unwrap_or_default(),
+                        ElfSymShndx::Reserved(_) => String::new(),
+                    }
+                } else {
+                    get_string(strtab, sym.get_name_offset() as usize)
+                };
+                sym.with_shndx(shndx).with_name(name)
+            })
+            .collect())
+    }
+
+    /// Reads the entries of the `PT_DYNAMIC` segment up to and including
+    /// `DT_NULL`, together with the segment's file offset.
+    pub fn dynamic(&self) -> Result<Option<(u64, &[ElfDyn])>, ElfError> {
+        if self.dynamic.get().is_none() {
+            let phdrs = self.program_headers()?;
+            let dynamic = match phdrs.iter().find(|p| p.get_type() == ElfPhdrType::PT_DYNAMIC) {
+                Some(phdr) => {
+                    let offset = phdr.get_offset();
+                    let size = phdr.get_filesz();
+                    let dyns = if self.is_32() {
+                        b32::read_dyns(&self.ehdr, offset, size, &self.data)?
+                    } else {
+                        b64::read_dyns(&self.ehdr, offset, size, &self.data)?
+                    };
+                    Some((offset, dyns))
+                }
+                None => None,
+            };
+            let _ = self.dynamic.set(dynamic);
+        }
+        Ok(self.dynamic.get()
+            .and_then(|d| d.as_ref())
+            .map(|&(offset, ref dyns)| (offset, &dyns[..])))
+    }
+
+    /// Like `dynamic`, but returns no entries if there's no dynamic section.
+    pub fn dynamic_entries(&self) -> Result<&[ElfDyn], ElfError> {
+        Ok(self.dynamic()?.map_or(&[][..], |(_, dyns)| dyns))
+    }
+
+    /// Whether the file is a position-independent executable rather than
+    /// a shared object, as told by `DF_1_PIE`.
+    pub fn is_pie(&self) -> Result<bool, ElfError> {
+        if self.file_type() != ElfEhdrType::ET_DYN {
+            return Ok(false);
+        }
+        let flags_1 = get_dyn_val(self.dynamic_entries()?, DT_FLAGS_1).unwrap_or(0);
+        Ok(flags_1 & DF_1_PIE != 0)
+    }
+
+    /// Translates a virtual address to a file offset through the
+    /// `PT_LOAD` segments.
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Result<Option<u64>, ElfError> {
+        Ok(self.program_headers()?.iter()
+            .filter(|p| p.get_type() == ElfPhdrType::PT_LOAD)
+            .find(|p| p.contains_vaddr(vaddr))
+            .and_then(|p| (vaddr - p.get_vaddr()).checked_add(p.get_offset())))
+    }
+
+    /// Returns the string table pointed to by `DT_STRTAB`/`DT_STRSZ`.
+    /// Works without section headers, translating the address through
+    /// the program headers.
+    pub fn dynamic_strtab(&self) -> Result<&[u8], ElfError> {
+        let dyns = self.dynamic_entries()?;
+        let strtab_vaddr = get_dyn_val(dyns, DT_STRTAB);
+        let strtab_size = get_dyn_val(dyns, DT_STRSZ);
+        match (strtab_vaddr, strtab_size) {
+            (Some(vaddr), Some(size)) => match self.vaddr_to_offset(vaddr)? {
+                Some(offset) => self.read_bytes(offset, size, "dynamic string table"),
+                None => Ok(&[]),
+            },
+            _ => Ok(&[]),
+        }
+    }
+
+    /// Reads the dynamic symbol at `index` through `DT_SYMTAB`, without
+    /// relying on section headers, and names it through `DT_STRTAB`.
+    /// Returns `None` if there's no symbol table or `index` points past
+    /// the end of the file.
+    pub fn dynamic_symbol(&self, index: u32) -> Result<Option<ElfSym>, ElfError> {
+        let dyns = self.dynamic_entries()?;
+        let symtab = match get_dyn_val(dyns, DT_SYMTAB) {
+            Some(vaddr) => self.vaddr_to_offset(vaddr)?,
+            None => None,
+        };
+        let symtab = match symtab {
+            Some(offset) => offset,
+            None => return Ok(None),
+        };
+        let entsize = get_dyn_val(dyns, DT_SYMENT);
+
+        let sym = if self.is_32() {
+            b32::read_sym_at(&self.ehdr, symtab, entsize, index, &self.data)?
+        } else {
+            b64::read_sym_at(&self.ehdr, symtab, entsize, index, &self.data)?
+        };
+        match sym {
+            Some(sym) => {
+                let name = get_string(self.dynamic_strtab()?, sym.get_name_offset() as usize);
+                Ok(Some(sym.with_name(name)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Reads a table of `Elf_Rel` or `Elf_Rela` entries of `size` bytes
+    /// at `offset`. REL entries are returned with a zero addend.
+    pub fn relocations(&self, offset: u64, size: u64, is_rela: bool)
+        -> Result<Vec<ElfRela>, ElfError>
+    {
+        if self.is_32() {
+            b32::read_rels(&self.ehdr, offset, size, is_rela, &self.data)
+        } else {
+            b64::read_rels(&self.ehdr, offset, size, is_rela, &self.data)
+        }
+    }
+
+    /// Reads a `SHT_RELR` table of `size` bytes at `offset` and expands
+    /// it into the list of addresses it relocates.
+    pub fn relr_addresses(&self, offset: u64, size: u64) -> Result<Vec<u64>, ElfError> {
+        if self.is_32() {
+            b32::read_relrs(&self.ehdr, offset, size, &self.data)
+        } else {
+            b64::read_relrs(&self.ehdr, offset, size, &self.data)
+        }
+    }
+
+    /// Returns the `SHT_REL` and `SHT_RELA` sections that relocate the
+    /// section at `index`, i.e. the non-empty ones whose `sh_info` is
+    /// `index`.
+    pub fn relocation_sections_for(&self, index: usize) -> Result<Vec<&ElfShdr>, ElfError> {
+        let shdrs = self.sections()?;
+        Ok(shdrs.iter()
+            .filter(|s| {
+                let t = s.get_type().get();
+                (t == SHT_REL || t == SHT_RELA)
+                    && s.get_info() as usize == index
+                    && s.get_size() != 0
+                    && (s.get_link() as usize) < shdrs.len()
+            })
+            .collect())
+    }
+
+    /// Returns the contents of the section at `index` with the relocations
+    /// against it applied, like readelf's `-R` shows them, together with
+    /// warnings about the relocations that couldn't be applied. Only
+    /// relocatable files are relocated; the sections of linked files are
+    /// returned as they are.
+    pub fn relocated_section_data(&self, index: usize)
+        -> Result<(Vec<u8>, Vec<String>), ElfError>
+    {
+        let mut data = self.section_data(&self.sections()?[index])?.to_vec();
+        let warnings = self.apply_relocations(index, &mut data)?;
+        Ok((data, warnings))
+    }
+
+    /// Applies the relocations against the section at `index` to `data`,
+    /// which stands for its contents, e.g. decompressed ones. Returns the
+    /// warnings about the relocations that couldn't be applied. Does
+    /// nothing unless the file is relocatable.
+    pub fn apply_relocations(&self, index: usize, data: &mut [u8])
+        -> Result<Vec<String>, ElfError>
+    {
+        let shdrs = self.sections()?;
+        let shdr = &shdrs[index];
+        let mut warnings = Vec::new();
+        if self.file_type() != ElfEhdrType::ET_REL {
+            return Ok(warnings);
+        }
+
+        let machine = self.machine();
+        let is_mips64 = !self.is_32() && machine == ElfEhdrMachine::EM_MIPS;
+        let e = self.endianness();
+        let mut prev_type = None;
+        for relsec in self.relocation_sections_for(index)? {
+            let symtab = relsec.get_link() as usize;
+            let symtab_type = shdrs[symtab].get_type().get();
+            if symtab_type != SHT_SYMTAB && symtab_type != SHT_DYNSYM {
+                continue;
+            }
+            let syms = self.symbols(symtab)?;
+            let is_rela = relsec.get_type().get() == SHT_RELA;
+            let rels = self.relocations(relsec.get_offset(), relsec.get_size(), is_rela)?;
+
+            for (i, rel) in rels.iter().enumerate() {
+                // The extra types of MIPS64 relocations are left alone.
+                let r_type = if is_mips64 { rel.get_type() & 0xff } else { rel.get_type() };
+                let op = match get_reloc_op(machine, r_type) {
+                    Some(RelocOp::None) => continue,
+                    Some(op) => op,
+                    None => {
+                        if prev_type != Some(r_type) {
+                            warnings.push(format!(
+                                "unable to apply unsupported reloc type {} to section {}",
+                                r_type, shdr.get_name()));
+                        }
+                        prev_type = Some(r_type);
+                        continue;
+                    }
+                };
+
+                let size = op.get_size();
+                let offset = rel.get_offset();
+                if offset > data.len() as u64 || data.len() as u64 - offset < size as u64 {
+                    warnings.push(format!(
+                        "skipping invalid relocation offset 0x{:x} in section {}",
+                        offset, shdr.get_name()));
+                    continue;
+                }
+                let sym = match syms.get(rel.get_sym() as usize) {
+                    Some(sym) => sym,
+                    None => {
+                        warnings.push(format!(
+                            "skipping invalid relocation symbol index 0x{:x} in section {}",
+                            rel.get_sym(), shdr.get_name()));
+                        continue;
+                    }
+                };
+                let sym_type = sym.get_type().get();
+                if rel.get_sym() != 0 && sym_type > STT_SECTION && sym_type != STT_COMMON {
+                    warnings.push(format!(
+                        "skipping unexpected symbol type {} in section {} relocation {}",
+                        sym.get_type().get_name(self.osabi()), relsec.get_name(), i));
+                    continue;
+                }
+
+                let field = &mut data[offset as usize..offset as usize + size];
+                let old = get_field(field, e);
+                // REL entries keep their addend in the relocated field.
+                let addend = if is_rela { rel.get_addend() as u64 } else { old };
+                let value = sym.get_value().wrapping_add(addend);
+                let new = match op {
+                    RelocOp::None => old,
+                    RelocOp::Abs(_) | RelocOp::Set(_) => value,
+                    RelocOp::PcRel(_) => value.wrapping_sub(offset),
+                    RelocOp::Add(_) => old.wrapping_add(value),
+                    RelocOp::Sub(_) => old.wrapping_sub(value),
+                    RelocOp::Sub6 => (old & 0xc0) | (old.wrapping_sub(value) & 0x3f),
+                    RelocOp::Set6 => (old & 0xc0) | (value & 0x3f),
+                };
+                put_field(field, new, e);
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Returns the bytes from `vaddr` up to the end of the `PT_LOAD`
+    /// segment that contains it. Used for tables the dynamic section gives
+    /// no size of.
+    fn get_segment_tail(&self, vaddr: Option<u64>) -> Result<Option<&[u8]>, ElfError> {
+        let vaddr = match vaddr {
+            Some(vaddr) => vaddr,
+            None => return Ok(None),
+        };
+        let phdr = self.program_headers()?.iter()
+            .filter(|p| p.get_type() == ElfPhdrType::PT_LOAD)
+            .find(|p| p.contains_vaddr(vaddr));
+        match phdr {
+            Some(p) => {
+                let skip = vaddr - p.get_vaddr();
+                let data = self.read_bytes(
+                    p.get_offset().saturating_add(skip), p.get_filesz() - skip,
+                    "version information")?;
+                Ok(Some(data))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the symbol version tables through `DT_VERSYM`, `DT_VERDEF`
+    /// and `DT_VERNEED`, like readelf does when annotating dynamic
+    /// symbols. Returns `None` if the object isn't versioned.
+    pub fn symbol_versions(&self) -> Result<Option<SymbolVersions>, ElfError> {
+        let e = self.endianness();
+        let dyns = self.dynamic_entries()?;
+        let versyms = match self.get_segment_tail(get_dyn_val(dyns, DT_VERSYM))? {
+            Some(data) => elf_version::read_versyms(data, &e),
+            None => return Ok(None),
+        };
+
+        let verdefs = self.get_segment_tail(get_dyn_val(dyns, DT_VERDEF))?
+            .map(|data| {
+                let count = get_dyn_val(dyns, DT_VERDEFNUM).unwrap_or(0);
+                elf_version::read_verdefs(data, &e, count as usize)
+            })
+            .unwrap_or_default();
+        let verneeds = self.get_segment_tail(get_dyn_val(dyns, DT_VERNEED))?
+            .map(|data| {
+                let count = get_dyn_val(dyns, DT_VERNEEDNUM).unwrap_or(0);
+                elf_version::read_verneeds(data, &e, count as usize)
+            })
+            .unwrap_or_default();
+        let strtab = self.dynamic_strtab()?.to_vec();
+
+        Ok(Some(SymbolVersions::new(versyms, verdefs, verneeds, strtab)))
+    }
+
+    /// Finds the `NT_GNU_BUILD_ID` note, looking at `SHT_NOTE` sections
+    /// first and at `PT_NOTE` segments if the file has no sections.
+    pub fn build_id(&self) -> Result<Option<&[u8]>, ElfError> {
+        let e = self.endianness();
+        let mut notes = Vec::new();
+        for shdr in self.sections()?.iter().filter(|s| s.get_type().get() == SHT_NOTE) {
+            notes.push((self.section_data(shdr)?, shdr.get_addralign()));
+        }
+        if notes.is_empty() {
+            for phdr in self.program_headers()?.iter()
+                .filter(|p| p.get_type() == ElfPhdrType::PT_NOTE)
+            {
+                let data = self.read_bytes(phdr.get_offset(), phdr.get_filesz(), "notes")?;
+                notes.push((data, phdr.get_align()));
+            }
+        }
+
+        for (data, align) in notes {
+            let iter = match ElfNoteIter::new(data, e, align, false) {
+                Some(iter) => iter,
+                None => continue,
+            };
+            for note in iter {
+                if let Some(build_id) = note.get_build_id() {
+                    return Ok(Some(build_id));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl ElfFile<'static> {
+    /// Reads the whole file from `reader` into memory and parses its ELF
+    /// header. Use `parse` to avoid the copy, e.g. for memory-mapped files.
+    pub fn open<R: Read>(mut reader: R) -> Result<ElfFile<'static>, ElfError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        ElfFile::from_data(Cow::Owned(data))
+    }
+}
+
+/// Reads a relocated field of up to 8 bytes.
+fn get_field(field: &[u8], e: Endianness) -> u64 {
+    let fold = |value, &byte| (value << 8) | byte as u64;
+    match e {
+        Endianness::LE => field.iter().rev().fold(0, fold),
+        Endianness::BE => field.iter().fold(0, fold),
+    }
+}
+
+/// Stores the low bytes of `value` in a relocated field.
+fn put_field(field: &mut [u8], value: u64, e: Endianness) {
+    let size = field.len();
+    for (i, byte) in field.iter_mut().enumerate() {
+        let shift = match e {
+            Endianness::LE => i,
+            Endianness::BE => size - 1 - i,
+        };
+        *byte = (value >> (8 * shift)) as u8;
+    }
+}
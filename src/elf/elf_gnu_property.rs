@@ -0,0 +1,399 @@
+use ::std::io::{self, Write};
+use ::to_host::Endianness;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_note::{read_word, read_addr};
+
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_UINT32_AND_LO: u32 = 0xb0000000;
+pub const GNU_PROPERTY_UINT32_AND_HI: u32 = 0xb0007fff;
+pub const GNU_PROPERTY_UINT32_OR_LO: u32 = 0xb0008000;
+pub const GNU_PROPERTY_UINT32_OR_HI: u32 = 0xb000ffff;
+pub const GNU_PROPERTY_1_NEEDED: u32 = GNU_PROPERTY_UINT32_OR_LO;
+pub const GNU_PROPERTY_LOPROC: u32 = 0xc0000000;
+pub const GNU_PROPERTY_HIPROC: u32 = 0xdfffffff;
+pub const GNU_PROPERTY_LOUSER: u32 = 0xe0000000;
+
+pub const GNU_PROPERTY_1_NEEDED_INDIRECT_EXTERN_ACCESS: u32 = 1 << 0;
+
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_USED: u32 = 0xc0000000;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_NEEDED: u32 = 0xc0000001;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_NEEDED: u32 = 0xc0008000;
+pub const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc0008001;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_USED: u32 = 0xc0010000;
+pub const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc0010001;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+
+pub const GNU_PROPERTY_X86_ISA_1_BASELINE: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_ISA_1_V2: u32 = 1 << 1;
+pub const GNU_PROPERTY_X86_ISA_1_V3: u32 = 1 << 2;
+pub const GNU_PROPERTY_X86_ISA_1_V4: u32 = 1 << 3;
+
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 1 << 1;
+pub const GNU_PROPERTY_X86_FEATURE_1_LAM_U48: u32 = 1 << 2;
+pub const GNU_PROPERTY_X86_FEATURE_1_LAM_U57: u32 = 1 << 3;
+
+pub const GNU_PROPERTY_X86_FEATURE_2_X86: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_FEATURE_2_X87: u32 = 1 << 1;
+pub const GNU_PROPERTY_X86_FEATURE_2_MMX: u32 = 1 << 2;
+pub const GNU_PROPERTY_X86_FEATURE_2_XMM: u32 = 1 << 3;
+pub const GNU_PROPERTY_X86_FEATURE_2_YMM: u32 = 1 << 4;
+pub const GNU_PROPERTY_X86_FEATURE_2_ZMM: u32 = 1 << 5;
+pub const GNU_PROPERTY_X86_FEATURE_2_FXSR: u32 = 1 << 6;
+pub const GNU_PROPERTY_X86_FEATURE_2_XSAVE: u32 = 1 << 7;
+pub const GNU_PROPERTY_X86_FEATURE_2_XSAVEOPT: u32 = 1 << 8;
+pub const GNU_PROPERTY_X86_FEATURE_2_XSAVEC: u32 = 1 << 9;
+pub const GNU_PROPERTY_X86_FEATURE_2_TMM: u32 = 1 << 10;
+pub const GNU_PROPERTY_X86_FEATURE_2_MASK: u32 = 1 << 11;
+
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_486: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_586: u32 = 1 << 1;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_686: u32 = 1 << 2;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_SSE: u32 = 1 << 3;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_SSE2: u32 = 1 << 4;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_SSE3: u32 = 1 << 5;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_SSSE3: u32 = 1 << 6;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_SSE4_1: u32 = 1 << 7;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_SSE4_2: u32 = 1 << 8;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX: u32 = 1 << 9;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX2: u32 = 1 << 10;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512F: u32 = 1 << 11;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512CD: u32 = 1 << 12;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512ER: u32 = 1 << 13;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512PF: u32 = 1 << 14;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512VL: u32 = 1 << 15;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512DQ: u32 = 1 << 16;
+pub const GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512BW: u32 = 1 << 17;
+
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_CMOV: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE: u32 = 1 << 1;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE2: u32 = 1 << 2;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE3: u32 = 1 << 3;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSSE3: u32 = 1 << 4;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE4_1: u32 = 1 << 5;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE4_2: u32 = 1 << 6;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX: u32 = 1 << 7;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX2: u32 = 1 << 8;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_FMA: u32 = 1 << 9;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512F: u32 = 1 << 10;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512CD: u32 = 1 << 11;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512ER: u32 = 1 << 12;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512PF: u32 = 1 << 13;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512VL: u32 = 1 << 14;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512DQ: u32 = 1 << 15;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512BW: u32 = 1 << 16;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_4FMAPS: u32 = 1 << 17;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_4VNNIW: u32 = 1 << 18;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_BITALG: u32 = 1 << 19;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_IFMA: u32 = 1 << 20;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_VBMI: u32 = 1 << 21;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_VBMI2: u32 = 1 << 22;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_VNNI: u32 = 1 << 23;
+pub const GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_BF16: u32 = 1 << 24;
+
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 1 << 0;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 1 << 1;
+
+/// Prints the set bits of `bitmask` from the lowest one, separated by
+/// commas. `name` returns the name of a single bit.
+fn print_bits<F>(bitmask: u32, none: Option<&str>, name: F, out: &mut dyn Write)
+    -> io::Result<()>
+    where F: Fn(u32) -> Option<&'static str>
+{
+    if bitmask == 0 {
+        if let Some(none) = none {
+            write!(out, "{}", none)?;
+        }
+        return Ok(());
+    }
+
+    let mut bitmask = bitmask;
+    while bitmask != 0 {
+        let bit = bitmask & bitmask.wrapping_neg();
+        bitmask &= !bit;
+        match name(bit) {
+            Some(s) => write!(out, "{}", s)?,
+            None => write!(out, "<unknown: {:x}>", bit)?,
+        }
+        if bitmask != 0 {
+            write!(out, ", ")?;
+        }
+    }
+    Ok(())
+}
+
+fn x86_isa_name(bit: u32) -> Option<&'static str> {
+    let s = match bit {
+        GNU_PROPERTY_X86_ISA_1_BASELINE => "x86-64-baseline",
+        GNU_PROPERTY_X86_ISA_1_V2 => "x86-64-v2",
+        GNU_PROPERTY_X86_ISA_1_V3 => "x86-64-v3",
+        GNU_PROPERTY_X86_ISA_1_V4 => "x86-64-v4",
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn x86_compat_isa_name(bit: u32) -> Option<&'static str> {
+    let s = match bit {
+        GNU_PROPERTY_X86_COMPAT_ISA_1_486 => "i486",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_586 => "586",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_686 => "686",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_SSE => "SSE",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_SSE2 => "SSE2",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_SSE3 => "SSE3",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_SSSE3 => "SSSE3",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_SSE4_1 => "SSE4_1",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_SSE4_2 => "SSE4_2",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX => "AVX",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX2 => "AVX2",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512F => "AVX512F",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512CD => "AVX512CD",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512ER => "AVX512ER",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512PF => "AVX512PF",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512VL => "AVX512VL",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512DQ => "AVX512DQ",
+        GNU_PROPERTY_X86_COMPAT_ISA_1_AVX512BW => "AVX512BW",
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn x86_compat_2_isa_name(bit: u32) -> Option<&'static str> {
+    let s = match bit {
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_CMOV => "CMOV",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE => "SSE",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE2 => "SSE2",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE3 => "SSE3",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSSE3 => "SSSE3",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE4_1 => "SSE4_1",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_SSE4_2 => "SSE4_2",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX => "AVX",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX2 => "AVX2",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_FMA => "FMA",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512F => "AVX512F",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512CD => "AVX512CD",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512ER => "AVX512ER",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512PF => "AVX512PF",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512VL => "AVX512VL",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512DQ => "AVX512DQ",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512BW => "AVX512BW",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_4FMAPS => "AVX512_4FMAPS",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_4VNNIW => "AVX512_4VNNIW",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_BITALG => "AVX512_BITALG",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_IFMA => "AVX512_IFMA",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_VBMI => "AVX512_VBMI",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_VBMI2 => "AVX512_VBMI2",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_VNNI => "AVX512_VNNI",
+        GNU_PROPERTY_X86_COMPAT_2_ISA_1_AVX512_BF16 => "AVX512_BF16",
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn x86_feature_1_name(bit: u32) -> Option<&'static str> {
+    let s = match bit {
+        GNU_PROPERTY_X86_FEATURE_1_IBT => "IBT",
+        GNU_PROPERTY_X86_FEATURE_1_SHSTK => "SHSTK",
+        GNU_PROPERTY_X86_FEATURE_1_LAM_U48 => "LAM_U48",
+        GNU_PROPERTY_X86_FEATURE_1_LAM_U57 => "LAM_U57",
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn x86_feature_2_name(bit: u32) -> Option<&'static str> {
+    let s = match bit {
+        GNU_PROPERTY_X86_FEATURE_2_X86 => "x86",
+        GNU_PROPERTY_X86_FEATURE_2_X87 => "x87",
+        GNU_PROPERTY_X86_FEATURE_2_MMX => "MMX",
+        GNU_PROPERTY_X86_FEATURE_2_XMM => "XMM",
+        GNU_PROPERTY_X86_FEATURE_2_YMM => "YMM",
+        GNU_PROPERTY_X86_FEATURE_2_ZMM => "ZMM",
+        GNU_PROPERTY_X86_FEATURE_2_FXSR => "FXSR",
+        GNU_PROPERTY_X86_FEATURE_2_XSAVE => "XSAVE",
+        GNU_PROPERTY_X86_FEATURE_2_XSAVEOPT => "XSAVEOPT",
+        GNU_PROPERTY_X86_FEATURE_2_XSAVEC => "XSAVEC",
+        GNU_PROPERTY_X86_FEATURE_2_TMM => "TMM",
+        GNU_PROPERTY_X86_FEATURE_2_MASK => "MASK",
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn aarch64_feature_1_name(bit: u32) -> Option<&'static str> {
+    let s = match bit {
+        GNU_PROPERTY_AARCH64_FEATURE_1_BTI => "BTI",
+        GNU_PROPERTY_AARCH64_FEATURE_1_PAC => "PAC",
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn needed_1_name(bit: u32) -> Option<&'static str> {
+    match bit {
+        GNU_PROPERTY_1_NEEDED_INDIRECT_EXTERN_ACCESS => Some("indirect external access"),
+        _ => None,
+    }
+}
+
+/// Prints a processor-specific property. Returns `false` if it isn't
+/// known for `machine`.
+fn print_proc_property(
+    pr_type: u32, data: &[u8], e: &Endianness, machine: ElfEhdrMachine, out: &mut dyn Write)
+    -> io::Result<bool>
+{
+    // Every known processor-specific property is a 4-byte bitmask.
+    let bitmask = if data.len() == 4 { Some(read_word(data, e)) } else { None };
+    let mut print = |title: &str, name: fn(u32) -> Option<&'static str>, none: Option<&str>| {
+        write!(out, "{}", title)?;
+        match bitmask {
+            Some(bitmask) => print_bits(bitmask, none, name, out),
+            None => write!(out, "<corrupt length: {:#x}> ", data.len()),
+        }
+    };
+
+    match machine {
+        ElfEhdrMachine::EM_X86_64 | ElfEhdrMachine::EM_386 => match pr_type {
+            GNU_PROPERTY_X86_ISA_1_USED =>
+                print("x86 ISA used: ", x86_isa_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_ISA_1_NEEDED =>
+                print("x86 ISA needed: ", x86_isa_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_FEATURE_1_AND =>
+                print("x86 feature: ", x86_feature_1_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_FEATURE_2_USED =>
+                print("x86 feature used: ", x86_feature_2_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_FEATURE_2_NEEDED =>
+                print("x86 feature needed: ", x86_feature_2_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_COMPAT_ISA_1_USED =>
+                print("x86 ISA used: ", x86_compat_isa_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_COMPAT_ISA_1_NEEDED =>
+                print("x86 ISA needed: ", x86_compat_isa_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_COMPAT_2_ISA_1_USED =>
+                print("x86 ISA used: ", x86_compat_2_isa_name, Some("<None>"))?,
+            GNU_PROPERTY_X86_COMPAT_2_ISA_1_NEEDED =>
+                print("x86 ISA needed: ", x86_compat_2_isa_name, Some("<None>"))?,
+            _ => return Ok(false),
+        },
+        ElfEhdrMachine::EM_AARCH64 => match pr_type {
+            GNU_PROPERTY_AARCH64_FEATURE_1_AND => {
+                write!(out, "AArch64 feature: ")?;
+                match bitmask {
+                    Some(bitmask) => print_bits(bitmask, None, aarch64_feature_1_name, out)?,
+                    None => write!(out, "<corrupt length: {:#x}> ", data.len())?,
+                }
+            }
+            _ => return Ok(false),
+        },
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Prints a generic property. Returns `false` if it isn't known.
+fn print_property(pr_type: u32, data: &[u8], e: &Endianness, is_32: bool, out: &mut dyn Write)
+    -> io::Result<bool>
+{
+    let size = if is_32 { 4 } else { 8 };
+
+    match pr_type {
+        GNU_PROPERTY_STACK_SIZE => {
+            write!(out, "stack size: ")?;
+            if data.len() != size {
+                write!(out, "<corrupt length: {:#x}> ", data.len())?;
+            } else {
+                write!(out, "{:#x}", read_addr(data, e, is_32))?;
+            }
+        }
+        GNU_PROPERTY_NO_COPY_ON_PROTECTED => {
+            write!(out, "no copy on protected ")?;
+            if !data.is_empty() {
+                write!(out, "<corrupt length: {:#x}> ", data.len())?;
+            }
+        }
+        GNU_PROPERTY_1_NEEDED => {
+            if data.len() != 4 {
+                write!(out, "1_needed: <corrupt length: {:#x}> ", data.len())?;
+            } else {
+                write!(out, "1_needed: ")?;
+                print_bits(read_word(data, e), Some("<None>"), needed_1_name, out)?;
+            }
+        }
+        GNU_PROPERTY_UINT32_AND_LO..=GNU_PROPERTY_UINT32_OR_HI => {
+            if pr_type <= GNU_PROPERTY_UINT32_AND_HI {
+                write!(out, "UINT32_AND ({:#x}): ", pr_type)?;
+            } else {
+                write!(out, "UINT32_OR ({:#x}): ", pr_type)?;
+            }
+            if data.len() != 4 {
+                write!(out, "<corrupt length: {:#x}> ", data.len())?;
+            } else {
+                write!(out, "{:#x}", read_word(data, e))?;
+            }
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Prints the properties in the descriptor of a `NT_GNU_PROPERTY_TYPE_0`
+/// note. Each property is a type and a size followed by the data padded to
+/// the address size.
+pub fn print_gnu_properties(
+    desc: &[u8], e: &Endianness, machine: ElfEhdrMachine, is_32: bool, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    let size = if is_32 { 4 } else { 8 };
+
+    write!(out, "      Properties: ")?;
+    if desc.len() < 8 || !desc.len().is_multiple_of(size) {
+        return writeln!(out, "<corrupt GNU_PROPERTY_TYPE, size = {:#x}>", desc.len());
+    }
+
+    let mut rest = desc;
+    while !rest.is_empty() {
+        if rest.len() < 8 {
+            writeln!(out, "<corrupt descsz: {:#x}>", desc.len())?;
+            break;
+        }
+        let pr_type = read_word(rest, e);
+        let datasz = read_word(&rest[4..], e) as usize;
+        rest = &rest[8..];
+        if datasz > rest.len() {
+            writeln!(out, "<corrupt type ({:#x}) datasz: {:#x}>", pr_type, datasz)?;
+            break;
+        }
+        let data = &rest[..datasz];
+
+        let known = if (GNU_PROPERTY_LOPROC..=GNU_PROPERTY_HIPROC).contains(&pr_type) {
+            print_proc_property(pr_type, data, e, machine, out)?
+        } else {
+            print_property(pr_type, data, e, is_32, out)?
+        };
+        if !known {
+            if pr_type < GNU_PROPERTY_LOPROC {
+                write!(out, "<unknown type {:#x} data: ", pr_type)?;
+            } else if pr_type < GNU_PROPERTY_LOUSER {
+                write!(out, "<processor-specific type {:#x} data: ", pr_type)?;
+            } else {
+                write!(out, "<application-specific type {:#x} data: ", pr_type)?;
+            }
+            for b in data {
+                write!(out, "{:02x} ", b)?;
+            }
+            write!(out, ">")?;
+        }
+
+        let padded = (datasz + size - 1) & !(size - 1);
+        rest = if padded < rest.len() { &rest[padded..] } else { &[] };
+        if rest.is_empty() {
+            break;
+        }
+        write!(out, "\n\t")?;
+    }
+    writeln!(out)
+}
@@ -1,6 +1,6 @@
 use ::std::fmt::{Display, Formatter};
 
-const EI_NIDENT : usize = 16;
+use super::elf_ident_named::EI_NIDENT;
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -11,10 +11,14 @@ pub struct ElfIdent {
 impl Display for ElfIdent {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
         for b in self.data.iter() {
-            try!(
-                write!(
-                    fmt, "{:02x} ", b));
+            write!(fmt, "{:02x} ", b)?;
         }
         Ok(())
     }
 }
+
+impl ElfIdent {
+    pub fn get_bytes(&self) -> &[u8; EI_NIDENT] {
+        &self.data
+    }
+}
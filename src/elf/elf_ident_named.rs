@@ -1,15 +1,27 @@
-use ::std::fmt::{Display, Formatter};
+use ::std::fmt::{Display, Formatter, Write};
 use ::std::io::{Read, Seek};
 
 use super::prelude::{ElfEiClass, ElfEiData, ElfEiVersion, ElfEiOsAbi, ElfEiAbiVersion};
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_ident::ElfIdent;
+use super::elf_error::ElfError;
 
 pub const EI_MAGIC_SIZE: usize = 4;
 const EI_MAGIC_CLASS_SIZE: usize = EI_MAGIC_SIZE + 1;
 
+// Indices into e_ident.
+pub const EI_CLASS: usize = 4;
+pub const EI_DATA: usize = 5;
+pub const EI_VERSION: usize = 6;
+pub const EI_OSABI: usize = 7;
+pub const EI_ABIVERSION: usize = 8;
+pub const EI_PAD: usize = 9;
+pub const EI_NIDENT: usize = 16;
+
 type ElfEiMagic = [u8; EI_MAGIC_SIZE]; 
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct ElfIdentNamed {
     ei_magic: ElfEiMagic,
     ei_class: ElfEiClass,
@@ -22,8 +34,22 @@ pub struct ElfIdentNamed {
 
 impl Display for ElfIdentNamed {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        self.write_to(fmt, &self.ei_osabi.to_string())
+    }
+}
+
+impl ElfIdentNamed {
+    /// Formats the fields like readelf's `-h`, naming the OS/ABI for files
+    /// of `machine`.
+    pub fn format(&self, machine: ElfEhdrMachine) -> String {
+        let mut s = String::new();
+        let _ = self.write_to(&mut s, &self.ei_osabi.display_for(machine));
+        s
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W, osabi: &str) -> ::std::fmt::Result {
         write!(
-            fmt,
+            out,
             concat!(
                 "  Class:                             {}\n",
                 "  Data:                              {}\n",
@@ -34,33 +60,65 @@ impl Display for ElfIdentNamed {
             self.ei_class,
             self.ei_data,
             self.ei_version,
-            self.ei_osabi,
+            osabi,
             self.ei_osabiversion,
         )
     }
 }
 
+impl From<&ElfIdent> for ElfIdentNamed {
+    fn from(ident: &ElfIdent) -> ElfIdentNamed {
+        let b = ident.get_bytes();
+        let mut ei_magic = [0; EI_MAGIC_SIZE];
+        ei_magic.copy_from_slice(&b[..EI_MAGIC_SIZE]);
+        let mut padding = [0; 7];
+        padding.copy_from_slice(&b[EI_PAD..]);
+
+        ElfIdentNamed {
+            ei_magic,
+            ei_class: ElfEiClass::from(b[EI_CLASS]),
+            ei_data: ElfEiData::from(b[EI_DATA]),
+            ei_version: ElfEiVersion::from(b[EI_VERSION]),
+            ei_osabi: ElfEiOsAbi::from(b[EI_OSABI]),
+            ei_osabiversion: ElfEiAbiVersion::from(b[EI_ABIVERSION]),
+            _padding: padding,
+        }
+    }
+}
+
 impl ElfIdentNamed {
-    pub fn get_endianness(&self) -> ::to_host::Endianness {
+    pub fn get_endianness(&self) -> Result<::to_host::Endianness, ElfError> {
         self.ei_data.get_endianness()
     }
     pub fn get_class(&self) -> ElfEiClass {
         self.ei_class
     }
+    pub fn get_data(&self) -> ElfEiData {
+        self.ei_data
+    }
+    pub fn get_version(&self) -> ElfEiVersion {
+        self.ei_version
+    }
+    pub fn get_osabi(&self) -> ElfEiOsAbi {
+        self.ei_osabi
+    }
+    pub fn get_abi_version(&self) -> ElfEiAbiVersion {
+        self.ei_osabiversion
+    }
     #[allow(dead_code)]
-    pub fn read_class<R: Read + Seek>(mut reader: R) -> ElfEiClass {
+    pub fn read_class<R: Read + Seek>(mut reader: R) -> Result<ElfEiClass, ElfError> {
         use std::io::SeekFrom;
 
         let offset = 0;
 
         let mut b = [0; EI_MAGIC_CLASS_SIZE];
-        reader.seek(SeekFrom::Start(offset)).unwrap();
-        reader.read_exact(&mut b).unwrap();
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut b).map_err(|_| ElfError::TruncatedHeader)?;
 
-        let class: ElfEiClass = unsafe {
-            ::std::mem::transmute(b[EI_MAGIC_SIZE])
-        };
-        class
+        match ElfEiClass::from(b[EI_CLASS]) {
+            class @ ElfEiClass::ELFCLASS32 | class @ ElfEiClass::ELFCLASS64 => Ok(class),
+            class => Err(ElfError::UnsupportedClass(class.get())),
+        }
     }
 }
 
@@ -0,0 +1,461 @@
+use ::std::io::{self, Write};
+use ::json::Json;
+use ::to_host::Endianness;
+use ::to_host::to_host_copy::ToHostCopy;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_gnu_property::print_gnu_properties;
+use super::elf_strtab::print_truncated_name;
+use super::elf_core::read_mapped_files;
+
+// Note types of the "GNU" owner.
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+pub const NT_GNU_BUILD_ATTRIBUTE_OPEN: u32 = 0x100;
+pub const NT_GNU_BUILD_ATTRIBUTE_FUNC: u32 = 0x101;
+
+// Note types of other owners in non-core files.
+pub const NT_VERSION: u32 = 1;
+pub const NT_ARCH: u32 = 2;
+pub const NT_GO_BUILDID: u32 = 4;
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe1a7e;
+
+// Note types of core files, whatever their owner, "CORE" or "LINUX".
+pub const NT_PRSTATUS: u32 = 1;
+pub const NT_FPREGSET: u32 = 2;
+pub const NT_PRPSINFO: u32 = 3;
+pub const NT_TASKSTRUCT: u32 = 4;
+pub const NT_AUXV: u32 = 6;
+pub const NT_PSTATUS: u32 = 10;
+pub const NT_FPREGS: u32 = 12;
+pub const NT_PSINFO: u32 = 13;
+pub const NT_LWPSTATUS: u32 = 16;
+pub const NT_LWPSINFO: u32 = 17;
+pub const NT_WIN32PSTATUS: u32 = 18;
+pub const NT_386_TLS: u32 = 0x200;
+pub const NT_386_IOPERM: u32 = 0x201;
+pub const NT_X86_XSTATE: u32 = 0x202;
+pub const NT_X86_CET: u32 = 0x203;
+pub const NT_ARM_VFP: u32 = 0x400;
+pub const NT_ARM_TLS: u32 = 0x401;
+pub const NT_ARM_HW_BREAK: u32 = 0x402;
+pub const NT_ARM_HW_WATCH: u32 = 0x403;
+pub const NT_ARM_SYSTEM_CALL: u32 = 0x404;
+pub const NT_ARM_SVE: u32 = 0x405;
+pub const NT_ARM_PAC_MASK: u32 = 0x406;
+pub const NT_ARM_PACA_KEYS: u32 = 0x407;
+pub const NT_ARM_PACG_KEYS: u32 = 0x408;
+pub const NT_ARM_TAGGED_ADDR_CTRL: u32 = 0x409;
+pub const NT_ARM_PAC_ENABLED_KEYS: u32 = 0x40a;
+pub const NT_RISCV_CSR: u32 = 0x900;
+pub const NT_GDB_TDESC: u32 = 0xff0;
+pub const NT_SIGINFO: u32 = 0x53494749;
+pub const NT_FILE: u32 = 0x46494c45;
+pub const NT_PRXFPREG: u32 = 0x46e62b7f;
+
+// Note type of the "stapsdt" owner.
+pub const NT_STAPSDT: u32 = 3;
+
+// Operating systems in the NT_GNU_ABI_TAG descriptor.
+pub const GNU_ABI_TAG_LINUX: u32 = 0;
+pub const GNU_ABI_TAG_HURD: u32 = 1;
+pub const GNU_ABI_TAG_SOLARIS: u32 = 2;
+pub const GNU_ABI_TAG_FREEBSD: u32 = 3;
+pub const GNU_ABI_TAG_NETBSD: u32 = 4;
+pub const GNU_ABI_TAG_SYLLABLE: u32 = 5;
+pub const GNU_ABI_TAG_NACL: u32 = 6;
+
+/// Size of the note header: `n_namesz`, `n_descsz` and `n_type`.
+/// It's the same in 32-bit and 64-bit files.
+const NHDR_SIZE: usize = 12;
+
+/// Reads a 32-bit word in file byte order from the start of `bytes`.
+pub fn read_word(bytes: &[u8], endianness: &Endianness) -> u32 {
+    let raw = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    raw.to_host_copy(endianness)
+}
+
+/// Reads an address-sized word in file byte order from the start of `bytes`.
+pub fn read_addr(bytes: &[u8], endianness: &Endianness, is_32: bool) -> u64 {
+    if is_32 {
+        read_word(bytes, endianness) as u64
+    } else {
+        let mut b = [0; 8];
+        b.copy_from_slice(&bytes[..8]);
+        u64::from_ne_bytes(b).to_host_copy(endianness)
+    }
+}
+
+/// A single note borrowed from the contents of a note section or segment.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfNote<'a> {
+    n_type: u32,
+    name: &'a [u8],
+    desc: &'a [u8],
+    endianness: Endianness,
+    is_core: bool,
+}
+
+impl<'a> ElfNote<'a> {
+    pub fn get_type(&self) -> u32 {
+        self.n_type
+    }
+    /// Returns the owner name without the terminating NUL.
+    pub fn get_name(&self) -> String {
+        let end = self.name.iter().position(|&b| b == 0).unwrap_or(self.name.len());
+        String::from_utf8_lossy(&self.name[..end]).into_owned()
+    }
+    pub fn get_desc(&self) -> &'a [u8] {
+        self.desc
+    }
+
+    /// Returns the build ID if this is a `NT_GNU_BUILD_ID` note.
+    pub fn get_build_id(&self) -> Option<&'a [u8]> {
+        if self.get_name() == "GNU" && self.n_type == NT_GNU_BUILD_ID {
+            Some(self.desc)
+        } else {
+            None
+        }
+    }
+
+    /// Describes the note type the way readelf's `-n` does.
+    pub fn get_type_name(&self) -> String {
+        let name = self.get_name();
+        if !self.name.is_empty() && name.starts_with("GNU") {
+            let s = match self.n_type {
+                NT_GNU_ABI_TAG => "NT_GNU_ABI_TAG (ABI version tag)",
+                NT_GNU_HWCAP => "NT_GNU_HWCAP (DSO-supplied software HWCAP info)",
+                NT_GNU_BUILD_ID => "NT_GNU_BUILD_ID (unique build ID bitstring)",
+                NT_GNU_GOLD_VERSION => "NT_GNU_GOLD_VERSION (gold version)",
+                NT_GNU_PROPERTY_TYPE_0 => "NT_GNU_PROPERTY_TYPE_0",
+                NT_GNU_BUILD_ATTRIBUTE_OPEN => "NT_GNU_BUILD_ATTRIBUTE_OPEN",
+                NT_GNU_BUILD_ATTRIBUTE_FUNC => "NT_GNU_BUILD_ATTRIBUTE_FUNC",
+                t => return format!("Unknown note type: (0x{:08x})", t),
+            };
+            return String::from(s);
+        }
+        if !self.name.is_empty() && name.starts_with("stapsdt") {
+            return match self.n_type {
+                NT_STAPSDT => String::from("NT_STAPSDT (SystemTap probe descriptors)"),
+                t => format!("Unknown note type: (0x{:08x})", t),
+            };
+        }
+
+        if self.is_core {
+            return get_core_note_type_name(self.n_type)
+                .map(String::from)
+                .unwrap_or_else(|| format!("Unknown note type: (0x{:08x})", self.n_type));
+        }
+
+        let s = match self.n_type {
+            NT_VERSION => "NT_VERSION (version)",
+            NT_ARCH => "NT_ARCH (architecture)",
+            NT_GNU_BUILD_ATTRIBUTE_OPEN => "OPEN",
+            NT_GNU_BUILD_ATTRIBUTE_FUNC => "func",
+            NT_GO_BUILDID => "GO BUILDID",
+            NT_FDO_PACKAGING_METADATA => "FDO_PACKAGING_METADATA",
+            t => return format!("Unknown note type: (0x{:08x})", t),
+        };
+        String::from(s)
+    }
+
+    /// Describes the note for `--output=json`. The description is given
+    /// as a string of hex digits, as it's not decoded.
+    pub fn to_json(&self) -> Json {
+        let desc: String = self.desc.iter().map(|b| format!("{:02x}", b)).collect();
+        Json::object(vec![
+            ("owner", Json::from(self.get_name())),
+            ("type", Json::named(self.n_type, self.get_type_name())),
+            ("description", desc.into()),
+        ])
+    }
+
+    /// Prints the note as a row of readelf's `-n` output followed by its
+    /// decoded description. Returns the error found in a description too
+    /// corrupt to decode, if any, for the caller to report.
+    pub fn print(&self, machine: ElfEhdrMachine, is_32: bool, out: &mut dyn Write)
+        -> io::Result<Option<String>>
+    {
+        let name = if self.name.is_empty() {
+            String::from("(NONE)")
+        } else {
+            self.get_name()
+        };
+
+        write!(out, "  ")?;
+        print_truncated_name(&name, 20, out)?;
+        writeln!(out, " 0x{:08x}\t{}", self.desc.len(), self.get_type_name())?;
+
+        if name.starts_with("GNU") {
+            return self.print_gnu_desc(machine, is_32, out);
+        } else if name.starts_with("stapsdt") {
+            return self.print_stapsdt_desc(is_32, out);
+        } else if name.starts_with("CORE") {
+            // Only the mapped files are decoded; `writeork core` does the rest.
+            if self.n_type == NT_FILE {
+                return self.print_file_desc(is_32, out);
+            }
+        } else if name.starts_with("FDO") {
+            if !self.desc.is_empty() && self.n_type == NT_FDO_PACKAGING_METADATA {
+                let end = self.desc.iter().position(|&b| b == 0)
+                    .unwrap_or(self.desc.len());
+                writeln!(out, "    Packaging Metadata: {}",
+                         String::from_utf8_lossy(&self.desc[..end]))?;
+            }
+        } else if !self.desc.is_empty() {
+            write!(out, "   description data: ")?;
+            for b in self.desc {
+                write!(out, "{:02x} ", b)?;
+            }
+            writeln!(out)?;
+        }
+        Ok(None)
+    }
+
+    /// Prints a SystemTap probe: three addresses followed by the provider,
+    /// probe name and argument format strings.
+    fn print_stapsdt_desc(&self, is_32: bool, out: &mut dyn Write)
+        -> io::Result<Option<String>>
+    {
+        let e = &self.endianness;
+        let addr_size = if is_32 { 4 } else { 8 };
+        if self.desc.len() < addr_size * 3 {
+            writeln!(out, "  <corrupt - note is too small>")?;
+            return Ok(Some(String::from("corrupt stapdt note - the data size is too small")));
+        }
+
+        let addrs: Vec<u64> = (0..3)
+            .map(|i| read_addr(&self.desc[i * addr_size..], e, is_32))
+            .collect();
+        let strings: Vec<String> = self.desc[addr_size * 3..]
+            .split(|&b| b == 0)
+            .take(3)
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+            .collect();
+        if strings.len() < 3 {
+            writeln!(out, "  <corrupt - note is too small>")?;
+            return Ok(Some(String::from("corrupt stapdt note - the data size is too small")));
+        }
+
+        writeln!(out, "    Provider: {}", strings[0])?;
+        writeln!(out, "    Name: {}", strings[1])?;
+        writeln!(
+            out,
+            "    Location: 0x{:0width$x}, Base: 0x{:0width$x}, Semaphore: 0x{:0width$x}",
+            addrs[0], addrs[1], addrs[2], width = addr_size * 2)?;
+        writeln!(out, "    Arguments: {}", strings[2])?;
+        Ok(None)
+    }
+
+    /// Prints the files mapped into the process of a core file, like
+    /// readelf: their address ranges and offsets in pages.
+    fn print_file_desc(&self, is_32: bool, out: &mut dyn Write)
+        -> io::Result<Option<String>>
+    {
+        let mapped = match read_mapped_files(self.desc, self.endianness, is_32) {
+            Ok(mapped) => mapped,
+            Err(err) => return Ok(Some(format!("    Malformed note - {}", err))),
+        };
+        let width = if is_32 { 8 } else { 16 };
+        writeln!(out, "    Page size: {}", mapped.page_size)?;
+        writeln!(
+            out,
+            "    {:>start$}{:>end$}{:>offset$}",
+            "Start", "End", "Page Offset",
+            start = 2 + width, end = 4 + width, offset = 4 + width)?;
+        for file in &mapped.files {
+            writeln!(
+                out,
+                "    0x{:0w$x}  0x{:0w$x}  0x{:0w$x}",
+                file.start, file.end, file.page_offset, w = width)?;
+            writeln!(out, "        {}", file.name)?;
+        }
+        Ok(None)
+    }
+
+    fn print_gnu_desc(&self, machine: ElfEhdrMachine, is_32: bool, out: &mut dyn Write)
+        -> io::Result<Option<String>>
+    {
+        let e = &self.endianness;
+        let desc = self.desc;
+
+        match self.n_type {
+            NT_GNU_BUILD_ID => {
+                write!(out, "    Build ID: ")?;
+                for b in desc {
+                    write!(out, "{:02x}", b)?;
+                }
+                writeln!(out)?;
+            }
+            NT_GNU_ABI_TAG => {
+                if desc.len() < 16 {
+                    writeln!(out, "    <corrupt GNU_ABI_TAG>")?;
+                    return Ok(None);
+                }
+                let os = match read_word(desc, e) {
+                    GNU_ABI_TAG_LINUX => "Linux",
+                    GNU_ABI_TAG_HURD => "Hurd",
+                    GNU_ABI_TAG_SOLARIS => "Solaris",
+                    GNU_ABI_TAG_FREEBSD => "FreeBSD",
+                    GNU_ABI_TAG_NETBSD => "NetBSD",
+                    GNU_ABI_TAG_SYLLABLE => "Syllable",
+                    GNU_ABI_TAG_NACL => "NaCl",
+                    _ => "Unknown",
+                };
+                writeln!(
+                    out,
+                    "    OS: {}, ABI: {}.{}.{}",
+                    os,
+                    read_word(&desc[4..], e),
+                    read_word(&desc[8..], e),
+                    read_word(&desc[12..], e))?;
+            }
+            NT_GNU_GOLD_VERSION => {
+                let end = desc.iter().position(|&b| b == 0).unwrap_or(desc.len());
+                writeln!(out, "    Version: {}", String::from_utf8_lossy(&desc[..end]))?;
+            }
+            NT_GNU_HWCAP => {
+                write!(out, "      Hardware Capabilities: ")?;
+                if desc.len() < 8 {
+                    writeln!(out)?;
+                    return Ok(Some(String::from("<corrupt GNU_HWCAP>")));
+                }
+                writeln!(
+                    out,
+                    "num entries: {}, enabled mask: {:x}",
+                    read_word(desc, e), read_word(&desc[4..], e))?;
+            }
+            NT_GNU_PROPERTY_TYPE_0 => {
+                print_gnu_properties(desc, e, machine, is_32, out)?;
+            }
+            _ => {
+                write!(out, "    Description data: ")?;
+                for b in desc {
+                    write!(out, "{:02x} ", b)?;
+                }
+                writeln!(out)?;
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Describes a note type of a core file the way readelf's `-n` does.
+fn get_core_note_type_name(n_type: u32) -> Option<&'static str> {
+    Some(match n_type {
+        NT_PRSTATUS => "NT_PRSTATUS (prstatus structure)",
+        NT_FPREGSET => "NT_FPREGSET (floating point registers)",
+        NT_PRPSINFO => "NT_PRPSINFO (prpsinfo structure)",
+        NT_TASKSTRUCT => "NT_TASKSTRUCT (task structure)",
+        NT_AUXV => "NT_AUXV (auxiliary vector)",
+        NT_PSTATUS => "NT_PSTATUS (pstatus structure)",
+        NT_FPREGS => "NT_FPREGS (floating point registers)",
+        NT_PSINFO => "NT_PSINFO (psinfo structure)",
+        NT_LWPSTATUS => "NT_LWPSTATUS (lwpstatus_t structure)",
+        NT_LWPSINFO => "NT_LWPSINFO (lwpsinfo_t structure)",
+        NT_WIN32PSTATUS => "NT_WIN32PSTATUS (win32_pstatus structure)",
+        NT_386_TLS => "NT_386_TLS (x86 TLS information)",
+        NT_386_IOPERM => "NT_386_IOPERM (x86 I/O permissions)",
+        NT_X86_XSTATE => "NT_X86_XSTATE (x86 XSAVE extended state)",
+        NT_X86_CET => "NT_X86_CET (x86 CET state)",
+        NT_ARM_VFP => "NT_ARM_VFP (arm VFP registers)",
+        NT_ARM_TLS => "NT_ARM_TLS (AArch TLS registers)",
+        NT_ARM_HW_BREAK => "NT_ARM_HW_BREAK (AArch hardware breakpoint registers)",
+        NT_ARM_HW_WATCH => "NT_ARM_HW_WATCH (AArch hardware watchpoint registers)",
+        NT_ARM_SYSTEM_CALL => "NT_ARM_SYSTEM_CALL (AArch system call number)",
+        NT_ARM_SVE => "NT_ARM_SVE (AArch SVE registers)",
+        NT_ARM_PAC_MASK => "NT_ARM_PAC_MASK (AArch pointer authentication code masks)",
+        NT_ARM_PACA_KEYS => "NT_ARM_PACA_KEYS (ARM pointer authentication address keys)",
+        NT_ARM_PACG_KEYS => "NT_ARM_PACG_KEYS (ARM pointer authentication generic keys)",
+        NT_ARM_TAGGED_ADDR_CTRL => "NT_ARM_TAGGED_ADDR_CTRL (AArch tagged address control)",
+        NT_ARM_PAC_ENABLED_KEYS => {
+            "NT_ARM_PAC_ENABLED_KEYS (AArch64 pointer authentication enabled keys)"
+        }
+        NT_RISCV_CSR => "NT_RISCV_CSR (RISC-V control and status registers)",
+        NT_GDB_TDESC => "NT_GDB_TDESC (GDB XML target description)",
+        NT_SIGINFO => "NT_SIGINFO (siginfo_t data)",
+        NT_FILE => "NT_FILE (mapped files)",
+        NT_PRXFPREG => "NT_PRXFPREG (user_xfpregs structure)",
+        _ => return None,
+    })
+}
+
+/// Iterates over the notes in the contents of a `SHT_NOTE` section or a
+/// `PT_NOTE` segment.
+pub struct ElfNoteIter<'a> {
+    data: &'a [u8],
+    endianness: Endianness,
+    align: usize,
+    is_core: bool,
+    truncated: bool,
+}
+
+impl<'a> ElfNoteIter<'a> {
+    /// `align` is the alignment of the section or segment. Notes are laid
+    /// out on 4-byte boundaries in 32-bit files and usually on 8-byte ones
+    /// in 64-bit files, though Linux uses 4 in both. Smaller alignments are
+    /// treated as 4. Returns `None` for any other alignment. `is_core`
+    /// tells that the notes are those of a core file, whose types have
+    /// other meanings.
+    pub fn new(data: &'a [u8], endianness: Endianness, align: u64, is_core: bool)
+        -> Option<ElfNoteIter<'a>>
+    {
+        let align = match align {
+            0..=4 => 4,
+            8 => 8,
+            _ => return None,
+        };
+        Some(ElfNoteIter { data, endianness, align, is_core, truncated: false })
+    }
+
+    /// Whether the iteration stopped at a note whose `namesz` or `descsz`
+    /// runs past the end of the data.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn align_up(&self, value: usize) -> usize {
+        (value + self.align - 1) & !(self.align - 1)
+    }
+}
+
+impl<'a> Iterator for ElfNoteIter<'a> {
+    type Item = ElfNote<'a>;
+
+    fn next(&mut self) -> Option<ElfNote<'a>> {
+        if self.data.len() < NHDR_SIZE {
+            return None;
+        }
+        let e = &self.endianness;
+        let namesz = read_word(self.data, e) as usize;
+        let descsz = read_word(&self.data[4..], e) as usize;
+        let n_type = read_word(&self.data[8..], e);
+
+        let desc_offset = self.align_up(NHDR_SIZE + namesz);
+        let next_offset = desc_offset.checked_add(descsz)
+            .map(|end| self.align_up(end))
+            .unwrap_or(usize::MAX);
+        // The last note doesn't have to be padded.
+        if desc_offset + descsz > self.data.len() {
+            self.truncated = true;
+            self.data = &[];
+            return None;
+        }
+
+        let note = ElfNote {
+            n_type,
+            name: &self.data[NHDR_SIZE..NHDR_SIZE + namesz],
+            desc: &self.data[desc_offset..desc_offset + descsz],
+            endianness: *e,
+            is_core: self.is_core,
+        };
+        self.data = if next_offset < self.data.len() {
+            &self.data[next_offset..]
+        } else {
+            &[]
+        };
+        Some(note)
+    }
+}
@@ -0,0 +1,353 @@
+use ::std::mem::size_of;
+use super::b32::helpers as b32;
+use super::b64::helpers as b64;
+use super::{Elf32_Phdr, Elf64_Phdr};
+use super::elf_error::ElfError;
+use super::elf_file::ElfFile;
+use super::elf_writer::ElfWriter;
+use super::elf_dyn::{ElfDyn, get_dyn_val};
+use super::elf_dyn_tag::{DT_NULL, DT_NEEDED, DT_STRTAB, DT_STRSZ, DT_SONAME, DT_RPATH, DT_RUNPATH};
+use super::elf_dyn_tag::{DT_VERNEED, DT_VERNEEDNUM, DT_VERSYM};
+use super::elf_phdr_type::ElfPhdrType;
+use super::elf_phdr_flags::{ElfPhdrFlags, PF_R, PF_W};
+use super::elf_shdr_type::{SHT_DYNAMIC, SHT_STRTAB, SHT_GNU_VERNEED, SHT_GNU_VERSYM};
+use super::elf_strtab::get_string;
+use super::elf_version::{VER_NDX_GLOBAL, VERSYM_VERSION, read_half, read_verneeds};
+use ::to_host::to_target_copy::ToTargetCopy;
+
+/// An edit of how a dynamically linked file is loaded, as made by
+/// `writeork patch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchOp {
+    /// Sets the path in `PT_INTERP`.
+    SetInterpreter(String),
+    /// Sets `DT_RPATH`, in place of `DT_RUNPATH` if the file has that.
+    SetRpath(String),
+    /// Sets `DT_RUNPATH`, in place of `DT_RPATH` if the file has that.
+    SetRunpath(String),
+    /// Sets `DT_SONAME`, adding it if needed.
+    SetSoname(String),
+    /// Adds a `DT_NEEDED` entry after the others, unless the library is
+    /// already needed.
+    AddNeeded(String),
+    /// Removes the `DT_NEEDED` entries of a library.
+    RemoveNeeded(String),
+}
+
+/// Returns the offset of `s` in the string table, appending it if it isn't
+/// there yet, not even as the tail of a longer string.
+fn add_string(strtab: &mut Vec<u8>, s: &str) -> u64 {
+    let mut needle = s.as_bytes().to_vec();
+    needle.push(0);
+    match strtab.windows(needle.len()).position(|w| w == &needle[..]) {
+        Some(offset) => offset as u64,
+        None => {
+            strtab.extend_from_slice(&needle);
+            (strtab.len() - needle.len()) as u64
+        }
+    }
+}
+
+/// Sets the value of the first entry with `tag`, or adds one after the
+/// `DT_NEEDED` entries.
+fn set_dyn(dyns: &mut Vec<ElfDyn>, tag: u64, val: u64) {
+    match dyns.iter_mut().find(|d| d.get_tag().get() == tag) {
+        Some(d) => d.set_val(val),
+        None => {
+            let index = dyns.iter()
+                .rposition(|d| d.get_tag().get() == DT_NEEDED)
+                .map_or(0, |i| i + 1);
+            dyns.insert(index, ElfDyn::new(tag, val));
+        }
+    }
+}
+
+/// Sets the library search path to `val` with `tag`, `DT_RPATH` or
+/// `DT_RUNPATH`, in the entry of either if there is one, and drops the
+/// other. Like patchelf does, since ld.so ignores `DT_RPATH` next to a
+/// `DT_RUNPATH`.
+fn set_search_path(dyns: &mut Vec<ElfDyn>, tag: u64, val: u64) {
+    let is_search_path = |d: &ElfDyn| {
+        d.get_tag().get() == DT_RPATH || d.get_tag().get() == DT_RUNPATH
+    };
+    match dyns.iter().position(is_search_path) {
+        Some(index) => {
+            // The entries before the first one stay where they are.
+            dyns.retain(|d| !is_search_path(d));
+            dyns.insert(index, ElfDyn::new(tag, val));
+        }
+        None => set_dyn(dyns, tag, val),
+    }
+}
+
+/// Unlinks the version requirements of the libraries in `removed` from
+/// `.gnu.version_r`, and makes the symbols that needed one of their
+/// versions unversioned. ld.so fails an assertion on requirements of a
+/// library it doesn't load, but looks unversioned symbols up anywhere.
+fn remove_verneeds(elf: &ElfFile, writer: &mut ElfWriter, dyns: &mut Vec<ElfDyn>,
+                   removed: &[&str]) -> Result<(), ElfError> {
+    let (vaddr, count) = match (get_dyn_val(dyns, DT_VERNEED), get_dyn_val(dyns, DT_VERNEEDNUM)) {
+        (Some(vaddr), Some(count)) => (vaddr, count),
+        _ => return Ok(()),
+    };
+    let e = elf.endianness();
+    let offset = elf.vaddr_to_offset(vaddr)?
+        .ok_or(ElfError::CannotPatch("version requirements aren't loaded"))?;
+    let data = elf.data().get(offset as usize..).unwrap_or(&[]);
+    let strtab = elf.dynamic_strtab()?;
+    let (kept, gone): (Vec<_>, Vec<_>) = read_verneeds(data, &e, count as usize)
+        .into_iter()
+        .partition(|v| !removed.contains(&&*get_string(strtab, v.get_file() as usize)));
+    if gone.is_empty() {
+        return Ok(());
+    }
+
+    // Chain the requirements that are left, or drop the table.
+    for (i, verneed) in kept.iter().enumerate() {
+        let next = kept.get(i + 1).map_or(0, |n| (n.get_offset() - verneed.get_offset()) as u32);
+        // vn_next is the last word of Elf_Verneed.
+        let at = offset + verneed.get_offset() as u64 + 12;
+        writer.put_at(at, &next.to_target_copy(&e).to_ne_bytes())?;
+    }
+    let skip = kept.first().map_or(0, |first| first.get_offset() as u64);
+    match kept.first() {
+        Some(_) => {
+            set_dyn(dyns, DT_VERNEED, vaddr + skip);
+            set_dyn(dyns, DT_VERNEEDNUM, kept.len() as u64);
+        }
+        None => dyns.retain(|d| d.get_tag().get() != DT_VERNEED
+                            && d.get_tag().get() != DT_VERNEEDNUM),
+    }
+    for shdr in writer.sections_mut().iter_mut()
+        .filter(|s| s.get_type().get() == SHT_GNU_VERNEED && s.get_addr() == vaddr) {
+        shdr.set_offset(shdr.get_offset() + skip);
+        shdr.set_addr(vaddr + skip);
+        shdr.set_size(shdr.get_size().saturating_sub(skip));
+        shdr.set_info(kept.len() as u32);
+    }
+
+    let versions: Vec<u16> = gone.iter()
+        .flat_map(|v| v.get_aux().iter().map(|aux| aux.get_other() & VERSYM_VERSION))
+        .collect();
+    let versym = match get_dyn_val(dyns, DT_VERSYM) {
+        Some(versym) => versym,
+        None => return Ok(()),
+    };
+    let shdr = elf.sections()?.iter()
+        .find(|s| s.get_type().get() == SHT_GNU_VERSYM && s.get_addr() == versym)
+        .ok_or(ElfError::CannotPatch("no .gnu.version section to update"))?;
+    let data = elf.section_data(shdr)?;
+    for (i, entry) in data.chunks(2).filter(|c| c.len() == 2).enumerate() {
+        if versions.contains(&(read_half(entry, &e) & VERSYM_VERSION)) {
+            let at = shdr.get_offset() + 2 * i as u64;
+            writer.put_at(at, &VER_NDX_GLOBAL.to_target_copy(&e).to_ne_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies `ops` in order to a dynamically linked executable or shared
+/// object and returns the patched file, with warnings about the edits
+/// that had nothing to change.
+///
+/// Whatever still fits is changed in place. A longer interpreter, new
+/// dynamic strings and a dynamic section that outgrows its segment go to
+/// a new `PT_LOAD` segment at the end of the file instead, together with
+/// the program header table, which has no room for the new entry where it
+/// is. The old contents stay behind, unused.
+pub fn patch_file(elf: &ElfFile, ops: &[PatchOp]) -> Result<(Vec<u8>, Vec<String>), ElfError> {
+    let dyn_phdr = elf.program_headers()?.iter()
+        .find(|p| p.get_type() == ElfPhdrType::PT_DYNAMIC)
+        .copied()
+        .ok_or(ElfError::CannotPatch("no dynamic section"))?;
+    let old_strtab = elf.dynamic_strtab()?;
+    if old_strtab.is_empty() {
+        return Err(ElfError::CannotPatch("no dynamic string table"));
+    }
+
+    let mut dyns: Vec<ElfDyn> = elf.dynamic_entries()?.iter()
+        .take_while(|d| d.get_tag().get() != DT_NULL)
+        .copied()
+        .collect();
+    let mut strtab = old_strtab.to_vec();
+    let mut interp = None;
+    let mut warnings = Vec::new();
+    for op in ops {
+        match *op {
+            PatchOp::SetInterpreter(ref path) => interp = Some(path),
+            PatchOp::SetRpath(ref path) => {
+                let offset = add_string(&mut strtab, path);
+                set_search_path(&mut dyns, DT_RPATH, offset);
+            }
+            PatchOp::SetRunpath(ref path) => {
+                let offset = add_string(&mut strtab, path);
+                set_search_path(&mut dyns, DT_RUNPATH, offset);
+            }
+            PatchOp::SetSoname(ref name) => {
+                let offset = add_string(&mut strtab, name);
+                set_dyn(&mut dyns, DT_SONAME, offset);
+            }
+            PatchOp::AddNeeded(ref name) => {
+                let is_needed = |d: &ElfDyn| d.get_tag().get() == DT_NEEDED
+                    && get_string(&strtab, d.get_val() as usize) == *name;
+                if !dyns.iter().any(is_needed) {
+                    let offset = add_string(&mut strtab, name);
+                    let index = dyns.iter()
+                        .rposition(|d| d.get_tag().get() == DT_NEEDED)
+                        .map_or(0, |i| i + 1);
+                    dyns.insert(index, ElfDyn::new(DT_NEEDED, offset));
+                }
+            }
+            PatchOp::RemoveNeeded(ref name) => {
+                let count = dyns.len();
+                dyns.retain(|d| d.get_tag().get() != DT_NEEDED
+                            || get_string(&strtab, d.get_val() as usize) != *name);
+                if dyns.len() == count {
+                    warnings.push(format!("{} is not a needed library", name));
+                }
+            }
+        }
+    }
+
+    // Libraries that are needed again by the end keep their versions.
+    let removed: Vec<&str> = ops.iter()
+        .filter_map(|op| match *op {
+            PatchOp::RemoveNeeded(ref name) => Some(name.as_str()),
+            _ => None,
+        })
+        .filter(|name| !dyns.iter().any(|d| d.get_tag().get() == DT_NEEDED
+                                        && get_string(&strtab, d.get_val() as usize) == *name))
+        .collect();
+    let mut writer = ElfWriter::new(elf)?;
+    remove_verneeds(elf, &mut writer, &mut dyns, &removed)?;
+    dyns.push(ElfDyn::new(DT_NULL, 0));
+
+    let interp_phdr = match interp {
+        Some(_) => Some(elf.program_headers()?.iter()
+            .find(|p| p.get_type() == ElfPhdrType::PT_INTERP)
+            .copied()
+            .ok_or(ElfError::CannotPatch("no program interpreter to replace"))?),
+        None => None,
+    };
+    let interp = interp.map(|path| {
+        let mut bytes = path.as_bytes().to_vec();
+        bytes.push(0);
+        bytes
+    });
+
+    let (word, phdr_size) = if elf.is_32() {
+        (4, size_of::<Elf32_Phdr>() as u64)
+    } else {
+        (8, size_of::<Elf64_Phdr>() as u64)
+    };
+    let dyn_size = 2 * word;
+    let move_interp = match (&interp, &interp_phdr) {
+        (Some(bytes), Some(phdr)) => bytes.len() as u64 > phdr.get_filesz(),
+        _ => false,
+    };
+    let move_strtab = strtab.len() > old_strtab.len();
+    let move_dynamic = dyns.len() as u64 * dyn_size > dyn_phdr.get_filesz();
+
+    // The new segment holds the program header table, then what moves.
+    let mut segment_size = (writer.program_headers().len() as u64 + 1) * phdr_size;
+    let interp_at = segment_size;
+    if let (Some(bytes), true) = (&interp, move_interp) {
+        segment_size += bytes.len() as u64;
+    }
+    let strtab_at = segment_size;
+    if move_strtab {
+        segment_size += strtab.len() as u64;
+    }
+    let dynamic_at = segment_size.div_ceil(word) * word;
+    if move_dynamic {
+        segment_size = dynamic_at + dyns.len() as u64 * dyn_size;
+    }
+
+    let (segment_offset, segment_vaddr) = if move_interp || move_strtab || move_dynamic {
+        let flags = if move_dynamic { PF_R | PF_W } else { PF_R };
+        let (offset, vaddr) = writer.add_load_segment(segment_size, ElfPhdrFlags::from(flags))?;
+        writer.header_mut().set_phoff(offset);
+        for phdr in writer.program_headers_mut().iter_mut()
+            .filter(|p| p.get_type() == ElfPhdrType::PT_PHDR) {
+            phdr.set_vaddr(vaddr);
+            phdr.set_paddr(vaddr);
+        }
+        (offset, vaddr)
+    } else {
+        (0, 0)
+    };
+
+    if let (Some(bytes), Some(old)) = (interp, interp_phdr) {
+        let size = bytes.len() as u64;
+        let (offset, vaddr) = if move_interp {
+            (segment_offset + interp_at, segment_vaddr + interp_at)
+        } else {
+            (old.get_offset(), old.get_vaddr())
+        };
+        let mut padded = bytes;
+        if !move_interp {
+            padded.resize(old.get_filesz() as usize, 0);
+        }
+        writer.put_at(offset, &padded)?;
+        for phdr in writer.program_headers_mut().iter_mut()
+            .filter(|p| p.get_type() == ElfPhdrType::PT_INTERP) {
+            phdr.set_offset(offset);
+            phdr.set_vaddr(vaddr);
+            phdr.set_paddr(vaddr);
+            phdr.set_filesz(size);
+            phdr.set_memsz(size);
+        }
+        for shdr in writer.sections_mut().iter_mut()
+            .filter(|s| s.get_offset() == old.get_offset() && s.get_size() == old.get_filesz()) {
+            shdr.set_offset(offset);
+            shdr.set_addr(vaddr);
+            shdr.set_size(size);
+        }
+    }
+
+    if move_strtab {
+        let old_vaddr = elf.dynamic_entries()?.iter()
+            .find(|d| d.get_tag().get() == DT_STRTAB)
+            .map_or(0, |d| d.get_val());
+        let (offset, vaddr) = (segment_offset + strtab_at, segment_vaddr + strtab_at);
+        writer.put_at(offset, &strtab)?;
+        set_dyn(&mut dyns, DT_STRTAB, vaddr);
+        set_dyn(&mut dyns, DT_STRSZ, strtab.len() as u64);
+        for shdr in writer.sections_mut().iter_mut()
+            .filter(|s| s.get_type().get() == SHT_STRTAB && s.get_addr() == old_vaddr && old_vaddr != 0) {
+            shdr.set_offset(offset);
+            shdr.set_addr(vaddr);
+            shdr.set_size(strtab.len() as u64);
+        }
+    }
+
+    let mut dynamic = if elf.is_32() {
+        b32::write_dyns(elf.header(), &dyns)
+    } else {
+        b64::write_dyns(elf.header(), &dyns)
+    };
+    let (offset, vaddr) = if move_dynamic {
+        (segment_offset + dynamic_at, segment_vaddr + dynamic_at)
+    } else {
+        // What's left of the old section reads as more DT_NULL entries.
+        dynamic.resize(dyn_phdr.get_filesz() as usize, 0);
+        (dyn_phdr.get_offset(), dyn_phdr.get_vaddr())
+    };
+    let size = dynamic.len() as u64;
+    writer.put_at(offset, &dynamic)?;
+    for phdr in writer.program_headers_mut().iter_mut()
+        .filter(|p| p.get_type() == ElfPhdrType::PT_DYNAMIC) {
+        phdr.set_offset(offset);
+        phdr.set_vaddr(vaddr);
+        phdr.set_paddr(vaddr);
+        phdr.set_filesz(size);
+        phdr.set_memsz(size);
+    }
+    for shdr in writer.sections_mut().iter_mut().filter(|s| s.get_type().get() == SHT_DYNAMIC) {
+        shdr.set_offset(offset);
+        shdr.set_addr(vaddr);
+        shdr.set_size(size);
+    }
+
+    Ok((writer.write()?, warnings))
+}
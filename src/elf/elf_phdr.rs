@@ -1,85 +1,361 @@
-#[macro_export]
 macro_rules! elf_phdr {
-    ($half:ty, $word:ty, $xword:ty, $addr:ty, $off:ty) => {
+    // Elf32_Phdr keeps p_flags after p_memsz, Elf64_Phdr moves it up
+    // for alignment.
+    (b32) => {
         use ::to_host::Endianness;
-        use ::to_host::to_host_copy::ToHostCopy;
-        use ::to_host::ToHostCopyStruct;
-        use super::super::elf_phdr_type::ElfPhdrType;
-        use super::super::elf_phdr_flags::ElfPhdrFlags;
+        use ::to_host::endian::*;
         use super::primitive::*;
 
-        #[derive(Debug)]
+        /// A program header as laid out in the file, stored in byte
+        /// order `E`.
+        #[derive(Debug, Clone, Copy)]
         #[repr(C)]
-        pub struct Elf_Phdr {
-            p_type: $word,
-            p_flags: $word,
-            p_offset: $off,
-            p_vaddr: $addr,
-            p_paddr: $addr,
-            p_filesz: $xword,
-            p_memsz: $xword,
-            p_align: $xword,
+        pub struct Elf_Phdr<E: ByteOrder = Endianness> {
+            p_type: U32<E>,
+            p_offset: U32<E>,
+            p_vaddr: U32<E>,
+            p_paddr: U32<E>,
+            p_filesz: U32<E>,
+            p_memsz: U32<E>,
+            p_flags: U32<E>,
+            p_align: U32<E>,
         }
 
-        impl Elf_Phdr {
-            #[allow(dead_code)]
-            pub fn print_with_endianness(&self, e: &Endianness) {
-                let p_type: ElfPhdrType = unsafe {
-                    ::std::mem::transmute(self.p_type.to_host_copy(e))
-                };
-                let p_flags: ElfPhdrFlags = unsafe {
-                    ::std::mem::transmute(self.p_flags.to_host_copy(e))
-                };
-
-                print!(
-                    concat!(
-                        "{: <15}",
-                        "{:#08x} ",
-                        "{:#018x} ",
-                        "{:#018x} ",
-                        "{:#08x} ",
-                        "{:#08x} ",
-                        "{:<3} ",
-                        "{:#x}",
-                    ),
-                    p_type,
-                    self.p_offset.to_host_copy(e),
-                    self.p_vaddr.to_host_copy(e),
-                    self.p_paddr.to_host_copy(e),
-                    self.p_filesz.to_host_copy(e),
-                    self.p_memsz.to_host_copy(e),
-                    p_flags,
-                    self.p_align.to_host_copy(e),
-                );
-            }
+        elf_phdr!(impl Elf32_Word, Elf32_Addr, Elf32_Off, U32);
+    };
+    (b64) => {
+        use ::to_host::Endianness;
+        use ::to_host::endian::*;
+        use super::primitive::*;
+
+        /// A program header as laid out in the file, stored in byte
+        /// order `E`.
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Phdr<E: ByteOrder = Endianness> {
+            p_type: U32<E>,
+            p_flags: U32<E>,
+            p_offset: U64<E>,
+            p_vaddr: U64<E>,
+            p_paddr: U64<E>,
+            p_filesz: U64<E>,
+            p_memsz: U64<E>,
+            p_align: U64<E>,
         }
 
-        impl Elf_Phdr {
-            #[allow(dead_code)]
-            fn from_slice(buffer: &[u8]) -> &Elf_Phdr {
-                let phdr_ptr: *const Elf_Phdr = unsafe {
-                    ::std::mem::transmute(buffer.as_ptr())
-                };
-                let phdr: &Elf_Phdr = unsafe { &*phdr_ptr };
+        elf_phdr!(impl Elf64_Xword, Elf64_Addr, Elf64_Off, U64);
+    };
+    // $wide is the storage type of the addresses, offsets and sizes.
+    (impl $xword:ty, $addr:ty, $off:ty, $wide:ident) => {
+        use super::super::elf_phdr::ElfPhdr;
+        use super::super::elf_phdr_type::ElfPhdrType;
 
-                phdr
+        /// Stores the fields of `phdr` in byte order `e`. Addresses,
+        /// offsets and sizes are truncated to the class, so they must fit.
+        impl<'a, E: ByteOrder> From<(&'a ElfPhdr, E)> for Elf_Phdr<E> {
+            fn from((phdr, e): (&'a ElfPhdr, E)) -> Elf_Phdr<E> {
+                Elf_Phdr {
+                    p_type: U32::new(e, phdr.get_type().get()),
+                    p_flags: U32::new(e, phdr.get_flags().get()),
+                    p_offset: $wide::new(e, phdr.get_offset() as $off),
+                    p_vaddr: $wide::new(e, phdr.get_vaddr() as $addr),
+                    p_paddr: $wide::new(e, phdr.get_paddr() as $addr),
+                    p_filesz: $wide::new(e, phdr.get_filesz() as $xword),
+                    p_memsz: $wide::new(e, phdr.get_memsz() as $xword),
+                    p_align: $wide::new(e, phdr.get_align() as $xword),
+                }
             }
         }
 
-        impl ToHostCopyStruct for Elf_Phdr {
-            fn to_host_copy(&self, endianness: &Endianness) -> Self {
-                let e = endianness;
-                Elf_Phdr {
-                    p_type: self.p_type.to_host_copy(e),
-                    p_flags: self.p_flags.to_host_copy(e),
-                    p_offset: self.p_offset.to_host_copy(e),
-                    p_vaddr: self.p_vaddr.to_host_copy(e),
-                    p_paddr: self.p_paddr.to_host_copy(e),
-                    p_filesz: self.p_filesz.to_host_copy(e),
-                    p_memsz: self.p_memsz.to_host_copy(e),
-                    p_align: self.p_align.to_host_copy(e),
+        impl<E: ByteOrder> Elf_Phdr<E> {
+            pub fn get_type(&self, e: E) -> ElfPhdrType {
+                ElfPhdrType::from(self.p_type.get(e))
+            }
+            pub fn get_flags(&self, e: E) -> u32 {
+                self.p_flags.get(e)
+            }
+            pub fn get_offset(&self, e: E) -> $off {
+                self.p_offset.get(e)
+            }
+            pub fn get_vaddr(&self, e: E) -> $addr {
+                self.p_vaddr.get(e)
+            }
+            pub fn get_paddr(&self, e: E) -> $addr {
+                self.p_paddr.get(e)
+            }
+            pub fn get_filesz(&self, e: E) -> $xword {
+                self.p_filesz.get(e)
+            }
+            pub fn get_memsz(&self, e: E) -> $xword {
+                self.p_memsz.get(e)
+            }
+            pub fn get_align(&self, e: E) -> $xword {
+                self.p_align.get(e)
+            }
+        }
+    }
+}
+
+use ::std::io::{self, Write};
+use ::json::Json;
+use ::to_host::endian::ByteOrder;
+use super::elf_phdr_type::ElfPhdrType;
+use super::elf_phdr_flags::ElfPhdrFlags;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_shdr::ElfShdr;
+use super::elf_shdr_flags::{SHF_ALLOC, SHF_TLS};
+use super::elf_shdr_type::SHT_NOBITS;
+use super::{Elf32_Phdr, Elf64_Phdr};
+
+/// A program header of a file of either class, in host byte order.
+/// Addresses, offsets and sizes are widened to 64 bits.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfPhdr {
+    p_type: ElfPhdrType,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+}
+
+macro_rules! elf_phdr_from {
+    ($t:ty) => {
+        /// Reads the fields of `phdr` in byte order `e`.
+        impl<'a, E: ByteOrder> From<(&'a $t, E)> for ElfPhdr {
+            fn from((phdr, e): (&'a $t, E)) -> ElfPhdr {
+                ElfPhdr {
+                    p_type: phdr.get_type(e),
+                    p_flags: phdr.get_flags(e),
+                    p_offset: phdr.get_offset(e) as u64,
+                    p_vaddr: phdr.get_vaddr(e) as u64,
+                    p_paddr: phdr.get_paddr(e) as u64,
+                    p_filesz: phdr.get_filesz(e) as u64,
+                    p_memsz: phdr.get_memsz(e) as u64,
+                    p_align: phdr.get_align(e) as u64,
                 }
             }
         }
     }
 }
+
+elf_phdr_from!(Elf32_Phdr<E>);
+elf_phdr_from!(Elf64_Phdr<E>);
+
+impl ElfPhdr {
+    /// Returns a header of type `p_type` with every other field zero, to
+    /// be filled in with the setters.
+    pub fn new(p_type: ElfPhdrType) -> ElfPhdr {
+        ElfPhdr {
+            p_type,
+            p_flags: 0,
+            p_offset: 0,
+            p_vaddr: 0,
+            p_paddr: 0,
+            p_filesz: 0,
+            p_memsz: 0,
+            p_align: 0,
+        }
+    }
+
+    pub fn get_type(&self) -> ElfPhdrType {
+        self.p_type
+    }
+    pub fn get_flags(&self) -> ElfPhdrFlags {
+        ElfPhdrFlags::from(self.p_flags)
+    }
+    pub fn get_offset(&self) -> u64 {
+        self.p_offset
+    }
+    pub fn get_vaddr(&self) -> u64 {
+        self.p_vaddr
+    }
+    pub fn get_paddr(&self) -> u64 {
+        self.p_paddr
+    }
+    pub fn get_filesz(&self) -> u64 {
+        self.p_filesz
+    }
+    pub fn get_memsz(&self) -> u64 {
+        self.p_memsz
+    }
+    pub fn get_align(&self) -> u64 {
+        self.p_align
+    }
+
+    pub fn set_type(&mut self, p_type: ElfPhdrType) {
+        self.p_type = p_type;
+    }
+    pub fn set_flags(&mut self, p_flags: ElfPhdrFlags) {
+        self.p_flags = p_flags.get();
+    }
+    pub fn set_offset(&mut self, p_offset: u64) {
+        self.p_offset = p_offset;
+    }
+    pub fn set_vaddr(&mut self, p_vaddr: u64) {
+        self.p_vaddr = p_vaddr;
+    }
+    pub fn set_paddr(&mut self, p_paddr: u64) {
+        self.p_paddr = p_paddr;
+    }
+    pub fn set_filesz(&mut self, p_filesz: u64) {
+        self.p_filesz = p_filesz;
+    }
+    pub fn set_memsz(&mut self, p_memsz: u64) {
+        self.p_memsz = p_memsz;
+    }
+    pub fn set_align(&mut self, p_align: u64) {
+        self.p_align = p_align;
+    }
+
+    /// Whether `vaddr` lies within the part of the segment that's backed
+    /// by the file.
+    pub fn contains_vaddr(&self, vaddr: u64) -> bool {
+        vaddr >= self.p_vaddr && vaddr - self.p_vaddr < self.p_filesz
+    }
+
+    /// Whether readelf's "Section to Segment mapping" lists `shdr` in this
+    /// segment. The rules are binutils' `ELF_SECTION_IN_SEGMENT_STRICT`:
+    /// the section must lie within the segment both in the file and in
+    /// memory, `SHT_NOBITS` sections only in memory, and only some segment
+    /// types may hold TLS or non-allocated sections.
+    pub fn contains_section(&self, shdr: &ElfShdr) -> bool {
+        let p_type = self.p_type;
+        let sh_flags = shdr.get_flags();
+        let is_tls = sh_flags.contains(SHF_TLS);
+        let is_alloc = sh_flags.contains(SHF_ALLOC);
+        let is_nobits = shdr.get_type().get() == SHT_NOBITS;
+        let sh_offset = shdr.get_offset();
+        let sh_addr = shdr.get_addr();
+
+        // .tbss takes no room outside of the TLS segment.
+        let is_tbss_special = is_tls && is_nobits && p_type != ElfPhdrType::PT_TLS;
+        if is_tbss_special {
+            return false;
+        }
+        let sh_size = shdr.get_size();
+
+        let type_fits = if is_tls {
+            p_type == ElfPhdrType::PT_TLS
+                || p_type == ElfPhdrType::PT_GNU_RELRO
+                || p_type == ElfPhdrType::PT_LOAD
+        } else {
+            p_type != ElfPhdrType::PT_TLS && p_type != ElfPhdrType::PT_PHDR
+        };
+        let needs_alloc = p_type == ElfPhdrType::PT_LOAD
+            || p_type == ElfPhdrType::PT_DYNAMIC
+            || p_type == ElfPhdrType::PT_GNU_EH_FRAME
+            || p_type == ElfPhdrType::PT_GNU_STACK
+            || p_type == ElfPhdrType::PT_GNU_RELRO
+            || p_type == ElfPhdrType::PT_GNU_SFRAME
+            || (p_type >= ElfPhdrType::PT_GNU_MBIND_LO
+                && p_type <= ElfPhdrType::PT_GNU_MBIND_HI);
+        if !type_fits || (needs_alloc && !is_alloc) {
+            return false;
+        }
+
+        // Like binutils, the arithmetic wraps, e.g. for empty segments.
+        let in_file = is_nobits
+            || (sh_offset >= self.p_offset
+                && sh_offset - self.p_offset <= self.p_filesz.wrapping_sub(1)
+                && (sh_offset - self.p_offset).wrapping_add(sh_size) <= self.p_filesz);
+        let in_memory = !is_alloc
+            || (sh_addr >= self.p_vaddr
+                && sh_addr - self.p_vaddr <= self.p_memsz.wrapping_sub(1)
+                && (sh_addr - self.p_vaddr).wrapping_add(sh_size) <= self.p_memsz);
+        if !in_file || !in_memory {
+            return false;
+        }
+
+        // Empty sections at the edges of PT_DYNAMIC and PT_NOTE don't
+        // count as inside of them.
+        if (p_type == ElfPhdrType::PT_DYNAMIC || p_type == ElfPhdrType::PT_NOTE)
+            && sh_size == 0 && self.p_memsz != 0
+        {
+            let strictly_in_file = is_nobits
+                || (sh_offset > self.p_offset && sh_offset - self.p_offset < self.p_filesz);
+            let strictly_in_memory = !is_alloc
+                || (sh_addr > self.p_vaddr && sh_addr - self.p_vaddr < self.p_memsz);
+            return strictly_in_file && strictly_in_memory;
+        }
+        true
+    }
+
+    /// Returns the name of the segment type, which may depend on `machine`.
+    pub fn get_type_name(&self, machine: ElfEhdrMachine) -> String {
+        match self.p_type.get_proc_name(machine) {
+            Some(name) => name.to_string(),
+            None => self.p_type.to_string(),
+        }
+    }
+
+    /// Describes the header for `--output=json`.
+    pub fn to_json(&self, machine: ElfEhdrMachine) -> Json {
+        let flags = self.get_flags();
+        Json::object(vec![
+            ("type", Json::named(self.p_type.get(), self.get_type_name(machine))),
+            ("flags", Json::named(flags.get(), flags.to_string().replace(' ', ""))),
+            ("offset", self.p_offset.into()),
+            ("vaddr", self.p_vaddr.into()),
+            ("paddr", self.p_paddr.into()),
+            ("filesz", self.p_filesz.into()),
+            ("memsz", self.p_memsz.into()),
+            ("align", self.p_align.into()),
+        ])
+    }
+
+    /// Prints a row of readelf's `-l` table. 64-bit headers take two lines.
+    pub fn print_row(&self, machine: ElfEhdrMachine, is_32: bool, out: &mut dyn Write)
+        -> io::Result<()>
+    {
+        let type_name = self.get_type_name(machine);
+
+        write!(out, "  {:<14.14} ", type_name)?;
+        if is_32 {
+            write!(
+                out,
+                concat!(
+                    "0x{:06x} ",
+                    "0x{:08x} ",
+                    "0x{:08x} ",
+                    "0x{:05x} ",
+                    "0x{:05x} ",
+                    "{} ",
+                ),
+                self.p_offset,
+                self.p_vaddr,
+                self.p_paddr,
+                self.p_filesz,
+                self.p_memsz,
+                self.get_flags())?;
+            if self.p_align == 0 {
+                writeln!(out, "0")?;
+            } else {
+                writeln!(out, "{:#x}", self.p_align)?;
+            }
+        } else {
+            writeln!(
+                out,
+                concat!(
+                    "0x{:016x} ",
+                    "0x{:016x} ",
+                    "0x{:016x}\n",
+                    "                 ",
+                    "0x{:016x} ",
+                    "0x{:016x}  ",
+                    "{}    ",
+                    "{:#x}",
+                ),
+                self.p_offset,
+                self.p_vaddr,
+                self.p_paddr,
+                self.p_filesz,
+                self.p_memsz,
+                self.get_flags(),
+                self.p_align)?;
+        }
+        Ok(())
+    }
+}
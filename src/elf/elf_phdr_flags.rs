@@ -1,32 +1,32 @@
 use ::std::fmt::{Display, Formatter};
 
+pub const PF_X: u32 = 0x1;
+pub const PF_W: u32 = 0x2;
+pub const PF_R: u32 = 0x4;
+
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct ElfPhdrFlags {
     flags: u32,
 }
 
+impl From<u32> for ElfPhdrFlags {
+    fn from(flags: u32) -> ElfPhdrFlags {
+        ElfPhdrFlags { flags }
+    }
+}
+
+impl ElfPhdrFlags {
+    pub fn get(&self) -> u32 {
+        self.flags
+    }
+}
+
 impl Display for ElfPhdrFlags {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        let maybe_r;
-        let maybe_w;
-        let maybe_x;
-
-        if (self.flags & 0b100) != 0 {
-            maybe_r = "R"
-        } else {
-            maybe_r = " "
-        }
-        if (self.flags & 0b010) != 0 {
-            maybe_w = "W"
-        } else {
-            maybe_w = " "
-        }
-        if (self.flags & 0b001) != 0 {
-            maybe_x = "E"
-        } else {
-            maybe_x = " "
-        }
+        let maybe_r = if (self.flags & PF_R) != 0 { "R" } else { " " };
+        let maybe_w = if (self.flags & PF_W) != 0 { "W" } else { " " };
+        let maybe_x = if (self.flags & PF_X) != 0 { "E" } else { " " };
 
         write!(fmt, "{}{}{}", maybe_r, maybe_w, maybe_x)
     }
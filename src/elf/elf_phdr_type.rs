@@ -1,49 +1,123 @@
 use ::std::fmt::{Display, Formatter};
 
-#[repr(u32)]
-#[derive(Debug)]
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::helpers::format_c_hex;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct ElfPhdrType {
+    data: u32,
+}
+
 #[allow(dead_code)]
-pub enum ElfPhdrType {
-    PT_NULL = 0,
-    PT_LOAD = 1,
-    PT_DYNAMIC = 2,
-    PT_INTERP = 3,
-    PT_NOTE = 4,
-    PT_SHLIB = 5,
-    PT_PHDR = 6,
-    PT_TLS = 7,
-    PT_NUM = 8,
-    PT_LOOS = 0x60000000,
-    PT_GNU_EH_FRAME = 0x6474e550,
-    PT_GNU_STACK = 0x6474e551,
-    PT_GNU_RELRO = 0x6474e552,
-    PT_LOSUNW = 0x6ffffffa,
-    PT_SUNWSTACK = 0x6ffffffb,
-    PT_HISUNW = 0x6fffffff,
-    PT_LOPROC = 0x70000000,
+impl ElfPhdrType {
+    pub const PT_NULL: ElfPhdrType = ElfPhdrType { data: 0 };
+    pub const PT_LOAD: ElfPhdrType = ElfPhdrType { data: 1 };
+    pub const PT_DYNAMIC: ElfPhdrType = ElfPhdrType { data: 2 };
+    pub const PT_INTERP: ElfPhdrType = ElfPhdrType { data: 3 };
+    pub const PT_NOTE: ElfPhdrType = ElfPhdrType { data: 4 };
+    pub const PT_SHLIB: ElfPhdrType = ElfPhdrType { data: 5 };
+    pub const PT_PHDR: ElfPhdrType = ElfPhdrType { data: 6 };
+    pub const PT_TLS: ElfPhdrType = ElfPhdrType { data: 7 };
+    pub const PT_LOOS: ElfPhdrType = ElfPhdrType { data: 0x60000000 };
+    pub const PT_GNU_EH_FRAME: ElfPhdrType = ElfPhdrType { data: 0x6474e550 };
+    pub const PT_GNU_STACK: ElfPhdrType = ElfPhdrType { data: 0x6474e551 };
+    pub const PT_GNU_RELRO: ElfPhdrType = ElfPhdrType { data: 0x6474e552 };
+    pub const PT_GNU_PROPERTY: ElfPhdrType = ElfPhdrType { data: 0x6474e553 };
+    pub const PT_GNU_SFRAME: ElfPhdrType = ElfPhdrType { data: 0x6474e554 };
+    pub const PT_GNU_MBIND_LO: ElfPhdrType = ElfPhdrType { data: 0x6474e555 };
+    pub const PT_GNU_MBIND_HI: ElfPhdrType = ElfPhdrType { data: 0x6474f554 };
+    pub const PT_OPENBSD_RANDOMIZE: ElfPhdrType = ElfPhdrType { data: 0x65a3dbe6 };
+    pub const PT_OPENBSD_WXNEEDED: ElfPhdrType = ElfPhdrType { data: 0x65a3dbe7 };
+    pub const PT_OPENBSD_BOOTDATA: ElfPhdrType = ElfPhdrType { data: 0x65a41be6 };
+    pub const PT_HIOS: ElfPhdrType = ElfPhdrType { data: 0x6fffffff };
+    pub const PT_LOPROC: ElfPhdrType = ElfPhdrType { data: 0x70000000 };
+    pub const PT_HIPROC: ElfPhdrType = ElfPhdrType { data: 0x7fffffff };
+
+    pub const PT_ARM_EXIDX: ElfPhdrType = ElfPhdrType { data: 0x70000001 };
+    pub const PT_AARCH64_ARCHEXT: ElfPhdrType = ElfPhdrType { data: 0x70000000 };
+    pub const PT_AARCH64_MEMTAG_MTE: ElfPhdrType = ElfPhdrType { data: 0x70000002 };
+    pub const PT_MIPS_REGINFO: ElfPhdrType = ElfPhdrType { data: 0x70000000 };
+    pub const PT_MIPS_RTPROC: ElfPhdrType = ElfPhdrType { data: 0x70000001 };
+    pub const PT_MIPS_OPTIONS: ElfPhdrType = ElfPhdrType { data: 0x70000002 };
+    pub const PT_MIPS_ABIFLAGS: ElfPhdrType = ElfPhdrType { data: 0x70000003 };
+    pub const PT_RISCV_ATTRIBUTES: ElfPhdrType = ElfPhdrType { data: 0x70000003 };
+    pub const PT_S390_PGSTE: ElfPhdrType = ElfPhdrType { data: 0x70000000 };
+}
+
+impl From<u32> for ElfPhdrType {
+    fn from(data: u32) -> ElfPhdrType {
+        ElfPhdrType { data }
+    }
+}
+
+impl ElfPhdrType {
+    pub fn get(&self) -> u32 {
+        self.data
+    }
+
+    /// Returns the name of a processor-specific segment type, if `machine`
+    /// defines one.
+    pub fn get_proc_name(&self, machine: ElfEhdrMachine) -> Option<&'static str> {
+        let s = match machine {
+            ElfEhdrMachine::EM_ARM => match *self {
+                ElfPhdrType::PT_ARM_EXIDX => "EXIDX",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_AARCH64 => match *self {
+                ElfPhdrType::PT_AARCH64_ARCHEXT => "AARCH64_ARCHEXT",
+                ElfPhdrType::PT_AARCH64_MEMTAG_MTE => "AARCH64_MEMTAG_MTE",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_MIPS | ElfEhdrMachine::EM_MIPS_RS3_LE => match *self {
+                ElfPhdrType::PT_MIPS_REGINFO => "REGINFO",
+                ElfPhdrType::PT_MIPS_RTPROC => "RTPROC",
+                ElfPhdrType::PT_MIPS_OPTIONS => "OPTIONS",
+                ElfPhdrType::PT_MIPS_ABIFLAGS => "ABIFLAGS",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_RISCV => match *self {
+                ElfPhdrType::PT_RISCV_ATTRIBUTES => "RISCV_ATTRIBUTES",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_S390 => match *self {
+                ElfPhdrType::PT_S390_PGSTE => "S390_PGSTE",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(s)
+    }
 }
 
 impl Display for ElfPhdrType {
     fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
-        use self::ElfPhdrType::*;
         let s = match *self {
-            PT_NULL => "NULL",
-            PT_LOAD => "LOAD",
-            PT_DYNAMIC => "DYNAMIC",
-            PT_INTERP => "INTERP",
-            PT_NOTE => "NOTE",
-            PT_SHLIB => "SHLIB",
-            PT_PHDR => "PHDR",
-            PT_TLS => "TLS",
-            PT_NUM => "NUM",
-            PT_LOOS => "LOOS",
-            PT_GNU_EH_FRAME => "EH_FRAME",
-            PT_GNU_STACK => "GNU_STACK",
-            PT_GNU_RELRO => "GNU_RELRO",
-            PT_LOSUNW => "LOSUNW",
-            PT_SUNWSTACK => "SUNWBSS",
-            PT_HISUNW => "HISUNW",
-            PT_LOPROC => "LOPROC",
+            ElfPhdrType::PT_NULL => "NULL",
+            ElfPhdrType::PT_LOAD => "LOAD",
+            ElfPhdrType::PT_DYNAMIC => "DYNAMIC",
+            ElfPhdrType::PT_INTERP => "INTERP",
+            ElfPhdrType::PT_NOTE => "NOTE",
+            ElfPhdrType::PT_SHLIB => "SHLIB",
+            ElfPhdrType::PT_PHDR => "PHDR",
+            ElfPhdrType::PT_TLS => "TLS",
+            ElfPhdrType::PT_GNU_EH_FRAME => "GNU_EH_FRAME",
+            ElfPhdrType::PT_GNU_STACK => "GNU_STACK",
+            ElfPhdrType::PT_GNU_RELRO => "GNU_RELRO",
+            ElfPhdrType::PT_GNU_PROPERTY => "GNU_PROPERTY",
+            ElfPhdrType::PT_GNU_SFRAME => "GNU_SFRAME",
+            ElfPhdrType::PT_OPENBSD_RANDOMIZE => "OPENBSD_RANDOMIZE",
+            ElfPhdrType::PT_OPENBSD_WXNEEDED => "OPENBSD_WXNEEDED",
+            ElfPhdrType::PT_OPENBSD_BOOTDATA => "OPENBSD_BOOTDATA",
+            x if x >= ElfPhdrType::PT_LOPROC && x <= ElfPhdrType::PT_HIPROC => {
+                return fmt.pad(&format!("LOPROC+{}", format_c_hex(x.data - ElfPhdrType::PT_LOPROC.data)));
+            }
+            x if x >= ElfPhdrType::PT_LOOS && x <= ElfPhdrType::PT_HIOS => {
+                return fmt.pad(&format!("LOOS+{}", format_c_hex(x.data - ElfPhdrType::PT_LOOS.data)));
+            }
+            x => {
+                return fmt.pad(&format!("<unknown>: {:x}", x.data));
+            }
         };
         fmt.pad(s)
     }
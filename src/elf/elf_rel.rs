@@ -0,0 +1,328 @@
+macro_rules! elf_rel {
+    (b32) => {
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Rel {
+            r_offset: Elf32_Addr,
+            r_info: Elf32_Word,
+        }
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Rela {
+            r_offset: Elf32_Addr,
+            r_info: Elf32_Word,
+            r_addend: Elf32_Sword,
+        }
+
+        // ELF32_R_SYM and ELF32_R_TYPE
+        elf_rel!(impl Elf32_Word, Elf32_Addr, 8, 0xff);
+    };
+    (b64) => {
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Rel {
+            r_offset: Elf64_Addr,
+            r_info: Elf64_Xword,
+        }
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Rela {
+            r_offset: Elf64_Addr,
+            r_info: Elf64_Xword,
+            r_addend: Elf64_Sxword,
+        }
+
+        // ELF64_R_SYM and ELF64_R_TYPE
+        elf_rel!(impl Elf64_Xword, Elf64_Addr, 32, 0xffffffff);
+    };
+    (impl $xword:ty, $addr:ty, $sym_shift:expr, $type_mask:expr) => {
+        use ::to_host::Endianness;
+        use ::to_host::to_host_copy::ToHostCopy;
+        use ::to_host::ToHostCopyStruct;
+
+        impl Elf_Rel {
+            pub fn get_offset(&self) -> $addr {
+                self.r_offset
+            }
+            pub fn get_info(&self) -> $xword {
+                self.r_info
+            }
+        }
+
+        /// `SHT_REL` entries are widened to this type with a zero addend,
+        /// so both kinds of tables are handled the same way.
+        impl From<Elf_Rel> for Elf_Rela {
+            fn from(rel: Elf_Rel) -> Elf_Rela {
+                Elf_Rela {
+                    r_offset: rel.r_offset,
+                    r_info: rel.r_info,
+                    r_addend: 0,
+                }
+            }
+        }
+
+        impl Elf_Rela {
+            pub fn get_offset(&self) -> $addr {
+                self.r_offset
+            }
+            pub fn get_info(&self) -> $xword {
+                self.r_info
+            }
+            pub fn get_addend(&self) -> i64 {
+                self.r_addend as i64
+            }
+            pub fn get_sym(&self) -> u32 {
+                (self.r_info as u64 >> $sym_shift) as u32
+            }
+            pub fn get_type(&self) -> u32 {
+                (self.r_info as u64 & $type_mask) as u32
+            }
+        }
+
+        impl ToHostCopyStruct for Elf_Rel {
+            fn to_host_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Rel {
+                    r_offset: self.r_offset.to_host_copy(e),
+                    r_info: self.r_info.to_host_copy(e),
+                }
+            }
+        }
+
+        impl ToHostCopyStruct for Elf_Rela {
+            fn to_host_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Rela {
+                    r_offset: self.r_offset.to_host_copy(e),
+                    r_info: self.r_info.to_host_copy(e),
+                    r_addend: self.r_addend.to_host_copy(e),
+                }
+            }
+        }
+    }
+}
+
+use ::std::io::{self, Write};
+use ::json::Json;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_rel_type::get_rel_type_name;
+use super::elf_sym::ElfSym;
+use super::elf_sym_type::{STT_SECTION, STT_GNU_IFUNC};
+use super::elf_strtab::truncate_name;
+use super::elf_version::SymbolVersion;
+use super::{Elf32_Rela, Elf64_Rela};
+
+/// A relocation of a file of either class, in host byte order.
+/// `SHT_REL` entries are widened to this type with a zero addend,
+/// so both kinds of tables are handled the same way.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfRela {
+    r_offset: u64,
+    r_info: u64,
+    r_addend: i64,
+    r_sym: u32,
+    r_type: u32,
+}
+
+macro_rules! elf_rela_from {
+    ($t:ty) => {
+        /// `rela` must already be converted to host byte order.
+        impl<'a> From<&'a $t> for ElfRela {
+            fn from(rela: &'a $t) -> ElfRela {
+                ElfRela {
+                    r_offset: rela.get_offset() as u64,
+                    r_info: rela.get_info() as u64,
+                    r_addend: rela.get_addend(),
+                    r_sym: rela.get_sym(),
+                    r_type: rela.get_type(),
+                }
+            }
+        }
+    }
+}
+
+elf_rela_from!(Elf32_Rela);
+elf_rela_from!(Elf64_Rela);
+
+impl ElfRela {
+    pub fn get_offset(&self) -> u64 {
+        self.r_offset
+    }
+    pub fn get_info(&self) -> u64 {
+        self.r_info
+    }
+    pub fn get_addend(&self) -> i64 {
+        self.r_addend
+    }
+    pub fn get_sym(&self) -> u32 {
+        self.r_sym
+    }
+    pub fn get_type(&self) -> u32 {
+        self.r_type
+    }
+
+    /// MIPS64 little-endian objects store `r_info` as a 32-bit symbol
+    /// index followed by four one-byte fields, which doesn't read
+    /// as a little-endian 64-bit number. Returns the entry with
+    /// `r_info` rearranged into the usual ELF64 layout, like readelf
+    /// does.
+    pub fn with_mips64el_info(&self) -> ElfRela {
+        let info = self.r_info;
+        let info = ((info & 0xffffffff) << 32)
+            | ((info >> 56) & 0xff)
+            | ((info >> 40) & 0xff00)
+            | ((info >> 24) & 0xff0000)
+            | ((info >> 8) & 0xff000000);
+        ElfRela {
+            r_info: info,
+            r_sym: (info >> 32) as u32,
+            r_type: info as u32,
+            ..*self
+        }
+    }
+
+    /// Describes the entry for `--output=json`. The arguments are those
+    /// of `print_row`. MIPS64 entries hold up to three types, which are
+    /// listed as `type`, `type2` and `type3`.
+    pub fn to_json(
+        &self, machine: ElfEhdrMachine, sym: Option<&ElfSym>,
+        version: Option<&SymbolVersion>, is_rela: bool, is_32: bool) -> Json
+    {
+        let is_mips64 = !is_32 && machine == ElfEhdrMachine::EM_MIPS;
+        let get_type = |r_type: u32| {
+            Json::named(r_type, get_rel_type_name(machine, r_type))
+        };
+
+        let mut json = Json::object(vec![
+            ("offset", Json::from(self.r_offset)),
+            ("info", self.r_info.into()),
+        ]);
+        if is_mips64 {
+            json.push("type", get_type(self.r_type & 0xff));
+            json.push("type2", get_type((self.r_info >> 8 & 0xff) as u32));
+            json.push("type3", get_type((self.r_info >> 16 & 0xff) as u32));
+        } else {
+            json.push("type", get_type(self.r_type));
+        }
+
+        let symbol = if self.r_sym == 0 {
+            Json::Null
+        } else {
+            let mut symbol = Json::object(vec![("index", Json::from(self.r_sym))]);
+            symbol.push("name", sym.map(|s| s.get_name()));
+            symbol.push("value", sym.map(|s| s.get_value()));
+            symbol.push("version", version.map(SymbolVersion::to_json));
+            symbol
+        };
+        json.push("symbol", symbol);
+        json.push("addend", if is_rela { Some(self.r_addend) } else { None });
+        json
+    }
+
+    /// Prints a row of readelf's `-r` table. `sym` is the symbol
+    /// referenced by the entry, or `None` if the index is out of range of
+    /// the symbol table, which leaves the symbol out of the row for the
+    /// caller to report. `version` is the version of a dynamic symbol.
+    pub fn print_row(
+        &self, machine: ElfEhdrMachine, sym: Option<&ElfSym>,
+        version: Option<&SymbolVersion>, is_rela: bool, is_32: bool, out: &mut dyn Write)
+        -> io::Result<()>
+    {
+        let is_mips64 = !is_32 && machine == ElfEhdrMachine::EM_MIPS;
+
+        if is_32 {
+            write!(out, "{:08x}  {:08x} ", self.r_offset, self.r_info)?;
+        } else {
+            write!(out, "{:012x}  {:012x} ", self.r_offset, self.r_info)?;
+        }
+
+        let r_type = if is_mips64 {
+            self.r_type & 0xff
+        } else {
+            self.r_type
+        };
+        print_rel_type(machine, r_type, out)?;
+
+        let addend = self.r_addend;
+        if self.r_sym != 0 {
+            if let Some(sym) = sym {
+                let name = if sym.get_name_offset() == 0
+                    && sym.get_type().get() != STT_SECTION
+                {
+                    "<null>"
+                } else {
+                    sym.get_name()
+                };
+                if sym.get_type().get() == STT_GNU_IFUNC {
+                    print_ifunc_resolver(sym, name, version, is_32, out)?;
+                } else if is_32 {
+                    write!(out, " {:08x}   ", sym.get_value())?;
+                } else {
+                    write!(out, " {:016x} ", sym.get_value())?;
+                }
+                write!(out, "{}", truncate_name(name, 22))?;
+                if let Some(version) = version {
+                    if sym.get_name_offset() != 0 {
+                        write!(out, "{}", version.get_reloc_suffix())?;
+                    }
+                }
+                if is_rela {
+                    if addend < 0 {
+                        write!(out, " - {:x}", addend.wrapping_neg())?;
+                    } else {
+                        write!(out, " + {:x}", addend)?;
+                    }
+                }
+            }
+        } else if is_rela {
+            write!(out, "{:width$}", "", width = if is_32 { 12 } else { 20 })?;
+            if addend < 0 {
+                write!(out, "-{:x}", addend.wrapping_neg())?;
+            } else {
+                write!(out, "{:x}", addend)?;
+            }
+        }
+        writeln!(out)?;
+
+        if is_mips64 {
+            write!(out, "                    Type2: ")?;
+            print_rel_type(machine, (self.r_info >> 8 & 0xff) as u32, out)?;
+            write!(out, "\n                    Type3: ")?;
+            print_rel_type(machine, (self.r_info >> 16 & 0xff) as u32, out)?;
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Relocations against `STT_GNU_IFUNC` symbols use the result of
+/// calling the symbol, so readelf shows `name()` in place of the
+/// symbol value.
+fn print_ifunc_resolver(
+    sym: &ElfSym, name: &str, version: Option<&SymbolVersion>, is_32: bool,
+    out: &mut dyn Write) -> io::Result<()>
+{
+    let width = if is_32 { 8 } else { 14 };
+    let name = if sym.get_name_offset() == 0 { "??" } else { name };
+    let name = truncate_name(name, width);
+    write!(out, " {}", name)?;
+    if let Some(version) = version {
+        write!(out, "{}", version.get_reloc_suffix())?;
+    }
+    let len = name.chars().count();
+    let pad = if len <= width { width + 1 - len } else { 1 };
+    write!(out, "(){:pad$}", "", pad = pad)
+}
+
+fn print_rel_type(machine: ElfEhdrMachine, r_type: u32, out: &mut dyn Write) -> io::Result<()> {
+    match get_rel_type_name(machine, r_type) {
+        Some(name) => write!(out, "{:<17.17}", name),
+        None => write!(out, "unrecognized: {:<7x}", r_type),
+    }
+}
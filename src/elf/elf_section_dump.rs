@@ -0,0 +1,104 @@
+//! Dumps of raw section contents, as readelf's `-x`, `-R` and `-p` show
+//! them.
+
+use ::std::io::{self, Write};
+
+/// Prints `data` as rows of 16 hex bytes followed by their ASCII
+/// characters. `address` is the address of the first byte.
+pub fn print_hex_dump(data: &[u8], address: u64, out: &mut dyn Write) -> io::Result<()> {
+    for (i, row) in data.chunks(16).enumerate() {
+        write!(out, "  0x{:08x} ", address.wrapping_add(16 * i as u64))?;
+        for j in 0..16 {
+            match row.get(j) {
+                Some(byte) => write!(out, "{:02x}", byte)?,
+                None => write!(out, "  ")?,
+            }
+            if j % 4 == 3 {
+                write!(out, " ")?;
+            }
+        }
+        let text: String = row.iter()
+            .map(|&b| if is_print(b) { b as char } else { '.' })
+            .collect();
+        writeln!(out, "{}", text)?;
+    }
+    writeln!(out)
+}
+
+/// A string found in a section by `get_strings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionString<'a> {
+    /// Offset of the string in the section.
+    pub offset: usize,
+    /// The bytes of the string, without the terminator. A string that
+    /// ends with a newline includes it.
+    pub bytes: &'a [u8],
+    /// Whether the string continues the previous one, i.e. the previous
+    /// one ended with a newline followed by something other than NUL.
+    pub continues: bool,
+}
+
+/// Splits `data` into strings like readelf's `-p` does: each one starts at
+/// a printable ASCII character and ends at a NUL, after a newline or at the
+/// end of the section.
+pub fn get_strings(data: &[u8]) -> Vec<SectionString<'_>> {
+    let mut strings = Vec::new();
+    let mut continuing = false;
+    let mut pos = 0;
+    while pos < data.len() {
+        if !is_print(data[pos]) {
+            pos += 1;
+            continue;
+        }
+        let start = pos;
+        let mut end = start;
+        let mut next_continues = false;
+        while end < data.len() && data[end] != 0 {
+            end += 1;
+            if data[end - 1] == b'\n' {
+                next_continues = data.get(end).is_some_and(|&b| b != 0);
+                break;
+            }
+        }
+        strings.push(SectionString {
+            offset: start,
+            bytes: &data[start..end],
+            continues: continuing,
+        });
+        continuing = next_continues;
+        pos = end;
+    }
+    strings
+}
+
+/// Prints the strings of `data`. Newlines are shown as `\n` and end the
+/// line, other control characters are shown as `^` followed by a letter.
+/// Bytes outside of ASCII are written as they are, like readelf does, so
+/// the output needn't be valid UTF-8.
+pub fn print_string_dump(data: &[u8], out: &mut dyn Write) -> io::Result<()> {
+    let strings = get_strings(data);
+    for string in &strings {
+        if string.continues {
+            write!(out, "            ")?;
+        } else {
+            write!(out, "  [{:6x}]  ", string.offset)?;
+        }
+        for &byte in string.bytes {
+            match byte {
+                b'\n' => write!(out, "\\n")?,
+                0..=0x1f | 0x7f => out.write_all(&[b'^', byte.wrapping_add(0x40)])?,
+                _ => out.write_all(&[byte])?,
+            }
+        }
+        writeln!(out)?;
+    }
+    // readelf ends the message with the newline it ends the dump with.
+    if strings.is_empty() {
+        write!(out, "  No strings found in this section.")?;
+    }
+    writeln!(out)
+}
+
+fn is_print(byte: u8) -> bool {
+    (0x20..0x7f).contains(&byte)
+}
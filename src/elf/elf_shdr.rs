@@ -0,0 +1,357 @@
+macro_rules! elf_shdr {
+    ($word:ty, $xword:ty, $addr:ty, $off:ty) => {
+        use ::to_host::Endianness;
+        use ::to_host::to_host_copy::ToHostCopy;
+        use ::to_host::ToHostCopyStruct;
+        use ::to_host::to_target_copy::ToTargetCopy;
+        use ::to_host::ToTargetCopyStruct;
+        use super::super::elf_shdr::ElfShdr;
+        use super::super::elf_shdr_type::ElfShdrType;
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Shdr {
+            sh_name: $word,
+            sh_type: $word,
+            sh_flags: $xword,
+            sh_addr: $addr,
+            sh_offset: $off,
+            sh_size: $xword,
+            sh_link: $word,
+            sh_info: $word,
+            sh_addralign: $xword,
+            sh_entsize: $xword,
+        }
+
+        impl Elf_Shdr {
+            pub fn get_name(&self) -> $word {
+                self.sh_name
+            }
+            pub fn get_type(&self) -> ElfShdrType {
+                ElfShdrType::from(self.sh_type)
+            }
+            pub fn get_flags(&self) -> $xword {
+                self.sh_flags
+            }
+            pub fn get_addr(&self) -> $addr {
+                self.sh_addr
+            }
+            pub fn get_offset(&self) -> $off {
+                self.sh_offset
+            }
+            pub fn get_size(&self) -> $xword {
+                self.sh_size
+            }
+            pub fn get_link(&self) -> $word {
+                self.sh_link
+            }
+            pub fn get_info(&self) -> $word {
+                self.sh_info
+            }
+            pub fn get_addralign(&self) -> $xword {
+                self.sh_addralign
+            }
+            pub fn get_entsize(&self) -> $xword {
+                self.sh_entsize
+            }
+        }
+
+        impl ToHostCopyStruct for Elf_Shdr {
+            fn to_host_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Shdr {
+                    sh_name: self.sh_name.to_host_copy(e),
+                    sh_type: self.sh_type.to_host_copy(e),
+                    sh_flags: self.sh_flags.to_host_copy(e),
+                    sh_addr: self.sh_addr.to_host_copy(e),
+                    sh_offset: self.sh_offset.to_host_copy(e),
+                    sh_size: self.sh_size.to_host_copy(e),
+                    sh_link: self.sh_link.to_host_copy(e),
+                    sh_info: self.sh_info.to_host_copy(e),
+                    sh_addralign: self.sh_addralign.to_host_copy(e),
+                    sh_entsize: self.sh_entsize.to_host_copy(e),
+                }
+            }
+        }
+
+        impl ToTargetCopyStruct for Elf_Shdr {
+            fn to_target_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Shdr {
+                    sh_name: self.sh_name.to_target_copy(e),
+                    sh_type: self.sh_type.to_target_copy(e),
+                    sh_flags: self.sh_flags.to_target_copy(e),
+                    sh_addr: self.sh_addr.to_target_copy(e),
+                    sh_offset: self.sh_offset.to_target_copy(e),
+                    sh_size: self.sh_size.to_target_copy(e),
+                    sh_link: self.sh_link.to_target_copy(e),
+                    sh_info: self.sh_info.to_target_copy(e),
+                    sh_addralign: self.sh_addralign.to_target_copy(e),
+                    sh_entsize: self.sh_entsize.to_target_copy(e),
+                }
+            }
+        }
+
+        /// Returns `shdr` in host byte order, to be converted with
+        /// `to_target_copy`. Addresses, offsets and sizes are truncated to
+        /// the class, so they must fit.
+        impl<'a> From<&'a ElfShdr> for Elf_Shdr {
+            fn from(shdr: &'a ElfShdr) -> Elf_Shdr {
+                Elf_Shdr {
+                    sh_name: shdr.get_name_offset(),
+                    sh_type: shdr.get_type().get(),
+                    sh_flags: shdr.get_flags().get() as $xword,
+                    sh_addr: shdr.get_addr() as $addr,
+                    sh_offset: shdr.get_offset() as $off,
+                    sh_size: shdr.get_size() as $xword,
+                    sh_link: shdr.get_link(),
+                    sh_info: shdr.get_info(),
+                    sh_addralign: shdr.get_addralign() as $xword,
+                    sh_entsize: shdr.get_entsize() as $xword,
+                }
+            }
+        }
+    }
+}
+
+use ::std::io::{self, Write};
+use ::json::Json;
+use super::elf_shdr_type::ElfShdrType;
+use super::elf_shdr_flags::ElfShdrFlags;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_ei_os_abi::ElfEiOsAbi;
+use super::elf_strtab::print_truncated_name;
+use super::{Elf32_Shdr, Elf64_Shdr};
+
+/// A section header of a file of either class, in host byte order,
+/// together with the section name. Addresses, offsets and sizes are
+/// widened to 64 bits.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ElfShdr {
+    sh_name: u32,
+    sh_type: ElfShdrType,
+    sh_flags: u64,
+    sh_addr: u64,
+    sh_offset: u64,
+    sh_size: u64,
+    sh_link: u32,
+    sh_info: u32,
+    sh_addralign: u64,
+    sh_entsize: u64,
+    name: String,
+}
+
+macro_rules! elf_shdr_from {
+    ($t:ty) => {
+        /// `shdr` must already be converted to host byte order.
+        /// The name is left empty until it's resolved with `with_name`.
+        impl<'a> From<&'a $t> for ElfShdr {
+            fn from(shdr: &'a $t) -> ElfShdr {
+                ElfShdr {
+                    sh_name: shdr.get_name(),
+                    sh_type: shdr.get_type(),
+                    sh_flags: shdr.get_flags() as u64,
+                    sh_addr: shdr.get_addr() as u64,
+                    sh_offset: shdr.get_offset() as u64,
+                    sh_size: shdr.get_size() as u64,
+                    sh_link: shdr.get_link(),
+                    sh_info: shdr.get_info(),
+                    sh_addralign: shdr.get_addralign() as u64,
+                    sh_entsize: shdr.get_entsize() as u64,
+                    name: String::new(),
+                }
+            }
+        }
+    }
+}
+
+elf_shdr_from!(Elf32_Shdr);
+elf_shdr_from!(Elf64_Shdr);
+
+impl ElfShdr {
+    /// Returns a header for a new section called `name` of type
+    /// `sh_type`, with every other field zero, to be filled in with the
+    /// setters. `ElfWriter` places its name and contents.
+    pub fn new(name: &str, sh_type: ElfShdrType) -> ElfShdr {
+        ElfShdr {
+            sh_name: 0,
+            sh_type,
+            sh_flags: 0,
+            sh_addr: 0,
+            sh_offset: 0,
+            sh_size: 0,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 0,
+            sh_entsize: 0,
+            name: String::from(name),
+        }
+    }
+
+    pub fn with_name(self, name: String) -> ElfShdr {
+        ElfShdr { name, ..self }
+    }
+
+    /// The offset of the name in the section header string table.
+    pub fn get_name_offset(&self) -> u32 {
+        self.sh_name
+    }
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn get_type(&self) -> ElfShdrType {
+        self.sh_type
+    }
+    pub fn get_flags(&self) -> ElfShdrFlags {
+        ElfShdrFlags::from(self.sh_flags)
+    }
+    pub fn get_addr(&self) -> u64 {
+        self.sh_addr
+    }
+    pub fn get_offset(&self) -> u64 {
+        self.sh_offset
+    }
+    pub fn get_size(&self) -> u64 {
+        self.sh_size
+    }
+    pub fn get_link(&self) -> u32 {
+        self.sh_link
+    }
+    pub fn get_info(&self) -> u32 {
+        self.sh_info
+    }
+    pub fn get_addralign(&self) -> u64 {
+        self.sh_addralign
+    }
+    pub fn get_entsize(&self) -> u64 {
+        self.sh_entsize
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = String::from(name);
+    }
+    pub fn set_type(&mut self, sh_type: ElfShdrType) {
+        self.sh_type = sh_type;
+    }
+    pub fn set_flags(&mut self, sh_flags: ElfShdrFlags) {
+        self.sh_flags = sh_flags.get();
+    }
+    pub fn set_addr(&mut self, sh_addr: u64) {
+        self.sh_addr = sh_addr;
+    }
+    /// Only `SHT_NOBITS` sections are sized this way; the size of the
+    /// others is that of the contents given to `ElfWriter`.
+    pub fn set_size(&mut self, sh_size: u64) {
+        self.sh_size = sh_size;
+    }
+    pub fn set_link(&mut self, sh_link: u32) {
+        self.sh_link = sh_link;
+    }
+    pub fn set_info(&mut self, sh_info: u32) {
+        self.sh_info = sh_info;
+    }
+    pub fn set_addralign(&mut self, sh_addralign: u64) {
+        self.sh_addralign = sh_addralign;
+    }
+    pub fn set_entsize(&mut self, sh_entsize: u64) {
+        self.sh_entsize = sh_entsize;
+    }
+    pub(crate) fn set_name_offset(&mut self, sh_name: u32) {
+        self.sh_name = sh_name;
+    }
+    pub(crate) fn set_offset(&mut self, sh_offset: u64) {
+        self.sh_offset = sh_offset;
+    }
+
+    /// Returns the name of the section type, which may depend on `machine`.
+    pub fn get_type_name(&self, machine: ElfEhdrMachine) -> String {
+        match self.sh_type.get_proc_name(machine) {
+            Some(name) => name.to_string(),
+            None => self.sh_type.to_string(),
+        }
+    }
+
+    /// Describes the header for `--output=json`. The name of the flags
+    /// is their letters in readelf's `-S` table.
+    pub fn to_json(&self, index: usize, machine: ElfEhdrMachine, osabi: ElfEiOsAbi) -> Json {
+        Json::object(vec![
+            ("index", index.into()),
+            ("name", self.name.as_str().into()),
+            ("type", Json::named(self.sh_type.get(), self.get_type_name(machine))),
+            ("flags", Json::named(
+                self.sh_flags, self.get_flags().get_letters(machine, osabi))),
+            ("addr", self.sh_addr.into()),
+            ("offset", self.sh_offset.into()),
+            ("size", self.sh_size.into()),
+            ("entsize", self.sh_entsize.into()),
+            ("link", self.sh_link.into()),
+            ("info", self.sh_info.into()),
+            ("addralign", self.sh_addralign.into()),
+        ])
+    }
+
+    /// Prints a row of readelf's `-S` table.
+    pub fn print_row(
+        &self, index: usize, machine: ElfEhdrMachine, osabi: ElfEiOsAbi, is_32: bool,
+        out: &mut dyn Write) -> io::Result<()>
+    {
+        let sh_flags = self.get_flags().get_letters(machine, osabi);
+        let sh_type = self.get_type_name(machine);
+
+        write!(out, "  [{:2}] ", index)?;
+        print_truncated_name(&self.name, 17, out)?;
+
+        if is_32 {
+            writeln!(
+                out,
+                concat!(
+                    " {:<15.15} ",
+                    "{:08x} ",
+                    "{:06x} ",
+                    "{:06x} ",
+                    "{:02x} ",
+                    "{:>3} ",
+                    "{:2} ",
+                    "{:3} ",
+                    "{:2}",
+                ),
+                sh_type,
+                self.sh_addr,
+                self.sh_offset,
+                self.sh_size,
+                self.sh_entsize,
+                sh_flags,
+                self.sh_link,
+                self.sh_info,
+                self.sh_addralign,
+            )?;
+        } else {
+            writeln!(
+                out,
+                concat!(
+                    " {:<15.15}  ",
+                    "{:016x}  ",
+                    "{:08x}\n",
+                    "       ",
+                    "{:016x}  ",
+                    "{:016x} ",
+                    "{:>3} ",
+                    "     {:2}   ",
+                    "{:3}     ",
+                    "{}",
+                ),
+                sh_type,
+                self.sh_addr,
+                self.sh_offset,
+                self.sh_size,
+                self.sh_entsize,
+                sh_flags,
+                self.sh_link,
+                self.sh_info,
+                self.sh_addralign,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use ::std::fmt::{Display, Formatter};
+
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_ei_os_abi::ElfEiOsAbi;
+
+pub const SHF_WRITE: u64 = 0x1;
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+pub const SHF_MERGE: u64 = 0x10;
+pub const SHF_STRINGS: u64 = 0x20;
+pub const SHF_INFO_LINK: u64 = 0x40;
+pub const SHF_LINK_ORDER: u64 = 0x80;
+pub const SHF_OS_NONCONFORMING: u64 = 0x100;
+pub const SHF_GROUP: u64 = 0x200;
+pub const SHF_TLS: u64 = 0x400;
+pub const SHF_COMPRESSED: u64 = 0x800;
+pub const SHF_GNU_RETAIN: u64 = 0x200000;
+pub const SHF_MASKOS: u64 = 0x0ff00000;
+pub const SHF_GNU_MBIND: u64 = 0x01000000;
+pub const SHF_MASKPROC: u64 = 0xf0000000;
+pub const SHF_EXCLUDE: u64 = 0x80000000;
+pub const SHF_X86_64_LARGE: u64 = 0x10000000;
+pub const SHF_ARM_PURECODE: u64 = 0x20000000;
+pub const SHF_PPC_VLE: u64 = 0x10000000;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfShdrFlags {
+    flags: u64,
+}
+
+impl From<u64> for ElfShdrFlags {
+    fn from(flags: u64) -> ElfShdrFlags {
+        ElfShdrFlags { flags }
+    }
+}
+
+impl ElfShdrFlags {
+    pub fn get(&self) -> u64 {
+        self.flags
+    }
+    pub fn contains(&self, flag: u64) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+impl ElfShdrFlags {
+    /// Returns the flag letters of readelf's `-S` table.
+    /// `SHF_GNU_RETAIN` is only known for GNU and FreeBSD files and
+    /// `SHF_GNU_MBIND` also for files without an OS/ABI; other OS-specific
+    /// flags show up as `o`. Processor-specific flags depend on `machine`.
+    pub fn get_letters(&self, machine: ElfEhdrMachine, osabi: ElfEiOsAbi) -> String {
+        let is_gnu = osabi == ElfEiOsAbi::ELFOSABI_GNU
+            || osabi == ElfEiOsAbi::ELFOSABI_FREEBSD;
+        let knows_mbind = is_gnu || osabi == ElfEiOsAbi::ELFOSABI_NONE;
+        let is_x86_64 = machine == ElfEhdrMachine::EM_X86_64;
+        let is_arm = machine == ElfEhdrMachine::EM_ARM;
+        let is_ppc = machine == ElfEhdrMachine::EM_PPC;
+
+        let mut s = String::new();
+        let mut os_seen = false;
+        let mut proc_seen = false;
+        let mut unknown_seen = false;
+
+        for bit in 0..64 {
+            let flag = 1u64 << bit;
+            if self.flags & flag == 0 {
+                continue;
+            }
+            match flag {
+                SHF_WRITE => s.push('W'),
+                SHF_ALLOC => s.push('A'),
+                SHF_EXECINSTR => s.push('X'),
+                SHF_MERGE => s.push('M'),
+                SHF_STRINGS => s.push('S'),
+                SHF_INFO_LINK => s.push('I'),
+                SHF_LINK_ORDER => s.push('L'),
+                SHF_OS_NONCONFORMING => s.push('O'),
+                SHF_GROUP => s.push('G'),
+                SHF_TLS => s.push('T'),
+                SHF_COMPRESSED => s.push('C'),
+                SHF_GNU_RETAIN if is_gnu => s.push('R'),
+                SHF_GNU_MBIND if knows_mbind => s.push('D'),
+                SHF_EXCLUDE => s.push('E'),
+                SHF_X86_64_LARGE if is_x86_64 => s.push('l'),
+                SHF_ARM_PURECODE if is_arm => s.push('y'),
+                SHF_PPC_VLE if is_ppc => s.push('v'),
+                f if f & SHF_MASKOS != 0 => os_seen = true,
+                f if f & SHF_MASKPROC != 0 => proc_seen = true,
+                _ => unknown_seen = true,
+            }
+        }
+        if os_seen {
+            s.push('o');
+        }
+        if proc_seen {
+            s.push('p');
+        }
+        if unknown_seen {
+            s.push('x');
+        }
+        s
+    }
+}
+
+impl Display for ElfShdrFlags {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        fmt.pad(&self.get_letters(ElfEhdrMachine::EM_NONE, ElfEiOsAbi::ELFOSABI_NONE))
+    }
+}
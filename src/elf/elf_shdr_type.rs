@@ -0,0 +1,158 @@
+use ::std::fmt::{Display, Formatter};
+
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::helpers::format_c_hex;
+
+pub const SHT_NULL: u32 = 0;
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_HASH: u32 = 5;
+pub const SHT_DYNAMIC: u32 = 6;
+pub const SHT_NOTE: u32 = 7;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_REL: u32 = 9;
+pub const SHT_SHLIB: u32 = 10;
+pub const SHT_DYNSYM: u32 = 11;
+pub const SHT_INIT_ARRAY: u32 = 14;
+pub const SHT_FINI_ARRAY: u32 = 15;
+pub const SHT_PREINIT_ARRAY: u32 = 16;
+pub const SHT_GROUP: u32 = 17;
+pub const SHT_SYMTAB_SHNDX: u32 = 18;
+pub const SHT_RELR: u32 = 19;
+pub const SHT_LOOS: u32 = 0x60000000;
+pub const SHT_LLVM_ADDRSIG: u32 = 0x6fff4c03;
+pub const SHT_GNU_ATTRIBUTES: u32 = 0x6ffffff5;
+pub const SHT_GNU_HASH: u32 = 0x6ffffff6;
+pub const SHT_GNU_LIBLIST: u32 = 0x6ffffff7;
+pub const SHT_CHECKSUM: u32 = 0x6ffffff8;
+pub const SHT_GNU_VERDEF: u32 = 0x6ffffffd;
+pub const SHT_GNU_VERNEED: u32 = 0x6ffffffe;
+pub const SHT_GNU_VERSYM: u32 = 0x6fffffff;
+pub const SHT_HIOS: u32 = 0x6fffffff;
+pub const SHT_LOPROC: u32 = 0x70000000;
+pub const SHT_HIPROC: u32 = 0x7fffffff;
+pub const SHT_X86_64_UNWIND: u32 = 0x70000001;
+pub const SHT_ARM_EXIDX: u32 = 0x70000001;
+pub const SHT_ARM_PREEMPTMAP: u32 = 0x70000002;
+pub const SHT_ARM_ATTRIBUTES: u32 = 0x70000003;
+pub const SHT_ARM_DEBUGOVERLAY: u32 = 0x70000004;
+pub const SHT_ARM_OVERLAYSECTION: u32 = 0x70000005;
+pub const SHT_AARCH64_ATTRIBUTES: u32 = 0x70000003;
+pub const SHT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+pub const SHT_LOUSER: u32 = 0x80000000;
+pub const SHT_HIUSER: u32 = 0xffffffff;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfShdrType {
+    data: u32,
+}
+
+impl From<u32> for ElfShdrType {
+    fn from(data: u32) -> ElfShdrType {
+        ElfShdrType { data }
+    }
+}
+
+/// MIPS section types, indexed from `SHT_LOPROC`.
+const MIPS_SECTION_TYPES: [&str; 0x2b] = [
+    "MIPS_LIBLIST", "MIPS_MSYM", "MIPS_CONFLICT", "MIPS_GPTAB", "MIPS_UCODE",
+    "MIPS_DEBUG", "MIPS_REGINFO", "MIPS_PACKAGE", "MIPS_PACKSYM", "MIPS_RELD",
+    "", "MIPS_IFACE", "MIPS_CONTENT", "MIPS_OPTIONS", "", "", "MIPS_SHDR",
+    "MIPS_FDESC", "MIPS_EXTSYM", "MIPS_DENSE", "MIPS_PDESC", "MIPS_LOCSYM",
+    "MIPS_AUXSYM", "MIPS_OPTSYM", "MIPS_LOCSTR", "MIPS_LINE", "MIPS_RFDESC",
+    "MIPS_DELTASYM", "MIPS_DELTAINST", "MIPS_DELTACLASS", "MIPS_DWARF",
+    "MIPS_DELTADECL", "MIPS_SYMBOL_LIB", "MIPS_EVENTS", "MIPS_TRANSLATE",
+    "MIPS_PIXIE", "MIPS_XLATE", "MIPS_XLATE_DEBUG", "MIPS_WHIRL",
+    "MIPS_EH_REGION", "MIPS_XLATE_OLD", "MIPS_PDR_EXCEPTION", "MIPS_ABIFLAGS",
+];
+
+impl ElfShdrType {
+    pub fn get(&self) -> u32 {
+        self.data
+    }
+
+    /// Returns the name of a processor-specific section type, if `machine`
+    /// defines one.
+    pub fn get_proc_name(&self, machine: ElfEhdrMachine) -> Option<&'static str> {
+        let s = match machine {
+            ElfEhdrMachine::EM_X86_64 => match self.data {
+                SHT_X86_64_UNWIND => "X86_64_UNWIND",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_ARM => match self.data {
+                SHT_ARM_EXIDX => "ARM_EXIDX",
+                SHT_ARM_PREEMPTMAP => "ARM_PREEMPTMAP",
+                SHT_ARM_ATTRIBUTES => "ARM_ATTRIBUTES",
+                SHT_ARM_DEBUGOVERLAY => "ARM_DEBUGOVERLAY",
+                SHT_ARM_OVERLAYSECTION => "ARM_OVERLAYSECTION",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_AARCH64 => match self.data {
+                SHT_AARCH64_ATTRIBUTES => "AARCH64_ATTRIBUTES",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_RISCV => match self.data {
+                SHT_RISCV_ATTRIBUTES => "RISCV_ATTRIBUTES",
+                _ => return None,
+            },
+            ElfEhdrMachine::EM_MIPS | ElfEhdrMachine::EM_MIPS_RS3_LE => {
+                let index = self.data.wrapping_sub(SHT_LOPROC) as usize;
+                match MIPS_SECTION_TYPES.get(index) {
+                    Some(name) if !name.is_empty() => name,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(s)
+    }
+}
+
+impl Display for ElfShdrType {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let s = match self.data {
+            SHT_NULL => "NULL",
+            SHT_PROGBITS => "PROGBITS",
+            SHT_SYMTAB => "SYMTAB",
+            SHT_STRTAB => "STRTAB",
+            SHT_RELA => "RELA",
+            SHT_HASH => "HASH",
+            SHT_DYNAMIC => "DYNAMIC",
+            SHT_NOTE => "NOTE",
+            SHT_NOBITS => "NOBITS",
+            SHT_REL => "REL",
+            SHT_SHLIB => "SHLIB",
+            SHT_DYNSYM => "DYNSYM",
+            SHT_INIT_ARRAY => "INIT_ARRAY",
+            SHT_FINI_ARRAY => "FINI_ARRAY",
+            SHT_PREINIT_ARRAY => "PREINIT_ARRAY",
+            SHT_GROUP => "GROUP",
+            SHT_SYMTAB_SHNDX => "SYMTAB SECTION INDICES",
+            SHT_RELR => "RELR",
+            SHT_LLVM_ADDRSIG => "LLVM_ADDRSIG",
+            SHT_GNU_ATTRIBUTES => "GNU_ATTRIBUTES",
+            SHT_GNU_HASH => "GNU_HASH",
+            SHT_GNU_LIBLIST => "GNU_LIBLIST",
+            SHT_CHECKSUM => "CHECKSUM",
+            SHT_GNU_VERDEF => "VERDEF",
+            SHT_GNU_VERNEED => "VERNEED",
+            SHT_GNU_VERSYM => "VERSYM",
+            x @ SHT_LOOS..=SHT_HIOS => {
+                return fmt.pad(&format!("LOOS+{}", format_c_hex(x - SHT_LOOS)));
+            }
+            x @ SHT_LOPROC..=SHT_HIPROC => {
+                return fmt.pad(&format!("LOPROC+{}", format_c_hex(x - SHT_LOPROC)));
+            }
+            x @ SHT_LOUSER..=SHT_HIUSER => {
+                return fmt.pad(&format!("LOUSER+{}", format_c_hex(x - SHT_LOUSER)));
+            }
+            x => {
+                return fmt.pad(&format!("{:08x}: <unknown>", x));
+            }
+        };
+        fmt.pad(s)
+    }
+}
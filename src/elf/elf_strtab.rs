@@ -0,0 +1,28 @@
+use ::std::io::{self, Write};
+
+/// Returns the NUL-terminated string starting at `offset` in a string table
+/// section. Out-of-range offsets produce readelf's `<corrupt>` marker.
+pub fn get_string(strtab: &[u8], offset: usize) -> String {
+    if offset >= strtab.len() {
+        return String::from("<corrupt>");
+    }
+    let tail = &strtab[offset..];
+    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+    String::from_utf8_lossy(&tail[..end]).into_owned()
+}
+
+/// Shortens `name` to at most `width` columns the way readelf does in
+/// non-wide mode, marking the cut with `[...]`.
+pub fn truncate_name(name: &str, width: usize) -> String {
+    if name.chars().count() > width {
+        let head: String = name.chars().take(width.saturating_sub(5)).collect();
+        format!("{}[...]", head)
+    } else {
+        String::from(name)
+    }
+}
+
+/// Prints `name` truncated and padded to `width` columns.
+pub fn print_truncated_name(name: &str, width: usize, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "{:<width$}", truncate_name(name, width), width = width)
+}
@@ -0,0 +1,287 @@
+macro_rules! elf_sym {
+    // Elf32_Sym and Elf64_Sym differ in field order, not only in widths.
+    (b32) => {
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Sym {
+            st_name: Elf32_Word,
+            st_value: Elf32_Addr,
+            st_size: Elf32_Word,
+            st_info: u8,
+            st_other: u8,
+            st_shndx: Elf32_Half,
+        }
+
+        elf_sym!(impl Elf32_Word, Elf32_Addr);
+    };
+    (b64) => {
+        use super::primitive::*;
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        pub struct Elf_Sym {
+            st_name: Elf64_Word,
+            st_info: u8,
+            st_other: u8,
+            st_shndx: Elf64_Half,
+            st_value: Elf64_Addr,
+            st_size: Elf64_Xword,
+        }
+
+        elf_sym!(impl Elf64_Xword, Elf64_Addr);
+    };
+    (impl $xword:ty, $addr:ty) => {
+        use ::to_host::Endianness;
+        use ::to_host::to_host_copy::ToHostCopy;
+        use ::to_host::ToHostCopyStruct;
+        use super::super::elf_sym_bind::ElfSymBind;
+        use super::super::elf_sym_type::ElfSymType;
+        use super::super::elf_sym_visibility::ElfSymVisibility;
+
+        impl Elf_Sym {
+            pub fn get_name(&self) -> u32 {
+                self.st_name
+            }
+            pub fn get_value(&self) -> $addr {
+                self.st_value
+            }
+            pub fn get_size(&self) -> $xword {
+                self.st_size
+            }
+            pub fn get_info(&self) -> u8 {
+                self.st_info
+            }
+            pub fn get_other(&self) -> u8 {
+                self.st_other
+            }
+            pub fn get_shndx(&self) -> u16 {
+                self.st_shndx
+            }
+            pub fn get_bind(&self) -> ElfSymBind {
+                ElfSymBind::from(self.st_info >> 4)
+            }
+            pub fn get_type(&self) -> ElfSymType {
+                ElfSymType::from(self.st_info & 0xf)
+            }
+            pub fn get_visibility(&self) -> ElfSymVisibility {
+                ElfSymVisibility::from(self.st_other & 0x3)
+            }
+        }
+
+        impl ToHostCopyStruct for Elf_Sym {
+            fn to_host_copy(&self, endianness: &Endianness) -> Self {
+                let e = endianness;
+                Elf_Sym {
+                    st_name: self.st_name.to_host_copy(e),
+                    st_value: self.st_value.to_host_copy(e),
+                    st_size: self.st_size.to_host_copy(e),
+                    st_info: self.st_info,
+                    st_other: self.st_other,
+                    st_shndx: self.st_shndx.to_host_copy(e),
+                }
+            }
+        }
+    }
+}
+
+use ::std::io::{self, Write};
+use ::json::Json;
+use super::elf_sym_bind::ElfSymBind;
+use super::elf_sym_type::ElfSymType;
+use super::elf_sym_visibility::ElfSymVisibility;
+use super::elf_sym_shndx::ElfSymShndx;
+use super::elf_ei_os_abi::ElfEiOsAbi;
+use super::elf_ehdr_machine::ElfEhdrMachine;
+use super::elf_version::{SymbolVersion, format_versioned_sym_name};
+use super::{Elf32_Sym, Elf64_Sym};
+
+pub const STO_OPTIONAL: u8 = 0x04;
+pub const STO_MIPS_PLT: u8 = 0x08;
+pub const STO_MIPS_PIC: u8 = 0x20;
+pub const STO_MICROMIPS: u8 = 0x80;
+pub const STO_MIPS16: u8 = 0xf0;
+pub const STO_AARCH64_VARIANT_PCS: u8 = 0x80;
+pub const STO_RISCV_VARIANT_CC: u8 = 0x80;
+pub const STO_PPC64_LOCAL_MASK: u8 = 0xe0;
+
+/// A symbol of a file of either class, in host byte order, together with
+/// its name and its section index. Values and sizes are widened to 64 bits.
+#[derive(Debug, Clone)]
+pub struct ElfSym {
+    st_name: u32,
+    st_value: u64,
+    st_size: u64,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+    shndx: ElfSymShndx,
+    name: String,
+}
+
+macro_rules! elf_sym_from {
+    ($t:ty) => {
+        /// `sym` must already be converted to host byte order.
+        /// The name is left empty and the section index unresolved until
+        /// they're set with `with_name` and `with_shndx`.
+        impl<'a> From<&'a $t> for ElfSym {
+            fn from(sym: &'a $t) -> ElfSym {
+                ElfSym {
+                    st_name: sym.get_name(),
+                    st_value: sym.get_value() as u64,
+                    st_size: sym.get_size() as u64,
+                    st_info: sym.get_info(),
+                    st_other: sym.get_other(),
+                    st_shndx: sym.get_shndx(),
+                    shndx: ElfSymShndx::from(sym.get_shndx()),
+                    name: String::new(),
+                }
+            }
+        }
+    }
+}
+
+elf_sym_from!(Elf32_Sym);
+elf_sym_from!(Elf64_Sym);
+
+impl ElfSym {
+    pub fn with_name(self, name: String) -> ElfSym {
+        ElfSym { name, ..self }
+    }
+    /// Sets the section index looked up in `SHT_SYMTAB_SHNDX` for
+    /// `SHN_XINDEX` symbols.
+    pub fn with_shndx(self, shndx: ElfSymShndx) -> ElfSym {
+        ElfSym { shndx, ..self }
+    }
+
+    /// The offset of the name in the string table.
+    pub fn get_name_offset(&self) -> u32 {
+        self.st_name
+    }
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn get_value(&self) -> u64 {
+        self.st_value
+    }
+    pub fn get_size(&self) -> u64 {
+        self.st_size
+    }
+    pub fn get_info(&self) -> u8 {
+        self.st_info
+    }
+    pub fn get_other(&self) -> u8 {
+        self.st_other
+    }
+    /// The section index as stored in `st_shndx`.
+    pub fn get_raw_shndx(&self) -> u16 {
+        self.st_shndx
+    }
+    /// The section index with `SHN_XINDEX` resolved.
+    pub fn get_shndx(&self) -> ElfSymShndx {
+        self.shndx
+    }
+    pub fn get_bind(&self) -> ElfSymBind {
+        ElfSymBind::from(self.st_info >> 4)
+    }
+    pub fn get_type(&self) -> ElfSymType {
+        ElfSymType::from(self.st_info & 0xf)
+    }
+    pub fn get_visibility(&self) -> ElfSymVisibility {
+        ElfSymVisibility::from(self.st_other & 0x3)
+    }
+
+    /// Describes the bits of `st_other` besides the visibility the way
+    /// readelf does, or returns `None` if there are none.
+    pub fn get_other_name(&self, machine: ElfEhdrMachine) -> Option<String> {
+        let other = self.st_other & !0x3;
+        if other == 0 {
+            return None;
+        }
+        let name = match machine {
+            ElfEhdrMachine::EM_MIPS | ElfEhdrMachine::EM_MIPS_RS3_LE => match other {
+                STO_OPTIONAL => Some("OPTIONAL"),
+                STO_MIPS_PLT => Some("MIPS PLT"),
+                STO_MIPS_PIC => Some("MIPS PIC"),
+                STO_MICROMIPS => Some("MICROMIPS"),
+                0xa0 => Some("MICROMIPS, MIPS PIC"),
+                STO_MIPS16 => Some("MIPS16"),
+                _ => None,
+            },
+            ElfEhdrMachine::EM_AARCH64 if other == STO_AARCH64_VARIANT_PCS =>
+                Some("VARIANT_PCS"),
+            ElfEhdrMachine::EM_RISCV if other == STO_RISCV_VARIANT_CC =>
+                Some("VARIANT_CC"),
+            // The three bits encode the distance between the global and
+            // the local entry point of a function.
+            ElfEhdrMachine::EM_PPC64 if other & !STO_PPC64_LOCAL_MASK == 0 => {
+                let local = (other & STO_PPC64_LOCAL_MASK) >> 5;
+                let local = if local >= 2 { ((1 << local) >> 2) << 2 } else { local };
+                return Some(format!("<localentry>: {}", local));
+            }
+            _ => None,
+        };
+        Some(match name {
+            Some(name) => name.to_string(),
+            None => format!("<other>: {:x}", other),
+        })
+    }
+
+    /// Describes the symbol for `--output=json`. The arguments are those
+    /// of `print_row`.
+    pub fn to_json(
+        &self, index: usize, version: Option<&SymbolVersion>, machine: ElfEhdrMachine,
+        osabi: ElfEiOsAbi) -> Json
+    {
+        Json::object(vec![
+            ("index", index.into()),
+            ("name", self.name.as_str().into()),
+            ("value", self.st_value.into()),
+            ("size", self.st_size.into()),
+            ("type", Json::named(self.get_type().get(), self.get_type().get_name(osabi))),
+            ("bind", Json::named(self.get_bind().get(), self.get_bind().get_name(osabi))),
+            ("visibility", Json::named(
+                self.get_visibility().get(), self.get_visibility().to_string())),
+            ("other", Json::named(self.st_other, self.get_other_name(machine))),
+            ("shndx", Json::named(self.shndx.get(), self.shndx.to_string())),
+            ("version", version.map(SymbolVersion::to_json).into()),
+        ])
+    }
+
+    /// Prints a row of readelf's `-s` table.
+    /// `version` is the symbol version of a dynamic symbol, `osabi`
+    /// selects the names of OS-specific types and bindings.
+    pub fn print_row(
+        &self, index: usize, version: Option<&SymbolVersion>, machine: ElfEhdrMachine,
+        osabi: ElfEiOsAbi, is_32: bool, out: &mut dyn Write) -> io::Result<()>
+    {
+        let size = if self.st_size <= 99999 {
+            format!("{:5}", self.st_size)
+        } else {
+            format!("{:#x}", self.st_size)
+        };
+        write!(
+            out,
+            concat!(
+                "{:6}: ",
+                "{:0width$x} ",
+                "{} ",
+                "{:<7} ",
+                "{:<6} ",
+                "{:<7}",
+            ),
+            index,
+            self.st_value,
+            size,
+            self.get_type().get_name(osabi),
+            self.get_bind().get_name(osabi),
+            self.get_visibility(),
+            width = if is_32 { 8 } else { 16 },
+        )?;
+        if let Some(other) = self.get_other_name(machine) {
+            write!(out, " [{}] ", other)?;
+        }
+        writeln!(out, " {:>4} {}", self.shndx, format_versioned_sym_name(&self.name, version))
+    }
+}
@@ -0,0 +1,60 @@
+use ::std::fmt::{Display, Formatter};
+
+use super::elf_ei_os_abi::ElfEiOsAbi;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STB_WEAK: u8 = 2;
+pub const STB_LOOS: u8 = 10;
+pub const STB_GNU_UNIQUE: u8 = 10;
+pub const STB_HIOS: u8 = 12;
+pub const STB_LOPROC: u8 = 13;
+pub const STB_HIPROC: u8 = 15;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfSymBind {
+    data: u8,
+}
+
+impl From<u8> for ElfSymBind {
+    fn from(data: u8) -> ElfSymBind {
+        ElfSymBind { data }
+    }
+}
+
+impl ElfSymBind {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
+
+    /// `STB_GNU_UNIQUE` only means something for `ELFOSABI_GNU`, other files
+    /// get the generic name of the OS-specific range.
+    pub fn get_name(&self, osabi: ElfEiOsAbi) -> String {
+        if self.data == STB_GNU_UNIQUE && osabi == ElfEiOsAbi::ELFOSABI_GNU {
+            "UNIQUE".to_string()
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl Display for ElfSymBind {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let s = match self.data {
+            STB_LOCAL => "LOCAL",
+            STB_GLOBAL => "GLOBAL",
+            STB_WEAK => "WEAK",
+            x @ STB_LOOS..=STB_HIOS => {
+                return fmt.pad(&format!("<OS specific>: {}", x));
+            }
+            x @ STB_LOPROC..=STB_HIPROC => {
+                return fmt.pad(&format!("<processor specific>: {}", x));
+            }
+            x => {
+                return fmt.pad(&format!("<unknown>: {}", x));
+            }
+        };
+        fmt.pad(s)
+    }
+}
@@ -0,0 +1,91 @@
+use ::std::fmt::{Display, Formatter};
+
+pub const SHN_UNDEF: u32 = 0;
+pub const SHN_LORESERVE: u32 = 0xff00;
+pub const SHN_LOPROC: u32 = 0xff00;
+pub const SHN_HIPROC: u32 = 0xff1f;
+pub const SHN_LOOS: u32 = 0xff20;
+pub const SHN_HIOS: u32 = 0xff3f;
+pub const SHN_ABS: u32 = 0xfff1;
+pub const SHN_COMMON: u32 = 0xfff2;
+pub const SHN_XINDEX: u32 = 0xffff;
+pub const SHN_HIRESERVE: u32 = 0xffff;
+
+/// Section index of a symbol.
+///
+/// Symbols with `st_shndx == SHN_XINDEX` keep their real index in the
+/// `SHT_SYMTAB_SHNDX` section, which may be in the range of the reserved
+/// values, so resolved indices are kept apart from those.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ElfSymShndx {
+    /// The index of a section header, read from `st_shndx` or resolved
+    /// through `SHT_SYMTAB_SHNDX`.
+    Section(u32),
+    /// `SHN_UNDEF` or one of the reserved values of `st_shndx`, e.g.
+    /// `SHN_ABS`.
+    Reserved(u16),
+}
+
+impl From<u16> for ElfSymShndx {
+    /// Takes `st_shndx` as it is, without resolving `SHN_XINDEX`.
+    fn from(st_shndx: u16) -> ElfSymShndx {
+        let index = st_shndx as u32;
+        if index == SHN_UNDEF || index >= SHN_LORESERVE {
+            ElfSymShndx::Reserved(st_shndx)
+        } else {
+            ElfSymShndx::Section(index)
+        }
+    }
+}
+
+impl ElfSymShndx {
+    /// Takes an index from `SHT_SYMTAB_SHNDX`, where 0 is `SHN_UNDEF`.
+    pub fn from_extended(index: u32) -> ElfSymShndx {
+        if index == SHN_UNDEF {
+            ElfSymShndx::Reserved(SHN_UNDEF as u16)
+        } else {
+            ElfSymShndx::Section(index)
+        }
+    }
+
+    pub fn get(&self) -> u32 {
+        match *self {
+            ElfSymShndx::Section(index) => index,
+            ElfSymShndx::Reserved(value) => value as u32,
+        }
+    }
+    /// Returns `true` if the index refers to a real section header.
+    pub fn is_regular(&self) -> bool {
+        match *self {
+            ElfSymShndx::Section(_) => true,
+            ElfSymShndx::Reserved(_) => false,
+        }
+    }
+}
+
+impl Display for ElfSymShndx {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let value = match *self {
+            ElfSymShndx::Section(index) => return fmt.pad(&format!("{:3}", index)),
+            ElfSymShndx::Reserved(value) => value as u32,
+        };
+        let s = match value {
+            SHN_UNDEF => "UND",
+            SHN_ABS => "ABS",
+            SHN_COMMON => "COM",
+            x @ SHN_LOPROC..=SHN_HIPROC => {
+                return fmt.pad(&format!("PRC[{:#06x}]", x));
+            }
+            x @ SHN_LOOS..=SHN_HIOS => {
+                return fmt.pad(&format!("OS [{:#06x}]", x));
+            }
+            x @ SHN_LORESERVE..=SHN_HIRESERVE => {
+                return fmt.pad(&format!("RSV[{:#06x}]", x));
+            }
+            x => {
+                return fmt.pad(&format!("{:3}", x));
+            }
+        };
+        fmt.pad(s)
+    }
+}
@@ -0,0 +1,71 @@
+use ::std::fmt::{Display, Formatter};
+
+use super::elf_ei_os_abi::ElfEiOsAbi;
+
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+pub const STT_LOOS: u8 = 10;
+pub const STT_GNU_IFUNC: u8 = 10;
+pub const STT_HIOS: u8 = 12;
+pub const STT_LOPROC: u8 = 13;
+pub const STT_HIPROC: u8 = 15;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfSymType {
+    data: u8,
+}
+
+impl From<u8> for ElfSymType {
+    fn from(data: u8) -> ElfSymType {
+        ElfSymType { data }
+    }
+}
+
+impl ElfSymType {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
+
+    /// `STT_GNU_IFUNC` is only recognized for `ELFOSABI_GNU` and
+    /// `ELFOSABI_FREEBSD`, other files get the generic name of the
+    /// OS-specific range.
+    pub fn get_name(&self, osabi: ElfEiOsAbi) -> String {
+        if self.data == STT_GNU_IFUNC
+            && (osabi == ElfEiOsAbi::ELFOSABI_GNU || osabi == ElfEiOsAbi::ELFOSABI_FREEBSD)
+        {
+            "IFUNC".to_string()
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl Display for ElfSymType {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let s = match self.data {
+            STT_NOTYPE => "NOTYPE",
+            STT_OBJECT => "OBJECT",
+            STT_FUNC => "FUNC",
+            STT_SECTION => "SECTION",
+            STT_FILE => "FILE",
+            STT_COMMON => "COMMON",
+            STT_TLS => "TLS",
+            x @ STT_LOOS..=STT_HIOS => {
+                return fmt.pad(&format!("<OS specific>: {}", x));
+            }
+            x @ STT_LOPROC..=STT_HIPROC => {
+                return fmt.pad(&format!("<processor specific>: {}", x));
+            }
+            x => {
+                return fmt.pad(&format!("<unknown>: {}", x));
+            }
+        };
+        fmt.pad(s)
+    }
+}
@@ -0,0 +1,39 @@
+use ::std::fmt::{Display, Formatter};
+
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElfSymVisibility {
+    data: u8,
+}
+
+impl From<u8> for ElfSymVisibility {
+    fn from(data: u8) -> ElfSymVisibility {
+        ElfSymVisibility { data }
+    }
+}
+
+impl ElfSymVisibility {
+    pub fn get(&self) -> u8 {
+        self.data
+    }
+}
+
+impl Display for ElfSymVisibility {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        let s = match self.data {
+            STV_DEFAULT => "DEFAULT",
+            STV_INTERNAL => "INTERNAL",
+            STV_HIDDEN => "HIDDEN",
+            STV_PROTECTED => "PROTECTED",
+            x => {
+                return fmt.pad(&format!("<unknown>: {}", x));
+            }
+        };
+        fmt.pad(s)
+    }
+}
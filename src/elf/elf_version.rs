@@ -0,0 +1,561 @@
+use ::std::io::{self, Write};
+use ::json::Json;
+use ::to_host::Endianness;
+use ::to_host::to_host_copy::ToHostCopy;
+use super::elf_note::read_word;
+use super::elf_strtab::{get_string, truncate_name};
+
+// Special `.gnu.version` entries and the bits of the others.
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7fff;
+
+// Flags of version definitions and requirements.
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+pub const VER_FLG_INFO: u16 = 0x4;
+
+// Sizes of Elf_Verdef, Elf_Verdaux, Elf_Verneed and Elf_Vernaux.
+// They are the same in 32-bit and 64-bit files.
+const VERDEF_SIZE: usize = 20;
+const VERDAUX_SIZE: usize = 8;
+const VERNEED_SIZE: usize = 16;
+const VERNAUX_SIZE: usize = 16;
+
+/// Reads a 16-bit half word in file byte order from the start of `bytes`.
+pub fn read_half(bytes: &[u8], endianness: &Endianness) -> u16 {
+    let raw = u16::from_ne_bytes([bytes[0], bytes[1]]);
+    raw.to_host_copy(endianness)
+}
+
+/// Reads the entries of a `.gnu.version` section.
+pub fn read_versyms(data: &[u8], endianness: &Endianness) -> Vec<u16> {
+    data.chunks(2)
+        .filter(|c| c.len() == 2)
+        .map(|c| read_half(c, endianness))
+        .collect()
+}
+
+/// Describes version flags the way readelf's `-V` does.
+pub fn get_ver_flags(flags: u16) -> String {
+    if flags == 0 {
+        return String::from("none");
+    }
+    let known = VER_FLG_BASE | VER_FLG_WEAK | VER_FLG_INFO;
+    let mut names = Vec::new();
+    if flags & VER_FLG_BASE != 0 {
+        names.push("BASE");
+    }
+    if flags & VER_FLG_WEAK != 0 {
+        names.push("WEAK");
+    }
+    if flags & VER_FLG_INFO != 0 {
+        names.push("INFO");
+    }
+    if flags & !known != 0 {
+        names.push("<unknown>");
+    }
+    names.join(" | ")
+}
+
+/// The string at `offset` for `--output=json`, `null` if it's out of range.
+fn get_json_name(strtab: &[u8], offset: u32) -> Json {
+    if (offset as usize) < strtab.len() {
+        get_string(strtab, offset as usize).into()
+    } else {
+        Json::Null
+    }
+}
+
+/// Formats a section-relative offset like readelf's `%#06x`.
+fn format_offset(offset: usize) -> String {
+    if offset == 0 {
+        String::from("000000")
+    } else {
+        format!("{:#06x}", offset)
+    }
+}
+
+/// A version definition from `.gnu.version_d` with its auxiliary entries.
+/// The first auxiliary entry names the version itself, the rest name its
+/// parents.
+#[derive(Debug, Clone)]
+pub struct ElfVerdef {
+    offset: usize,
+    vd_version: u16,
+    vd_flags: u16,
+    vd_ndx: u16,
+    vd_cnt: u16,
+    names: Vec<(usize, u32)>,
+}
+
+impl ElfVerdef {
+    pub fn get_flags(&self) -> u16 {
+        self.vd_flags
+    }
+    pub fn get_ndx(&self) -> u16 {
+        self.vd_ndx
+    }
+    /// Returns the string table offset of the version name.
+    pub fn get_name(&self) -> Option<u32> {
+        self.names.first().map(|&(_, name)| name)
+    }
+
+    /// Describes the definition for `--output=json`.
+    pub fn to_json(&self, strtab: &[u8]) -> Json {
+        let name = self.names.first().map_or(Json::Null, |&(_, name)| get_json_name(strtab, name));
+        let parents: Vec<Json> = self.names.iter().skip(1)
+            .map(|&(_, name)| get_json_name(strtab, name))
+            .collect();
+        Json::object(vec![
+            ("offset", self.offset.into()),
+            ("version", self.vd_version.into()),
+            ("flags", Json::named(self.vd_flags, get_ver_flags(self.vd_flags))),
+            ("index", self.vd_ndx.into()),
+            ("count", self.vd_cnt.into()),
+            ("name", name),
+            ("parents", parents.into()),
+        ])
+    }
+
+    /// Prints the definition as readelf's `-V` does.
+    pub fn print(&self, strtab: &[u8], out: &mut dyn Write) -> io::Result<()> {
+        write!(
+            out,
+            "  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  ",
+            format_offset(self.offset), self.vd_version,
+            get_ver_flags(self.vd_flags), self.vd_ndx, self.vd_cnt)?;
+        if let Some(&(_, name)) = self.names.first() {
+            if (name as usize) < strtab.len() {
+                writeln!(out, "Name: {}", get_string(strtab, name as usize))?;
+            } else {
+                writeln!(out, "Name index: {}", name)?;
+            }
+        }
+        for (j, &(offset, name)) in self.names.iter().enumerate().skip(1) {
+            if (name as usize) < strtab.len() {
+                writeln!(
+                    out,
+                    "  {}: Parent {}: {}",
+                    format_offset(offset), j, get_string(strtab, name as usize))?;
+            } else {
+                writeln!(
+                    out,
+                    "  {}: Parent {}, name index: {}", format_offset(offset), j, name)?;
+            }
+        }
+        if self.names.len() < self.vd_cnt as usize {
+            writeln!(out, "  Version def aux past end of section")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads up to `count` version definitions chained from the start of
+/// `data`. Stops early if the chain runs past the end of `data`.
+pub fn read_verdefs(data: &[u8], endianness: &Endianness, count: usize) -> Vec<ElfVerdef> {
+    let e = endianness;
+    let mut verdefs = Vec::new();
+    let mut offset = 0;
+    while verdefs.len() < count && offset + VERDEF_SIZE <= data.len() {
+        let bytes = &data[offset..];
+        let vd_cnt = read_half(&bytes[6..], e);
+        let vd_aux = read_word(&bytes[12..], e) as usize;
+        let vd_next = read_word(&bytes[16..], e) as usize;
+
+        let mut names = Vec::new();
+        let mut aux_offset = offset + vd_aux;
+        while names.len() < vd_cnt as usize && aux_offset + VERDAUX_SIZE <= data.len() {
+            let vda_name = read_word(&data[aux_offset..], e);
+            let vda_next = read_word(&data[aux_offset + 4..], e) as usize;
+            names.push((aux_offset, vda_name));
+            if vda_next == 0 {
+                break;
+            }
+            aux_offset += vda_next;
+        }
+
+        verdefs.push(ElfVerdef {
+            offset,
+            vd_version: read_half(bytes, e),
+            vd_flags: read_half(&bytes[2..], e),
+            vd_ndx: read_half(&bytes[4..], e),
+            vd_cnt,
+            names,
+        });
+        if vd_next == 0 {
+            break;
+        }
+        offset += vd_next;
+    }
+    verdefs
+}
+
+/// An auxiliary entry of a version requirement: one required version.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfVernaux {
+    offset: usize,
+    vna_flags: u16,
+    vna_other: u16,
+    vna_name: u32,
+}
+
+impl ElfVernaux {
+    pub fn get_flags(&self) -> u16 {
+        self.vna_flags
+    }
+    /// Returns the version index `.gnu.version` uses for this version.
+    pub fn get_other(&self) -> u16 {
+        self.vna_other
+    }
+    pub fn get_name(&self) -> u32 {
+        self.vna_name
+    }
+}
+
+/// A version requirement from `.gnu.version_r`: the versions needed from
+/// one shared object.
+#[derive(Debug, Clone)]
+pub struct ElfVerneed {
+    offset: usize,
+    vn_version: u16,
+    vn_cnt: u16,
+    vn_file: u32,
+    aux: Vec<ElfVernaux>,
+}
+
+impl ElfVerneed {
+    /// Returns the offset of the requirement in `.gnu.version_r`.
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+    pub fn get_file(&self) -> u32 {
+        self.vn_file
+    }
+    pub fn get_aux(&self) -> &[ElfVernaux] {
+        &self.aux
+    }
+
+    /// Describes the requirement for `--output=json`.
+    pub fn to_json(&self, strtab: &[u8]) -> Json {
+        let versions: Vec<Json> = self.aux.iter()
+            .map(|aux| Json::object(vec![
+                ("offset", aux.offset.into()),
+                ("name", get_json_name(strtab, aux.vna_name)),
+                ("flags", Json::named(aux.vna_flags, get_ver_flags(aux.vna_flags))),
+                ("index", aux.vna_other.into()),
+            ]))
+            .collect();
+        Json::object(vec![
+            ("offset", self.offset.into()),
+            ("version", self.vn_version.into()),
+            ("file", get_json_name(strtab, self.vn_file)),
+            ("count", self.vn_cnt.into()),
+            ("versions", versions.into()),
+        ])
+    }
+
+    /// Prints the requirement as readelf's `-V` does.
+    pub fn print(&self, strtab: &[u8], out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "  {}: Version: {}", format_offset(self.offset), self.vn_version)?;
+        if (self.vn_file as usize) < strtab.len() {
+            write!(out, "  File: {}", get_string(strtab, self.vn_file as usize))?;
+        } else {
+            write!(out, "  File: {:x}", self.vn_file)?;
+        }
+        writeln!(out, "  Cnt: {}", self.vn_cnt)?;
+
+        for aux in &self.aux {
+            if (aux.vna_name as usize) < strtab.len() {
+                write!(
+                    out,
+                    "  {}:   Name: {}",
+                    format_offset(aux.offset), get_string(strtab, aux.vna_name as usize))?;
+            } else {
+                write!(out, "  {}:   Name index: {:x}", format_offset(aux.offset), aux.vna_name)?;
+            }
+            writeln!(
+                out,
+                "  Flags: {}  Version: {}", get_ver_flags(aux.vna_flags), aux.vna_other)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the chain of required versions ends before the `vn_cnt`
+    /// the requirement announces.
+    pub fn is_missing_aux(&self) -> bool {
+        self.aux.len() < self.vn_cnt as usize
+    }
+}
+
+/// Reads up to `count` version requirements chained from the start of
+/// `data`. Stops early if the chain runs past the end of `data`.
+pub fn read_verneeds(data: &[u8], endianness: &Endianness, count: usize) -> Vec<ElfVerneed> {
+    let e = endianness;
+    let mut verneeds = Vec::new();
+    let mut offset = 0;
+    while verneeds.len() < count && offset + VERNEED_SIZE <= data.len() {
+        let bytes = &data[offset..];
+        let vn_cnt = read_half(&bytes[2..], e);
+        let vn_aux = read_word(&bytes[8..], e) as usize;
+        let vn_next = read_word(&bytes[12..], e) as usize;
+
+        let mut aux = Vec::new();
+        let mut aux_offset = offset + vn_aux;
+        while aux.len() < vn_cnt as usize && aux_offset + VERNAUX_SIZE <= data.len() {
+            let a = &data[aux_offset..];
+            aux.push(ElfVernaux {
+                offset: aux_offset,
+                vna_flags: read_half(&a[4..], e),
+                vna_other: read_half(&a[6..], e),
+                vna_name: read_word(&a[8..], e),
+            });
+            let vna_next = read_word(&a[12..], e) as usize;
+            if vna_next == 0 {
+                break;
+            }
+            aux_offset += vna_next;
+        }
+
+        verneeds.push(ElfVerneed {
+            offset,
+            vn_version: read_half(bytes, e),
+            vn_cnt,
+            vn_file: read_word(&bytes[4..], e),
+            aux,
+        });
+        if vn_next == 0 {
+            break;
+        }
+        offset += vn_next;
+    }
+    verneeds
+}
+
+/// How a symbol's version is attached to its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolVersionKind {
+    /// The default version of a defined symbol, shown as `name@@VERSION`.
+    Public,
+    /// A non-default version of a defined symbol, shown as `name@VERSION`.
+    Hidden,
+    /// A version required from another object, shown as `name@VERSION (n)`
+    /// where `n` is the version index.
+    Undefined(u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolVersion {
+    pub name: String,
+    pub kind: SymbolVersionKind,
+}
+
+impl SymbolVersion {
+    /// Returns the suffix readelf's `-s` appends to the symbol name.
+    pub fn get_symbol_suffix(&self) -> String {
+        match self.kind {
+            SymbolVersionKind::Public => format!("@@{}", self.name),
+            SymbolVersionKind::Hidden => format!("@{}", self.name),
+            SymbolVersionKind::Undefined(index) => format!("@{} ({})", self.name, index),
+        }
+    }
+
+    /// Describes the version for `--output=json`.
+    pub fn to_json(&self) -> Json {
+        let mut json = Json::object(vec![("name", Json::from(self.name.as_str()))]);
+        match self.kind {
+            SymbolVersionKind::Public => json.push("kind", "public"),
+            SymbolVersionKind::Hidden => json.push("kind", "hidden"),
+            SymbolVersionKind::Undefined(index) => {
+                json.push("kind", "undefined");
+                json.push("index", index);
+            }
+        }
+        json
+    }
+
+    /// Returns the suffix readelf's `-r` appends to the symbol name.
+    pub fn get_reloc_suffix(&self) -> String {
+        match self.kind {
+            SymbolVersionKind::Public => format!("@@{}", self.name),
+            _ => format!("@{}", self.name),
+        }
+    }
+}
+
+/// Formats a symbol name for readelf's `-s` table: the name is shortened
+/// so that it fits in 21 columns together with its version suffix.
+pub fn format_versioned_sym_name(name: &str, version: Option<&SymbolVersion>) -> String {
+    let suffix = version.map(|v| v.get_symbol_suffix()).unwrap_or_default();
+    let width = 21 - suffix.chars().count() as isize;
+    // Like readelf, a negative width still truncates, but pads the name.
+    let name = if width < 0 {
+        let width = (-width) as usize;
+        format!("{:<width$}", truncate_name(name, width), width = width)
+    } else if width == 0 {
+        String::new()
+    } else {
+        truncate_name(name, width as usize)
+    };
+    name + &suffix
+}
+
+/// The version tables of a dynamic object: the `.gnu.version` entries of
+/// its dynamic symbols and the definitions and requirements they refer to.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolVersions {
+    versyms: Vec<u16>,
+    verdefs: Vec<ElfVerdef>,
+    verneeds: Vec<ElfVerneed>,
+    strtab: Vec<u8>,
+}
+
+impl SymbolVersions {
+    pub fn new(
+        versyms: Vec<u16>, verdefs: Vec<ElfVerdef>, verneeds: Vec<ElfVerneed>,
+        strtab: Vec<u8>)
+        -> SymbolVersions
+    {
+        SymbolVersions { versyms, verdefs, verneeds, strtab }
+    }
+
+    pub fn get_versyms(&self) -> &[u16] {
+        &self.versyms
+    }
+
+    /// Returns the version of the dynamic symbol at `index`, following
+    /// readelf: defined symbols are looked up among the definitions first,
+    /// then all symbols among the requirements. `st_name` is the symbol's
+    /// name offset; a definition naming the symbol itself isn't shown.
+    pub fn get_symbol_version(
+        &self, index: usize, is_defined: bool, st_name: u32)
+        -> Option<SymbolVersion>
+    {
+        let versym = match self.versyms.get(index) {
+            Some(&versym) if versym != VER_NDX_LOCAL => versym,
+            _ => return None,
+        };
+        let ndx = versym & VERSYM_VERSION;
+        let kind = if versym & VERSYM_HIDDEN != 0 {
+            SymbolVersionKind::Hidden
+        } else {
+            SymbolVersionKind::Public
+        };
+
+        let mut max_def_ndx = 0;
+        if is_defined && versym != VERSYM_HIDDEN | VER_NDX_GLOBAL {
+            for verdef in &self.verdefs {
+                max_def_ndx = ::std::cmp::max(max_def_ndx, verdef.vd_ndx & VERSYM_VERSION);
+                if verdef.vd_ndx != ndx {
+                    continue;
+                }
+                if verdef.vd_ndx == VER_NDX_GLOBAL && verdef.vd_flags == VER_FLG_BASE {
+                    return None;
+                }
+                match verdef.get_name() {
+                    Some(name) if name != st_name => {
+                        let name = get_string(&self.strtab, name as usize);
+                        return Some(SymbolVersion { name, kind });
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        if self.verneeds.is_empty() {
+            return None;
+        }
+        let needed = self.verneeds.iter()
+            .flat_map(|n| n.aux.iter())
+            .find(|a| a.vna_other == versym);
+        if let Some(aux) = needed {
+            return Some(SymbolVersion {
+                name: get_string(&self.strtab, aux.vna_name as usize),
+                kind: SymbolVersionKind::Undefined(aux.vna_other),
+            });
+        }
+        if (max_def_ndx != 0 || ndx != VER_NDX_GLOBAL) && ndx > max_def_ndx {
+            return Some(SymbolVersion { name: String::from("<corrupt>"), kind });
+        }
+        None
+    }
+
+    /// Returns the name of a `.gnu.version` entry as readelf's `-V` shows
+    /// it: `*both*` if it matches both a requirement and a definition.
+    pub fn get_version_name(&self, versym: u16) -> Option<String> {
+        let get_name = |offset: u32| {
+            if (offset as usize) < self.strtab.len() {
+                get_string(&self.strtab, offset as usize)
+            } else {
+                String::from("*invalid*")
+            }
+        };
+
+        let mut name = self.verneeds.iter()
+            .flat_map(|n| n.aux.iter())
+            .find(|a| a.vna_other == versym)
+            .map(|a| get_name(a.vna_name));
+
+        if versym != VERSYM_HIDDEN | VER_NDX_GLOBAL {
+            let verdef = self.verdefs.iter()
+                .find(|d| d.vd_ndx == versym & VERSYM_VERSION);
+            if let Some(def_name) = verdef.and_then(|d| d.get_name()) {
+                let def_name = get_name(def_name);
+                name = match name {
+                    Some(ref n) if n != "*invalid*" && def_name != "*invalid*" => {
+                        Some(String::from("*both*"))
+                    }
+                    _ => Some(def_name),
+                };
+            }
+        }
+        name
+    }
+
+    /// Describes `versyms` for `--output=json`, with the names the table of
+    /// `-V` gives them.
+    pub fn versyms_to_json(&self, versyms: &[u16]) -> Json {
+        let entries: Vec<Json> = versyms.iter()
+            .map(|&versym| {
+                let name = match versym {
+                    VER_NDX_LOCAL => Some(String::from("*local*")),
+                    VER_NDX_GLOBAL => Some(String::from("*global*")),
+                    _ => self.get_version_name(versym),
+                };
+                Json::object(vec![
+                    ("version", (versym & VERSYM_VERSION).into()),
+                    ("hidden", (versym & VERSYM_HIDDEN != 0).into()),
+                    ("name", name.into()),
+                ])
+            })
+            .collect();
+        entries.into()
+    }
+
+    /// Prints `versyms` as the table of readelf's `-V`, four entries a row.
+    pub fn print_versym_table(&self, versyms: &[u16], out: &mut dyn Write) -> io::Result<()> {
+        for (i, row) in versyms.chunks(4).enumerate() {
+            write!(out, "  {:03x}:", i * 4)?;
+            for &versym in row {
+                match versym {
+                    VER_NDX_LOCAL => write!(out, "   0 (*local*)    ")?,
+                    VER_NDX_GLOBAL => write!(out, "   1 (*global*)   ")?,
+                    _ => {
+                        let mut entry = format!(
+                            "{:4x}{}",
+                            versym & VERSYM_VERSION,
+                            if versym & VERSYM_HIDDEN != 0 { 'h' } else { ' ' });
+                        if let Some(name) = self.get_version_name(versym) {
+                            let width = (12 - name.len() as isize).unsigned_abs();
+                            entry += &format!("({}{:<width$}", name, ")", width = width);
+                        }
+                        write!(out, "{:<18}", entry)?;
+                    }
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
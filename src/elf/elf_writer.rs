@@ -0,0 +1,470 @@
+use super::b32::helpers as b32;
+use super::b64::helpers as b64;
+use super::{Elf32_Phdr, Elf64_Phdr, Elf32_Shdr, Elf64_Shdr};
+use super::helpers::get_bytes_at;
+use super::elf_error::ElfError;
+use super::elf_file::ElfFile;
+use super::elf_ehdr::ElfEhdr;
+use super::elf_phdr::ElfPhdr;
+use super::elf_phdr_type::ElfPhdrType;
+use super::elf_phdr_flags::ElfPhdrFlags;
+use super::elf_shdr::ElfShdr;
+use super::elf_shdr_type::{SHT_NOBITS, SHT_STRTAB};
+use super::elf_shdr_flags::SHF_ALLOC;
+use super::elf_sym_shndx::{SHN_LORESERVE, SHN_XINDEX};
+use super::elf_strtab::get_string;
+
+/// How far past the end of the file the writer pads to put something
+/// where the headers say it goes. Going further means they're bogus.
+const MAX_PADDING: u64 = 1 << 30;
+
+/// Edits an ELF file and serialises it again.
+///
+/// The writer works on a copy of the file and only moves what no longer
+/// fits: section contents that grow, and the header tables when they gain
+/// entries, are appended at the end of the file. Everything else, bytes
+/// no header points at included, stays where it was, so writing a file
+/// without editing it gives back the same bytes.
+///
+/// Allocated sections keep their address when they move, and their new
+/// offset is congruent with it modulo the largest `PT_LOAD` alignment, but
+/// making a segment map them is up to the caller, e.g. with
+/// `add_load_segment`. So is the address of a moved program header table:
+/// only the offset and the sizes of `PT_PHDR` are updated.
+pub struct ElfWriter {
+    data: Vec<u8>,
+    ehdr: ElfEhdr,
+    phdrs: Vec<ElfPhdr>,
+    shdrs: Vec<ElfShdr>,
+    /// Where the program header table was read from and its size.
+    phdr_table: (u64, u64),
+    /// Where the section header table was read from and its size.
+    shdr_table: (u64, u64),
+}
+
+impl ElfWriter {
+    /// Starts editing `elf`, whose program and section headers must be
+    /// readable.
+    pub fn new(elf: &ElfFile) -> Result<ElfWriter, ElfError> {
+        let ehdr = *elf.header();
+        let phdrs = elf.program_headers()?.to_vec();
+        let shdrs = elf.sections()?.to_vec();
+        let phdr_size = phdrs.len() as u64 * ehdr.get_phentsize() as u64;
+        let shdr_size = shdrs.len() as u64 * ehdr.get_shentsize() as u64;
+        Ok(ElfWriter {
+            data: elf.data().to_vec(),
+            phdr_table: (ehdr.get_phoff(), phdr_size),
+            shdr_table: (ehdr.get_shoff(), shdr_size),
+            ehdr,
+            phdrs,
+            shdrs,
+        })
+    }
+
+    pub fn header(&self) -> &ElfEhdr {
+        &self.ehdr
+    }
+    pub fn header_mut(&mut self) -> &mut ElfEhdr {
+        &mut self.ehdr
+    }
+    pub fn program_headers(&self) -> &[ElfPhdr] {
+        &self.phdrs
+    }
+    /// Program headers can be added and removed: the table moves to the
+    /// end of the file if it outgrows its place.
+    pub fn program_headers_mut(&mut self) -> &mut Vec<ElfPhdr> {
+        &mut self.phdrs
+    }
+    pub fn sections(&self) -> &[ElfShdr] {
+        &self.shdrs
+    }
+    /// Sections are added with `add_section` and their contents replaced
+    /// with `set_section_data`, which keep `sh_offset` and `sh_size` right.
+    pub fn sections_mut(&mut self) -> &mut [ElfShdr] {
+        &mut self.shdrs
+    }
+
+    fn is_32(&self) -> bool {
+        self.ehdr.is_32()
+    }
+    fn word_size(&self) -> u64 {
+        if self.is_32() { 4 } else { 8 }
+    }
+
+    fn get_section(&self, index: usize) -> Result<&ElfShdr, ElfError> {
+        self.shdrs.get(index).ok_or(ElfError::CannotPatch("no such section"))
+    }
+
+    /// Returns the current contents of section `index`.
+    /// `SHT_NOBITS` sections read as empty.
+    pub fn section_data(&self, index: usize) -> Result<&[u8], ElfError> {
+        let shdr = self.get_section(index)?;
+        if shdr.get_type().get() == SHT_NOBITS {
+            return Ok(&[]);
+        }
+        get_bytes_at(&self.data, shdr.get_offset(), shdr.get_size(), "section data")
+    }
+
+    /// Replaces the contents of section `index`, in place if they fit in
+    /// its old size and at the end of the file otherwise. Returns their
+    /// offset. Only the size of `SHT_NOBITS` sections changes.
+    pub fn set_section_data(&mut self, index: usize, contents: &[u8]) -> Result<u64, ElfError> {
+        let size = contents.len() as u64;
+        let shdr = self.get_section(index)?;
+        if shdr.get_type().get() == SHT_NOBITS {
+            self.shdrs[index].set_size(size);
+            return Ok(self.shdrs[index].get_offset());
+        }
+
+        let (offset, old_size) = (shdr.get_offset(), shdr.get_size());
+        let fits = size <= old_size && offset != 0
+            && offset.checked_add(old_size).is_some_and(|end| end <= self.data.len() as u64);
+        let offset = if fits {
+            let start = offset as usize;
+            self.data[start..start + contents.len()].copy_from_slice(contents);
+            for byte in &mut self.data[start + contents.len()..(offset + old_size) as usize] {
+                *byte = 0;
+            }
+            offset
+        } else {
+            let addr = if shdr.get_flags().contains(SHF_ALLOC) {
+                Some(shdr.get_addr())
+            } else {
+                None
+            };
+            let offset = self.next_offset(shdr.get_addralign(), addr)?;
+            self.put_at(offset, contents)?;
+            offset
+        };
+
+        let shdr = &mut self.shdrs[index];
+        shdr.set_offset(offset);
+        shdr.set_size(size);
+        Ok(offset)
+    }
+
+    /// Appends a section with `contents` and returns its index. The name,
+    /// `sh_offset` and `sh_size` of `shdr` are set when the file is written.
+    pub fn add_section(&mut self, shdr: ElfShdr, contents: &[u8]) -> Result<usize, ElfError> {
+        let mut shdr = shdr;
+        shdr.set_offset(0);
+        shdr.set_size(0);
+        if shdr.get_type().get() == SHT_NOBITS {
+            shdr.set_offset(self.data.len() as u64);
+        }
+        self.shdrs.push(shdr);
+        let index = self.shdrs.len() - 1;
+        self.set_section_data(index, contents)?;
+        Ok(index)
+    }
+
+    /// Appends `contents` at the end of the file, aligned to `align`, and
+    /// returns their offset. It's how room is made for data no section
+    /// points at, e.g. a new segment.
+    pub fn append(&mut self, contents: &[u8], align: u64) -> Result<u64, ElfError> {
+        let offset = self.next_offset(align, None)?;
+        self.put_at(offset, contents)?;
+        Ok(offset)
+    }
+
+    /// Appends a new `PT_LOAD` segment of `size` zero bytes with `flags`,
+    /// mapped past the end of the others, and returns its offset and
+    /// address, for the contents to be filled in with `put_at`.
+    /// The segment is as far from its offset as the first one is, which
+    /// kernels before 5.18 assume when they find the program headers of
+    /// an executable, so the file may be padded a little to get there.
+    pub fn add_load_segment(&mut self, size: u64, flags: ElfPhdrFlags)
+                            -> Result<(u64, u64), ElfError> {
+        let too_high = || ElfError::CannotPatch("segments end past the address space");
+        let page = self.page_size();
+        let loads = || self.phdrs.iter().filter(|p| p.get_type() == ElfPhdrType::PT_LOAD);
+        let mut mem_end = 0;
+        for phdr in loads() {
+            let end = phdr.get_vaddr().checked_add(phdr.get_memsz()).ok_or_else(too_high)?;
+            mem_end = mem_end.max(end);
+        }
+        let mem_end = align_up(mem_end, page).ok_or_else(too_high)?;
+        let bias = loads().next()
+            .and_then(|p| p.get_vaddr().checked_sub(p.get_offset()))
+            .filter(|bias| bias % page == 0);
+
+        let (offset, vaddr) = match bias {
+            Some(bias) => {
+                let file_end = (self.data.len() as u64).checked_add(bias).ok_or_else(too_high)?;
+                let vaddr = mem_end.max(file_end);
+                (vaddr - bias, vaddr)
+            }
+            None => (self.next_offset(page, None)?, mem_end),
+        };
+        let vaddr_end = vaddr.checked_add(size).ok_or_else(too_high)?;
+        if self.is_32() && vaddr_end > u32::MAX as u64 {
+            return Err(ElfError::DoesNotFit { what: "p_vaddr", value: vaddr });
+        }
+        self.pad_to(offset.checked_add(size).ok_or_else(too_high)?)?;
+
+        let mut phdr = ElfPhdr::new(ElfPhdrType::PT_LOAD);
+        phdr.set_flags(flags);
+        phdr.set_offset(offset);
+        phdr.set_vaddr(vaddr);
+        phdr.set_paddr(vaddr);
+        phdr.set_filesz(size);
+        phdr.set_memsz(size);
+        phdr.set_align(page);
+        // Loadable segments are sorted by address.
+        let index = self.phdrs.iter()
+            .rposition(|p| p.get_type() == ElfPhdrType::PT_LOAD)
+            .map_or(self.phdrs.len(), |i| i + 1);
+        self.phdrs.insert(index, phdr);
+        Ok((offset, vaddr))
+    }
+
+    /// Returns the first offset at the end of the file aligned to `align`,
+    /// and congruent with `addr` modulo the page size if given.
+    fn next_offset(&self, align: u64, addr: Option<u64>) -> Result<u64, ElfError> {
+        let too_far = || ElfError::CannotPatch("alignment too large");
+        let mut offset = align_up(self.data.len() as u64, align).ok_or_else(too_far)?;
+        if let Some(addr) = addr {
+            let page = self.page_size();
+            let (addr, rem) = (addr % page, offset % page);
+            let pad = if addr >= rem { addr - rem } else { page - (rem - addr) };
+            offset = offset.checked_add(pad).ok_or_else(too_far)?;
+        }
+        Ok(offset)
+    }
+
+    /// The largest alignment of the loadable segments, which is what the
+    /// offsets and addresses of their contents have to agree modulo.
+    fn page_size(&self) -> u64 {
+        self.phdrs.iter()
+            .filter(|phdr| phdr.get_type() == ElfPhdrType::PT_LOAD)
+            .map(|phdr| phdr.get_align())
+            .max()
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Grows the file with zeros up to `end`, failing rather than pad it
+    /// by more than a gigabyte.
+    fn pad_to(&mut self, end: u64) -> Result<(), ElfError> {
+        let len = self.data.len() as u64;
+        if end > len {
+            if end - len > MAX_PADDING {
+                return Err(ElfError::CannotPatch("offset too far past the end of the file"));
+            }
+            self.data.resize(end as usize, 0);
+        }
+        Ok(())
+    }
+
+    /// Overwrites the bytes at `offset` with `contents`, growing the file
+    /// if they end past it, e.g. to fill in a segment once its address is
+    /// known.
+    pub fn put_at(&mut self, offset: u64, contents: &[u8]) -> Result<(), ElfError> {
+        self.pad_to(offset)?;
+        let start = offset as usize;
+        let end = start + contents.len();
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+        self.data[start..end].copy_from_slice(contents);
+        Ok(())
+    }
+
+    /// The index of the section name string table, or 0 if there's none.
+    fn get_shstrndx(&self) -> usize {
+        let shstrndx = if self.ehdr.get_shstrndx() as u32 == SHN_XINDEX {
+            self.shdrs.first().map_or(0, |s| s.get_link() as usize)
+        } else {
+            self.ehdr.get_shstrndx() as usize
+        };
+        if shstrndx < self.shdrs.len() { shstrndx } else { 0 }
+    }
+
+    /// Makes `sh_name` of every section point at its name, adding the
+    /// names that aren't in the section name string table yet, and the
+    /// table itself if there was none.
+    fn place_names(&mut self) -> Result<(), ElfError> {
+        if self.shdrs.is_empty() {
+            return Ok(());
+        }
+        let mut shstrndx = self.get_shstrndx();
+        let mut table = if shstrndx == 0 {
+            if self.shdrs.iter().skip(1).all(|s| s.get_name() == "<no-strings>") {
+                return Ok(());
+            }
+            let mut shdr = ElfShdr::new(".shstrtab", SHT_STRTAB.into());
+            shdr.set_addralign(1);
+            shstrndx = self.add_section(shdr, &[])?;
+            vec![0]
+        } else {
+            self.section_data(shstrndx)?.to_vec()
+        };
+
+        let mut changed = false;
+        for i in 0..self.shdrs.len() {
+            let name = self.shdrs[i].get_name();
+            let name_offset = self.shdrs[i].get_name_offset() as usize;
+            if name_offset < table.len() && get_string(&table, name_offset) == name {
+                continue;
+            }
+            let mut needle = name.as_bytes().to_vec();
+            needle.push(0);
+            let offset = match table.windows(needle.len()).position(|w| w == &needle[..]) {
+                Some(offset) => offset,
+                None => {
+                    table.extend_from_slice(&needle);
+                    table.len() - needle.len()
+                }
+            };
+            self.shdrs[i].set_name_offset(offset as u32);
+            changed = true;
+        }
+        if changed {
+            self.set_section_data(shstrndx, &table)?;
+        }
+        if shstrndx as u32 >= SHN_LORESERVE {
+            self.ehdr.set_shstrndx(SHN_XINDEX as u16);
+            self.shdrs[0].set_link(shstrndx as u32);
+        } else {
+            self.ehdr.set_shstrndx(shstrndx as u16);
+        }
+        Ok(())
+    }
+
+    /// Puts the program header table back where it was if it still fits
+    /// there or the caller has moved it, at the end of the file otherwise.
+    fn place_phdrs(&mut self) -> Result<(), ElfError> {
+        let entsize = if self.is_32() {
+            ::std::mem::size_of::<Elf32_Phdr>()
+        } else {
+            ::std::mem::size_of::<Elf64_Phdr>()
+        } as u64;
+        if self.phdrs.len() >= 0xffff {
+            return Err(ElfError::DoesNotFit {
+                what: "e_phnum", value: self.phdrs.len() as u64,
+            });
+        }
+        self.ehdr.set_phnum(self.phdrs.len() as u16);
+        if self.phdrs.is_empty() {
+            return Ok(());
+        }
+
+        let size = self.phdrs.len() as u64 * entsize;
+        let (old_offset, old_size) = self.phdr_table;
+        let mut offset = self.ehdr.get_phoff();
+        if offset == old_offset && (offset == 0 || size > old_size) {
+            offset = self.append(&vec![0; size as usize], self.word_size())?;
+            self.ehdr.set_phoff(offset);
+        } else if offset != old_offset {
+            // Make room for the table where the caller moved it.
+            self.put_at(offset, &vec![0; size as usize])?;
+        } else {
+            // Zero what's left of the old table when it shrinks.
+            let start = (offset + size) as usize;
+            let end = ((offset + old_size) as usize).min(self.data.len());
+            for byte in &mut self.data[start.min(end)..end] {
+                *byte = 0;
+            }
+        }
+        self.ehdr.set_phentsize(entsize as u16);
+
+        if (offset, size) != (old_offset, old_size) {
+            for phdr in self.phdrs.iter_mut().filter(|p| p.get_type() == ElfPhdrType::PT_PHDR) {
+                phdr.set_offset(offset);
+                phdr.set_filesz(size);
+                phdr.set_memsz(size);
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts the section header table back where it was if it still fits
+    /// there, at the end of the file otherwise, and sets the counts, with
+    /// extended numbering if there are too many sections for `e_shnum`.
+    fn place_shdrs(&mut self) -> Result<(), ElfError> {
+        let entsize = if self.is_32() {
+            ::std::mem::size_of::<Elf32_Shdr>()
+        } else {
+            ::std::mem::size_of::<Elf64_Shdr>()
+        } as u64;
+        if self.shdrs.is_empty() {
+            return Ok(());
+        }
+
+        let count = self.shdrs.len() as u64;
+        let size = count * entsize;
+        let (old_offset, old_size) = self.shdr_table;
+        if old_offset == 0 || size > old_size {
+            let offset = self.append(&vec![0; size as usize], self.word_size())?;
+            self.ehdr.set_shoff(offset);
+        } else {
+            self.ehdr.set_shoff(old_offset);
+        }
+        self.ehdr.set_shentsize(entsize as u16);
+
+        if count >= SHN_LORESERVE as u64 {
+            self.ehdr.set_shnum(0);
+            self.shdrs[0].set_size(count);
+        } else {
+            self.ehdr.set_shnum(count as u16);
+        }
+        Ok(())
+    }
+
+    /// Fails with `ElfError::DoesNotFit` if a 32-bit file has an address,
+    /// an offset or a size that needs more than 32 bits.
+    fn check_class(&self) -> Result<(), ElfError> {
+        if !self.is_32() {
+            return Ok(());
+        }
+        let check = |what, value: u64| {
+            if value > u32::MAX as u64 {
+                Err(ElfError::DoesNotFit { what, value })
+            } else {
+                Ok(())
+            }
+        };
+
+        check("e_entry", self.ehdr.get_entry())?;
+        check("e_phoff", self.ehdr.get_phoff())?;
+        check("e_shoff", self.ehdr.get_shoff())?;
+        for phdr in &self.phdrs {
+            check("p_offset", phdr.get_offset())?;
+            check("p_vaddr", phdr.get_vaddr())?;
+            check("p_paddr", phdr.get_paddr())?;
+            check("p_filesz", phdr.get_filesz())?;
+            check("p_memsz", phdr.get_memsz())?;
+            check("p_align", phdr.get_align())?;
+        }
+        for shdr in &self.shdrs {
+            check("sh_flags", shdr.get_flags().get())?;
+            check("sh_addr", shdr.get_addr())?;
+            check("sh_offset", shdr.get_offset())?;
+            check("sh_size", shdr.get_size())?;
+            check("sh_addralign", shdr.get_addralign())?;
+            check("sh_entsize", shdr.get_entsize())?;
+        }
+        Ok(())
+    }
+
+    /// Lays out the names and the header tables and returns the file.
+    pub fn write(mut self) -> Result<Vec<u8>, ElfError> {
+        self.place_names()?;
+        self.place_phdrs()?;
+        self.place_shdrs()?;
+        self.check_class()?;
+
+        if self.is_32() {
+            b32::write_headers(&self.ehdr, &self.phdrs, &self.shdrs, &mut self.data);
+        } else {
+            b64::write_headers(&self.ehdr, &self.phdrs, &self.shdrs, &mut self.data);
+        }
+        Ok(self.data)
+    }
+}
+
+/// Rounds `value` up to a multiple of `align`, unless that overflows.
+fn align_up(value: u64, align: u64) -> Option<u64> {
+    let align = align.max(1);
+    value.div_ceil(align).checked_mul(align)
+}
@@ -1,93 +1,449 @@
-#[macro_export]
+use ::elf::elf_error::ElfError;
+use ::elf::elf_ident::ElfIdent;
+
 macro_rules! read_ehdr {
-    ($t:ty) => {
+    ($ehdr:ty) => {
+        use ::to_host::ToHostCopyStruct;
+        use super::super::elf_ehdr::ElfEhdr;
+        use super::super::elf_error::ElfError;
+        use super::super::helpers::read_struct_at;
+
+        /// Reads the ELF header, whose `e_ident` must already have been
+        /// checked with `read_ident`.
+        pub fn read_ehdr(
+            data: &[u8])
+            -> Result<ElfEhdr, ElfError>
+        {
+            let ehdr = match unsafe { read_struct_at::<$ehdr>(data, 0, "ELF header") } {
+                Ok(ehdr) => ehdr,
+                Err(ElfError::OutOfRange { .. }) => return Err(ElfError::TruncatedHeader),
+                Err(err) => return Err(err),
+            };
+            Ok(ElfEhdr::from((&ehdr, ehdr.get_endianness())))
+        }
+    }
+}
+
+macro_rules! read_phdrs {
+    ($phdr:ty) => {
+        use super::super::elf_phdr::ElfPhdr;
+        use super::super::helpers::check_range;
+
+        /// Reads the program header table.
         #[allow(dead_code)]
-        fn convert_byte_vec_to_ehdr_box(
-            mut v: Vec<u8>)
-            -> Result<Box<$t>, ()>
+        pub fn read_phdrs(
+            ehdr: &ElfEhdr, data: &[u8])
+            -> Result<Vec<ElfPhdr>, ElfError>
         {
-            let ehdr_size = ::std::mem::size_of::<$t>();
+            let e = ehdr.get_endianness();
+            let phdr_offset = ehdr.get_phoff();
+            let phdr_size = ehdr.get_phentsize() as u64;
+            let phdr_num = ehdr.get_phnum() as u64;
 
-            assert_eq!(ehdr_size as usize, v.len());
-            
-            try!(validate_elf_magic(&v[..EI_MAGIC_SIZE]));
+            if phdr_num == 0 {
+                return Ok(Vec::new());
+            }
+            if phdr_size < ::std::mem::size_of::<$phdr>() as u64 {
+                return Err(ElfError::BadEntsize {
+                    what: "program headers",
+                    entsize: phdr_size,
+                });
+            }
+            check_range(data, phdr_offset, phdr_num * phdr_size, "program headers")?;
+
+            let mut phdrs = Vec::with_capacity(phdr_num as usize);
+            for i in 0..phdr_num {
+                let offset = phdr_offset + i * phdr_size;
+                let phdr = unsafe {
+                    read_struct_at::<$phdr>(data, offset, "program headers")?
+                };
+                phdrs.push(ElfPhdr::from((&phdr, e)));
+            }
+            Ok(phdrs)
+        }
+    }
+}
 
-            let bytes_ptr: *mut u8 = v.as_mut_ptr();
-            ::std::mem::forget(v);
+macro_rules! read_shdrs {
+    ($shdr:ty) => {
+        use super::super::elf_shdr::ElfShdr;
+
+        /// Reads the section header table. The section names are left
+        /// empty.
+        pub fn read_shdrs(
+            ehdr: &ElfEhdr, data: &[u8])
+            -> Result<Vec<ElfShdr>, ElfError>
+        {
+            let e = ehdr.get_endianness();
+            let shdr_offset = ehdr.get_shoff();
+            let shdr_size = ehdr.get_shentsize() as u64;
+
+            if shdr_offset == 0 {
+                return Ok(Vec::new());
+            }
+            if shdr_size < ::std::mem::size_of::<$shdr>() as u64 {
+                return Err(ElfError::BadEntsize {
+                    what: "section headers",
+                    entsize: shdr_size,
+                });
+            }
 
-            let ehdr_ptr: *mut $t = unsafe {
-                ::std::mem::transmute(bytes_ptr)
+            // With extended numbering, the real count lives in sh_size of
+            // the first entry.
+            let first = unsafe {
+                read_struct_at::<$shdr>(data, shdr_offset, "section headers")?
             };
-            let ehdr_box: Box<$t> = unsafe {
-                Box::from_raw(ehdr_ptr)
+            let first = first.to_host_copy(&e);
+            let shdr_num = if ehdr.get_shnum() == 0 {
+                first.get_size() as u64
+            } else {
+                ehdr.get_shnum() as u64
             };
-            Ok(ehdr_box)
+            check_range(
+                data, shdr_offset, shdr_num.saturating_mul(shdr_size),
+                "section headers")?;
+
+            let mut shdrs = Vec::with_capacity(shdr_num as usize);
+            for i in 0..shdr_num {
+                let offset = shdr_offset + i * shdr_size;
+                let shdr = unsafe {
+                    read_struct_at::<$shdr>(data, offset, "section headers")?
+                };
+                shdrs.push(ElfShdr::from(&shdr.to_host_copy(&e)));
+            }
+            Ok(shdrs)
         }
+    }
+}
+
+macro_rules! read_syms {
+    ($sym:ty) => {
+        use super::super::elf_sym::ElfSym;
 
-        fn validate_elf_magic(magic: &[u8]) -> Result<(), ()> 
+        /// Reads the entries of a `SHT_SYMTAB` or `SHT_DYNSYM` section.
+        /// The symbol names are left empty.
+        pub fn read_syms(
+            ehdr: &ElfEhdr, shdr: &ElfShdr, data: &[u8])
+            -> Result<Vec<ElfSym>, ElfError>
         {
-            let proper_magic = &[0x7f, b'E', b'L', b'F'];
-            assert_eq!(magic.len(), proper_magic.len());
+            let e = ehdr.get_endianness();
+            let sym_size = match shdr.get_entsize() {
+                0 => ::std::mem::size_of::<$sym>() as u64,
+                entsize if entsize < ::std::mem::size_of::<$sym>() as u64 => {
+                    return Err(ElfError::BadEntsize {
+                        what: "symbol table",
+                        entsize,
+                    });
+                }
+                entsize => entsize,
+            };
+            let sym_num = shdr.get_size() / sym_size;
+            check_range(data, shdr.get_offset(), sym_num * sym_size, "symbol table")?;
 
-            if proper_magic != magic {
-                return Err(())
+            let mut syms = Vec::with_capacity(sym_num as usize);
+            for i in 0..sym_num {
+                let offset = shdr.get_offset() + i * sym_size;
+                let sym = unsafe { read_struct_at::<$sym>(data, offset, "symbol table")? };
+                syms.push(ElfSym::from(&sym.to_host_copy(&e)));
             }
-            Ok(())
+            Ok(syms)
         }
 
-        #[allow(dead_code)]
-        pub fn read_ehdr<R: Read + Seek>(
-            reader: &mut R)
-            -> Box<$t>
+        /// Reads the symbol at `index` of the table at `offset`, with
+        /// entries of `entsize` bytes or the size of the structure if it's
+        /// unknown. Returns `None` if the symbol lies past the end of the
+        /// file.
+        pub fn read_sym_at(
+            ehdr: &ElfEhdr, offset: u64, entsize: Option<u64>, index: u32,
+            data: &[u8])
+            -> Result<Option<ElfSym>, ElfError>
         {
-            use std::io::SeekFrom;
+            let sym_size = entsize.unwrap_or(::std::mem::size_of::<$sym>() as u64);
+            let offset = match (index as u64).checked_mul(sym_size)
+                .and_then(|o| o.checked_add(offset))
+            {
+                Some(offset) => offset,
+                None => return Ok(None),
+            };
+
+            match unsafe { read_struct_at::<$sym>(data, offset, "dynamic symbol") } {
+                Ok(sym) => Ok(Some(ElfSym::from(&sym.to_host_copy(&ehdr.get_endianness())))),
+                Err(ElfError::OutOfRange { .. }) => Ok(None),
+                Err(err) => Err(err),
+            }
+        }
+    }
+}
 
-            let ehdr_size = ::std::mem::size_of::<$t>();
-            let ehdr_offset = 0;
+macro_rules! read_dyns {
+    ($dynamic:ty) => {
+        use super::super::elf_dyn::ElfDyn;
+        use super::super::elf_dyn_tag::DT_NULL;
 
-            let mut b = Vec::<u8>::with_capacity(ehdr_size as usize);
-            reader.seek(SeekFrom::Start(ehdr_offset)).unwrap();
-            reader.take(ehdr_size as u64).read_to_end(&mut b).unwrap();
+        /// Reads the entries of the dynamic section of `size` bytes at
+        /// `offset`, up to and including `DT_NULL`.
+        pub fn read_dyns(
+            ehdr: &ElfEhdr, offset: u64, size: u64, data: &[u8])
+            -> Result<Vec<ElfDyn>, ElfError>
+        {
+            let e = ehdr.get_endianness();
+            let dyn_size = ::std::mem::size_of::<$dynamic>() as u64;
+            let dyn_num = size / dyn_size;
+            check_range(data, offset, dyn_num * dyn_size, "dynamic section")?;
 
-            convert_byte_vec_to_ehdr_box(b).unwrap()
+            let mut dyns = Vec::new();
+            for i in 0..dyn_num {
+                let d = unsafe {
+                    read_struct_at::<$dynamic>(
+                        data, offset + i * dyn_size, "dynamic section")?
+                };
+                let d = ElfDyn::from(&d.to_host_copy(&e));
+                dyns.push(d);
+                if d.get_tag().get() == DT_NULL {
+                    break;
+                }
+            }
+            Ok(dyns)
         }
+    }
+}
 
+macro_rules! read_rels {
+    ($rel:ty, $rela:ty, $relr:ty) => {
+        use super::super::elf_rel::ElfRela;
+        use super::super::elf_ehdr_machine::ElfEhdrMachine;
+
+        /// Reads a table of `Elf_Rel` or `Elf_Rela` entries of `size` bytes
+        /// at `offset`. REL entries are returned with a zero addend.
+        pub fn read_rels(
+            ehdr: &ElfEhdr, offset: u64, size: u64, is_rela: bool, data: &[u8])
+            -> Result<Vec<ElfRela>, ElfError>
+        {
+            let e = ehdr.get_endianness();
+            let rel_size = if is_rela {
+                ::std::mem::size_of::<$rela>() as u64
+            } else {
+                ::std::mem::size_of::<$rel>() as u64
+            };
+            let mips64el = ::std::mem::size_of::<$rela>() == 24
+                && ehdr.get_machine() == ElfEhdrMachine::EM_MIPS
+                && e == ::to_host::Endianness::LE;
+            check_range(data, offset, size / rel_size * rel_size, "relocations")?;
+
+            let mut rels = Vec::with_capacity((size / rel_size) as usize);
+            for i in 0..size / rel_size {
+                let offset = offset + i * rel_size;
+                let rela = if is_rela {
+                    let rela = unsafe {
+                        read_struct_at::<$rela>(data, offset, "relocations")?
+                    };
+                    rela.to_host_copy(&e)
+                } else {
+                    let rel = unsafe {
+                        read_struct_at::<$rel>(data, offset, "relocations")?
+                    };
+                    <$rela>::from(rel.to_host_copy(&e))
+                };
+                let rela = ElfRela::from(&rela);
+                if mips64el {
+                    rels.push(rela.with_mips64el_info());
+                } else {
+                    rels.push(rela);
+                }
+            }
+            Ok(rels)
+        }
+
+        /// Reads a `SHT_RELR` table of `size` bytes at `offset` and expands
+        /// it into the list of addresses it relocates.
+        pub fn read_relrs(
+            ehdr: &ElfEhdr, offset: u64, size: u64, data: &[u8])
+            -> Result<Vec<u64>, ElfError>
+        {
+            use ::to_host::to_host_copy::ToHostCopy;
+
+            let e = ehdr.get_endianness();
+            let word_size = ::std::mem::size_of::<$relr>() as u64;
+            let word_bits = word_size * 8;
+            check_range(data, offset, size / word_size * word_size, "relocations")?;
+
+            let mut addrs = Vec::new();
+            let mut base: u64 = 0;
+            for i in 0..size / word_size {
+                let entry = unsafe {
+                    read_struct_at::<$relr>(data, offset + i * word_size, "relocations")?
+                };
+                let entry = entry.to_host_copy(&e) as u64;
+                if entry & 1 == 0 {
+                    addrs.push(entry);
+                    base = entry.wrapping_add(word_size);
+                } else {
+                    for bit in 1..word_bits {
+                        if (entry >> bit) & 1 != 0 {
+                            addrs.push(base.wrapping_add((bit - 1) * word_size));
+                        }
+                    }
+                    base = base.wrapping_add((word_bits - 1) * word_size);
+                }
+            }
+            Ok(addrs)
+        }
     }
 }
 
-#[macro_export]
-macro_rules! read_phdrs {
-    ($phdr:ty, $ehdr:ty) => {
-        #[allow(dead_code)]
-        fn convert_byte_vec_to_phdrs_vec(
-            v: Vec<u8>, phdr_num: u16, phdr_size: u16) 
-            -> Vec<$phdr> {
-            assert_eq!(phdr_num as usize * phdr_size as usize, v.len());
-            let mut r: Vec<$phdr> = unsafe {
-                ::std::mem::transmute(v)
+macro_rules! read_chdr {
+    ($chdr:ty) => {
+        use super::super::elf_chdr::ElfChdr;
+
+        /// Reads the compression header at the start of the contents of a
+        /// `SHF_COMPRESSED` section. Returns it with its size, after which
+        /// the compressed data starts.
+        pub fn read_chdr(
+            ehdr: &ElfEhdr, data: &[u8])
+            -> Result<(ElfChdr, u64), ElfError>
+        {
+            let chdr = unsafe {
+                read_struct_at::<$chdr>(data, 0, "compression header")?
             };
+            let chdr = ElfChdr::from(&chdr.to_host_copy(&ehdr.get_endianness()));
+            Ok((chdr, ::std::mem::size_of::<$chdr>() as u64))
+        }
+    }
+}
+
+macro_rules! write_headers {
+    ($ehdr:ty, $phdr:ty, $shdr:ty) => {
+        use ::to_host::ToTargetCopyStruct;
+        use super::super::helpers::write_struct_at;
+
+        /// Writes the ELF header, then the program header table at
+        /// `e_phoff` and the section header table at `e_shoff`, in the
+        /// byte order of the file. Their entry sizes must be those of the
+        /// raw structures.
+        pub fn write_headers(
+            ehdr: &ElfEhdr, phdrs: &[ElfPhdr], shdrs: &[ElfShdr], data: &mut Vec<u8>)
+        {
+            let e = ehdr.get_endianness();
+            let phdr_size = ::std::mem::size_of::<$phdr>() as u64;
+            let shdr_size = ::std::mem::size_of::<$shdr>() as u64;
+
             unsafe {
-                r.set_len(phdr_num as usize);
+                write_struct_at(data, 0, &<$ehdr>::from((ehdr, e)));
+                for (i, phdr) in phdrs.iter().enumerate() {
+                    let offset = ehdr.get_phoff() + i as u64 * phdr_size;
+                    write_struct_at(data, offset, &<$phdr>::from((phdr, e)));
+                }
+                for (i, shdr) in shdrs.iter().enumerate() {
+                    let offset = ehdr.get_shoff() + i as u64 * shdr_size;
+                    write_struct_at(data, offset, &<$shdr>::from(shdr).to_target_copy(&e));
+                }
             }
-            r
         }
+    }
+}
 
-        #[allow(dead_code)]
-        pub fn read_phdrs<R: Read + Seek>(
-            ehdr: &$ehdr, reader: &mut R)
-            -> Vec<$phdr>
-        {
-            use std::io::SeekFrom;
+macro_rules! write_dyns {
+    ($dynamic:ty) => {
+        /// Returns the dynamic entries as laid out in the file.
+        pub fn write_dyns(ehdr: &ElfEhdr, dyns: &[ElfDyn]) -> Vec<u8> {
+            let e = ehdr.get_endianness();
+            let dyn_size = ::std::mem::size_of::<$dynamic>() as u64;
 
-            let phdr_size = ehdr.get_phentsize() * ehdr.get_phnum();
-            let phdr_offset = ehdr.get_phoff();
-            let phdr_num = ehdr.get_phnum();
+            let mut data = Vec::with_capacity(dyns.len() * dyn_size as usize);
+            for (i, d) in dyns.iter().enumerate() {
+                let d = <$dynamic>::from(d).to_target_copy(&e);
+                unsafe { write_struct_at(&mut data, i as u64 * dyn_size, &d) };
+            }
+            data
+        }
+    }
+}
+
+/// Reads `e_ident` and checks the parts of it the rest of the header
+/// depends on: the magic bytes, the class and the data encoding.
+pub fn read_ident(data: &[u8]) -> Result<ElfIdent, ElfError> {
+    use super::elf_ident_named::{EI_MAGIC_SIZE, EI_CLASS, EI_DATA};
 
-            let mut b = Vec::<u8>::with_capacity(phdr_size as usize * phdr_num as usize);
-            reader.seek(SeekFrom::Start(phdr_offset as u64)).unwrap();
-            reader.take(phdr_size as u64 * phdr_num as u64).read_to_end(&mut b).unwrap();
+    let ident = match unsafe { read_struct_at::<ElfIdent>(data, 0, "ELF header") } {
+        Ok(ident) => ident,
+        Err(ElfError::OutOfRange { .. }) => return Err(ElfError::TruncatedHeader),
+        Err(err) => return Err(err),
+    };
+    let bytes = ident.get_bytes();
+
+    let proper_magic = &[0x7f, b'E', b'L', b'F'];
+    if &bytes[..EI_MAGIC_SIZE] != proper_magic {
+        return Err(ElfError::BadMagic);
+    }
+    match bytes[EI_CLASS] {
+        1 | 2 => {}
+        class => return Err(ElfError::UnsupportedClass(class)),
+    }
+    match bytes[EI_DATA] {
+        1 | 2 => Ok(ident),
+        data => Err(ElfError::UnsupportedData(data)),
+    }
+}
 
-            convert_byte_vec_to_phdrs_vec(b, phdr_num, phdr_size)
+/// Returns the `size` bytes at `offset`, e.g. the contents of a segment,
+/// failing with `ElfError::OutOfRange` unless they lie within `data`.
+/// `what` names the data in the error.
+pub fn get_bytes_at<'a>(
+    data: &'a [u8], offset: u64, size: u64, what: &'static str)
+    -> Result<&'a [u8], ElfError>
+{
+    match offset.checked_add(size) {
+        Some(end) if end <= data.len() as u64 => {
+            Ok(&data[offset as usize..end as usize])
         }
+        _ => Err(ElfError::OutOfRange { what, offset, size }),
     }
-}
\ No newline at end of file
+}
+
+/// Fails with `ElfError::OutOfRange` unless `size` bytes at `offset` lie
+/// within the file. `what` names the data in the error.
+pub fn check_range(
+    data: &[u8], offset: u64, size: u64, what: &'static str)
+    -> Result<(), ElfError>
+{
+    get_bytes_at(data, offset, size, what).map(|_| ())
+}
+
+/// Reads a plain-old-data structure as laid out in the file. The bytes
+/// don't need to be aligned for `T`. `what` names the structure in errors.
+///
+/// Only safe to call for `#[repr(C)]` structs consisting of integer fields,
+/// for which any bit pattern is valid.
+pub unsafe fn read_struct_at<T>(
+    data: &[u8], offset: u64, what: &'static str)
+    -> Result<T, ElfError>
+{
+    let size = ::std::mem::size_of::<T>() as u64;
+    let bytes = get_bytes_at(data, offset, size, what)?;
+    Ok(::std::ptr::read_unaligned(bytes.as_ptr() as *const T))
+}
+
+/// Writes a plain-old-data structure at `offset` as it's laid out in
+/// memory, growing `data` with zeros if it ends before the structure does.
+///
+/// Only safe to call for `#[repr(C)]` structs without padding, already in
+/// the byte order of the file.
+pub unsafe fn write_struct_at<T>(data: &mut Vec<u8>, offset: u64, value: &T) {
+    let size = ::std::mem::size_of::<T>();
+    let offset = offset as usize;
+    if data.len() < offset + size {
+        data.resize(offset + size, 0);
+    }
+    let bytes = ::std::slice::from_raw_parts(value as *const T as *const u8, size);
+    data[offset..offset + size].copy_from_slice(bytes);
+}
+
+/// Formats like C's `%#x`, which prints zero without the `0x` prefix.
+pub fn format_c_hex<T: Into<u64>>(x: T) -> String {
+    let x = x.into();
+    if x == 0 {
+        "0".to_string()
+    } else {
+        format!("{:#x}", x)
+    }
+}
@@ -1,3 +1,11 @@
+//! ELF structures and the readers and the writer for them.
+//!
+//! [`ElfFile`] is the entry point: it detects the class and the data
+//! encoding of a file and reads everything else on demand. The `Elf*`
+//! newtypes, e.g. [`elf_ehdr_machine::ElfEhdrMachine`], wrap raw values of
+//! header fields and know how readelf shows them. [`ElfWriter`] edits a
+//! parsed file and serialises it again.
+
 pub mod elf_ident;
 pub mod elf_ei_class;
 pub mod elf_ei_data;
@@ -7,52 +15,84 @@ pub mod elf_ei_abi_version;
 pub mod elf_ident_named;
 pub mod elf_ehdr_type;
 pub mod elf_ehdr_machine;
+pub mod elf_ehdr_flags;
 pub mod elf_phdr_type;
 pub mod elf_phdr_flags;
+pub mod elf_shdr_type;
+pub mod elf_shdr_flags;
+pub mod elf_strtab;
+pub mod elf_sym_bind;
+pub mod elf_sym_type;
+pub mod elf_sym_visibility;
+pub mod elf_sym_shndx;
+pub mod elf_dyn_tag;
+pub mod elf_dyn_flags;
+pub mod elf_dyn_flags_1;
+pub mod elf_rel_type;
+pub mod elf_note;
+pub mod elf_auxv;
+pub mod elf_core;
+pub mod elf_gnu_property;
+pub mod elf_version;
+pub mod elf_section_dump;
+pub mod elf_error;
 
 #[macro_use]
-mod elf_ehdr;
+pub mod elf_ehdr;
+#[macro_use]
+pub mod elf_phdr;
+#[macro_use]
+pub mod elf_shdr;
+#[macro_use]
+pub mod elf_sym;
 #[macro_use]
-mod elf_phdr;
+pub mod elf_dyn;
 #[macro_use]
-mod helpers;
+pub mod elf_rel;
+#[macro_use]
+pub mod elf_chdr;
+#[macro_use]
+pub(crate) mod helpers;
 
 mod b32;
 mod b64;
 
+pub mod elf_file;
+pub use self::elf_file::ElfFile;
+pub mod elf_writer;
+pub use self::elf_writer::ElfWriter;
+pub mod elf_patch;
+
 pub use ::elf::b32::elf_ehdr::Elf_Ehdr as Elf32_Ehdr;
 pub use ::elf::b32::elf_phdr::Elf_Phdr as Elf32_Phdr;
+pub use ::elf::b32::elf_shdr::Elf_Shdr as Elf32_Shdr;
+pub use ::elf::b32::elf_sym::Elf_Sym as Elf32_Sym;
+pub use ::elf::b32::elf_dyn::Elf_Dyn as Elf32_Dyn;
+pub use ::elf::b32::elf_rel::Elf_Rel as Elf32_Rel;
+pub use ::elf::b32::elf_rel::Elf_Rela as Elf32_Rela;
+pub use ::elf::b32::elf_chdr::Elf_Chdr as Elf32_Chdr;
 
 pub use ::elf::b64::elf_ehdr::Elf_Ehdr as Elf64_Ehdr;
 pub use ::elf::b64::elf_phdr::Elf_Phdr as Elf64_Phdr;
+pub use ::elf::b64::elf_shdr::Elf_Shdr as Elf64_Shdr;
+pub use ::elf::b64::elf_sym::Elf_Sym as Elf64_Sym;
+pub use ::elf::b64::elf_dyn::Elf_Dyn as Elf64_Dyn;
+pub use ::elf::b64::elf_rel::Elf_Rel as Elf64_Rel;
+pub use ::elf::b64::elf_rel::Elf_Rela as Elf64_Rela;
+pub use ::elf::b64::elf_chdr::Elf_Chdr as Elf64_Chdr;
 
 pub mod prelude;
 
-pub use ::elf::b32::helpers::read_ehdr as read_elf32_ehdr;
-pub use ::elf::b64::helpers::read_ehdr as read_elf64_ehdr;
-
-pub use ::elf::b32::helpers::read_phdrs as read_elf32_phdrs;
-pub use ::elf::b64::helpers::read_phdrs as read_elf64_phdrs;
-
-fn _static_asserts() {
-    let ei_bytes: elf_ident::ElfIdent = unsafe {
-        ::std::mem::uninitialized()
-    };
-    let _ei_named: elf_ident_named::ElfIdentNamed = unsafe {
-        ::std::mem::transmute(ei_bytes)
-    };
-
-    let ehdr_type_bytes: b64::primitive::Elf64_Half = unsafe {
-        ::std::mem::uninitialized()
-    };
-    let _ehdr_type: elf_ehdr_type::ElfEhdrType = unsafe {
-        ::std::mem::transmute(ehdr_type_bytes)
-    };
-
-    let ehdr_machine_bytes: b64::primitive::Elf64_Half = unsafe {
-        ::std::mem::uninitialized()
-    };
-    let _ehdr_machine: elf_ehdr_machine::ElfEhdrMachine = unsafe {
-        ::std::mem::transmute(ehdr_machine_bytes)
-    };
-}
+
+// The raw headers are read straight from the file, so the wrapper and
+// storage types must keep the size of the fields they stand for.
+const _: () = assert!(
+    ::std::mem::size_of::<elf_ident_named::ElfIdentNamed>() == elf_ident_named::EI_NIDENT);
+const _: () = assert!(::std::mem::size_of::<Elf32_Ehdr>() == 52);
+const _: () = assert!(::std::mem::size_of::<Elf64_Ehdr>() == 64);
+const _: () = assert!(::std::mem::size_of::<Elf32_Phdr>() == 32);
+const _: () = assert!(::std::mem::size_of::<Elf64_Phdr>() == 56);
+const _: () = assert!(::std::mem::size_of::<Elf32_Shdr>() == 40);
+const _: () = assert!(::std::mem::size_of::<Elf64_Shdr>() == 64);
+const _: () = assert!(::std::mem::size_of::<Elf32_Chdr>() == 12);
+const _: () = assert!(::std::mem::size_of::<Elf64_Chdr>() == 24);
@@ -1,3 +1,5 @@
+#![allow(unused_imports)]
+
 pub use super::elf_ident::ElfIdent;
 pub use super::b64::primitive::*;
 pub use super::elf_ei_class::ElfEiClass;
@@ -8,9 +10,36 @@ pub use super::elf_ei_abi_version::ElfEiAbiVersion;
 pub use super::elf_ident_named::ElfIdentNamed;
 pub use super::elf_ehdr_type::ElfEhdrType;
 pub use super::elf_ehdr_machine::ElfEhdrMachine;
+pub use super::elf_ehdr_flags::{ElfEhdrFlags, ElfFloatAbi};
 pub use super::{Elf32_Ehdr, Elf64_Ehdr};
+pub use super::elf_ehdr::ElfEhdr;
 pub use super::elf_phdr_type::ElfPhdrType;
 pub use super::elf_phdr_flags::ElfPhdrFlags;
 pub use super::{Elf32_Phdr, Elf64_Phdr};
-pub use super::{read_elf32_ehdr, read_elf64_ehdr};
-pub use super::{read_elf32_phdrs, read_elf64_phdrs};
+pub use super::elf_phdr::ElfPhdr;
+pub use super::elf_shdr_type::ElfShdrType;
+pub use super::elf_shdr_flags::ElfShdrFlags;
+pub use super::{Elf32_Shdr, Elf64_Shdr};
+pub use super::elf_shdr::ElfShdr;
+pub use super::elf_sym_bind::ElfSymBind;
+pub use super::elf_sym_type::ElfSymType;
+pub use super::elf_sym_visibility::ElfSymVisibility;
+pub use super::elf_sym_shndx::ElfSymShndx;
+pub use super::{Elf32_Sym, Elf64_Sym};
+pub use super::elf_sym::ElfSym;
+pub use super::elf_dyn_tag::ElfDynTag;
+pub use super::elf_dyn_flags::ElfDynFlags;
+pub use super::elf_dyn_flags_1::ElfDynFlags1;
+pub use super::{Elf32_Dyn, Elf64_Dyn};
+pub use super::elf_dyn::{ElfDyn, get_dyn_val};
+pub use super::{Elf32_Rel, Elf64_Rel};
+pub use super::{Elf32_Rela, Elf64_Rela};
+pub use super::elf_rel::ElfRela;
+pub use super::{Elf32_Chdr, Elf64_Chdr};
+pub use super::elf_chdr::ElfChdr;
+pub use super::elf_strtab::get_string;
+pub use super::elf_note::{ElfNote, ElfNoteIter};
+pub use super::elf_version::{SymbolVersion, SymbolVersions};
+pub use super::elf_error::ElfError;
+pub use super::elf_file::ElfFile;
+pub use super::elf_writer::ElfWriter;
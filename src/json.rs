@@ -0,0 +1,148 @@
+//! A minimal JSON value, used for `--output=json`.
+//!
+//! Objects keep their keys in insertion order, so the output of a dump
+//! is always laid out the same way. Integers are kept as integers, even
+//! the ones that don't fit in a double; readers that parse numbers as
+//! doubles may lose precision above 2^53.
+
+use ::std::fmt::{Display, Formatter, Write};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i128),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Builds an object from `(key, value)` pairs, keeping their order.
+    pub fn object<K: Into<String>>(fields: Vec<(K, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Describes an enumerated field: `{"value": value, "name": name}`.
+    pub fn named<V: Into<Json>, N: Into<Json>>(value: V, name: N) -> Json {
+        Json::object(vec![("value", value.into()), ("name", name.into())])
+    }
+
+    /// Appends a field to an object. Does nothing to other values.
+    pub fn push<K: Into<String>, V: Into<Json>>(&mut self, key: K, value: V) {
+        if let Json::Object(ref mut fields) = *self {
+            fields.push((key.into(), value.into()));
+        }
+    }
+
+    /// Returns the value of field `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match *self {
+            Json::Object(ref fields) => fields.iter()
+                .find(|&(k, _)| k == key)
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn write_indented<W: Write>(&self, out: &mut W, indent: usize) -> ::std::fmt::Result {
+        match *self {
+            Json::Null => out.write_str("null"),
+            Json::Bool(b) => write!(out, "{}", b),
+            Json::Int(i) => write!(out, "{}", i),
+            Json::String(ref s) => write_string(out, s),
+            Json::Array(ref items) if items.is_empty() => out.write_str("[]"),
+            Json::Array(ref items) => {
+                out.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(out, "{:1$}", "", indent + 2)?;
+                    item.write_indented(out, indent + 2)?;
+                    out.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write!(out, "{:1$}]", "", indent)
+            }
+            Json::Object(ref fields) if fields.is_empty() => out.write_str("{}"),
+            Json::Object(ref fields) => {
+                out.write_str("{\n")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write!(out, "{:1$}", "", indent + 2)?;
+                    write_string(out, key)?;
+                    out.write_str(": ")?;
+                    value.write_indented(out, indent + 2)?;
+                    out.write_str(if i + 1 < fields.len() { ",\n" } else { "\n" })?;
+                }
+                write!(out, "{:1$}}}", "", indent)
+            }
+        }
+    }
+}
+
+/// Pretty-prints the value with two-space indentation.
+impl Display for Json {
+    fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+        self.write_indented(fmt, 0)
+    }
+}
+
+fn write_string<W: Write>(out: &mut W, s: &str) -> ::std::fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+macro_rules! json_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(i: $t) -> Json {
+                    Json::Int(i as i128)
+                }
+            }
+        )*
+    }
+}
+
+json_from_int!(u8, u16, u32, u64, usize, i64);
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl<'a> From<&'a str> for Json {
+    fn from(s: &'a str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(o: Option<T>) -> Json {
+        match o {
+            Some(v) => v.into(),
+            None => Json::Null,
+        }
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Json {
+        Json::Array(v.into_iter().map(Into::into).collect())
+    }
+}
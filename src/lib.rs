@@ -0,0 +1,48 @@
+//! Parsing of ELF files, as used by the `writeork` tool.
+//!
+//! [`elf::ElfFile`] parses an ELF file of either class and byte order from
+//! a byte slice, e.g. a memory-mapped file, without copying it, and hands
+//! out its headers, sections, symbols, dynamic entries and
+//! relocations converted to host byte order and widened to 64 bits.
+//! Most of the types needed for that are gathered in [`elf::prelude`].
+//!
+//! ```no_run
+//! extern crate writeork;
+//!
+//! use std::fs::File;
+//! use writeork::elf::prelude::*;
+//!
+//! # fn main() -> Result<(), ElfError> {
+//! let elf = ElfFile::open(File::open("/bin/true")?)?;
+//! println!("{} entry point {:#x}", elf.machine(), elf.entry());
+//! for shdr in elf.sections()? {
+//!     println!("{} at {:#x}", shdr.get_name(), shdr.get_addr());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The raw per-class structures, e.g. [`elf::Elf64_Phdr`], are laid out as
+//! in the file. The [`to_host`] module converts them and their fields from
+//! the byte order of the file.
+//!
+//! [`elf::ElfWriter`] takes a copy of a parsed file whose headers and
+//! section contents can be edited, and writes it back out with the
+//! tables moved wherever they no longer fit.
+//!
+//! [`dwarf`] reads the DWARF debugging information of an `ElfFile`, e.g.
+//! to map addresses to source lines.
+//!
+//! [`archive`] reads `ar` archives, whose members are usually ELF
+//! objects to be parsed with `ElfFile::parse`.
+
+#![allow(non_camel_case_types)]
+
+#[macro_use]
+pub mod to_host;
+
+pub mod elf;
+pub mod json;
+pub mod compress;
+pub mod dwarf;
+pub mod archive;
@@ -1,127 +1,2141 @@
-#![allow(non_camel_case_types)]
-
-extern crate clap;
-
-// FIXME: Macro export from to_host is ugly. I'd like to hide to_host module.
 #[macro_use]
-mod to_host;
+extern crate clap;
+extern crate memmap2;
+extern crate writeork;
 
-mod elf;
-use elf::prelude::*;
+use writeork::archive;
+use writeork::archive::{Archive, ArchiveMember};
+use writeork::elf;
+use writeork::elf::prelude::*;
+use writeork::elf::elf_shdr_type::{SHT_NOTE, SHT_NOBITS, SHT_GNU_VERDEF, SHT_GNU_VERNEED, SHT_GNU_VERSYM};
+use writeork::elf::elf_section_dump::{print_hex_dump, print_string_dump, get_strings};
+use writeork::elf::elf_version;
+use writeork::elf::elf_core::CoreFile;
+use writeork::elf::elf_patch::{PatchOp, patch_file};
+use writeork::json::Json;
+use writeork::dwarf::Dwarf;
+use writeork::dwarf::info_dump::print_debug_info;
+use writeork::dwarf::frame_dump::{print_debug_frames, print_eh_frame_hdr};
+use writeork::dwarf::line_table::LineTable;
+use writeork::to_host::Endianness;
 
-use clap::App;
+use clap::{ArgAction, Command};
+use clap::parser::ValueSource;
+use memmap2::Mmap;
 
+use std::borrow::Cow;
 use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever a field of the `--output=json` schema changes its
+/// meaning or goes away. New fields don't bump it.
+const JSON_SCHEMA_VERSION: u32 = 1;
+
+fn work(options: &clap::ArgMatches, path: &str, out: &mut dyn Write) -> Result<bool, ElfError> {
+    let file = File::open(path)?;
+    with_file_data(options, &file, |data| dump_file(options, path, data, out))
+}
+
+/// Passes the contents of `file` to `f`, mapped into memory unless
+/// `--no-mmap` asks to read them.
+fn with_file_data<F>(options: &clap::ArgMatches, mut file: &File, f: F) -> Result<bool, ElfError>
+    where F: FnOnce(&[u8]) -> Result<bool, ElfError>
+{
+    if options.get_flag("no-mmap") {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        f(&data)
+    } else {
+        // The map is only sound as long as nobody truncates or rewrites the
+        // file while we look at it; readelf makes the same assumption.
+        let map = unsafe { Mmap::map(file)? };
+        f(&map)
+    }
+}
+
+/// Prints the dumps of the ELF file `path` with contents `data`, or of
+/// each member if it's an archive.
+fn dump_file(options: &clap::ArgMatches, path: &str, data: &[u8], out: &mut dyn Write)
+    -> Result<bool, ElfError>
+{
+    if archive::is_archive(data) {
+        return dump_archive(options, path, &Archive::parse(data)?, out);
+    }
+    if options.get_flag("archive-index") {
+        // Like in readelf, this doesn't fail the other dumps.
+        eprintln!(
+            "writeork: Error: File {} is not an archive so its index cannot be displayed.", path);
+    }
+    dump(options, &ElfFile::parse(data)?, None, None, out)
+}
 
-fn work(options: clap::ArgMatches) {
-    let path = options.value_of("FILE").unwrap();
+/// The options that dump something of each ELF file. With none of them,
+/// `--archive-index` only prints the index of an archive.
+const DUMP_OPTIONS: &[&str] = &[
+    "file-header", "program-headers", "segments", "section-headers", "sections", "dynamic",
+    "relocs", "syms", "symbols", "dyn-syms", "version-info", "notes", "hex-dump", "string-dump",
+    "relocated-dump", "w", "debug-dump",
+];
 
-    let mut f = File::open(path).unwrap();
+/// Whether the flag or option `name` was given on the command line.
+fn is_given(options: &clap::ArgMatches, name: &str) -> bool {
+    options.value_source(name) == Some(ValueSource::CommandLine)
+}
+
+/// Prints the index of the archive `path` if asked to, and the dumps of
+/// each member under a `File: ` line naming it, or as one JSON document
+/// with an object per member. A member that can't be dumped is reported
+/// and skipped.
+fn dump_archive(options: &clap::ArgMatches, path: &str, archive: &Archive, out: &mut dyn Write)
+    -> Result<bool, ElfError>
+{
+    let mut all_dumped = true;
+    let mut json_members = Vec::new();
+    if options.get_flag("archive-index") {
+        all_dumped &= print_archive_index(path, archive, out)?;
+        if !DUMP_OPTIONS.iter().any(|option| is_given(options, option)) {
+            return Ok(all_dumped);
+        }
+    }
+
+    for member in archive.members() {
+        let member = member?;
+        let name = get_member_qualified_name(path, archive, &member);
+        let result = match member.data {
+            Some(data) => dump_member(options, &name, &member, data, &mut json_members, out),
+            None => {
+                let member_path = get_thin_member_path(path, &member.name);
+                match File::open(&member_path) {
+                    Ok(file) => with_file_data(options, &file, |data| {
+                        dump_member(options, &name, &member, data, &mut json_members, out)
+                    }),
+                    Err(err) => {
+                        eprintln!("writeork: Error: '{}': {}", member_path.display(), err);
+                        all_dumped = false;
+                        continue;
+                    }
+                }
+            }
+        };
+        match result {
+            Ok(dumped) => all_dumped &= dumped,
+            Err(ref err) if is_broken_pipe(err) => return result,
+            Err(err) => {
+                eprintln!("writeork: Error: '{}': {}", name, err);
+                all_dumped = false;
+            }
+        }
+    }
+
+    if options.get_one::<String>("output").map(String::as_str) == Some("json") {
+        writeln!(out, "{}", Json::object(vec![
+            ("schema_version", Json::from(JSON_SCHEMA_VERSION)),
+            ("archive", path.into()),
+            ("members", json_members.into()),
+        ]))?;
+    }
+    Ok(all_dumped)
+}
 
-    // FIXME: This is lazy guessing of bitness.
-    // We first read Ehdr as 32-bit variant and then check 
-    // if it's actually 32-bit. It works because ELF_EICLASS is always the same.
-    let ehdr = read_elf32_ehdr(&mut f);
-    let elf_ident = ehdr.get_ident();
-    let elf_class = elf_ident.get_class();
+/// Prints the dumps of the archive member `name` with contents `data`, or
+/// adds them to `json_members`.
+fn dump_member(
+    options: &clap::ArgMatches, name: &str, member: &ArchiveMember, data: &[u8],
+    json_members: &mut Vec<Json>,
+    out: &mut dyn Write)
+    -> Result<bool, ElfError>
+{
+    // Like readelf, members that aren't ELF files get no header.
+    let elf = ElfFile::parse(data)?;
+    if options.get_one::<String>("output").map(String::as_str) != Some("json") {
+        writeln!(out)?;
+        writeln!(out, "File: {}", name)?;
+    }
+    dump(options, &elf, Some(member), Some(json_members), out)
+}
+
+/// Names a member like readelf: `lib.a(member.o)`, or `lib.a[member.o]`
+/// in thin archives.
+fn get_member_qualified_name(path: &str, archive: &Archive, member: &ArchiveMember) -> String {
+    if archive.is_thin() {
+        format!("{}[{}]", path, member.name)
+    } else {
+        format!("{}({})", path, member.name)
+    }
+}
+
+/// The path of the file of a member of the thin archive `path`, whose
+/// name is relative to the directory of the archive.
+fn get_thin_member_path(path: &str, name: &str) -> PathBuf {
+    Path::new(path).parent().unwrap_or_else(|| Path::new("")).join(name)
+}
+
+/// Prints the symbol index of `archive` like `readelf -c`, with the
+/// symbols grouped by the member defining them. Returns `false` if some
+/// member couldn't be read.
+fn print_archive_index(path: &str, archive: &Archive, out: &mut dyn Write)
+    -> io::Result<bool>
+{
+    let index = match archive.index() {
+        Some(index) => index,
+        None => {
+            // readelf only looks for the index if there's a member to hold it.
+            if archive.members().next().is_some() {
+                writeln!(out, "{} has no archive index", path)?;
+            }
+            eprintln!("writeork: Error: {}: unable to dump the index as none was found", path);
+            return Ok(true);
+        }
+    };
+    writeln!(
+        out,
+        "Index of archive {}: ({} entries, {:#x} bytes in the symbol table)",
+        path, index.symbols.len(), index.strings_size)?;
+
+    let mut all_read = true;
+    let mut member_offset = None;
+    for symbol in &index.symbols {
+        if member_offset != Some(symbol.member_offset) {
+            member_offset = Some(symbol.member_offset);
+            match archive.member_at(symbol.member_offset) {
+                Ok(member) => writeln!(
+                    out,
+                    "Contents of binary {} at offset {:#x}",
+                    get_member_qualified_name(path, archive, &member), symbol.member_offset)?,
+                Err(err) => {
+                    eprintln!("writeork: Error: '{}': {}", path, err);
+                    all_read = false;
+                }
+            }
+        }
+        writeln!(out, "\t{}", symbol.name)?;
+    }
+    Ok(all_read)
+}
+
+/// Prints the dumps selected by `options`. Returns `false` if some of
+/// them couldn't be done, which makes the exit status 1 like in readelf.
+/// The JSON of archive members goes to `json_members` instead.
+fn dump(
+    options: &clap::ArgMatches, elf: &ElfFile, member: Option<&ArchiveMember>,
+    json_members: Option<&mut Vec<Json>>,
+    out: &mut dyn Write)
+    -> Result<bool, ElfError>
+{
+    let is_32 = elf.is_32();
+    let machine = elf.machine();
+    let osabi = elf.osabi();
+    // readelf names position-independent executables apart from shared
+    // objects. A broken dynamic section shouldn't stop us from printing
+    // the headers, so it just makes the file not count as one.
+    let is_pie = options.get_one::<String>("compat").map(String::as_str) == Some("readelf")
+        && elf.is_pie().unwrap_or(false);
+
+    if options.get_one::<String>("output").map(String::as_str) == Some("json") {
+        let (json, all_dumped) = dump_json(options, elf, member)?;
+        match json_members {
+            Some(json_members) => json_members.push(json),
+            None => writeln!(out, "{}", json)?,
+        }
+        let requests = get_section_dump_requests(options);
+        return Ok(all_dumped
+            && (requests.is_empty() || warn_missing_sections(&requests, elf.sections()?)));
+    }
+
+    if options.get_flag("file-header") {
+        let first_section = elf.sections().ok().and_then(|shdrs| shdrs.first());
+        write!(out, "{}", elf.header().format(is_pie, first_section))?;
+    }
+
+    if options.get_flag("section-headers")
+    || options.get_flag("sections") {
+        let with_file_header = options.get_flag("file-header");
+        let shdrs = elf.sections()?;
+
+        if print_section_headers_title(
+            shdrs.len(), elf.header().get_shoff(), with_file_header, is_32, out)?
+        {
+            for (i, shdr) in shdrs.iter().enumerate() {
+                shdr.print_row(i, machine, osabi, is_32, out)?;
+            }
+            print_section_flags_key(machine, osabi, out)?;
+        }
+    }
 
-    if options.is_present("file-header") {
-        if elf_class != ElfEiClass::ELFCLASS32 {
-            // Reread the header as Elf64_Ehdr
-            let ehdr = read_elf64_ehdr(&mut f);
-            print!("{}", ehdr);
+    if options.get_flag("program-headers")
+    || options.get_flag("segments") {
+        let with_file_header = options.get_flag("file-header");
+        let phdrs = elf.program_headers()?;
+
+        if print_program_headers_title(
+            elf.header(), phdrs.len(), with_file_header, is_pie, is_32, out)?
+        {
+            for phdr in phdrs {
+                phdr.print_row(machine, is_32, out)?;
+                if phdr.get_type() == ElfPhdrType::PT_INTERP {
+                    let interp = elf.read_bytes(
+                        phdr.get_offset(), phdr.get_filesz(), "program interpreter")?;
+                    writeln!(
+                        out,
+                        "      [Requesting program interpreter: {}]",
+                        get_string(interp, 0))?;
+                }
+            }
+            if let Some(shdrs) = get_mapped_sections(elf) {
+                print_section_to_segment_mapping(phdrs, shdrs, out)?;
+            }
+        }
+    }
+
+    if options.get_flag("dynamic") {
+        match elf.dynamic()? {
+            Some((offset, dyns)) => {
+                let strtab = elf.dynamic_strtab()?;
+
+                print_dynamic_section_title(offset, dyns.len(), out)?;
+                for d in dyns {
+                    d.print_row(strtab, is_32, out)?;
+                }
+            }
+            None => {
+                writeln!(out)?;
+                writeln!(out, "There is no dynamic section in this file.")?;
+            }
+        }
+    }
+
+    if options.get_flag("relocs") {
+        let use_dynamic = options.get_flag("use-dynamic");
+        let mut found = false;
+
+        if use_dynamic {
+            let dyns = elf.dynamic_entries()?;
+            let versions = elf.symbol_versions()?;
+
+            for table in get_dynamic_reloc_tables(|tag| get_dyn_val(dyns, tag)) {
+                let offset = match elf.vaddr_to_offset(table.vaddr)? {
+                    Some(offset) => offset,
+                    None => continue,
+                };
+                found = true;
+                print_dynamic_reloc_table_title(&table, out)?;
+                if table.kind == RelocKind::Relr {
+                    let addrs = elf.relr_addresses(offset, table.size)?;
+                    print_relr_addrs(&addrs, is_32, out)?;
+                    continue;
+                }
+
+                let is_rela = table.kind == RelocKind::Rela;
+                let rels = elf.relocations(offset, table.size, is_rela)?;
+                print_reloc_columns_title(is_rela, is_32, out)?;
+                for rel in rels {
+                    let sym = elf.dynamic_symbol(rel.get_sym())?;
+                    let version = match (versions.as_ref(), sym.as_ref()) {
+                        (Some(v), Some(s)) => v.get_symbol_version(
+                            rel.get_sym() as usize, s.get_raw_shndx() != 0,
+                            s.get_name_offset()),
+                        _ => None,
+                    };
+                    rel.print_row(machine, sym.as_ref(), version.as_ref(), is_rela, is_32, out)?;
+                    if rel.get_sym() != 0 && sym.is_none() {
+                        eprintln!("Error: bad symbol index: {:08x} in reloc", rel.get_sym());
+                    }
+                }
+            }
         } else {
-            print!("{}", ehdr);
+            let shdrs = elf.sections()?;
+            let versions = elf.symbol_versions()?;
+
+            for shdr in shdrs {
+                let kind = match get_reloc_section_kind(shdr.get_type()) {
+                    Some(kind) => kind,
+                    None => continue,
+                };
+                found = true;
+                let offset = shdr.get_offset();
+                let size = shdr.get_size();
+                let entsize = match shdr.get_entsize() {
+                    0 => 1,
+                    entsize => entsize,
+                };
+                print_reloc_section_title(shdr.get_name(), offset, size / entsize, out)?;
+                if kind == RelocKind::Relr {
+                    let addrs = elf.relr_addresses(offset, size)?;
+                    print_relr_addrs(&addrs, is_32, out)?;
+                    continue;
+                }
+
+                let is_rela = kind == RelocKind::Rela;
+                let rels = elf.relocations(offset, size, is_rela)?;
+                let link = shdr.get_link() as usize;
+                let (syms, is_dynsym) = match shdrs.get(link) {
+                    Some(symtab) if link != 0
+                        && is_symbol_table(symtab.get_type(), false) =>
+                    {
+                        (elf.symbols(link)?, is_symbol_table(symtab.get_type(), true))
+                    }
+                    _ => (Vec::new(), false),
+                };
+
+                print_reloc_columns_title(is_rela, is_32, out)?;
+                for rel in rels {
+                    let index = rel.get_sym() as usize;
+                    let sym = syms.get(index);
+                    let version = match (versions.as_ref(), sym) {
+                        (Some(v), Some(s)) if is_dynsym => v.get_symbol_version(
+                            index, s.get_raw_shndx() != 0, s.get_name_offset()),
+                        _ => None,
+                    };
+                    rel.print_row(machine, sym, version.as_ref(), is_rela, is_32, out)?;
+                    if rel.get_sym() != 0 && sym.is_none() {
+                        eprintln!("Error: bad symbol index: {:08x} in reloc", rel.get_sym());
+                    }
+                }
+            }
+        }
+
+        print_no_relocs(found, use_dynamic, out)?;
+    }
+
+    if options.get_flag("syms")
+    || options.get_flag("symbols")
+    || options.get_flag("dyn-syms") {
+        let dynamic_only = !options.get_flag("syms")
+            && !options.get_flag("symbols");
+
+        let shdrs = elf.sections()?;
+        let versions = elf.symbol_versions()?;
+
+        // Like readelf, which without -D only reads the symbol tables
+        // through the sections, e.g. none in core files.
+        if shdrs.is_empty() && !dynamic_only {
+            writeln!(out)?;
+            writeln!(out, "Dynamic symbol information is not available for displaying symbols.")?;
+        }
+        for (i, shdr) in shdrs.iter().enumerate() {
+            if !is_symbol_table(shdr.get_type(), dynamic_only) {
+                continue;
+            }
+            let syms = elf.symbols(i)?;
+            let is_dynsym = is_symbol_table(shdr.get_type(), true);
+
+            print_symbol_table_title(shdr.get_name(), syms.len(), is_32, out)?;
+            for (j, sym) in syms.iter().enumerate() {
+                let version = match versions.as_ref() {
+                    Some(v) if is_dynsym => v.get_symbol_version(
+                        j, sym.get_raw_shndx() != 0, sym.get_name_offset()),
+                    _ => None,
+                };
+                sym.print_row(j, version.as_ref(), machine, osabi, is_32, out)?;
+            }
         }
     }
 
-    if options.is_present("program-headers")
-    || options.is_present("segments") {
-        use to_host::ToHostCopyStruct;
-        // FIXME: All of this is broken due to ehdr being always 32-bit here.
+    if options.get_flag("version-info") {
+        let e = elf.endianness();
+        let shdrs = elf.sections()?;
+        let versions = elf.symbol_versions()?.unwrap_or_default();
+
+        let sections = get_version_sections(shdrs);
+        for &(shdr, link_shdr) in &sections {
+            let sh_type = shdr.get_type().get();
+            let data = elf.section_data(shdr)?;
+            if sh_type == SHT_GNU_VERSYM {
+                let versyms = elf_version::read_versyms(data, &e);
+                print_version_section_title(
+                    "symbols", shdr, link_shdr, versyms.len(), is_32, out)?;
+                versions.print_versym_table(&versyms, out)?;
+                continue;
+            }
 
-        let ehdr = ehdr.to_host_copy(&ehdr.get_endianness());
-        let e = ehdr.get_endianness();
-        let e_type: ElfEhdrType = unsafe {
-            std::mem::transmute(ehdr.get_type())
+            let strtab = elf.section_data(link_shdr)?;
+            let count = shdr.get_info() as usize;
+            if sh_type == SHT_GNU_VERDEF {
+                print_version_section_title("definition", shdr, link_shdr, count, is_32, out)?;
+                let verdefs = elf_version::read_verdefs(data, &e, count);
+                for verdef in &verdefs {
+                    verdef.print(strtab, out)?;
+                }
+                if verdefs.len() < count {
+                    writeln!(out, "  Version definition past end of section")?;
+                }
+            } else {
+                print_version_section_title("needs", shdr, link_shdr, count, is_32, out)?;
+                let verneeds = elf_version::read_verneeds(data, &e, count);
+                for verneed in &verneeds {
+                    verneed.print(strtab, out)?;
+                    if verneed.is_missing_aux() {
+                        eprintln!("Warning: Missing Version Needs auxiliary information");
+                    }
+                }
+                if verneeds.len() < count {
+                    eprintln!("Warning: Missing Version Needs information");
+                }
+            }
+        }
+
+        if sections.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "No version information found in this file.")?;
+        }
+    }
+
+    let mut all_dumped = true;
+    let requests = get_section_dump_requests(options);
+    let debug_dumps = get_debug_dumps(options);
+    if !requests.is_empty() || !debug_dumps.is_empty() {
+        let shdrs = elf.sections()?;
+        let decompress = options.get_flag("decompress");
+        let compat = options.get_one::<String>("compat").map(String::as_str) == Some("readelf");
+        let mut dwarf = LoadedDwarf::default();
+        for (index, kind) in resolve_section_dumps(&requests, &debug_dumps, shdrs, compat) {
+            if kind.is_debug_dump() {
+                print_debug_dump(elf, index, kind, &mut dwarf, out)?;
+            } else {
+                all_dumped &= print_section_dump(elf, index, kind, decompress, out)?;
+            }
+        }
+        all_dumped &= warn_missing_sections(&requests, shdrs);
+    }
+
+    if options.get_flag("notes") {
+        let e = elf.endianness();
+        for area in get_note_areas(elf)? {
+            print_note_area(&area, e, machine, is_32, out)?;
+        }
+    }
+
+    Ok(all_dumped)
+}
+
+/// Collects the dumps selected by `options` into a single JSON object,
+/// with a field per dump, after those describing `member` if the file is
+/// one of an archive. The schema is described in `JSON.md`. Also returns
+/// `false` if some of the sections couldn't be dumped.
+fn dump_json(
+    options: &clap::ArgMatches, elf: &ElfFile, member: Option<&ArchiveMember>)
+    -> Result<(Json, bool), ElfError>
+{
+    let is_32 = elf.is_32();
+    let machine = elf.machine();
+    let osabi = elf.osabi();
+
+    let mut json = match member {
+        Some(member) => member.to_json(),
+        None => Json::object(vec![("schema_version", Json::from(JSON_SCHEMA_VERSION))]),
+    };
+
+    if options.get_flag("file-header") {
+        json.push("file_header", elf.header().to_json());
+    }
+
+    if options.get_flag("section-headers")
+    || options.get_flag("sections") {
+        let shdrs = elf.sections()?.iter()
+            .enumerate()
+            .map(|(i, shdr)| shdr.to_json(i, machine, osabi))
+            .collect::<Vec<_>>();
+        json.push("section_headers", shdrs);
+    }
+
+    if options.get_flag("program-headers")
+    || options.get_flag("segments") {
+        let shdrs = get_mapped_sections(elf);
+        let mut phdrs = Vec::new();
+        for phdr in elf.program_headers()? {
+            let mut phdr_json = phdr.to_json(machine);
+            if phdr.get_type() == ElfPhdrType::PT_INTERP {
+                let interp = elf.read_bytes(
+                    phdr.get_offset(), phdr.get_filesz(), "program interpreter")?;
+                phdr_json.push("interpreter", get_string(interp, 0));
+            }
+            phdr_json.push("sections", shdrs.map(|shdrs| get_segment_sections(phdr, shdrs)));
+            phdrs.push(phdr_json);
+        }
+        json.push("program_headers", phdrs);
+    }
+
+    if options.get_flag("dynamic") {
+        let dyns = match elf.dynamic()? {
+            Some((_, dyns)) => {
+                let strtab = elf.dynamic_strtab()?;
+                Some(dyns.iter().map(|d| d.to_json(strtab)).collect::<Vec<_>>())
+            }
+            None => None,
         };
+        json.push("dynamic", dyns);
+    }
+
+    if options.get_flag("relocs") {
+        let mut tables = Vec::new();
+
+        if options.get_flag("use-dynamic") {
+            let dyns = elf.dynamic_entries()?;
+            let versions = elf.symbol_versions()?;
+
+            for table in get_dynamic_reloc_tables(|tag| get_dyn_val(dyns, tag)) {
+                let offset = match elf.vaddr_to_offset(table.vaddr)? {
+                    Some(offset) => offset,
+                    None => continue,
+                };
+                let entries = if table.kind == RelocKind::Relr {
+                    elf.relr_addresses(offset, table.size)?.into()
+                } else {
+                    let is_rela = table.kind == RelocKind::Rela;
+                    let mut entries = Vec::new();
+                    for rel in elf.relocations(offset, table.size, is_rela)? {
+                        let sym = elf.dynamic_symbol(rel.get_sym())?;
+                        let version = match (versions.as_ref(), sym.as_ref()) {
+                            (Some(v), Some(s)) => v.get_symbol_version(
+                                rel.get_sym() as usize, s.get_raw_shndx() != 0,
+                                s.get_name_offset()),
+                            _ => None,
+                        };
+                        entries.push(rel.to_json(
+                            machine, sym.as_ref(), version.as_ref(), is_rela, is_32));
+                    }
+                    Json::Array(entries)
+                };
+                tables.push(Json::object(vec![
+                    ("name", Json::from(table.name)),
+                    ("kind", table.kind.get_name().into()),
+                    ("address", table.vaddr.into()),
+                    ("offset", offset.into()),
+                    ("size", table.size.into()),
+                    ("entries", entries),
+                ]));
+            }
+        } else {
+            let shdrs = elf.sections()?;
+            let versions = elf.symbol_versions()?;
+
+            for shdr in shdrs {
+                let kind = match get_reloc_section_kind(shdr.get_type()) {
+                    Some(kind) => kind,
+                    None => continue,
+                };
+                let offset = shdr.get_offset();
+                let size = shdr.get_size();
+                let entries = if kind == RelocKind::Relr {
+                    elf.relr_addresses(offset, size)?.into()
+                } else {
+                    let is_rela = kind == RelocKind::Rela;
+                    let link = shdr.get_link() as usize;
+                    let (syms, is_dynsym) = match shdrs.get(link) {
+                        Some(symtab) if link != 0
+                            && is_symbol_table(symtab.get_type(), false) =>
+                        {
+                            (elf.symbols(link)?, is_symbol_table(symtab.get_type(), true))
+                        }
+                        _ => (Vec::new(), false),
+                    };
+                    let mut entries = Vec::new();
+                    for rel in elf.relocations(offset, size, is_rela)? {
+                        let index = rel.get_sym() as usize;
+                        let sym = syms.get(index);
+                        let version = match (versions.as_ref(), sym) {
+                            (Some(v), Some(s)) if is_dynsym => v.get_symbol_version(
+                                index, s.get_raw_shndx() != 0, s.get_name_offset()),
+                            _ => None,
+                        };
+                        entries.push(rel.to_json(machine, sym, version.as_ref(), is_rela, is_32));
+                    }
+                    Json::Array(entries)
+                };
+                tables.push(Json::object(vec![
+                    ("name", Json::from(shdr.get_name())),
+                    ("kind", kind.get_name().into()),
+                    ("address", shdr.get_addr().into()),
+                    ("offset", offset.into()),
+                    ("size", size.into()),
+                    ("entries", entries),
+                ]));
+            }
+        }
+
+        json.push("relocations", tables);
+    }
+
+    if options.get_flag("syms")
+    || options.get_flag("symbols")
+    || options.get_flag("dyn-syms") {
+        let dynamic_only = !options.get_flag("syms")
+            && !options.get_flag("symbols");
+
+        let shdrs = elf.sections()?;
+        let versions = elf.symbol_versions()?;
+
+        let mut tables = Vec::new();
+        for (i, shdr) in shdrs.iter().enumerate() {
+            if !is_symbol_table(shdr.get_type(), dynamic_only) {
+                continue;
+            }
+            let is_dynsym = is_symbol_table(shdr.get_type(), true);
+            let mut entries = Vec::new();
+            for (j, sym) in elf.symbols(i)?.iter().enumerate() {
+                let version = match versions.as_ref() {
+                    Some(v) if is_dynsym => v.get_symbol_version(
+                        j, sym.get_raw_shndx() != 0, sym.get_name_offset()),
+                    _ => None,
+                };
+                entries.push(sym.to_json(j, version.as_ref(), machine, osabi));
+            }
+            tables.push(Json::object(vec![
+                ("name", Json::from(shdr.get_name())),
+                ("entries", entries.into()),
+            ]));
+        }
+        json.push("symbol_tables", tables);
+    }
+
+    if options.get_flag("version-info") {
+        let e = elf.endianness();
+        let versions = elf.symbol_versions()?.unwrap_or_default();
+        let mut sections = Vec::new();
+        for (shdr, link_shdr) in get_version_sections(elf.sections()?) {
+            let data = elf.section_data(shdr)?;
+            let count = shdr.get_info() as usize;
+            let (kind, entries) = match shdr.get_type().get() {
+                SHT_GNU_VERSYM => {
+                    ("symbols", versions.versyms_to_json(&elf_version::read_versyms(data, &e)))
+                }
+                SHT_GNU_VERDEF => {
+                    let strtab = elf.section_data(link_shdr)?;
+                    let verdefs: Vec<Json> = elf_version::read_verdefs(data, &e, count).iter()
+                        .map(|verdef| verdef.to_json(strtab))
+                        .collect();
+                    ("definitions", verdefs.into())
+                }
+                _ => {
+                    let strtab = elf.section_data(link_shdr)?;
+                    let verneeds: Vec<Json> = elf_version::read_verneeds(data, &e, count).iter()
+                        .map(|verneed| verneed.to_json(strtab))
+                        .collect();
+                    ("requirements", verneeds.into())
+                }
+            };
+            sections.push(Json::object(vec![
+                ("name", shdr.get_name().into()),
+                ("kind", kind.into()),
+                ("address", shdr.get_addr().into()),
+                ("offset", shdr.get_offset().into()),
+                ("link", shdr.get_link().into()),
+                ("entries", entries),
+            ]));
+        }
+        json.push("version_info", sections);
+    }
+
+    let mut all_dumped = true;
+    let requests = get_section_dump_requests(options);
+    if !requests.is_empty() {
+        let decompress = options.get_flag("decompress");
+        let mut contents = Vec::new();
+        for (index, kind) in resolve_section_dumps(&requests, &[], elf.sections()?, false) {
+            match section_dump_to_json(elf, index, kind, decompress)? {
+                Some(dump) => contents.push(dump),
+                None => all_dumped = false,
+            }
+        }
+        json.push("section_contents", contents);
+    }
+
+    if options.get_flag("notes") {
+        let e = elf.endianness();
+
+        let mut areas = Vec::new();
+        for area in get_note_areas(elf)? {
+            // Like in the text output, a bad alignment only drops the
+            // notes of this area.
+            let notes = ElfNoteIter::new(area.data, e, area.align, area.is_core)
+                .map(|mut notes| {
+                    let json = notes.by_ref().map(|note| note.to_json()).collect::<Vec<_>>();
+                    if notes.is_truncated() {
+                        eprintln!("Warning: note with invalid namesz and/or descsz found");
+                    }
+                    json
+                });
+            areas.push(Json::object(vec![
+                ("section", Json::from(area.section_name)),
+                ("offset", area.offset.into()),
+                ("size", area.data.len().into()),
+                ("notes", notes.into()),
+            ]));
+        }
+        json.push("notes", areas);
+    }
+
+    Ok((json, all_dumped))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SectionDumpKind {
+    Hex,
+    Relocated,
+    Strings,
+    /// The units of `.debug_info` and `.debug_types`, from `--debug-dump=info`.
+    DebugInfo,
+    /// The CIEs and FDEs of `.eh_frame` and `.debug_frame`, from
+    /// `--debug-dump=frames`.
+    Frames,
+    /// The rules that the CIEs and FDEs make, from
+    /// `--debug-dump=frames-interp`.
+    FramesInterp,
+}
+
+impl SectionDumpKind {
+    fn get_name(&self) -> &'static str {
+        match *self {
+            SectionDumpKind::Hex => "hex",
+            SectionDumpKind::Relocated => "relocated",
+            SectionDumpKind::Strings => "strings",
+            SectionDumpKind::DebugInfo => "debug-info",
+            SectionDumpKind::Frames => "frames",
+            SectionDumpKind::FramesInterp => "frames-interp",
+        }
+    }
+
+    /// Whether this is one of the `--debug-dump` dumps.
+    fn is_debug_dump(&self) -> bool {
+        !matches!(*self,
+                  SectionDumpKind::Hex | SectionDumpKind::Relocated | SectionDumpKind::Strings)
+    }
+}
+
+/// A section named on the command line with `-x`, `-R` or `-p`, either
+/// by its name or by its index.
+struct SectionDumpRequest<'a> {
+    kind: SectionDumpKind,
+    section: &'a str,
+}
+
+/// The section dump options: their kind and name.
+const SECTION_DUMP_OPTIONS: &[(SectionDumpKind, &str)] = &[
+    (SectionDumpKind::Hex, "hex-dump"),
+    (SectionDumpKind::Relocated, "relocated-dump"),
+    (SectionDumpKind::Strings, "string-dump"),
+];
+
+/// Returns the values given to the repeatable option `name`.
+fn get_repeatable_values<'a>(options: &'a clap::ArgMatches, name: &str) -> Vec<&'a str> {
+    options.get_many::<String>(name).into_iter().flatten().map(String::as_str).collect()
+}
+
+fn get_section_dump_requests<'a>(options: &'a clap::ArgMatches) -> Vec<SectionDumpRequest<'a>> {
+    let mut requests = Vec::new();
+    for &(kind, option) in SECTION_DUMP_OPTIONS {
+        for section in get_repeatable_values(options, option) {
+            requests.push(SectionDumpRequest { kind, section });
+        }
+    }
+    requests
+}
+
+/// The `--debug-dump` names readelf knows, with their `-w` letters, and
+/// the dump of the ones we do.
+const DEBUG_DUMP_OPTIONS: &[(char, &str, Option<SectionDumpKind>)] = &[
+    ('i', "info", Some(SectionDumpKind::DebugInfo)),
+    ('a', "abbrev", None),
+    ('l', "rawline", None),
+    ('L', "decodedline", None),
+    ('p', "pubnames", None),
+    ('t', "pubtypes", None),
+    ('r', "aranges", None),
+    ('R', "Ranges", None),
+    ('m', "macro", None),
+    ('f', "frames", Some(SectionDumpKind::Frames)),
+    ('F', "frames-interp", Some(SectionDumpKind::FramesInterp)),
+    ('s', "str", None),
+    ('O', "str-offsets", None),
+    ('o', "loc", None),
+    ('g', "gdb_index", None),
+    ('T', "trace_info", None),
+    ('u', "trace_abbrev", None),
+    ('U', "trace_aranges", None),
+    ('A', "addr", None),
+    ('c', "cu_index", None),
+    ('k', "links", None),
+    ('K', "follow-links", None),
+    ('N', "no-follow-links", None),
+];
+
+/// Returns the debug dumps selected with `-w` letters or `--debug-dump`
+/// names, or all the ones we do if none are named. Exits for the ones we
+/// don't do yet. Separate debug files are never followed, so the options
+/// about following links are accepted and ignored.
+fn get_debug_dumps(options: &clap::ArgMatches) -> Vec<SectionDumpKind> {
+    if !options.contains_id("w") && !options.contains_id("debug-dump") {
+        return Vec::new();
+    }
+    let mut values = get_repeatable_values(options, "w");
+    values.extend(get_repeatable_values(options, "debug-dump"));
+    if values.iter().all(|value| value.is_empty()) {
+        // As in readelf, frames-interp is only shown if asked for.
+        return DEBUG_DUMP_OPTIONS.iter()
+            .filter_map(|&(_, _, kind)| kind)
+            .filter(|&kind| kind != SectionDumpKind::FramesInterp)
+            .collect();
+    }
+    let mut names = Vec::new();
+    for value in values {
+        for name in value.split(',') {
+            if DEBUG_DUMP_OPTIONS.iter().any(|&(_, long, _)| long == name) {
+                names.push(name.to_string());
+                continue;
+            }
+            let letters: Option<Vec<&str>> = name.chars()
+                .map(|letter| DEBUG_DUMP_OPTIONS.iter()
+                    .find(|&&(short, _, _)| short == letter)
+                    .map(|&(_, long, _)| long))
+                .collect();
+            match letters {
+                Some(longs) => names.extend(longs.iter().map(|long| long.to_string())),
+                None => eprintln!("Warning: Unrecognized debug option '{}'", name),
+            }
+        }
+    }
+
+    let mut dumps = Vec::new();
+    for name in &names {
+        match DEBUG_DUMP_OPTIONS.iter().find(|&&(_, long, _)| long == name) {
+            Some(&(_, _, Some(kind))) => {
+                if !dumps.contains(&kind) {
+                    dumps.push(kind);
+                }
+            }
+            Some(_) if name == "follow-links" || name == "no-follow-links" => {}
+            _ => {
+                eprintln!("writeork: Error: --debug-dump={} isn't supported yet", name);
+                std::process::exit(1);
+            }
+        }
+    }
+    // Like readelf, frames-interp replaces frames rather than adding to it.
+    if dumps.contains(&SectionDumpKind::FramesInterp) {
+        dumps.retain(|&kind| kind != SectionDumpKind::Frames);
+    }
+    dumps
+}
 
-        println!("");
-        println!("Elf file type is {}", e_type);
-        println!("Entry point {:#x}", ehdr.get_entry());
-        println!(
-            "There are {} program headers, starting at offset {}",
-            ehdr.get_phnum(), ehdr.get_phoff());
-        println!("");
-
-        match elf_class {
-            // FIXME: This fugly code is due to ehdr and phdrs being of 
-            // different type in different branches of control flow
-            ElfEiClass::ELFCLASS32 => {
-                let phdrs = read_elf32_phdrs(&ehdr, &mut f);
-
-                println!("Program headers:");
-                println!(
-                    concat!(
-                        "  ",
-                        "Type           ",
-                        "Offset   ",
-                        "VirtAddr           ",
-                        "PhysAddr           ",
-                        "FileSiz  ",
-                        "MemSiz   ",
-                        "Flg ",
-                        "Align"));
-                for phdr in phdrs {
-                    print!("  ");
-                    phdr.print_with_endianness(&e);
-                    println!("");
-                }
-            }
-            ElfEiClass::ELFCLASS64 => {
-                let ehdr = read_elf64_ehdr(&mut f);
-                let phdrs = read_elf64_phdrs(&ehdr, &mut f);
-
-                println!("Program headers:");
-                println!(
-                    concat!(
-                        "  ",
-                        "Type           ",
-                        "Offset   ",
-                        "VirtAddr           ",
-                        "PhysAddr           ",
-                        "FileSiz  ",
-                        "MemSiz   ",
-                        "Flg ",
-                        "Align"));
-                for phdr in phdrs {
-                    print!("  ");
-                    phdr.print_with_endianness(&e);
-                    println!("");
-                }
-            }
-            ElfEiClass::ELFCLASSNONE => {
-                println!("This ELF file has ELFCLASSNONE. We can't get its bitness");
+/// Whether the section `name` holds what the debug dump `kind` shows,
+/// maybe compressed as a `.zdebug` section or split off as a `.dwo` one.
+/// Unless `compat` is set, the frame dumps also show `.eh_frame_hdr`,
+/// which readelf doesn't.
+fn is_debug_dump_section(kind: SectionDumpKind, name: &str, compat: bool) -> bool {
+    if name == ".eh_frame" || name == ".eh_frame_hdr" {
+        return (kind == SectionDumpKind::Frames || kind == SectionDumpKind::FramesInterp)
+            && (name == ".eh_frame" || !compat);
+    }
+    let is_dwo = name.ends_with(".dwo");
+    let name = name.strip_suffix(".dwo").unwrap_or(name);
+    let name = match name.strip_prefix(".zdebug_") {
+        Some(rest) => format!(".debug_{}", rest),
+        None => name.to_string(),
+    };
+    match kind {
+        SectionDumpKind::DebugInfo => name == ".debug_info" || name == ".debug_types",
+        SectionDumpKind::Frames | SectionDumpKind::FramesInterp => {
+            name == ".debug_frame" && !is_dwo
+        }
+        _ => false,
+    }
+}
+
+/// Parses a section index the way readelf does, with `strtoul`: decimal,
+/// or hex after `0x`, or octal after `0`. Anything else is a name.
+fn parse_section_index(s: &str) -> Option<u64> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else if s.len() > 1 && s.starts_with('0') {
+        u64::from_str_radix(&s[1..], 8).ok()
+    } else if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Matches the requests and the debug dumps against the sections. Returns
+/// the dumps to do, in the order readelf does them: by section, and for
+/// each section the hex dump, then the relocated one, then the strings,
+/// then the debug dumps. Names that match no section are warned about
+/// here, like readelf does before dumping anything.
+fn resolve_section_dumps(
+    requests: &[SectionDumpRequest], debug_dumps: &[SectionDumpKind], shdrs: &[ElfShdr],
+    compat: bool)
+    -> Vec<(usize, SectionDumpKind)>
+{
+    let is_requested = |index: usize, shdr: &ElfShdr, request: &SectionDumpRequest| {
+        match parse_section_index(request.section) {
+            Some(i) => i == index as u64,
+            None => request.section == shdr.get_name(),
+        }
+    };
+
+    for request in requests.iter().rev() {
+        if parse_section_index(request.section).is_none()
+            && !shdrs.iter().any(|s| s.get_name() == request.section)
+        {
+            eprintln!(
+                "Warning: Section '{}' was not dumped because it does not exist",
+                request.section);
+        }
+    }
+
+    let mut dumps = Vec::new();
+    for (index, shdr) in shdrs.iter().enumerate() {
+        for &kind in &[SectionDumpKind::Hex, SectionDumpKind::Relocated, SectionDumpKind::Strings] {
+            if requests.iter().any(|r| r.kind == kind && is_requested(index, shdr, r)) {
+                dumps.push((index, kind));
+            }
+        }
+        for &kind in debug_dumps {
+            if is_debug_dump_section(kind, shdr.get_name(), compat) {
+                dumps.push((index, kind));
             }
         }
     }
+
+    dumps
+}
+
+/// Warns about the requested indices past the last section, which readelf
+/// does after the dumps. Returns `false` if there were any.
+fn warn_missing_sections(requests: &[SectionDumpRequest], shdrs: &[ElfShdr]) -> bool {
+    let mut missing: Vec<u64> = requests.iter()
+        .filter_map(|r| parse_section_index(r.section))
+        .filter(|&i| i >= shdrs.len() as u64)
+        .collect();
+    missing.sort();
+    missing.dedup();
+    for index in &missing {
+        eprintln!("Warning: Section {} was not dumped because it does not exist!", index);
+    }
+    missing.is_empty()
+}
+
+/// Returns the contents of a section to dump, decompressed with `-z`.
+/// If they don't decompress, prints what readelf does and returns `None`.
+fn get_section_dump_data<'a>(elf: &'a ElfFile, shdr: &ElfShdr, decompress: bool)
+    -> Result<Option<Cow<'a, [u8]>>, ElfError>
+{
+    if !decompress {
+        return Ok(Some(Cow::Borrowed(elf.section_data(shdr)?)));
+    }
+    match elf.decompressed_section_data(shdr) {
+        Ok(data) => Ok(Some(data)),
+        Err(ElfError::UnsupportedCompression(ch_type)) => {
+            eprintln!(
+                "Warning: section '{}' has unsupported compress type: {}",
+                shdr.get_name(), ch_type);
+            Ok(None)
+        }
+        Err(ElfError::OutOfRange { what: "compression header", .. }) => {
+            eprintln!("Error: Compressed section is too small even for a compression header");
+            Ok(None)
+        }
+        Err(ElfError::Decompress(_)) => {
+            eprintln!("Error: Unable to decompress section {}", shdr.get_name());
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Prints a dump of the section at `index`. Returns `false` if its
+/// contents didn't decompress.
+fn print_section_dump(
+    elf: &ElfFile, index: usize, kind: SectionDumpKind, decompress: bool, out: &mut dyn Write)
+    -> Result<bool, ElfError>
+{
+    let shdr = &elf.sections()?[index];
+    let name = shdr.get_name();
+    if shdr.get_size() == 0 || shdr.get_type().get() == SHT_NOBITS {
+        writeln!(out, "Section '{}' has no data to dump.", name)?;
+        return Ok(true);
+    }
+
+    writeln!(out)?;
+    match kind {
+        SectionDumpKind::Hex | SectionDumpKind::Relocated => {
+            writeln!(out, "Hex dump of section '{}':", name)?;
+        }
+        SectionDumpKind::Strings => writeln!(out, "String dump of section '{}':", name)?,
+        _ => unreachable!("debug dumps are printed by print_debug_dump"),
+    }
+    let data = match get_section_dump_data(elf, shdr, decompress)? {
+        Some(data) => data,
+        None => return Ok(false),
+    };
+
+    match kind {
+        SectionDumpKind::Hex => {
+            if !elf.relocation_sections_for(index)?.is_empty() {
+                writeln!(out, concat!(
+                    " NOTE: This section has relocations against it,",
+                    " but these have NOT been applied to this dump."))?;
+            }
+            print_hex_dump(&data, shdr.get_addr(), out)?;
+        }
+        SectionDumpKind::Relocated => {
+            let mut data = data.into_owned();
+            for warning in elf.apply_relocations(index, &mut data)? {
+                eprintln!("Warning: {}", warning);
+            }
+            print_hex_dump(&data, shdr.get_addr(), out)?;
+        }
+        SectionDumpKind::Strings => {
+            if !elf.relocation_sections_for(index)?.is_empty() {
+                writeln!(out, concat!(
+                    "  Note: This section has relocations against it,",
+                    " but these have NOT been applied to this dump."))?;
+            }
+            print_string_dump(&data, out)?;
+        }
+        _ => unreachable!("debug dumps are printed by print_debug_dump"),
+    }
+    Ok(true)
+}
+
+/// The debugging sections that the debug dumps refer to, loaded on first
+/// use: those of the file, and those of split DWARF for `.dwo` sections.
+/// `warned` are the sections whose relocation warnings were printed when
+/// loading them, like readelf does before any dump.
+#[derive(Default)]
+struct LoadedDwarf<'a> {
+    dwarf: Option<Dwarf<'a>>,
+    dwo: Option<Dwarf<'a>>,
+    warned: Vec<usize>,
+}
+
+/// Prints the debug dump `kind` of the section at `index`, which is
+/// decompressed and, in relocatable files, relocated for it like readelf
+/// does.
+fn print_debug_dump<'a>(
+    elf: &'a ElfFile, index: usize, kind: SectionDumpKind, loaded: &mut LoadedDwarf<'a>,
+    out: &mut dyn Write)
+    -> Result<(), ElfError>
+{
+    let shdr = &elf.sections()?[index];
+    let name = shdr.get_name();
+    if shdr.get_type().get() == SHT_NOBITS {
+        writeln!(out, "section '{}' has the NOBITS type - its contents are unreliable.", name)?;
+        return Ok(());
+    }
+    if shdr.get_size() == 0 {
+        writeln!(out)?;
+        writeln!(out, "Section '{}' has no debugging data.", name)?;
+        return Ok(());
+    }
+
+    if name.ends_with(".dwo") {
+        if loaded.dwo.is_none() {
+            loaded.dwo = Some(Dwarf::load_dwo(elf)?);
+        }
+    } else if loaded.dwarf.is_none() {
+        let shdrs = elf.sections()?;
+        for &section in &["info", "addr"] {
+            let names = [format!(".debug_{}", section), format!(".zdebug_{}", section)];
+            if let Some(i) = shdrs.iter().position(|s| names.iter().any(|n| s.get_name() == n)) {
+                for warning in elf.debug_section_data_at(i)?.1 {
+                    eprintln!("Warning: {}", warning);
+                }
+                loaded.warned.push(i);
+            }
+        }
+        loaded.dwarf = Some(Dwarf::load(elf)?);
+    }
+    let (data, warnings) = elf.debug_section_data_at(index)?;
+    if !loaded.warned.contains(&index) {
+        for warning in warnings {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+    let dwarf = if name.ends_with(".dwo") { &loaded.dwo } else { &loaded.dwarf };
+    let dwarf = dwarf.as_ref().unwrap();
+
+    let mut out = BufWriter::new(out);
+    let address = shdr.get_addr();
+    match kind {
+        SectionDumpKind::DebugInfo => print_debug_info(dwarf, name, &data, elf.machine(), &mut out)?,
+        _ if name == ".eh_frame_hdr" => print_eh_frame_hdr(elf, name, &data, address, &mut out)?,
+        SectionDumpKind::FramesInterp => {
+            print_debug_frames(elf, name, &data, address, true, &mut out)?
+        }
+        _ => print_debug_frames(elf, name, &data, address, false, &mut out)?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Describes a dump of the section at `index` for `--output=json`.
+/// Returns `None` if its contents didn't decompress.
+fn section_dump_to_json(elf: &ElfFile, index: usize, kind: SectionDumpKind, decompress: bool)
+    -> Result<Option<Json>, ElfError>
+{
+    let shdr = &elf.sections()?[index];
+    let contents = match get_section_dump_data(elf, shdr, decompress)? {
+        Some(contents) => contents,
+        None => return Ok(None),
+    };
+    let mut data = None;
+    let mut strings = None;
+    match kind {
+        SectionDumpKind::Hex => data = Some(contents.into_owned()),
+        SectionDumpKind::Relocated => {
+            let mut relocated = contents.into_owned();
+            for warning in elf.apply_relocations(index, &mut relocated)? {
+                eprintln!("Warning: {}", warning);
+            }
+            data = Some(relocated);
+        }
+        SectionDumpKind::Strings => {
+            strings = Some(get_strings(&contents).iter()
+                .map(|s| Json::object(vec![
+                    ("offset", Json::from(s.offset)),
+                    ("string", String::from_utf8_lossy(s.bytes).into_owned().into()),
+                ]))
+                .collect::<Vec<_>>());
+        }
+        _ => unreachable!("--debug-dump doesn't support --output=json"),
+    }
+    let data = data.map(|data| data.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+
+    Ok(Some(Json::object(vec![
+        ("index", Json::from(index)),
+        ("name", shdr.get_name().into()),
+        ("kind", kind.get_name().into()),
+        ("address", shdr.get_addr().into()),
+        ("data", data.into()),
+        ("strings", strings.into()),
+    ])))
+}
+
+/// Returns the sections to map to segments in `-l`. Like readelf, the
+/// mapping is left out if the sections or their names can't be read.
+fn get_mapped_sections<'a>(elf: &'a ElfFile) -> Option<&'a [ElfShdr]> {
+    match elf.section_name_table() {
+        Ok(Some(_)) => elf.sections().ok(),
+        _ => None,
+    }
+}
+
+/// Returns the names of the sections in `phdr`, skipping the null section.
+fn get_segment_sections<'a>(phdr: &ElfPhdr, shdrs: &'a [ElfShdr]) -> Vec<&'a str> {
+    shdrs.iter()
+        .skip(1)
+        .filter(|shdr| phdr.contains_section(shdr))
+        .map(|shdr| shdr.get_name())
+        .collect()
+}
+
+fn print_section_to_segment_mapping(phdrs: &[ElfPhdr], shdrs: &[ElfShdr], out: &mut dyn Write)
+    -> io::Result<()>
+{
+    writeln!(out)?;
+    writeln!(out, " Section to Segment mapping:")?;
+    writeln!(out, "  Segment Sections...")?;
+    for (i, phdr) in phdrs.iter().enumerate() {
+        write!(out, "   {:02}     ", i)?;
+        for name in get_segment_sections(phdr, shdrs) {
+            write!(out, "{} ", name)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Prints the preamble and column titles of the program header table.
+/// Returns `false` if there are no program headers to print.
+fn print_program_headers_title(
+    ehdr: &ElfEhdr, phnum: usize, with_file_header: bool, is_pie: bool, is_32: bool,
+    out: &mut dyn Write)
+    -> io::Result<bool>
+{
+    if phnum == 0 {
+        writeln!(out)?;
+        writeln!(out, "There are no program headers in this file.")?;
+        return Ok(false);
+    }
+
+    if !with_file_header {
+        writeln!(out)?;
+        writeln!(out, "Elf file type is {}", ehdr.get_type().get_name(is_pie))?;
+        writeln!(out, "Entry point {:#x}", ehdr.get_entry())?;
+        if phnum == 1 {
+            writeln!(
+                out,
+                "There is {} program header, starting at offset {}",
+                phnum, ehdr.get_phoff())?;
+        } else {
+            writeln!(
+                out,
+                "There are {} program headers, starting at offset {}",
+                phnum, ehdr.get_phoff())?;
+        }
+    }
+    writeln!(out)?;
+    writeln!(out, "Program Headers:")?;
+
+    if is_32 {
+        writeln!(
+            out,
+            concat!(
+                "  ",
+                "Type           ",
+                "Offset   ",
+                "VirtAddr   ",
+                "PhysAddr   ",
+                "FileSiz ",
+                "MemSiz  ",
+                "Flg ",
+                "Align"))?;
+    } else {
+        writeln!(
+            out,
+            concat!(
+                "  ",
+                "Type           ",
+                "Offset             ",
+                "VirtAddr           ",
+                "PhysAddr"))?;
+        writeln!(
+            out,
+            concat!(
+                "                 ",
+                "FileSiz            ",
+                "MemSiz              ",
+                "Flags  ",
+                "Align"))?;
+    }
+    Ok(true)
+}
+
+/// The version definition, requirement and symbol sections, in the order
+/// of the file, with the sections they link to. Those with a bad link are
+/// left out, like in readelf.
+fn get_version_sections(shdrs: &[ElfShdr]) -> Vec<(&ElfShdr, &ElfShdr)> {
+    shdrs.iter()
+        .filter(|shdr| {
+            let sh_type = shdr.get_type().get();
+            sh_type == SHT_GNU_VERDEF || sh_type == SHT_GNU_VERNEED || sh_type == SHT_GNU_VERSYM
+        })
+        .filter_map(|shdr| shdrs.get(shdr.get_link() as usize).map(|link| (shdr, link)))
+        .collect()
+}
+
+/// Prints the title of a version definition, requirement or symbol
+/// section. `kind` is the word readelf uses for it.
+fn print_version_section_title(
+    kind: &str, shdr: &ElfShdr, link_shdr: &ElfShdr, num: usize, is_32: bool,
+    out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out)?;
+    if num == 1 {
+        writeln!(out, "Version {} section '{}' contains {} entry:", kind, shdr.get_name(), num)?;
+    } else {
+        writeln!(out, "Version {} section '{}' contains {} entries:", kind, shdr.get_name(), num)?;
+    }
+    if is_32 {
+        write!(out, " Addr: 0x{:08x}", shdr.get_addr())?;
+    } else {
+        write!(out, " Addr: 0x{:016x}", shdr.get_addr())?;
+    }
+    writeln!(
+        out,
+        "  Offset: 0x{:08x}  Link: {} ({})",
+        shdr.get_offset(), shdr.get_link(), link_shdr.get_name())?;
+    Ok(())
+}
+
+/// Contents of a `SHT_NOTE` section or a `PT_NOTE` segment.
+struct NoteArea<'a> {
+    section_name: Option<String>,
+    offset: u64,
+    data: &'a [u8],
+    align: u64,
+    /// Whether the notes are those of a core dump.
+    is_core: bool,
+}
+
+/// Finds the notes the way readelf does: in the note sections, or in the
+/// note segments if there are none or the file is a core dump.
+fn get_note_areas<'a>(elf: &'a ElfFile) -> Result<Vec<NoteArea<'a>>, ElfError> {
+    let mut notes = Vec::new();
+    let is_core = elf.file_type() == ElfEhdrType::ET_CORE;
+    if !is_core {
+        for shdr in elf.sections()? {
+            if shdr.get_type().get() != SHT_NOTE {
+                continue;
+            }
+            notes.push(NoteArea {
+                section_name: Some(shdr.get_name().to_string()),
+                offset: shdr.get_offset(),
+                data: elf.section_data(shdr)?,
+                align: shdr.get_addralign(),
+                is_core,
+            });
+        }
+    }
+    if notes.is_empty() {
+        for phdr in elf.program_headers()? {
+            if phdr.get_type() != ElfPhdrType::PT_NOTE {
+                continue;
+            }
+            let offset = phdr.get_offset();
+            notes.push(NoteArea {
+                section_name: None,
+                offset,
+                data: elf.read_bytes(offset, phdr.get_filesz(), "notes")?,
+                align: phdr.get_align(),
+                is_core,
+            });
+        }
+    }
+    Ok(notes)
+}
+
+fn print_note_area(
+    area: &NoteArea, endianness: Endianness, machine: ElfEhdrMachine, is_32: bool,
+    out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out)?;
+    match area.section_name {
+        Some(ref name) => writeln!(out, "Displaying notes found in: {}", name)?,
+        None => writeln!(
+            out,
+            "Displaying notes found at file offset 0x{:08x} with length 0x{:08x}:",
+            area.offset, area.data.len())?,
+    }
+
+    match ElfNoteIter::new(area.data, endianness, area.align, area.is_core) {
+        Some(mut notes) => {
+            writeln!(out, "  {:<20} {:<10}\tDescription", "Owner", "Data size")?;
+            for note in notes.by_ref() {
+                if let Some(err) = note.print(machine, is_32, out)? {
+                    eprintln!("Error: {}", err);
+                }
+            }
+            if notes.is_truncated() {
+                eprintln!("Warning: note with invalid namesz and/or descsz found");
+            }
+        }
+        None => {
+            eprintln!("Warning: Corrupt note: alignment {}, expecting 4 or 8", area.align);
+        }
+    }
+    Ok(())
+}
+
+/// Prints the preamble and column titles of the section header table.
+/// Returns `false` if there are no sections to print.
+fn print_section_headers_title(
+    shnum: usize, shoff: u64, with_file_header: bool, is_32: bool,
+    out: &mut dyn Write)
+    -> io::Result<bool>
+{
+    if shnum == 0 {
+        writeln!(out)?;
+        writeln!(out, "There are no sections in this file.")?;
+        return Ok(false);
+    }
+
+    if !with_file_header {
+        writeln!(
+            out,
+            "There are {} section headers, starting at offset {:#x}:",
+            shnum, shoff)?;
+    }
+    writeln!(out)?;
+    if shnum > 1 {
+        writeln!(out, "Section Headers:")?;
+    } else {
+        writeln!(out, "Section Header:")?;
+    }
+
+    if is_32 {
+        writeln!(
+            out,
+            concat!(
+                "  ",
+                "[Nr] ",
+                "Name              ",
+                "Type            ",
+                "Addr     ",
+                "Off    ",
+                "Size   ",
+                "ES ",
+                "Flg ",
+                "Lk ",
+                "Inf ",
+                "Al"))?;
+    } else {
+        writeln!(
+            out,
+            concat!(
+                "  ",
+                "[Nr] ",
+                "Name              ",
+                "Type             ",
+                "Address           ",
+                "Offset"))?;
+        writeln!(
+            out,
+            concat!(
+                "       ",
+                "Size              ",
+                "EntSize          ",
+                "Flags  ",
+                "Link  ",
+                "Info  ",
+                "Align"))?;
+    }
+    Ok(true)
+}
+
+fn print_dynamic_section_title(offset: u64, num: usize, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out)?;
+    if num == 1 {
+        writeln!(out, "Dynamic section at offset {:#x} contains {} entry:", offset, num)?;
+    } else {
+        writeln!(out, "Dynamic section at offset {:#x} contains {} entries:", offset, num)?;
+    }
+    writeln!(out, "  Tag        Type                         Name/Value")?;
+    Ok(())
+}
+
+#[derive(PartialEq)]
+enum RelocKind {
+    Rel,
+    Rela,
+    Relr,
+}
+
+impl RelocKind {
+    fn get_name(&self) -> &'static str {
+        match *self {
+            RelocKind::Rel => "rel",
+            RelocKind::Rela => "rela",
+            RelocKind::Relr => "relr",
+        }
+    }
+}
+
+/// A relocation table found through the dynamic section.
+struct DynamicRelocTable {
+    name: &'static str,
+    kind: RelocKind,
+    vaddr: u64,
+    size: u64,
+}
+
+fn get_reloc_section_kind(sh_type: ElfShdrType) -> Option<RelocKind> {
+    use elf::elf_shdr_type::{SHT_REL, SHT_RELA, SHT_RELR};
+
+    match sh_type.get() {
+        SHT_REL => Some(RelocKind::Rel),
+        SHT_RELA => Some(RelocKind::Rela),
+        SHT_RELR => Some(RelocKind::Relr),
+        _ => None,
+    }
+}
+
+/// Lists the relocation tables described by the dynamic section, in the
+/// order readelf prints them. `get_dyn_val` looks up a dynamic entry by tag.
+fn get_dynamic_reloc_tables<F>(get_dyn_val: F) -> Vec<DynamicRelocTable>
+    where F: Fn(u64) -> Option<u64>
+{
+    use elf::elf_dyn_tag::*;
+
+    let plt_kind = match get_dyn_val(DT_PLTREL) {
+        Some(DT_RELA) => RelocKind::Rela,
+        _ => RelocKind::Rel,
+    };
+    let tables = vec![
+        ("REL", RelocKind::Rel, DT_REL, DT_RELSZ),
+        ("RELA", RelocKind::Rela, DT_RELA, DT_RELASZ),
+        ("RELR", RelocKind::Relr, DT_RELR, DT_RELRSZ),
+        ("PLT", plt_kind, DT_JMPREL, DT_PLTRELSZ),
+    ];
+
+    tables.into_iter()
+        .filter_map(|(name, kind, addr_tag, size_tag)| {
+            match (get_dyn_val(addr_tag), get_dyn_val(size_tag)) {
+                (Some(vaddr), Some(size)) if size != 0 => {
+                    Some(DynamicRelocTable { name, kind, vaddr, size })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn print_reloc_section_title(name: &str, offset: u64, num: u64, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    writeln!(out)?;
+    if num == 1 {
+        writeln!(
+            out,
+            "Relocation section '{}' at offset {:#x} contains {} entry:",
+            name, offset, num)?;
+    } else {
+        writeln!(
+            out,
+            "Relocation section '{}' at offset {:#x} contains {} entries:",
+            name, offset, num)?;
+    }
+    Ok(())
+}
+
+/// Like readelf, reports the address of the table from the dynamic
+/// section as its "offset".
+fn print_dynamic_reloc_table_title(table: &DynamicRelocTable, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    writeln!(out)?;
+    writeln!(
+        out,
+        "'{}' relocation section at offset {:#x} contains {} bytes:",
+        table.name, table.vaddr, table.size)?;
+    Ok(())
+}
+
+fn print_reloc_columns_title(is_rela: bool, is_32: bool, out: &mut dyn Write) -> io::Result<()> {
+    match (is_32, is_rela) {
+        (true, true) => writeln!(
+            out,
+            " Offset     Info    Type            Sym.Value  Sym. Name + Addend")?,
+        (true, false) => writeln!(
+            out,
+            " Offset     Info    Type            Sym.Value  Sym. Name")?,
+        (false, true) => writeln!(
+            out,
+            "  Offset          Info           Type           Sym. Value    Sym. Name + Addend")?,
+        (false, false) => writeln!(
+            out,
+            "  Offset          Info           Type           Sym. Value    Sym. Name")?,
+    }
+    Ok(())
+}
+
+fn print_relr_addrs(addrs: &[u64], is_32: bool, out: &mut dyn Write) -> io::Result<()> {
+    if addrs.len() == 1 {
+        writeln!(out, "  {} offset", addrs.len())?;
+    } else {
+        writeln!(out, "  {} offsets", addrs.len())?;
+    }
+    for addr in addrs {
+        if is_32 {
+            writeln!(out, "{:08x}", addr)?;
+        } else {
+            writeln!(out, "{:016x}", addr)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_no_relocs(found: bool, use_dynamic: bool, out: &mut dyn Write) -> io::Result<()> {
+    if found {
+        return Ok(());
+    }
+    writeln!(out)?;
+    if use_dynamic {
+        writeln!(out, "There are no dynamic relocations in this file.")?;
+    } else {
+        writeln!(out, "There are no relocations in this file.")?;
+    }
+    Ok(())
+}
+
+fn is_symbol_table(sh_type: ElfShdrType, dynamic_only: bool) -> bool {
+    use elf::elf_shdr_type::{SHT_SYMTAB, SHT_DYNSYM};
+
+    match sh_type.get() {
+        SHT_DYNSYM => true,
+        SHT_SYMTAB => !dynamic_only,
+        _ => false,
+    }
+}
+
+fn print_symbol_table_title(name: &str, num: usize, is_32: bool, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    writeln!(out)?;
+    if num == 1 {
+        writeln!(out, "Symbol table '{}' contains {} entry:", name, num)?;
+    } else {
+        writeln!(out, "Symbol table '{}' contains {} entries:", name, num)?;
+    }
+    if is_32 {
+        writeln!(out, "   Num:    Value  Size Type    Bind   Vis      Ndx Name")?;
+    } else {
+        writeln!(out, "   Num:    Value          Size Type    Bind   Vis      Ndx Name")?;
+    }
+    Ok(())
+}
+
+fn print_section_flags_key(machine: ElfEhdrMachine, osabi: ElfEiOsAbi, out: &mut dyn Write)
+    -> io::Result<()>
+{
+    writeln!(out, "Key to Flags:")?;
+    writeln!(out, "  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),")?;
+    writeln!(out, "  L (link order), O (extra OS processing required), G (group), T (TLS),")?;
+    writeln!(out, "  C (compressed), x (unknown), o (OS specific), E (exclude),")?;
+    write!(out, "  ")?;
+    // The same OS/ABIs as the letters in `ElfShdrFlags::get_letters`.
+    let is_gnu = osabi == ElfEiOsAbi::ELFOSABI_GNU || osabi == ElfEiOsAbi::ELFOSABI_FREEBSD;
+    if is_gnu {
+        write!(out, "R (retain), ")?;
+    }
+    if is_gnu || osabi == ElfEiOsAbi::ELFOSABI_NONE {
+        write!(out, "D (mbind), ")?;
+    }
+    if machine == ElfEhdrMachine::EM_X86_64 {
+        write!(out, "l (large), ")?;
+    } else if machine == ElfEhdrMachine::EM_ARM {
+        write!(out, "y (purecode), ")?;
+    } else if machine == ElfEhdrMachine::EM_PPC {
+        write!(out, "v (VLE), ")?;
+    }
+    writeln!(out, "p (processor specific)")?;
+    Ok(())
+}
+
+/// Prints the source file and line of each address given to the
+/// `addr2line` subcommand, or of each one read from the standard input if
+/// none are given, like GNU addr2line does.
+fn addr2line(options: &clap::ArgMatches, path: &str) -> Result<(), ElfError> {
+    let file = File::open(path)?;
+    let map = unsafe { Mmap::map(&file)? };
+    let elf = ElfFile::parse(&map)?;
+    let table = LineTable::new(&Dwarf::load(&elf)?)?;
+    let shdrs = elf.sections()?;
+    let symbols = get_addr2line_symbols(&elf)?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let mut translate = |address: &str| -> std::io::Result<()> {
+        let mut address = parse_address(address);
+        // Addresses are as wide as the class, and MIPS sign-extends the
+        // 32-bit ones.
+        if elf.is_32() {
+            address &= 0xffff_ffff;
+            if elf.machine() == ElfEhdrMachine::EM_MIPS {
+                address = address as u32 as i32 as i64 as u64;
+            }
+        }
+        if options.get_flag("addresses") {
+            if elf.is_32() {
+                writeln!(out, "0x{:08x}", address & 0xffff_ffff)?;
+            } else {
+                writeln!(out, "0x{:016x}", address)?;
+            }
+        }
+        match find_source_line(shdrs, &table, &symbols, address) {
+            Some((file, line, discriminator)) => {
+                let file = match file {
+                    Some(file) if options.get_flag("basenames") => {
+                        file.rsplit('/').next().unwrap_or(file)
+                    }
+                    Some(file) => file,
+                    None => "??",
+                };
+                if line == 0 {
+                    write!(out, "{}:?", file)?;
+                } else {
+                    write!(out, "{}:{}", file, line)?;
+                }
+                if discriminator != 0 {
+                    write!(out, " (discriminator {})", discriminator)?;
+                }
+                writeln!(out)?;
+            }
+            None => writeln!(out, "??:0")?,
+        }
+        // Answer each address read from a pipe as soon as it's read.
+        out.flush()
+    };
+
+    let addresses = get_repeatable_values(options, "ADDR");
+    if addresses.is_empty() {
+        let stdin = std::io::stdin();
+        for line in stdin.lock().lines() {
+            for address in line?.split_whitespace() {
+                translate(address)?;
+            }
+        }
+    } else {
+        for address in addresses {
+            translate(address)?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints what the notes of a core dump tell about the crashed process,
+/// for the `core` subcommand. Returns `false` if the file isn't a core
+/// dump.
+fn core(path: &str, out: &mut dyn Write) -> Result<bool, ElfError> {
+    let file = File::open(path)?;
+    let map = unsafe { Mmap::map(&file)? };
+    let elf = ElfFile::parse(&map)?;
+    if elf.file_type() != ElfEhdrType::ET_CORE {
+        return Ok(false);
+    }
+    let core = CoreFile::new(&elf)?;
+    for warning in &core.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    core.print(out)?;
+    Ok(true)
+}
+
+/// The options of the `patch` subcommand that ask for an edit.
+const PATCH_OPTIONS: &[&str] = &[
+    "set-interpreter", "set-rpath", "set-runpath", "set-soname", "add-needed", "remove-needed",
+];
+
+/// Returns the edit the option `name` of the `patch` subcommand asks for.
+fn get_patch_op(name: &str, value: &str) -> PatchOp {
+    let value = value.to_string();
+    match name {
+        "set-interpreter" => PatchOp::SetInterpreter(value),
+        "set-rpath" => PatchOp::SetRpath(value),
+        "set-runpath" => PatchOp::SetRunpath(value),
+        "set-soname" => PatchOp::SetSoname(value),
+        "add-needed" => PatchOp::AddNeeded(value),
+        _ => PatchOp::RemoveNeeded(value),
+    }
+}
+
+/// Returns the edits the options of the `patch` subcommand ask for, in the
+/// order they were given in, which matters when a library is both added
+/// and removed.
+fn get_patch_ops(options: &clap::ArgMatches) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    for &name in PATCH_OPTIONS {
+        let indices = options.indices_of(name).into_iter().flatten();
+        let values = options.get_many::<String>(name).into_iter().flatten();
+        ops.extend(indices.zip(values).map(|(index, value)| (index, get_patch_op(name, value))));
+    }
+    ops.sort_by_key(|&(index, _)| index);
+    ops.into_iter().map(|(_, op)| op).collect()
+}
+
+/// Writes `data` to a new file next to `path` and renames it over `path`,
+/// so that `path` is either replaced whole or left as it was.
+fn replace_file(path: &Path, data: &[u8], permissions: std::fs::Permissions)
+                -> std::io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.writeork-{}", name, std::process::id()));
+    let result = std::fs::write(&temp, data)
+        .and_then(|_| std::fs::set_permissions(&temp, permissions))
+        .and_then(|_| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Patches the file at `path` for the `patch` subcommand and writes it to
+/// `--output`, with the same permissions, or in place of itself, through
+/// symbolic links.
+fn patch(options: &clap::ArgMatches, path: &str, ops: &[PatchOp]) -> Result<(), ElfError> {
+    let data = std::fs::read(path)?;
+    let (patched, warnings) = patch_file(&ElfFile::parse(&data)?, ops)?;
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    let permissions = std::fs::metadata(path)?.permissions();
+    match options.get_one::<String>("output").map(String::as_str) {
+        Some(output) => replace_file(Path::new(output), &patched, permissions)?,
+        None => replace_file(&std::fs::canonicalize(path)?, &patched, permissions)?,
+    }
+    Ok(())
+}
+
+/// The symbols addr2line falls back to when an address has no line
+/// information: those of `.symtab`, or of `.dynsym` if there's none,
+/// without the null symbol.
+fn get_addr2line_symbols(elf: &ElfFile) -> Result<Vec<ElfSym>, ElfError> {
+    use writeork::elf::elf_shdr_type::{SHT_SYMTAB, SHT_DYNSYM};
+
+    let shdrs = elf.sections()?;
+    let index = shdrs.iter().position(|s| s.get_type().get() == SHT_SYMTAB)
+        .or_else(|| shdrs.iter().position(|s| s.get_type().get() == SHT_DYNSYM));
+    Ok(match index {
+        Some(index) => elf.symbols(index)?.into_iter().skip(1).collect(),
+        None => Vec::new(),
+    })
+}
+
+/// Finds the file, line and discriminator of `address` like addr2line
+/// does. Only addresses within allocated sections are looked up. Those
+/// without line information get line 0 and the file of the function
+/// that contains them, if the symbols tell it. Returns `None` if the
+/// address can't be attributed to anything.
+fn find_source_line<'a>(
+    shdrs: &[ElfShdr], table: &'a LineTable, symbols: &'a [ElfSym], address: u64)
+    -> Option<(Option<&'a str>, u64, u64)>
+{
+    use writeork::elf::elf_shdr_flags::SHF_ALLOC;
+
+    let sections: Vec<usize> = (0..shdrs.len())
+        .filter(|&i| {
+            let shdr = &shdrs[i];
+            shdr.get_flags().contains(SHF_ALLOC)
+                && shdr.get_addr() <= address
+                && address - shdr.get_addr() < shdr.get_size()
+        })
+        .collect();
+    if sections.is_empty() {
+        return None;
+    }
+    if let Some(location) = table.find(address) {
+        return Some((Some(location.file), location.line, location.discriminator));
+    }
+    sections.into_iter()
+        .filter_map(|index| find_function_file(symbols, index, &shdrs[index], address))
+        .next()
+        .map(|file| (file, 0, 0))
+}
+
+/// Finds the function symbol of section `index`, whose header is `shdr`,
+/// closest before `address` the way binutils' `elf_find_function` does,
+/// and returns the name of the `STT_FILE` symbol it comes under, if it's
+/// known. Global symbols only come under a file symbol if no file symbol
+/// follows any other symbol. Returns `None` if there's no such function.
+fn find_function_file<'a>(symbols: &'a [ElfSym], index: usize, shdr: &ElfShdr, address: u64)
+    -> Option<Option<&'a str>>
+{
+    use writeork::elf::elf_sym_bind::STB_LOCAL;
+    use writeork::elf::elf_sym_type::{STT_NOTYPE, STT_OBJECT, STT_SECTION, STT_FILE, STT_TLS};
+    use writeork::elf::elf_sym_visibility::STV_HIDDEN;
+
+    let mut file = None;
+    let mut symbol_seen = false;
+    let mut file_after_symbol = false;
+    let mut found = None;
+    let mut low = 0;
+    let mut found_size = 0;
+    for sym in symbols {
+        let sym_type = sym.get_type().get();
+        if sym_type == STT_FILE {
+            file = Some(sym.get_name());
+            file_after_symbol = symbol_seen;
+            continue;
+        }
+        symbol_seen = true;
+
+        let is_local = sym.get_bind().get() == STB_LOCAL;
+        if sym_type == STT_SECTION || sym_type == STT_OBJECT || sym_type == STT_TLS
+            || sym.get_shndx().get() as usize != index
+        {
+            continue;
+        }
+        // Hidden local labels without a size, e.g. those of annobin,
+        // aren't functions.
+        if sym.get_size() == 0 && is_local && sym_type == STT_NOTYPE
+            && sym.get_visibility().get() == STV_HIDDEN
+        {
+            continue;
+        }
+        let size = sym.get_size().max(1);
+        // Compared as offsets into the section, like binutils does, so
+        // symbols before its start, e.g. `__bss_start`, wrap around.
+        let value = sym.get_value().wrapping_sub(shdr.get_addr());
+        if value <= address - shdr.get_addr() && (value > low || (value == low && size > found_size)) {
+            low = value;
+            found_size = size;
+            found = Some(file.filter(|_| is_local || !file_after_symbol));
+        }
+    }
+    found
+}
+
+/// Parses an address the way addr2line does, with `strtoul` in base 16:
+/// an optional `0x`, then hex digits up to the first character that isn't
+/// one. Addresses without any digits are 0.
+fn parse_address(s: &str) -> u64 {
+    let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    s.chars()
+        .map_while(|c| c.to_digit(16))
+        .fold(0u64, |address, digit| address.checked_mul(16)
+              .map_or(u64::MAX, |a| a | digit as u64))
 }
 
 fn process_args_and_work() {
     let options =
-        App::new("writeork")
+        Command::new("writeork")
         .version("0.0.1")
         .author("Michael K. Pankov <work@michaelpankov.com>")
         .about(
             concat!("Parse and output information from ELF files.",
                     " Similar to readelf, but is not fully compatible."))
-        .args_from_usage(
-            "-h --file-header     'Display ELF file header'
-             -l --program-headers 'Display the program headers'
-                --segments        'An alias for --program-headers'
-             <FILE> 'ELF file to parse'")
+        // -h and -V are readelf's, so help and the version are long only.
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .disable_help_subcommand(true)
+        .args([
+            arg!(-h --"file-header"     "Display ELF file header"),
+            arg!(-l --"program-headers" "Display the program headers"),
+            arg!(   --segments          "An alias for --program-headers"),
+            arg!(-S --"section-headers" "Display the sections' header"),
+            arg!(   --sections          "An alias for --section-headers"),
+            arg!(-d --dynamic           "Display the dynamic section (if present)"),
+            arg!(-r --relocs            "Display the relocations (if present)"),
+            arg!(-s --syms              "Display the symbol table"),
+            arg!(   --symbols           "An alias for --syms"),
+            arg!(   --"dyn-syms"        "Display the dynamic symbol table"),
+            arg!(-V --"version-info"    "Display the version sections (if present)"),
+            arg!(-n --notes             "Display the core notes (if present)"),
+            arg!(-c --"archive-index"   "Display the symbol/file index in an archive"),
+            arg!(-x --"hex-dump" <SECTION> "Dump the contents of section <name|index> as bytes")
+                .action(ArgAction::Append),
+            arg!(-p --"string-dump" <SECTION> "Dump the contents of section <name|index> as strings")
+                .action(ArgAction::Append),
+            arg!(-R --"relocated-dump" <SECTION> "Dump the relocated contents of section <name|index>")
+                .action(ArgAction::Append),
+            arg!(-z --decompress        "Decompress section before dumping it"),
+            // Like readelf's, the letters of -w can be attached, as in -wi,
+            // and the names of --debug-dump only follow an `=`, so that
+            // `--debug-dump FILE` dumps them all. A lone -w takes the next
+            // argument as its letters, so it can't come right before the
+            // file.
+            arg!(-w [LETTERS] "Display the DWARF debug sections: -wi, -wf or -wF, all if none")
+                .id("w")
+                .action(ArgAction::Append)
+                .default_missing_value(""),
+            arg!(--"debug-dump" [SECTIONS] "Display the DWARF debug sections: info, frames or frames-interp, all if none")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .default_missing_value(""),
+            arg!(-D --"use-dynamic"     "Use the dynamic section info when displaying relocations"),
+            arg!(   --mmap              "Map the file into memory (default)"),
+            arg!(   --"no-mmap"         "Read the file into memory instead of mapping it"),
+            arg!(   --compat <TOOL>     "Match the output of another tool exactly (readelf)"),
+            arg!(   --output <FORMAT>   "Output format: text (default) or json"),
+            arg!(   --help              "Print help").action(ArgAction::Help),
+            arg!(   --version           "Print version").action(ArgAction::Version),
+            arg!([FILE] "ELF file or archive to parse"),
+        ])
+        .subcommand(
+            Command::new("addr2line")
+            .about("Convert addresses into file names and line numbers")
+            .args([
+                arg!(-a --addresses "Show the addresses before their locations"),
+                arg!(-s --basenames "Strip the directories from file names"),
+                arg!(<FILE>         "ELF file to read the debugging information of"),
+                arg!([ADDR]...      "Addresses to convert, in hex; read from stdin if none"),
+            ]))
+        .subcommand(
+            Command::new("core")
+            .about("Show the crashing signal, threads, mapped files and auxiliary vector of a core dump")
+            .arg(arg!(<FILE> "Core dump to read")))
+        .subcommand(
+            Command::new("patch")
+            .about("Change the interpreter, library search paths, soname or needed libraries of a dynamically linked file")
+            .args([
+                arg!(--"set-interpreter" <PATH>  "Set the program interpreter"),
+                arg!(--"set-rpath" <RPATH>       "Set DT_RPATH"),
+                arg!(--"set-runpath" <RUNPATH>   "Set DT_RUNPATH"),
+                arg!(--"set-soname" <SONAME>     "Set DT_SONAME"),
+                arg!(--"add-needed" <LIBRARY>    "Add a DT_NEEDED entry for a library")
+                    .action(ArgAction::Append),
+                arg!(--"remove-needed" <LIBRARY> "Remove the DT_NEEDED entries of a library")
+                    .action(ArgAction::Append),
+                arg!(-o --output <FILE>          "Write the patched file there instead of over FILE"),
+                arg!(<FILE>                      "ELF file to patch"),
+            ]))
         .get_matches();
-    work(options);
+    if let Some(options) = options.subcommand_matches("addr2line") {
+        let path = options.get_one::<String>("FILE").map(String::as_str).unwrap();
+        if let Err(err) = addr2line(options, path) {
+            if is_broken_pipe(&err) {
+                std::process::exit(1);
+            }
+            eprintln!("writeork: Error: '{}': {}", path, err);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(options) = options.subcommand_matches("core") {
+        let path = options.get_one::<String>("FILE").map(String::as_str).unwrap();
+        let stdout = io::stdout();
+        match core(path, &mut stdout.lock()) {
+            Ok(true) => {}
+            Err(ref err) if is_broken_pipe(err) => std::process::exit(1),
+            Ok(false) => {
+                eprintln!("writeork: Error: '{}': Not a core file", path);
+                std::process::exit(1);
+            }
+            Err(err) => {
+                eprintln!("writeork: Error: '{}': {}", path, err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if let Some(options) = options.subcommand_matches("patch") {
+        let path = options.get_one::<String>("FILE").map(String::as_str).unwrap();
+        let ops = get_patch_ops(options);
+        if ops.is_empty() {
+            eprintln!("writeork: Error: no changes given to patch");
+            std::process::exit(1);
+        }
+        if let Err(err) = patch(options, path, &ops) {
+            eprintln!("writeork: Error: '{}': {}", path, err);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(tool) = options.get_one::<String>("compat").map(String::as_str) {
+        if tool != "readelf" {
+            eprintln!("writeork: Error: unsupported --compat value '{}'", tool);
+            std::process::exit(1);
+        }
+    }
+    if let Some(format) = options.get_one::<String>("output").map(String::as_str) {
+        if format != "text" && format != "json" {
+            eprintln!("writeork: Error: unsupported --output value '{}'", format);
+            std::process::exit(1);
+        }
+        if format == "json" && options.get_flag("archive-index") {
+            eprintln!("writeork: Error: --archive-index doesn't support --output=json");
+            std::process::exit(1);
+        }
+        if format == "json" && (options.contains_id("w") || options.contains_id("debug-dump")) {
+            eprintln!("writeork: Error: --debug-dump doesn't support --output=json");
+            std::process::exit(1);
+        }
+    }
+    let path = match options.get_one::<String>("FILE") {
+        Some(path) => path,
+        None => {
+            eprintln!("writeork: Error: no ELF file given");
+            std::process::exit(1);
+        }
+    };
+    let stdout = io::stdout();
+    match work(&options, path, &mut stdout.lock()) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        // Like readelf killed by SIGPIPE, stop quietly when whoever reads
+        // the output, e.g. `head`, is gone.
+        Err(ref err) if is_broken_pipe(err) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("writeork: Error: '{}': {}", path, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Whether `err` is a write to a pipe that nobody reads anymore.
+fn is_broken_pipe(err: &ElfError) -> bool {
+    match *err {
+        ElfError::Io(ref err) => err.kind() == io::ErrorKind::BrokenPipe,
+        _ => false,
+    }
 }
 
 fn asserts() {
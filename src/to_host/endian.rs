@@ -0,0 +1,93 @@
+//! Integers stored in the byte order of a file.
+//!
+//! [`U16`], [`U32`] and [`U64`] keep the bytes as they are in the file and
+//! only hand out their value through `get`, which takes the byte order, so
+//! a field can't be used without being converted. The byte order is either
+//! fixed at compile time with [`LittleEndian`] and [`BigEndian`], or chosen
+//! at runtime with [`Endianness`], which is what the raw ELF structures use
+//! by default. The storage types have an alignment of 1, so structures made
+//! of them can be read from any offset.
+
+use ::std::fmt::{Debug, Formatter};
+use ::std::marker::PhantomData;
+use super::Endianness;
+
+/// A byte order to read and write integers in.
+pub trait ByteOrder: Copy + Debug {
+    fn is_big_endian(self) -> bool;
+}
+
+impl ByteOrder for Endianness {
+    fn is_big_endian(self) -> bool {
+        self == Endianness::BE
+    }
+}
+
+/// Little endian byte order, fixed at compile time.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct LittleEndian;
+
+impl ByteOrder for LittleEndian {
+    fn is_big_endian(self) -> bool {
+        false
+    }
+}
+
+/// Big endian byte order, fixed at compile time.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct BigEndian;
+
+impl ByteOrder for BigEndian {
+    fn is_big_endian(self) -> bool {
+        true
+    }
+}
+
+macro_rules! endian_int {
+    ( $(#[$attr:meta])* $name:ident, $t:ty, $size:expr ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy)]
+        pub struct $name<E: ByteOrder = Endianness>([u8; $size], PhantomData<E>);
+
+        impl<E: ByteOrder> $name<E> {
+            pub fn new(endianness: E, value: $t) -> Self {
+                let bytes = if endianness.is_big_endian() {
+                    value.to_be_bytes()
+                } else {
+                    value.to_le_bytes()
+                };
+                $name(bytes, PhantomData)
+            }
+            /// Returns the value in host byte order.
+            pub fn get(&self, endianness: E) -> $t {
+                if endianness.is_big_endian() {
+                    <$t>::from_be_bytes(self.0)
+                } else {
+                    <$t>::from_le_bytes(self.0)
+                }
+            }
+            pub fn set(&mut self, endianness: E, value: $t) {
+                *self = $name::new(endianness, value);
+            }
+        }
+
+        /// Shows the bytes as they are stored, since the byte order isn't
+        /// known here.
+        impl<E: ByteOrder> Debug for $name<E> {
+            fn fmt(&self, fmt: &mut Formatter) -> ::std::fmt::Result {
+                write!(fmt, "{}({:02x?})", stringify!($name), self.0)
+            }
+        }
+    }
+}
+
+endian_int!(
+    /// A `u16` stored in byte order `E`.
+    U16, u16, 2);
+endian_int!(
+    /// A `u32` stored in byte order `E`.
+    U32, u32, 4);
+endian_int!(
+    /// A `u64` stored in byte order `E`.
+    U64, u64, 8);
@@ -1,3 +1,15 @@
+//! Conversion of integers and structures from the byte order of a file to
+//! the byte order of the host, and back.
+//!
+//! [`to_host_copy::ToHostCopy`] and [`to_host_in_place::ToHostInPlace`]
+//! convert single fields, [`ToHostCopyStruct`] and [`ToHostInPlaceStruct`]
+//! convert all the fields of a raw ELF structure at once.
+//! [`to_target_copy::ToTargetCopy`] and [`ToTargetCopyStruct`] go the
+//! other way, for writing files.
+//!
+//! Structures made of the [`endian`] storage types don't need converting:
+//! their fields are only readable in host byte order.
+
 #[macro_use]
 pub mod swap_copy;
 #[macro_use]
@@ -6,18 +18,29 @@ pub mod swap_in_place;
 pub mod to_host_in_place;
 #[macro_use]
 pub mod to_host_copy;
+pub mod to_target_copy;
+pub mod endian;
 
+/// Byte order of the data in a file, as told by `EI_DATA`.
 #[allow(dead_code)]
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Endianness {
     LE,
     BE,
 }
 
+/// Converts all the fields of a structure to host byte order in place.
 pub trait ToHostInPlaceStruct {
     fn to_host_in_place(&mut self, endianness: &Endianness);
 }
 
+/// Returns a copy of a structure with all the fields in host byte order.
 pub trait ToHostCopyStruct {
     fn to_host_copy(&self, endianness: &Endianness) -> Self;
 }
+
+/// Returns a copy of a structure in host byte order with all the fields
+/// in the byte order of a file.
+pub trait ToTargetCopyStruct {
+    fn to_target_copy(&self, endianness: &Endianness) -> Self;
+}
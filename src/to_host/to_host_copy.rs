@@ -1,6 +1,7 @@
 use to_host::swap_copy::SwapCopy;
 use super::Endianness;
 
+/// Returns a copy of a value read in `endianness`, in host byte order.
 pub trait ToHostCopy: SwapCopy + Sized + Copy {
     fn to_host_copy(&self, endianness: &Endianness) -> Self {
         use super::Endianness::*;
@@ -26,18 +27,17 @@ impl ToHostCopy for u32 { }
 
 impl ToHostCopy for u16 { }
 
-#[macro_export]
+impl ToHostCopy for i64 { }
+
+impl ToHostCopy for i32 { }
+
+/// Implements `ToHostCopy` for a newtype with `get()` and `From` over `$t`.
 macro_rules! to_host_copy_wrapper {
     ( $wrapper:ty, $t:ty ) => {
         impl ::to_host::to_host_copy::ToHostCopy for $wrapper {
             fn to_host_copy(&self, endianness: &::to_host::Endianness) -> Self {
-                let self_: &$t = unsafe {
-                    ::std::mem::transmute(self)
-                };
-                let result_ = self_.to_host_copy(endianness);
-                unsafe {
-                    ::std::mem::transmute(result_)
-                }
+                let data: $t = self.get();
+                <$wrapper>::from(data.to_host_copy(endianness))
             }
         }
     }
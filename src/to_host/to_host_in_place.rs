@@ -1,6 +1,7 @@
 use to_host::swap_in_place::SwapInPlace;
 use super::Endianness;
 
+/// Converts a value read in `endianness` to host byte order in place.
 pub trait ToHostInPlace: SwapInPlace {
     fn to_host_in_place(&mut self, endianness: &Endianness) {
         use super::Endianness::*;
@@ -22,15 +23,14 @@ impl ToHostInPlace for u32 { }
 
 impl ToHostInPlace for u16 { }
 
-#[macro_export]
+/// Implements `ToHostInPlace` for a newtype with `get()` and `From` over `$t`.
 macro_rules! to_host_in_place_wrapper {
     ( $wrapper:ty, $t:ty ) => {
         impl ::to_host::to_host_in_place::ToHostInPlace for $wrapper {
             fn to_host_in_place(&mut self, endianness: &::to_host::Endianness) {
-                let self_: &mut $t = unsafe {
-                    ::std::mem::transmute(self)
-                };
-                self_.to_host_in_place(endianness);
+                let mut data: $t = self.get();
+                data.to_host_in_place(endianness);
+                *self = <$wrapper>::from(data);
             }
         }
     }
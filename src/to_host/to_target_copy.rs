@@ -0,0 +1,33 @@
+use to_host::swap_copy::SwapCopy;
+use super::Endianness;
+
+/// Returns a copy of a host value in `endianness`, ready to be written to
+/// a file. It's the same swap as `ToHostCopy`, in the other direction.
+pub trait ToTargetCopy: SwapCopy + Sized + Copy {
+    fn to_target_copy(&self, endianness: &Endianness) -> Self {
+        use super::Endianness::*;
+
+        match *endianness {
+            BE => if cfg!(target_endian = "little") {
+                SwapCopy::swap_copy(self)
+            } else {
+                *self
+            },
+            LE => if cfg!(target_endian = "big") {
+                SwapCopy::swap_copy(self)
+            } else {
+                *self
+            }
+        }
+    }
+}
+
+impl ToTargetCopy for u64 { }
+
+impl ToTargetCopy for u32 { }
+
+impl ToTargetCopy for u16 { }
+
+impl ToTargetCopy for i64 { }
+
+impl ToTargetCopy for i32 { }
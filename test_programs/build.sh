@@ -0,0 +1,75 @@
+#!/bin/sh
+# Rebuilds the fixtures of the integration tests. The objects for other
+# machines are compiled from LLVM IR with llc, the executables need gcc
+# with 64- and 32-bit x86 support. After rebuilding, regenerate the
+# expected outputs with test_results/update.sh.
+set -e
+cd "$(dirname "$0")"
+
+for target in aarch64 armv7 armeb i386 mips mipsel mips64 mips64el \
+              powerpc64 powerpc64le riscv32 riscv64 x86_64; do
+    llc -filetype=obj -relocation-model=pic -mtriple=$target-linux-gnu \
+        src/fixture.ll -o $target.o
+done
+
+gcc -O2 -fPIE -pie -o x86_64-pie src/fixture.c
+# Requires versions from libm of a function libc defines too.
+gcc -O2 -fPIE -pie -o x86_64-libm src/frexp.c -lm
+gcc -m32 -O2 -fPIC -shared -nostdlib -o i386-shlib src/fixture.c
+
+# Debug sections compressed in each of the ways objcopy can do it.
+for target in x86_64:-m64 i386:-m32; do
+    arch=${target%%:*}
+    gcc ${target#*:} -g -O2 -c -o $arch-debug.o src/fixture.c
+    for type in zlib zlib-gnu zstd; do
+        objcopy --compress-debug-sections=$type $arch-debug.o $arch-$type.o
+    done
+    rm $arch-debug.o
+done
+
+# Line programs of each DWARF version for addr2line. lines.c has inlined
+# code from a header and loops that get discriminators.
+for version in 2 3 4 5; do
+    gcc -g -gdwarf-$version -O2 -fPIC -shared -nostdlib \
+        -o x86_64-dwarf$version src/fixture.c src/lines.c
+done
+gcc -m32 -g -O2 -fPIC -shared -nostdlib -o i386-dwarf src/fixture.c src/lines.c
+
+# More sections than e_shnum can count, all named .x, with a symbol in one
+# whose index is in the range of the reserved ones.
+awk 'BEGIN {
+    for (i = 0; i < 65290; i++) {
+        printf "\t.section .x,\"a\",@progbits,unique,%d\n", i
+        if (i == 65284) print "\t.globl high\nhigh:\n\t.byte 1"
+    }
+    print "\t.text\n\t.globl low\nlow:\n\tret"
+}' > /tmp/writeork-sections.s
+gcc -m32 -c -o i386-sections.o /tmp/writeork-sections.s
+rm /tmp/writeork-sections.s
+
+# Static libraries of some of the objects above: x86_64-zlib-gnu.o needs
+# the long name table. The thin archive refers to the objects next to it.
+# The BSD archive, which readelf can't read, is only used by
+# tests/archives.rs.
+rm -f libfixture*.a
+ar rcs libfixture.a x86_64.o x86_64-zlib-gnu.o i386.o
+ar rcsT libfixture-thin.a x86_64.o x86_64-zlib-gnu.o i386.o
+SYM64_THRESHOLD=0 llvm-ar rcs --format=gnu libfixture-sym64.a x86_64.o x86_64-zlib-gnu.o i386.o
+llvm-ar rcs --format=bsd libfixture-bsd.a x86_64.o x86_64-zlib-gnu.o i386.o
+
+# Core dumps of a program that crashes while two other threads wait. The
+# kernel must write them to "core" in the current directory, which
+# /proc/sys/kernel/core_pattern decides. Clearing coredump_filter leaves
+# out the memory of the process to keep them small. The AArch64 one is
+# written by a script instead, with memory for the auxiliary vector strings.
+dir=/tmp/writeork-core
+mkdir -p $dir
+for target in x86_64:-m64 i386:-m32; do
+    arch=${target%%:*}
+    gcc ${target#*:} -O1 -static -nostdlib -fno-pic -no-pie -fno-stack-protector \
+        -o $dir/crash-$arch src/crash.c
+    (cd $dir && ulimit -c unlimited && echo 0 > /proc/self/coredump_filter &&
+        rm -f core && { ./crash-$arch || true; } && mv core $arch-core)
+    mv $dir/$arch-core .
+done
+python3 src/aarch64-core.py aarch64-core
@@ -0,0 +1,68 @@
+#!/usr/bin/env python3
+"""Writes a small AArch64 core dump to the file named by the first
+argument, for the core dump fixtures: there's no AArch64 machine to crash
+a program on. It has the notes Linux writes for a process with two threads
+whose first one got SIGSEGV at address 0x10, and a segment with the
+strings the auxiliary vector points at."""
+
+import struct
+import sys
+
+PID = 4242
+STRINGS_ADDR = 0xfffff7ff0000
+STRINGS = b"/usr/bin/crash\0aarch64\0"
+
+
+def note(name, n_type, desc):
+    name = name + b"\0"
+    pad = lambda b: b + b"\0" * (-len(b) % 4)
+    return struct.pack("<III", len(name), len(desc), n_type) + pad(name) + pad(desc)
+
+
+def prstatus(pid, signal, regs):
+    timeval = lambda usec: struct.pack("<qq", usec // 1000000, usec % 1000000)
+    return (struct.pack("<iiiHxxQQiiii", signal, 0, 0, signal, 0, 0, pid, PID - 42, PID, PID - 42)
+            + timeval(1500) + timeval(250) + timeval(0) + timeval(0)
+            + struct.pack("<34Q", *regs) + struct.pack("<ixxxx", 1))
+
+
+def fpregset(low_doubles):
+    vregs = b"".join(struct.pack("<dQ", d, 0) for d in low_doubles)
+    vregs += b"\0" * (32 * 16 - len(vregs))
+    return vregs + struct.pack("<IIQ", 0x10, 0x3000000, 0)
+
+
+crashing = [i * 0x1111 for i in range(31)] + [0xfffffffff000, 0x400124, 0x60000000]
+waiting = [0] * 8 + [101] + [0] * 22 + [0xfffff7fe8000, 0x4000f8, 0x80000000]
+notes = b"".join([
+    note(b"CORE", 1, prstatus(PID, 11, crashing)),
+    note(b"CORE", 3, struct.pack("<BBBBxxxxQIIiiii16s80s", 0, ord("R"), 0, 0, 0x400600,
+                                 1000, 1000, PID, PID - 42, PID, PID - 42,
+                                 b"crash", b"/usr/bin/crash --now ")),
+    note(b"CORE", 0x53494749, struct.pack("<iiixxxxQ", 11, 0, 1, 0x10) + b"\0" * 104),
+    note(b"CORE", 6, struct.pack("<34Q",
+                                 33, 0xfffff7fff000, 16, 0xefffff, 6, 4096, 17, 100,
+                                 3, 0x400040, 4, 56, 5, 3, 7, 0, 9, 0x400100, 11, 1000,
+                                 23, 0, 25, STRINGS_ADDR + 0x100, 31, STRINGS_ADDR,
+                                 15, STRINGS_ADDR + 15, 26, 0x2, 51, 5120, 0, 0)),
+    note(b"CORE", 0x46494c45,
+         struct.pack("<8Q", 2, 4096, 0x400000, 0x401000, 0, 0x410000, 0x411000, 0)
+         + b"/usr/bin/crash\0/usr/bin/crash\0"),
+    note(b"CORE", 2, fpregset([6.5, -0.125])),
+    note(b"LINUX", 0x401, struct.pack("<Q", 0xfffff7ff8700)),
+    note(b"CORE", 1, prstatus(PID + 1, 0, waiting)),
+    note(b"CORE", 2, fpregset([])),
+    note(b"LINUX", 0x401, struct.pack("<Q", 0xfffff7fe88f0)),
+])
+
+EHDR_SIZE, PHDR_SIZE = 64, 56
+notes_offset = EHDR_SIZE + 2 * PHDR_SIZE
+strings_offset = notes_offset + len(notes)
+ehdr = (b"\x7fELF" + bytes([2, 1, 1, 0]) + b"\0" * 8
+        + struct.pack("<HHIQQQIHHHHHH", 4, 183, 1, 0, EHDR_SIZE, 0, 0,
+                      EHDR_SIZE, PHDR_SIZE, 2, 64, 0, 0))
+phdrs = (struct.pack("<IIQQQQQQ", 4, 0, notes_offset, 0, 0, len(notes), 0, 4)
+         + struct.pack("<IIQQQQQQ", 1, 4, strings_offset, STRINGS_ADDR, 0,
+                       len(STRINGS), 0x1000, 0x1000))
+with open(sys.argv[1], "wb") as f:
+    f.write(ehdr + phdrs + notes + STRINGS)
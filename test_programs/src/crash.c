@@ -0,0 +1,60 @@
+/* Starts two threads that wait forever, then crashes with SIGSEGV on a
+ * write to address 0x10, for the core dump fixtures. Built without libc,
+ * so the threads are started with raw clone system calls. */
+
+#define CLONE_THREAD_FLAGS 0x50f00 /* VM, FS, FILES, SIGHAND, THREAD, SYSVSEM */
+
+volatile double value = 3.25;
+static volatile int started;
+static char stacks[2][8192] __attribute__((aligned(16)));
+
+static void __attribute__((noreturn, used)) thread_main(void)
+{
+    __atomic_add_fetch(&started, 1, __ATOMIC_SEQ_CST);
+    for (;;) {
+#ifdef __x86_64__
+        __asm__ volatile("syscall" : : "a"(34L) : "rcx", "r11", "memory"); /* pause */
+#else
+        __asm__ volatile("int $0x80" : : "a"(29) : "memory"); /* pause */
+#endif
+    }
+}
+
+static void start_thread(char *stack_top)
+{
+    long ret;
+#ifdef __x86_64__
+    register long child_tid __asm__("r10") = 0;
+    register long tls __asm__("r8") = 0;
+    __asm__ volatile("syscall\n\t"
+                     "test %%rax, %%rax\n\t"
+                     "jnz 1f\n\t"
+                     "call thread_main\n"
+                     "1:"
+                     : "=a"(ret)
+                     : "0"(56L), "D"(CLONE_THREAD_FLAGS), "S"(stack_top), "d"(0L),
+                       "r"(child_tid), "r"(tls)
+                     : "rcx", "r11", "memory");
+#else
+    __asm__ volatile("int $0x80\n\t"
+                     "test %%eax, %%eax\n\t"
+                     "jnz 1f\n\t"
+                     "call thread_main\n"
+                     "1:"
+                     : "=a"(ret)
+                     : "0"(120), "b"(CLONE_THREAD_FLAGS), "c"(stack_top), "d"(0),
+                       "S"(0), "D"(0)
+                     : "memory");
+#endif
+    (void)ret;
+}
+
+void __attribute__((force_align_arg_pointer)) _start(void)
+{
+    start_thread(stacks[0] + sizeof stacks[0]);
+    start_thread(stacks[1] + sizeof stacks[1]);
+    while (started < 2)
+        ;
+    value *= 2;
+    *(volatile int *)16 = 1;
+}
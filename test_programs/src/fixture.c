@@ -0,0 +1,15 @@
+int puts(const char *s);
+
+int counter;
+const char *message = "hello";
+
+int greet(int n)
+{
+    counter += n;
+    return puts(message);
+}
+
+int main(void)
+{
+    return greet(1) < 0;
+}
@@ -0,0 +1,14 @@
+@counter = global i32 0, align 4
+@message = private unnamed_addr constant [6 x i8] c"hello\00", align 1
+@table = global [2 x i8*] [i8* bitcast (i32* @counter to i8*), i8* getelementptr ([6 x i8], [6 x i8]* @message, i32 0, i32 0)], align 8
+
+declare i32 @puts(i8*)
+
+define i32 @greet(i32 %n) {
+entry:
+  %c = load i32, i32* @counter, align 4
+  %s = add i32 %c, %n
+  store i32 %s, i32* @counter, align 4
+  %r = call i32 @puts(i8* getelementptr ([6 x i8], [6 x i8]* @message, i32 0, i32 0))
+  ret i32 %s
+}
@@ -0,0 +1,11 @@
+#include <math.h>
+#include <stdio.h>
+
+/* libc exports frexp too, so the program still runs once libm is removed
+   from its needed libraries. */
+int main(int argc, char **argv) {
+    int exp;
+    double mantissa = frexp(argc * 48.0, &exp);
+    printf("%g %d\n", mantissa, exp);
+    return 0;
+}
@@ -0,0 +1,26 @@
+#include "lines.h"
+
+int table[16];
+
+int sum_squares(int n)
+{
+    int sum = 0;
+    for (int i = 0; i < n; i++)
+        sum += square(table[i & 15]);
+    return sum;
+}
+
+int clamp(int x, int lo, int hi)
+{
+    if (x < lo) return lo; if (x > hi) return hi;
+    return x;
+}
+
+int run(void)
+{
+    int total = 0;
+    for (int i = 0; i < 4; i++)
+        for (int j = 0; j < 4; j++)
+            total += clamp(sum_squares(i * j), 0, 100);
+    return total;
+}
@@ -0,0 +1,88 @@
+#!/bin/sh
+# Regenerates the expected outputs of the integration tests from readelf,
+# one file per fixture and option, e.g. test_results/x86_64.o/syms for
+# `readelf --syms test_programs/x86_64.o`. The outputs were made with GNU
+# readelf 2.40. Of the core dumps, only i386-core is compared: that readelf
+# can't decode the NT_FILE notes of the 64-bit ones. All three are checked
+# by tests/core_dumps.rs instead.
+set -e
+cd "$(dirname "$0")/.."
+
+FIXTURES="be/a.out x86_64-pie i386-shlib
+          aarch64.o armv7.o armeb.o i386.o mips.o mipsel.o mips64.o mips64el.o
+          powerpc64.o powerpc64le.o riscv32.o riscv64.o x86_64.o
+          x86_64-zlib.o x86_64-zlib-gnu.o x86_64-zstd.o
+          i386-zlib.o i386-zlib-gnu.o i386-zstd.o
+          x86_64-dwarf2 x86_64-dwarf3 x86_64-dwarf4 x86_64-dwarf5
+          i386-dwarf libfixture.a libfixture-thin.a libfixture-sym64.a
+          i386-core"
+OPTIONS="file-header program-headers section-headers syms dyn-syms relocs
+         dynamic notes version-info"
+# Kept in sync with SECTION_DUMPS in tests/readelf_compat.rs. Only stdout is
+# kept, as the warnings about missing sections differ in order.
+SECTION_DUMPS="-x .text -R .text -R .eh_frame -R .data -p .strtab -p .text -x 1"
+# Kept in sync with DECOMPRESSED_DUMPS, checked on the fixtures with
+# compressed sections, whose names end in -zlib, -zlib-gnu or -zstd.
+DECOMPRESSED_DUMPS="-z -x .debug_info -R .debug_info -R .debug_line -p .debug_line_str
+                    -x .zdebug_info -R .zdebug_info -R .zdebug_line"
+# Kept in sync with FRAME_DUMPS in tests/readelf_compat.rs.
+FRAME_DUMPS="frames frames-interp"
+# Kept in sync with ADDR2LINE_FIXTURES in tests/addr2line.rs, which feeds
+# addr2line every address of .text and the one just past it.
+ADDR2LINE_FIXTURES="x86_64-dwarf2 x86_64-dwarf3 x86_64-dwarf4 x86_64-dwarf5
+                    i386-dwarf x86_64-zlib.o x86_64-zstd.o i386-zlib-gnu.o"
+
+for fixture in $FIXTURES; do
+    mkdir -p test_results/$fixture
+    for option in $OPTIONS; do
+        LC_ALL=C readelf --$option test_programs/$fixture \
+            > test_results/$fixture/$option 2>&1 || true
+    done
+    LC_ALL=C readelf $SECTION_DUMPS test_programs/$fixture \
+        > test_results/$fixture/section-dumps 2>/dev/null || true
+    case $fixture in
+        *-zlib.o | *-zlib-gnu.o | *-zstd.o)
+            LC_ALL=C readelf $DECOMPRESSED_DUMPS test_programs/$fixture \
+                > test_results/$fixture/decompressed-dumps 2>/dev/null || true
+            ;;
+    esac
+    # The fixtures with DWARF are those with compressed sections and those
+    # named -dwarf, as in has_dwarf in tests/readelf_compat.rs.
+    case $fixture in
+        *-zlib.o | *-zlib-gnu.o | *-zstd.o | *-dwarf*)
+            LC_ALL=C readelf --debug-dump=info test_programs/$fixture \
+                > test_results/$fixture/debug-info 2>/dev/null || true
+            ;;
+    esac
+    for dump in $FRAME_DUMPS; do
+        LC_ALL=C readelf --debug-dump=$dump test_programs/$fixture \
+            > test_results/$fixture/$dump 2>/dev/null || true
+    done
+    # The archives also get their index, as in is_archive in
+    # tests/readelf_compat.rs.
+    case $fixture in
+        *.a)
+            LC_ALL=C readelf --archive-index test_programs/$fixture \
+                > test_results/$fixture/archive-index 2>&1 || true
+            ;;
+    esac
+done
+
+# Kept in sync with MANY_SECTIONS_OPTIONS in tests/readelf_compat.rs. The
+# section headers of this fixture would take megabytes.
+mkdir -p test_results/i386-sections.o
+for option in file-header syms; do
+    LC_ALL=C readelf --$option test_programs/i386-sections.o \
+        > test_results/i386-sections.o/$option 2>&1
+done
+
+for fixture in $ADDR2LINE_FIXTURES; do
+    set -- $(LC_ALL=C readelf -SW test_programs/$fixture | sed -n \
+        's/^ *\[ *[0-9]*\] \.text  *[A-Z]*  *\([0-9a-f]*\) [0-9a-f]* \([0-9a-f]*\) .*/\1 \2/p')
+    address=$((0x$1))
+    end=$((address + 0x$2))
+    while [ $address -le $end ]; do
+        printf '%x\n' $address
+        address=$((address + 1))
+    done | addr2line -a -e test_programs/$fixture > test_results/$fixture/addr2line
+done
@@ -0,0 +1,119 @@
+//! Runs `writeork addr2line` over the fixtures with debugging information
+//! and compares its output with GNU addr2line's, stored in
+//! `test_results/<fixture>/addr2line` by `test_results/update.sh`.
+
+extern crate writeork;
+
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use common::{fixture_path, read_fixture};
+use writeork::dwarf::Dwarf;
+use writeork::dwarf::line_table::LineTable;
+use writeork::elf::prelude::*;
+
+/// Kept in sync with `ADDR2LINE_FIXTURES` in `test_results/update.sh`.
+const ADDR2LINE_FIXTURES: &[&str] = &[
+    "x86_64-dwarf2",
+    "x86_64-dwarf3",
+    "x86_64-dwarf4",
+    "x86_64-dwarf5",
+    "i386-dwarf",
+    "x86_64-zlib.o",
+    "x86_64-zstd.o",
+    "i386-zlib-gnu.o",
+];
+
+/// Every address of `.text` and the one just past it, one per line.
+fn text_addresses(elf: &ElfFile) -> String {
+    let text = elf.sections().unwrap().iter()
+        .find(|s| s.get_name() == ".text")
+        .unwrap();
+    (text.get_addr()..=text.get_addr() + text.get_size())
+        .map(|address| format!("{:x}\n", address))
+        .collect()
+}
+
+#[test]
+fn matches_addr2line() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut failures = Vec::new();
+    for fixture in ADDR2LINE_FIXTURES {
+        let data = read_fixture(fixture);
+        let addresses = text_addresses(&ElfFile::parse(&data).unwrap());
+        let expected_path = root.join("test_results").join(fixture).join("addr2line");
+        let expected = fs::read_to_string(&expected_path)
+            .unwrap_or_else(|err| panic!("{}: {}", expected_path.display(), err));
+
+        // The addresses go through the standard input, like update.sh
+        // gives them to addr2line.
+        let mut child = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .args(["addr2line", "-a"])
+            .arg(fixture_path(fixture))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to run writeork");
+        child.stdin.take().unwrap().write_all(addresses.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        let actual = String::from_utf8_lossy(&output.stdout);
+
+        let line = actual.lines().zip(expected.lines()).position(|(a, e)| a != e);
+        if line.is_some() || actual.lines().count() != expected.lines().count() {
+            let line = line.unwrap_or_else(|| actual.lines().count().min(expected.lines().count()));
+            failures.push(format!(
+                "{}: line {} differs\n  expected: {:?}\n  actual:   {:?}",
+                fixture, line + 1,
+                expected.lines().nth(line).unwrap_or("<end of output>"),
+                actual.lines().nth(line).unwrap_or("<end of output>")));
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn line_table() {
+    let data = read_fixture("x86_64-dwarf5");
+    let elf = ElfFile::parse(&data).unwrap();
+    let table = LineTable::new(&Dwarf::load(&elf).unwrap()).unwrap();
+
+    let symtab = elf.sections().unwrap().iter()
+        .position(|s| s.get_name() == ".symtab")
+        .unwrap();
+    let greet = elf.symbols(symtab).unwrap().into_iter()
+        .find(|sym| sym.get_name() == "greet")
+        .unwrap();
+    // At -O2 the function starts with the first line of its body.
+    let location = table.find(greet.get_value()).unwrap();
+    assert!(location.file.ends_with("/src/fixture.c"), "{}", location.file);
+    assert_eq!(location.line, 8);
+
+    assert!(table.find(0).is_none());
+    let lines: Vec<_> = (0..0x2000).filter_map(|a| table.find(a)).collect();
+    assert!(lines.iter().any(|l| l.file.ends_with("/src/lines.h")));
+    assert!(lines.iter().any(|l| l.discriminator != 0));
+}
+
+/// Addresses given on the command line, even more than 256 of them, are
+/// translated like those read from the standard input.
+#[test]
+fn takes_addresses_as_arguments() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let fixture = "i386-dwarf";
+    let data = read_fixture(fixture);
+    let addresses = text_addresses(&ElfFile::parse(&data).unwrap());
+    assert!(addresses.lines().count() > 256);
+    let expected = fs::read_to_string(root.join("test_results").join(fixture).join("addr2line"))
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .args(["addr2line", "-a"])
+        .arg(fixture_path(fixture))
+        .args(addresses.lines())
+        .output()
+        .expect("failed to run writeork");
+    assert!(String::from_utf8_lossy(&output.stdout) == expected);
+}
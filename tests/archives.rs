@@ -0,0 +1,127 @@
+//! Checks the reading of the static libraries in `test_programs` through
+//! the library API: their members and the symbol index, in each of the
+//! formats `test_programs/build.sh` makes them in, and the JSON writeork
+//! prints of them.
+
+extern crate serde_json;
+extern crate writeork;
+
+mod common;
+
+use std::process::Command;
+use common::{fixture_path, read_fixture};
+use writeork::archive::*;
+use writeork::elf::elf_shdr_type::SHT_SYMTAB;
+use writeork::elf::elf_sym_bind::STB_LOCAL;
+use writeork::elf::elf_sym_shndx::SHN_UNDEF;
+use writeork::elf::prelude::*;
+
+/// The archives and whether they're thin and have 64-bit index offsets.
+const ARCHIVES: &[(&str, bool, bool)] = &[
+    ("libfixture.a", false, false),
+    ("libfixture-thin.a", true, false),
+    ("libfixture-sym64.a", false, true),
+    ("libfixture-bsd.a", false, false),
+];
+
+const MEMBERS: &[&str] = &["x86_64.o", "x86_64-zlib-gnu.o", "i386.o"];
+
+/// The names of the global symbols `data` defines.
+fn get_defined_symbols(data: &[u8]) -> Vec<String> {
+    let elf = ElfFile::parse(data).unwrap();
+    let index = elf.sections().unwrap().iter()
+        .position(|shdr| shdr.get_type().get() == SHT_SYMTAB)
+        .unwrap();
+    elf.symbols(index).unwrap().iter()
+        .filter(|sym| sym.get_bind().get() != STB_LOCAL && sym.get_shndx().get() != SHN_UNDEF)
+        .map(|sym| sym.get_name().to_string())
+        .collect()
+}
+
+#[test]
+fn reads_members() {
+    for &(fixture, is_thin, _) in ARCHIVES {
+        let data = read_fixture(fixture);
+        let archive = Archive::parse(&data).unwrap();
+        assert_eq!(archive.is_thin(), is_thin, "{}", fixture);
+
+        let members: Vec<ArchiveMember> = archive.members().map(|m| m.unwrap()).collect();
+        let names: Vec<&str> = members.iter().map(|m| &m.name[..]).collect();
+        assert_eq!(names, MEMBERS, "{}", fixture);
+        for member in &members {
+            let contents = read_fixture(&member.name);
+            assert_eq!(member.size, contents.len() as u64, "{}", fixture);
+            if is_thin {
+                assert_eq!(member.data, None, "{}", fixture);
+            } else {
+                assert_eq!(member.data, Some(&contents[..]), "{}", fixture);
+            }
+            assert_eq!(archive.member_at(member.offset).as_ref(), Ok(member), "{}", fixture);
+        }
+    }
+}
+
+#[test]
+fn index_points_at_defining_members() {
+    for &(fixture, _, is_64) in ARCHIVES {
+        let data = read_fixture(fixture);
+        let archive = Archive::parse(&data).unwrap();
+        let index = archive.index().unwrap();
+        assert_eq!(index.is_64, is_64, "{}", fixture);
+
+        let expected: usize = MEMBERS.iter()
+            .map(|name| get_defined_symbols(&read_fixture(name)).len())
+            .sum();
+        assert_eq!(index.symbols.len(), expected, "{}", fixture);
+        for symbol in &index.symbols {
+            let member = archive.member_at(symbol.member_offset).unwrap();
+            let defined = get_defined_symbols(&read_fixture(&member.name));
+            assert!(defined.contains(&symbol.name), "{}: {} in {}", fixture, symbol.name, member.name);
+        }
+    }
+}
+
+#[test]
+fn rejects_other_files() {
+    let data = read_fixture("x86_64.o");
+    assert!(!is_archive(&data));
+    assert_eq!(Archive::parse(&data).err(), Some(ArchiveError::BadMagic));
+
+    let mut data = read_fixture("libfixture.a");
+    data.truncate(data.len() - 1);
+    let archive = Archive::parse(&data).unwrap();
+    let last = archive.members().last().unwrap();
+    assert!(matches!(last, Err(ArchiveError::Truncated { .. })));
+}
+
+/// An archive is dumped as one JSON document, with an object per member.
+#[test]
+fn dumps_archive_as_json() {
+    for &(fixture, _, _) in ARCHIVES {
+        let path = fixture_path(fixture);
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .args(["--output=json", "-h", "-s"])
+            .arg(&path)
+            .output()
+            .expect("failed to run writeork");
+        assert!(output.status.success(), "{}", fixture);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .unwrap_or_else(|err| panic!("{}: {}", fixture, err));
+
+        assert_eq!(json["schema_version"], 1, "{}", fixture);
+        assert_eq!(json["archive"], path.to_str().unwrap(), "{}", fixture);
+        let data = read_fixture(fixture);
+        let archive = Archive::parse(&data).unwrap();
+        let members = json["members"].as_array().unwrap();
+        assert_eq!(members.len(), MEMBERS.len(), "{}", fixture);
+        for (json, member) in members.iter().zip(archive.members()) {
+            let member = member.unwrap();
+            assert_eq!(json["name"], member.name, "{}", fixture);
+            assert_eq!(json["offset"], member.offset, "{}", fixture);
+            assert_eq!(json["size"], member.size, "{}", fixture);
+            assert!(json.get("schema_version").is_none(), "{}", fixture);
+            assert!(json["file_header"]["machine"]["name"].is_string(), "{}", fixture);
+            assert!(json["symbol_tables"][0]["entries"].is_array(), "{}", fixture);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Checks the reading of `.eh_frame` and the search table of
+//! `.eh_frame_hdr` through the library API on the linked fixtures in
+//! `test_programs`.
+
+extern crate writeork;
+
+mod common;
+
+use common::read_fixture;
+use writeork::dwarf::cfi::*;
+use writeork::elf::prelude::*;
+
+const FIXTURES: &[&str] = &["x86_64-pie", "i386-shlib", "x86_64-dwarf4", "i386-dwarf"];
+
+/// Returns the contents of section `name` of `elf` and its address.
+fn section<'a>(elf: &'a ElfFile, name: &str) -> (&'a [u8], u64) {
+    let shdr = elf.sections().unwrap().iter().find(|s| s.get_name() == name).unwrap();
+    (elf.section_data(shdr).unwrap(), shdr.get_addr())
+}
+
+#[test]
+fn eh_frame_hdr_finds_every_fde() {
+    for fixture in FIXTURES {
+        let data = read_fixture(fixture);
+        let elf = ElfFile::parse(&data).unwrap();
+        let address_size = if elf.is_32() { 4 } else { 8 };
+        let (hdr_data, hdr_address) = section(&elf, ".eh_frame_hdr");
+        let (eh_frame_data, eh_frame_address) = section(&elf, ".eh_frame");
+        let hdr = EhFrameHdr::parse(hdr_data, hdr_address, elf.endianness(), address_size)
+            .unwrap();
+        assert_eq!(hdr.eh_frame_ptr, eh_frame_address, "{}", fixture);
+        let eh_frame = CfiSection::new(
+            eh_frame_data, CfiKind::EhFrame, elf.endianness(), address_size, eh_frame_address);
+
+        let fdes: Vec<Fde> = eh_frame.entries()
+            .filter_map(|entry| match entry.unwrap() {
+                CfiEntry::Fde(fde) => Some(fde),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(hdr.fde_count, fdes.len() as u64, "{}", fixture);
+        for fde in &fdes {
+            let fde_address = Some(eh_frame_address + fde.offset);
+            let last = fde.initial_location + fde.address_range - 1;
+            assert_eq!(hdr.lookup(fde.initial_location).unwrap(), fde_address, "{}", fixture);
+            assert_eq!(hdr.lookup(last).unwrap(), fde_address, "{}", fixture);
+            assert_eq!(eh_frame.fde_for_address(last).unwrap().as_ref(), Some(fde));
+            let cie = eh_frame.cie_at(fde.cie_offset).unwrap();
+            assert_eq!(cie.augmentation, b"zR", "{}", fixture);
+        }
+        let first = fdes.iter().map(|fde| fde.initial_location).min().unwrap();
+        assert_eq!(hdr.lookup(first - 1).unwrap(), None, "{}", fixture);
+    }
+}
+
+#[test]
+fn pointer_encodings() {
+    assert_eq!(format_pointer_encoding(DW_EH_PE_pcrel | DW_EH_PE_sdata4), "sdata4, pcrel");
+    assert_eq!(format_pointer_encoding(DW_EH_PE_datarel | DW_EH_PE_sdata4), "sdata4, datarel");
+    assert_eq!(format_pointer_encoding(DW_EH_PE_udata4), "udata4");
+    assert_eq!(get_pointer_size(DW_EH_PE_absptr, 8), Some(8));
+    assert_eq!(get_pointer_size(DW_EH_PE_sdata2, 8), Some(2));
+    assert_eq!(get_pointer_size(DW_EH_PE_uleb128, 8), None);
+}
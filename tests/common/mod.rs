@@ -0,0 +1,17 @@
+//! Helpers shared by the integration tests. Each test crate includes this
+//! with `mod common;` and uses only some of them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The path of `fixture` in `test_programs`.
+pub fn fixture_path(fixture: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("test_programs").join(fixture)
+}
+
+/// Reads `fixture` from `test_programs`.
+pub fn read_fixture(fixture: &str) -> Vec<u8> {
+    let path = fixture_path(fixture);
+    fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
@@ -0,0 +1,182 @@
+//! Checks the decoding of the core dumps in `test_programs`: `writeork core`
+//! against the outputs kept in `test_results/<fixture>/core`, made by
+//! writeork itself, and values known from elsewhere through the library
+//! API and in that output. `readelf -n` of `i386-core` is compared in
+//! `readelf_compat.rs`. After an intended change, regenerate the outputs
+//! with `writeork core test_programs/<fixture>`.
+
+extern crate writeork;
+
+mod common;
+
+use std::convert::TryInto;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use common::{fixture_path, read_fixture};
+use writeork::elf::elf_auxv::{AT_ENTRY, AT_EXECFN, AT_PLATFORM, AT_PAGESZ};
+use writeork::elf::elf_core::*;
+use writeork::elf::elf_note::NT_PRSTATUS;
+use writeork::elf::prelude::*;
+
+const FIXTURES: &[&str] = &["x86_64-core", "i386-core", "aarch64-core"];
+
+#[test]
+fn matches_expected_output() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    for fixture in FIXTURES {
+        let expected_path = root.join("test_results").join(fixture).join("core");
+        let expected = fs::read_to_string(&expected_path)
+            .unwrap_or_else(|err| panic!("{}: {}", expected_path.display(), err));
+
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .arg("core")
+            .arg(fixture_path(fixture))
+            .output()
+            .expect("failed to run writeork");
+        assert!(output.status.success(), "{}", fixture);
+        assert!(output.stderr.is_empty(), "{}: {}", fixture, String::from_utf8_lossy(&output.stderr));
+        let actual = String::from_utf8_lossy(&output.stdout);
+
+        if let Some(line) = actual.lines().zip(expected.lines()).position(|(a, e)| a != e) {
+            panic!(
+                "{}: line {} differs\n  expected: {:?}\n  actual:   {:?}",
+                fixture, line + 1,
+                expected.lines().nth(line).unwrap(), actual.lines().nth(line).unwrap());
+        }
+        assert_eq!(actual.lines().count(), expected.lines().count(), "{}", fixture);
+    }
+}
+
+/// The values `test_programs/src/aarch64-core.py` writes.
+#[test]
+fn decodes_aarch64_notes() {
+    let data = read_fixture("aarch64-core");
+    let elf = ElfFile::parse(&data).unwrap();
+    let core = CoreFile::new(&elf).unwrap();
+
+    let process = core.process.as_ref().unwrap();
+    assert_eq!((process.pid, process.ppid, process.uid), (4242, 4200, 1000));
+    assert_eq!(process.fname, "crash");
+    assert_eq!(process.psargs, "/usr/bin/crash --now ");
+
+    assert_eq!(core.signal, Some(SigInfo {
+        signo: SIGSEGV, errno: 0, code: 1, addr: Some(0x10), sender: None,
+    }));
+
+    let pids: Vec<i32> = core.threads.iter().map(|t| t.status.pid).collect();
+    assert_eq!(pids, [4242, 4243]);
+    let crashing = &core.threads[0];
+    assert_eq!(crashing.status.regs.len(), 34);
+    assert_eq!(crashing.status.regs[1], 0x1111);
+    assert_eq!(crashing.status.regs[32], 0x400124);
+    let fpregs = crashing.fpregs.as_ref().unwrap();
+    assert_eq!(fpregs.vectors[0] as u64, 6.5f64.to_bits());
+    assert_eq!(fpregs.vectors[1] as u64, (-0.125f64).to_bits());
+    assert_eq!(fpregs.control, [("fpsr", 0x10), ("fpcr", 0x3000000)]);
+
+    let files = core.files.as_ref().unwrap();
+    assert_eq!(files.page_size, 4096);
+    assert_eq!(files.files.len(), 2);
+    assert_eq!(files.files[1].start, 0x410000);
+    assert_eq!(files.files[1].name, "/usr/bin/crash");
+
+    let auxv = |a_type| core.auxv.iter().find(|e| e.a_type == a_type).unwrap().value;
+    assert_eq!(auxv(AT_PAGESZ), 4096);
+    assert_eq!(core.read_string(auxv(AT_EXECFN)).unwrap(), "/usr/bin/crash");
+    assert_eq!(core.read_string(auxv(AT_PLATFORM)).unwrap(), "aarch64");
+}
+
+/// Values read from the notes of the x86 core dumps with Python's
+/// `struct`, apart from writeork: the PID of the process, the index and
+/// value of the instruction pointer of the crashing thread and `AT_ENTRY`.
+const X86_CORES: &[(&str, i32, usize, u64, u64)] = &[
+    ("x86_64-core", 10386, 16, 0x401074, 0x401011),
+    ("i386-core", 10390, 12, 0x8049067, 0x8049011),
+];
+
+#[test]
+fn decodes_x86_notes() {
+    for &(fixture, pid, ip, ip_value, entry) in X86_CORES {
+        let data = read_fixture(fixture);
+        let elf = ElfFile::parse(&data).unwrap();
+        let core = CoreFile::new(&elf).unwrap();
+
+        assert_eq!(core.process.as_ref().unwrap().pid, pid, "{}", fixture);
+        assert_eq!(core.signal, Some(SigInfo {
+            signo: SIGSEGV, errno: 0, code: 1, addr: Some(0x10), sender: None,
+        }), "{}", fixture);
+        let crashing = &core.threads[0];
+        assert_eq!(crashing.status.pid, pid, "{}", fixture);
+        assert_eq!(crashing.status.cursig, SIGSEGV as u16, "{}", fixture);
+        assert_eq!(crashing.status.regs[ip], ip_value, "{}", fixture);
+        let auxv_entry = core.auxv.iter().find(|e| e.a_type == AT_ENTRY).unwrap();
+        assert_eq!(auxv_entry.value, entry, "{}", fixture);
+
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .arg("core")
+            .arg(fixture_path(fixture))
+            .output()
+            .expect("failed to run writeork");
+        let output = String::from_utf8_lossy(&output.stdout);
+        let has_line = |line: &str| output.lines().any(|l| l == line);
+        assert!(has_line("Signal: SIGSEGV (11), code SEGV_MAPERR (1), fault address 0x10"),
+                "{}", fixture);
+        assert!(has_line(&format!("Thread {} (crashing):", pid)), "{}", fixture);
+        let entry_line = format!(
+            "  {:<20} {:<40} {:#x}", "AT_ENTRY", "Entry point of program", entry);
+        assert!(has_line(&entry_line), "{}", fixture);
+    }
+}
+
+#[test]
+fn rejects_other_files() {
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("core")
+        .arg(fixture_path("x86_64-pie"))
+        .output()
+        .expect("failed to run writeork");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+/// Problems with the notes are kept in `warnings` rather than printed, and
+/// `writeork core` reports them.
+#[test]
+fn records_warnings() {
+    let mut data = read_fixture("aarch64-core");
+    let offset = {
+        let elf = ElfFile::parse(&data).unwrap();
+        let core = CoreFile::new(&elf).unwrap();
+        assert_eq!(core.warnings, Vec::<String>::new());
+
+        // Hide the first NT_PRSTATUS, which the NT_FPREGSET after it
+        // belongs to. Notes are aligned to 4 bytes in core dumps.
+        let phdr = elf.program_headers().unwrap().iter()
+            .find(|p| p.get_type() == ElfPhdrType::PT_NOTE)
+            .copied()
+            .unwrap();
+        let word = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap()) as usize;
+        let mut at = phdr.get_offset() as usize;
+        while word(at + 8) != NT_PRSTATUS as usize {
+            at += 12 + word(at).div_ceil(4) * 4 + word(at + 4).div_ceil(4) * 4;
+        }
+        at + 8
+    };
+    data[offset..offset + 4].copy_from_slice(&0x7fffu32.to_le_bytes());
+
+    let elf = ElfFile::parse(&data).unwrap();
+    let core = CoreFile::new(&elf).unwrap();
+    assert_eq!(core.warnings, ["NT_FPREGSET note comes before NT_PRSTATUS"]);
+
+    let path = std::env::temp_dir().join(format!("writeork-core-{}", std::process::id()));
+    fs::write(&path, &data).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("core")
+        .arg(&path)
+        .output()
+        .expect("failed to run writeork");
+    fs::remove_file(&path).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stderr),
+               "Warning: NT_FPREGSET note comes before NT_PRSTATUS\n");
+}
@@ -0,0 +1,38 @@
+//! Checks the decoding of `e_flags` through the library API on the
+//! fixtures in `test_programs`.
+
+extern crate writeork;
+
+mod common;
+
+use common::read_fixture;
+use writeork::elf::prelude::*;
+
+fn flags_of(fixture: &str) -> (ElfEhdrMachine, ElfEhdrFlags, Option<ElfFloatAbi>) {
+    let data = read_fixture(fixture);
+    let elf = ElfFile::parse(&data).unwrap();
+    (elf.machine(), elf.flags(), elf.float_abi())
+}
+
+#[test]
+fn float_abi() {
+    assert_eq!(flags_of("riscv64.o").2, Some(ElfFloatAbi::Soft));
+    assert_eq!(flags_of("armv7.o").2, None);
+    assert_eq!(flags_of("x86_64.o").2, None);
+
+    let hard = ElfEhdrFlags::from(0x05000400);
+    assert_eq!(hard.get_float_abi(ElfEhdrMachine::EM_ARM), Some(ElfFloatAbi::Hard));
+    let double = ElfEhdrFlags::from(0x5);
+    assert_eq!(double.get_float_abi(ElfEhdrMachine::EM_RISCV), Some(ElfFloatAbi::Double));
+    assert_eq!(double.get_float_abi(ElfEhdrMachine::EM_MIPS), None);
+}
+
+#[test]
+fn names() {
+    let (machine, flags, _) = flags_of("powerpc64le.o");
+    assert_eq!(flags.get_names(machine), ["abiv2"]);
+    assert_eq!(flags.format(machine), "0x2, abiv2");
+
+    let (machine, flags, _) = flags_of("mips.o");
+    assert_eq!(flags.get_names(machine), ["noreorder", "pic", "cpic", "o32", "mips32"]);
+}
@@ -0,0 +1,291 @@
+//! Checks `writeork --output=json` against the outputs kept in
+//! `test_results/<fixture>/json`. Unlike the readelf outputs, these are
+//! made by writeork itself, so they pin down the schema described in
+//! `JSON.md`. After an intended change, regenerate them with
+//! `writeork --output=json -h -l -S -s -d -r -n test_programs/<fixture>`.
+//! The key names and types of every dump are also checked against
+//! `JSON.md` directly.
+
+extern crate serde_json;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use common::fixture_path;
+use serde_json::Value;
+
+const FIXTURES: &[&str] = &["be/a.out", "x86_64-pie", "mips64el.o"];
+
+#[test]
+fn matches_expected_json() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    for fixture in FIXTURES {
+        let expected_path = root.join("test_results").join(fixture).join("json");
+        let expected = fs::read_to_string(&expected_path)
+            .unwrap_or_else(|err| panic!("{}: {}", expected_path.display(), err));
+
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .args(["--output=json", "-h", "-l", "-S", "-s", "-d", "-r", "-n"])
+            .arg(fixture_path(fixture))
+            .output()
+            .expect("failed to run writeork");
+        let actual = String::from_utf8_lossy(&output.stdout);
+
+        if let Some(line) = actual.lines().zip(expected.lines()).position(|(a, e)| a != e) {
+            panic!(
+                "{}: line {} differs\n  expected: {:?}\n  actual:   {:?}",
+                fixture, line + 1,
+                expected.lines().nth(line).unwrap(), actual.lines().nth(line).unwrap());
+        }
+        assert_eq!(actual.lines().count(), expected.lines().count(), "{}", fixture);
+    }
+}
+
+#[test]
+fn rejects_unknown_format() {
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .args(["--output=yaml", "-h"])
+        .arg(fixture_path("x86_64.o"))
+        .output()
+        .expect("failed to run writeork");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+/// `--compat` only changes the text output; a PIE is still named a
+/// shared object.
+#[test]
+fn ignores_compat() {
+    let plain = run_json("x86_64-pie", &["-h"]);
+    let compat = run_json("x86_64-pie", &["--compat=readelf", "-h"]);
+    assert_eq!(plain, compat);
+    assert_eq!(compat["file_header"]["type"]["name"], "DYN (Shared object file)");
+}
+
+/// The type of a field as `JSON.md` describes it.
+#[derive(Clone, Copy, Debug)]
+enum Type {
+    Int,
+    Str,
+    Bool,
+    /// `{"value": <integer>, "name": <string or null>}`.
+    Enumerated,
+    Array,
+    Object,
+    OrNull(&'static Type),
+}
+
+fn has_type(value: &Value, ty: Type) -> bool {
+    match ty {
+        Type::Int => value.is_u64() || value.is_i64(),
+        Type::Str => value.is_string(),
+        Type::Bool => value.is_boolean(),
+        Type::Enumerated => {
+            value.as_object().is_some_and(|object| object.len() == 2)
+                && has_type(&value["value"], Type::Int)
+                && has_type(&value["name"], Type::OrNull(&Type::Str))
+        }
+        Type::Array => value.is_array(),
+        Type::Object => value.is_object(),
+        Type::OrNull(ty) => value.is_null() || has_type(value, *ty),
+    }
+}
+
+/// Checks that `value` is an object with exactly `fields`, of their types.
+fn check_fields(value: &Value, fields: &[(&str, Type)], what: &str) {
+    let object = value.as_object().unwrap_or_else(|| panic!("{}: not an object", what));
+    let mut keys: Vec<&str> = object.keys().map(|key| &key[..]).collect();
+    let mut names: Vec<&str> = fields.iter().map(|&(name, _)| name).collect();
+    keys.sort_unstable();
+    names.sort_unstable();
+    assert_eq!(keys, names, "{}", what);
+    for &(name, ty) in fields {
+        assert!(has_type(&value[name], ty), "{}.{}: {} isn't {:?}", what, name, value[name], ty);
+    }
+}
+
+fn elements<'a>(value: &'a Value, what: &str) -> &'a [Value] {
+    let elements = value.as_array().unwrap_or_else(|| panic!("{}: not an array", what));
+    assert!(!elements.is_empty(), "{}: empty", what);
+    elements
+}
+
+fn run_json(fixture: &str, args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("--output=json")
+        .args(args)
+        .arg(fixture_path(fixture))
+        .output()
+        .expect("failed to run writeork");
+    assert!(output.status.success(), "{}", fixture);
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn follows_schema() {
+    use Type::*;
+
+    let json = run_json("x86_64-pie", &[
+        "-h", "-l", "-S", "-s", "--dyn-syms", "-d", "-r", "-V", "-n",
+        "-x", ".text", "-R", ".data", "-p", ".dynstr",
+    ]);
+    check_fields(&json, &[
+        ("schema_version", Int), ("file_header", Object), ("program_headers", Array),
+        ("section_headers", Array), ("dynamic", OrNull(&Array)), ("relocations", Array),
+        ("symbol_tables", Array), ("version_info", Array), ("section_contents", Array),
+        ("notes", Array),
+    ], "top level");
+    assert_eq!(json["schema_version"], 1);
+
+    let ehdr = &json["file_header"];
+    check_fields(ehdr, &[
+        ("ident", Object), ("type", Enumerated), ("machine", Enumerated), ("version", Int),
+        ("entry", Int), ("phoff", Int), ("shoff", Int), ("ehsize", Int), ("phentsize", Int),
+        ("phnum", Int), ("shentsize", Int), ("shnum", Int), ("shstrndx", Int),
+        ("flags", Object),
+    ], "file_header");
+    check_fields(&ehdr["ident"], &[
+        ("class", Enumerated), ("data", Enumerated), ("version", Enumerated),
+        ("osabi", Enumerated), ("abi_version", Int),
+    ], "file_header.ident");
+    check_fields(&ehdr["flags"], &[("value", Int), ("names", Array)], "file_header.flags");
+
+    for phdr in elements(&json["program_headers"], "program_headers") {
+        let mut fields = vec![
+            ("type", Enumerated), ("flags", Enumerated), ("offset", Int), ("vaddr", Int),
+            ("paddr", Int), ("filesz", Int), ("memsz", Int), ("align", Int),
+            ("sections", OrNull(&Array)),
+        ];
+        if phdr["type"]["name"] == "INTERP" {
+            fields.push(("interpreter", Str));
+        }
+        check_fields(phdr, &fields, "program header");
+    }
+
+    for shdr in elements(&json["section_headers"], "section_headers") {
+        check_fields(shdr, &[
+            ("index", Int), ("name", Str), ("type", Enumerated), ("flags", Enumerated),
+            ("addr", Int), ("offset", Int), ("size", Int), ("entsize", Int), ("link", Int),
+            ("info", Int), ("addralign", Int),
+        ], "section header");
+    }
+
+    for entry in elements(&json["dynamic"], "dynamic") {
+        check_fields(entry, &[("tag", Enumerated), ("value", Int), ("text", Str)], "dynamic");
+    }
+
+    for table in elements(&json["relocations"], "relocations") {
+        check_fields(table, &[
+            ("name", Str), ("kind", Str), ("address", Int), ("offset", Int), ("size", Int),
+            ("entries", Array),
+        ], "relocation table");
+        for reloc in elements(&table["entries"], "relocations") {
+            check_reloc(reloc, &[]);
+        }
+    }
+
+    for table in elements(&json["symbol_tables"], "symbol_tables") {
+        check_fields(table, &[("name", Str), ("entries", Array)], "symbol table");
+        for sym in elements(&table["entries"], "symbols") {
+            check_fields(sym, &[
+                ("index", Int), ("name", Str), ("value", Int), ("size", Int),
+                ("type", Enumerated), ("bind", Enumerated), ("visibility", Enumerated),
+                ("other", Enumerated), ("shndx", Enumerated), ("version", OrNull(&Object)),
+            ], "symbol");
+            if let Some(version) = sym["version"].as_object() {
+                let kind = version["kind"].as_str().unwrap();
+                assert!(["public", "hidden", "undefined"].contains(&kind), "{}", kind);
+            }
+        }
+    }
+
+    let version_sections = elements(&json["version_info"], "version_info");
+    for section in version_sections {
+        check_fields(section, &[
+            ("name", Str), ("kind", Str), ("address", Int), ("offset", Int), ("link", Int),
+            ("entries", Array),
+        ], "version section");
+        for entry in elements(&section["entries"], "version entries") {
+            match section["kind"].as_str().unwrap() {
+                "symbols" => check_fields(entry, &[
+                    ("version", Int), ("hidden", Bool), ("name", OrNull(&Str)),
+                ], "version symbol"),
+                "requirements" => {
+                    check_fields(entry, &[
+                        ("offset", Int), ("version", Int), ("file", OrNull(&Str)),
+                        ("count", Int), ("versions", Array),
+                    ], "version requirement");
+                    for version in elements(&entry["versions"], "versions") {
+                        check_fields(version, &[
+                            ("offset", Int), ("name", OrNull(&Str)), ("flags", Enumerated),
+                            ("index", Int),
+                        ], "required version");
+                    }
+                }
+                kind => panic!("version section kind {}", kind),
+            }
+        }
+    }
+    let kinds: Vec<&Value> = version_sections.iter().map(|section| &section["kind"]).collect();
+    assert_eq!(kinds, ["symbols", "requirements"]);
+
+    let dumps = elements(&json["section_contents"], "section_contents");
+    for dump in dumps {
+        check_fields(dump, &[
+            ("index", Int), ("name", Str), ("kind", Str), ("address", Int),
+            ("data", OrNull(&Str)), ("strings", OrNull(&Array)),
+        ], "section dump");
+        if dump["kind"] == "strings" {
+            assert!(dump["data"].is_null());
+            for string in elements(&dump["strings"], "strings") {
+                check_fields(string, &[("offset", Int), ("string", Str)], "string");
+            }
+        } else {
+            assert!(dump["strings"].is_null());
+        }
+    }
+    let kinds: Vec<&Value> = dumps.iter().map(|dump| &dump["kind"]).collect();
+    assert_eq!(kinds, ["strings", "hex", "relocated"]);
+
+    for area in elements(&json["notes"], "notes") {
+        check_fields(area, &[
+            ("section", OrNull(&Str)), ("offset", Int), ("size", Int),
+            ("notes", OrNull(&Array)),
+        ], "note area");
+        for note in elements(&area["notes"], "notes") {
+            check_fields(note, &[
+                ("owner", Str), ("type", Enumerated), ("description", Str),
+            ], "note");
+        }
+    }
+}
+
+/// MIPS64 relocations have three types.
+#[test]
+fn follows_schema_for_mips64_relocations() {
+    let json = run_json("mips64el.o", &["-r"]);
+    for table in elements(&json["relocations"], "relocations") {
+        for reloc in elements(&table["entries"], "relocations") {
+            check_reloc(reloc, &[("type2", Type::Enumerated), ("type3", Type::Enumerated)]);
+        }
+    }
+}
+
+fn check_reloc(reloc: &Value, extra: &[(&str, Type)]) {
+    use Type::*;
+
+    let mut fields = vec![
+        ("offset", Int), ("info", Int), ("type", Enumerated), ("symbol", OrNull(&Object)),
+        ("addend", OrNull(&Int)),
+    ];
+    fields.extend_from_slice(extra);
+    check_fields(reloc, &fields, "relocation");
+    if reloc["symbol"].is_object() {
+        check_fields(&reloc["symbol"], &[
+            ("index", Int), ("name", OrNull(&Str)), ("value", OrNull(&Int)),
+            ("version", OrNull(&Object)),
+        ], "relocation symbol");
+    }
+}
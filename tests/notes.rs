@@ -0,0 +1,46 @@
+//! Prints hand-made note areas with `ElfNote` and `ElfNoteIter`.
+
+extern crate writeork;
+
+use writeork::elf::elf_note::NT_GNU_HWCAP;
+use writeork::elf::prelude::*;
+use writeork::to_host::Endianness;
+
+/// Appends a little-endian note named `name` to `data`.
+fn push_note(data: &mut Vec<u8>, name: &[u8], n_type: u32, desc: &[u8]) {
+    data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+    data.extend_from_slice(&(desc.len() as u32).to_le_bytes());
+    data.extend_from_slice(&n_type.to_le_bytes());
+    data.extend_from_slice(name);
+    data.resize(data.len().next_multiple_of(4), 0);
+    data.extend_from_slice(desc);
+    data.resize(data.len().next_multiple_of(4), 0);
+}
+
+/// Corrupt notes are reported to the caller instead of being printed to
+/// stderr.
+#[test]
+fn returns_diagnostics() {
+    let mut data = Vec::new();
+    push_note(&mut data, b"GNU\0", NT_GNU_HWCAP, &[1, 0, 0, 0]);
+    push_note(&mut data, b"stapsdt\0", 3, &[0; 8]);
+    // A note whose description runs past the end of the area.
+    data.extend_from_slice(&[4, 0, 0, 0, 0x40, 0, 0, 0, 1, 0, 0, 0]);
+    data.extend_from_slice(b"GNU\0");
+
+    let mut notes = ElfNoteIter::new(&data, Endianness::LE, 4, false).unwrap();
+    let mut out = Vec::new();
+    let errors: Vec<Option<String>> = notes.by_ref()
+        .map(|note| note.print(ElfEhdrMachine::EM_X86_64, false, &mut out).unwrap())
+        .collect();
+    assert_eq!(errors, [
+        Some(String::from("<corrupt GNU_HWCAP>")),
+        Some(String::from("corrupt stapdt note - the data size is too small")),
+    ]);
+    assert!(notes.is_truncated());
+    assert!(String::from_utf8(out).unwrap().contains("  <corrupt - note is too small>"));
+
+    let mut notes = ElfNoteIter::new(&data[..48], Endianness::LE, 4, false).unwrap();
+    assert_eq!(notes.by_ref().count(), 2);
+    assert!(!notes.is_truncated());
+}
@@ -0,0 +1,322 @@
+//! Patches the dynamically linked fixtures in `test_programs` with
+//! `elf_patch` and `writeork patch` and reads the results back.
+
+extern crate writeork;
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use common::fixture_path;
+use writeork::elf::elf_dyn::get_dyn_val;
+use writeork::elf::elf_dyn_tag::{DT_NEEDED, DT_RPATH, DT_RUNPATH, DT_SONAME, DT_VERNEED, DT_VERNEEDNUM};
+use writeork::elf::elf_patch::{PatchOp, patch_file};
+use writeork::elf::prelude::*;
+
+fn patch(name: &str, ops: &[PatchOp]) -> Vec<u8> {
+    let data = fs::read(fixture_path(name)).unwrap();
+    let (patched, warnings) = patch_file(&ElfFile::parse(&data).unwrap(), ops).unwrap();
+    assert_eq!(warnings, Vec::<String>::new());
+    patched
+}
+
+/// The strings of the dynamic entries with `tag`, in order.
+fn dyn_strings(elf: &ElfFile, tag: u64) -> Vec<String> {
+    let strtab = elf.dynamic_strtab().unwrap();
+    elf.dynamic_entries().unwrap().iter()
+        .filter(|d| d.get_tag().get() == tag)
+        .map(|d| get_string(strtab, d.get_val() as usize))
+        .collect()
+}
+
+/// A path in the temporary directory for the output of a test.
+fn output_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("writeork-{}-{}", name, std::process::id()))
+}
+
+fn interpreter(elf: &ElfFile) -> String {
+    let phdr = elf.program_headers().unwrap().iter()
+        .find(|p| p.get_type() == ElfPhdrType::PT_INTERP)
+        .unwrap();
+    let bytes = elf.read_bytes(phdr.get_offset(), phdr.get_filesz(), "interpreter").unwrap();
+    String::from_utf8(bytes.split(|&b| b == 0).next().unwrap().to_vec()).unwrap()
+}
+
+/// Checks that every segment that moved is loaded by the new `PT_LOAD`,
+/// which has to be as far from its offset as the first one is.
+fn check_new_segment(elf: &ElfFile) {
+    let phdrs = elf.program_headers().unwrap();
+    let loads: Vec<_> = phdrs.iter().filter(|p| p.get_type() == ElfPhdrType::PT_LOAD).collect();
+    let last = loads.last().unwrap();
+    assert_eq!(last.get_vaddr() - last.get_offset(), loads[0].get_vaddr() - loads[0].get_offset());
+    assert!(loads.windows(2).all(|w| w[0].get_vaddr() + w[0].get_memsz() <= w[1].get_vaddr()));
+
+    for phdr in phdrs.iter().filter(|p| p.get_type() != ElfPhdrType::PT_LOAD) {
+        if phdr.get_offset() >= last.get_offset() {
+            assert!(last.contains_vaddr(phdr.get_vaddr()), "{:?}", phdr);
+            assert_eq!(phdr.get_vaddr() - last.get_vaddr(), phdr.get_offset() - last.get_offset());
+        }
+    }
+    let pt_phdr = phdrs.iter().find(|p| p.get_type() == ElfPhdrType::PT_PHDR);
+    if let Some(pt_phdr) = pt_phdr {
+        assert_eq!(pt_phdr.get_offset(), elf.header().get_phoff());
+    }
+}
+
+#[test]
+fn keeps_what_fits_in_place() {
+    let data = fs::read(fixture_path("x86_64-pie")).unwrap();
+    let elf = ElfFile::parse(&data).unwrap();
+    let needed = dyn_strings(&elf, DT_NEEDED);
+    let interp = interpreter(&elf);
+    let shorter = &interp[..interp.len() - 1];
+
+    let patched = patch("x86_64-pie", &[
+        PatchOp::SetInterpreter(shorter.to_string()),
+        PatchOp::SetSoname(needed[0].clone()),
+    ]);
+    assert_eq!(patched.len(), data.len());
+    let edited = ElfFile::parse(&patched).unwrap();
+    assert_eq!(interpreter(&edited), shorter);
+    assert_eq!(dyn_strings(&edited, DT_SONAME), &needed[..1]);
+    assert_eq!(edited.program_headers().unwrap().len(), elf.program_headers().unwrap().len());
+}
+
+#[test]
+fn moves_what_grows() {
+    let interp = "/opt/sysroot/lib64/ld-linux-x86-64.so.2";
+    let patched = patch("x86_64-pie", &[
+        PatchOp::SetInterpreter(interp.to_string()),
+        PatchOp::SetRunpath("$ORIGIN/../lib".to_string()),
+        PatchOp::AddNeeded("libm.so.6".to_string()),
+        PatchOp::AddNeeded("libz.so.1".to_string()),
+    ]);
+    let elf = ElfFile::parse(&patched).unwrap();
+    assert_eq!(interpreter(&elf), interp);
+    assert_eq!(dyn_strings(&elf, DT_NEEDED), ["libc.so.6", "libm.so.6", "libz.so.1"]);
+    assert_eq!(dyn_strings(&elf, DT_RUNPATH), ["$ORIGIN/../lib"]);
+    check_new_segment(&elf);
+
+    // The section headers follow the moved contents.
+    let sections = elf.sections().unwrap();
+    let interp_shdr = sections.iter().find(|s| s.get_name() == ".interp").unwrap();
+    assert_eq!(elf.section_data(interp_shdr).unwrap(), format!("{}\0", interp).as_bytes());
+    let dynstr = sections.iter().find(|s| s.get_name() == ".dynstr").unwrap();
+    assert_eq!(elf.section_data(dynstr).unwrap(), elf.dynamic_strtab().unwrap());
+}
+
+/// Enough new entries to outgrow the dynamic section move it too, to a
+/// writable segment.
+#[test]
+fn moves_dynamic_section() {
+    let libraries: Vec<String> = (0..40).map(|i| format!("libextra{}.so", i)).collect();
+    let ops: Vec<_> = libraries.iter().map(|l| PatchOp::AddNeeded(l.clone())).collect();
+    for fixture in &["x86_64-pie", "i386-shlib"] {
+        let patched = patch(fixture, &ops);
+        let elf = ElfFile::parse(&patched).unwrap();
+        let needed = dyn_strings(&elf, DT_NEEDED);
+        assert_eq!(&needed[needed.len() - 40..], &libraries[..], "{}", fixture);
+        check_new_segment(&elf);
+
+        let phdrs = elf.program_headers().unwrap();
+        let dynamic = phdrs.iter().find(|p| p.get_type() == ElfPhdrType::PT_DYNAMIC).unwrap();
+        let last = phdrs.iter().rfind(|p| p.get_type() == ElfPhdrType::PT_LOAD).unwrap();
+        assert!(last.contains_vaddr(dynamic.get_vaddr()), "{}", fixture);
+        assert_eq!(last.get_flags().to_string(), "RW ", "{}", fixture);
+        let shdr = elf.sections().unwrap().iter().find(|s| s.get_name() == ".dynamic").unwrap();
+        assert_eq!(shdr.get_addr(), dynamic.get_vaddr(), "{}", fixture);
+    }
+}
+
+#[test]
+fn removes_needed() {
+    let patched = patch("x86_64-pie", &[
+        PatchOp::AddNeeded("libm.so.6".to_string()),
+        PatchOp::RemoveNeeded("libc.so.6".to_string()),
+    ]);
+    let elf = ElfFile::parse(&patched).unwrap();
+    assert_eq!(dyn_strings(&elf, DT_NEEDED), ["libm.so.6"]);
+}
+
+/// Removing a library that isn't needed changes nothing and is reported.
+#[test]
+fn warns_about_libraries_not_needed() {
+    let data = fs::read(fixture_path("x86_64-pie")).unwrap();
+    let ops = [PatchOp::RemoveNeeded("libm.so.6".to_string())];
+    let (patched, warnings) = patch_file(&ElfFile::parse(&data).unwrap(), &ops).unwrap();
+    assert_eq!(warnings, ["libm.so.6 is not a needed library"]);
+    let elf = ElfFile::parse(&patched).unwrap();
+    assert_eq!(dyn_strings(&elf, DT_NEEDED), ["libc.so.6"]);
+}
+
+/// Setting either search path replaces the other one, which ld.so would
+/// otherwise let win over `DT_RPATH`, in its place among the entries.
+#[test]
+fn replaces_search_path() {
+    let data = patch("x86_64-pie", &[PatchOp::SetRunpath("/opt/lib".to_string())]);
+    let elf = ElfFile::parse(&data).unwrap();
+    let tags = |elf: &ElfFile| -> Vec<u64> {
+        elf.dynamic_entries().unwrap().iter().map(|d| d.get_tag().get()).collect()
+    };
+    let index = tags(&elf).iter().position(|&t| t == DT_RUNPATH).unwrap();
+
+    for (op, tag, other) in [(PatchOp::SetRpath("$ORIGIN".to_string()), DT_RPATH, DT_RUNPATH),
+                             (PatchOp::SetRunpath("$ORIGIN".to_string()), DT_RUNPATH, DT_RPATH)] {
+        let (patched, _) = patch_file(&elf, &[PatchOp::SetRpath("/usr/lib".to_string()), op])
+            .unwrap();
+        let edited = ElfFile::parse(&patched).unwrap();
+        assert_eq!(dyn_strings(&edited, tag), ["$ORIGIN"]);
+        assert!(dyn_strings(&edited, other).is_empty());
+        assert_eq!(tags(&edited).iter().position(|&t| t == tag), Some(index));
+    }
+}
+
+/// Removing a library takes its version requirements along, which is what
+/// lets the program still run once libm is gone: libc defines `frexp`
+/// too.
+#[test]
+fn removes_version_requirements() {
+    let data = fs::read(fixture_path("x86_64-libm")).unwrap();
+    let elf = ElfFile::parse(&data).unwrap();
+    let dyns = elf.dynamic_entries().unwrap();
+    let verneed = get_dyn_val(dyns, DT_VERNEED).unwrap();
+    assert_eq!(get_dyn_val(dyns, DT_VERNEEDNUM), Some(2));
+
+    // libm's requirement comes first and has version 4, libc's 2 and 3.
+    for (library, versions) in [("libm.so.6", &[4][..]), ("libc.so.6", &[2, 3][..])] {
+        let patched = patch("x86_64-libm", &[PatchOp::RemoveNeeded(library.to_string())]);
+        let edited = ElfFile::parse(&patched).unwrap();
+        let dyns = edited.dynamic_entries().unwrap();
+        assert_eq!(get_dyn_val(dyns, DT_VERNEEDNUM), Some(1), "{}", library);
+        assert_eq!(get_dyn_val(dyns, DT_VERNEED) == Some(verneed), library == "libc.so.6");
+        // There are 8 dynamic symbols, the rest is what follows the table.
+        let versyms = edited.symbol_versions().unwrap().unwrap().get_versyms()[..8].to_vec();
+        assert!(versyms.iter().all(|v| !versions.contains(v)), "{}: {:?}", library, versyms);
+        assert!(versyms.contains(&1), "{}: {:?}", library, versyms);
+    }
+
+    let patched = patch("x86_64-libm", &[
+        PatchOp::RemoveNeeded("libc.so.6".to_string()),
+        PatchOp::RemoveNeeded("libm.so.6".to_string()),
+    ]);
+    let edited = ElfFile::parse(&patched).unwrap();
+    assert_eq!(get_dyn_val(edited.dynamic_entries().unwrap(), DT_VERNEED), None);
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[test]
+fn patched_program_runs() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("writeork-run-{}", std::process::id()));
+    let patched = patch("x86_64-libm", &[PatchOp::RemoveNeeded("libm.so.6".to_string())]);
+    fs::write(&path, &patched).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    let output = Command::new(&path).output();
+    fs::remove_file(&path).unwrap();
+
+    let output = output.expect("failed to run the patched program");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(output.stdout, b"0.75 6\n");
+}
+
+#[test]
+fn rejects_files_without_dynamic_section() {
+    let data = fs::read(fixture_path("x86_64.o")).unwrap();
+    match patch_file(&ElfFile::parse(&data).unwrap(), &[]) {
+        Err(ElfError::CannotPatch(_)) => {}
+        other => panic!("unexpected {:?}", other.map(|_| ())),
+    }
+    let data = fs::read(fixture_path("i386-shlib")).unwrap();
+    let ops = [PatchOp::SetInterpreter("/lib/ld-linux.so.2".to_string())];
+    match patch_file(&ElfFile::parse(&data).unwrap(), &ops) {
+        Err(ElfError::CannotPatch(_)) => {}
+        other => panic!("unexpected {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn patches_from_command_line() {
+    let output = std::env::temp_dir().join(format!("writeork-patch-{}", std::process::id()));
+    let status = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("patch")
+        .arg(fixture_path("i386-shlib"))
+        .args(["--set-soname", "librenamed.so", "--add-needed", "libm.so.6"])
+        .arg("-o").arg(&output)
+        .status()
+        .expect("failed to run writeork");
+    assert!(status.success());
+
+    let data = fs::read(&output).unwrap();
+    fs::remove_file(&output).unwrap();
+    let elf = ElfFile::parse(&data).unwrap();
+    assert_eq!(dyn_strings(&elf, DT_SONAME), ["librenamed.so"]);
+    assert_eq!(dyn_strings(&elf, DT_NEEDED), ["libm.so.6"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("patch")
+        .arg(fixture_path("i386-shlib"))
+        .output()
+        .expect("failed to run writeork");
+    assert!(!output.status.success());
+
+    // Each option takes a single value.
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("patch")
+        .arg(fixture_path("i386-shlib"))
+        .args(["--add-needed", "libm.so.6", "libz.so.1"])
+        .arg("-o").arg(output_path("single-value"))
+        .output()
+        .expect("failed to run writeork");
+    assert!(!output.status.success());
+}
+
+/// The options can come before the file too, as with patchelf.
+#[test]
+fn patches_with_file_last() {
+    let output = output_path("file-last");
+    let status = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .args(["patch", "--remove-needed", "libc.so.6", "--add-needed", "libm.so.6", "-o"])
+        .arg(&output)
+        .arg(fixture_path("x86_64-pie"))
+        .status()
+        .expect("failed to run writeork");
+    assert!(status.success());
+
+    let data = fs::read(&output).unwrap();
+    fs::remove_file(&output).unwrap();
+    let elf = ElfFile::parse(&data).unwrap();
+    assert_eq!(dyn_strings(&elf, DT_NEEDED), ["libm.so.6"]);
+}
+
+/// Without `-o` the file is replaced, keeping its permissions, and the
+/// edits are made in the order they are given in.
+#[cfg(unix)]
+#[test]
+fn patches_in_place_in_order() {
+    let dir = std::env::temp_dir().join(format!("writeork-in-place-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("x86_64-pie");
+    for (first, second, needed) in [("--add-needed=libm.so.6", "--remove-needed=libm.so.6", false),
+                                    ("--remove-needed=libm.so.6", "--add-needed=libm.so.6", true)] {
+        fs::copy(fixture_path("x86_64-pie"), &path).unwrap();
+        let status = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .arg("patch").arg(&path).args([first, second])
+            .status()
+            .expect("failed to run writeork");
+        assert!(status.success());
+
+        let data = fs::read(&path).unwrap();
+        let elf = ElfFile::parse(&data).unwrap();
+        let libraries = dyn_strings(&elf, DT_NEEDED);
+        assert_eq!(libraries.iter().any(|l| l == "libm.so.6"), needed, "{} {}", first, second);
+    }
+    let mode = |path: &Path| {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).unwrap().permissions().mode()
+    };
+    assert_eq!(mode(&path), mode(&fixture_path("x86_64-pie")));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,271 @@
+//! Runs `writeork --compat=readelf` over the fixtures in `test_programs`
+//! and compares its output with readelf's, stored in `test_results`.
+//! `test_programs/build.sh` rebuilds the fixtures and
+//! `test_results/update.sh` regenerates the expected outputs.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use common::{fixture_path, read_fixture};
+
+/// Options checked on every fixture.
+const OPTIONS: &[&str] = &[
+    "file-header",
+    "program-headers",
+    "section-headers",
+    "syms",
+    "dyn-syms",
+    "relocs",
+    "dynamic",
+    "notes",
+    "version-info",
+];
+
+/// Section contents dumped from every fixture, compared with
+/// `test_results/<fixture>/section-dumps`.
+const SECTION_DUMPS: &[&str] = &[
+    "-x", ".text", "-R", ".text", "-R", ".eh_frame", "-R", ".data", "-p", ".strtab", "-p", ".text",
+    "-x", "1",
+];
+
+/// Sections dumped with `-z` from the fixtures with compressed sections,
+/// compared with `test_results/<fixture>/decompressed-dumps`. Both the
+/// `SHF_COMPRESSED` and the `.zdebug` names are asked for; the ones a
+/// fixture doesn't have are left out with a warning.
+const DECOMPRESSED_DUMPS: &[&str] = &[
+    "-z", "-x", ".debug_info", "-R", ".debug_info", "-R", ".debug_line", "-p", ".debug_line_str",
+    "-x", ".zdebug_info", "-R", ".zdebug_info", "-R", ".zdebug_line",
+];
+
+/// The `--debug-dump` frame dumps, checked on every fixture and compared
+/// with `test_results/<fixture>/<dump>`.
+const FRAME_DUMPS: &[&str] = &["frames", "frames-interp"];
+
+const FIXTURES: &[&str] = &[
+    "be/a.out",
+    "x86_64-pie",
+    "i386-shlib",
+    "aarch64.o",
+    "armv7.o",
+    "armeb.o",
+    "i386.o",
+    "mips.o",
+    "mipsel.o",
+    "mips64.o",
+    "mips64el.o",
+    "powerpc64.o",
+    "powerpc64le.o",
+    "riscv32.o",
+    "riscv64.o",
+    "x86_64.o",
+    "x86_64-zlib.o",
+    "x86_64-zlib-gnu.o",
+    "x86_64-zstd.o",
+    "i386-zlib.o",
+    "i386-zlib-gnu.o",
+    "i386-zstd.o",
+    "x86_64-dwarf2",
+    "x86_64-dwarf3",
+    "x86_64-dwarf4",
+    "x86_64-dwarf5",
+    "i386-dwarf",
+    "libfixture.a",
+    "libfixture-thin.a",
+    "libfixture-sym64.a",
+    "i386-core",
+];
+
+/// The fixture with more sections than `e_shnum` can count, and the
+/// options checked on it. readelf's section headers would take megabytes.
+const MANY_SECTIONS: &str = "i386-sections.o";
+const MANY_SECTIONS_OPTIONS: &[&str] = &["file-header", "syms"];
+
+/// Whether `fixture` is one of those with compressed debug sections.
+fn is_compressed(fixture: &str) -> bool {
+    ["-zlib.o", "-zlib-gnu.o", "-zstd.o"].iter().any(|s| fixture.ends_with(s))
+}
+
+/// Whether `fixture` is an archive, whose `--archive-index` is compared
+/// with `test_results/<fixture>/archive-index`.
+fn is_archive(fixture: &str) -> bool {
+    fixture.ends_with(".a")
+}
+
+/// Whether `fixture` has DWARF debugging information, whose
+/// `--debug-dump=info` is compared with `test_results/<fixture>/debug-info`.
+fn has_dwarf(fixture: &str) -> bool {
+    is_compressed(fixture) || fixture.contains("-dwarf")
+}
+
+/// Runs writeork with `args` on `fixture` and returns a description of
+/// how its output differs from the expected one, stored as `name`, if it
+/// does.
+fn check(fixture: &str, name: &str, args: &[&str]) -> Option<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let expected_path = root.join("test_results").join(fixture).join(name);
+    // String dumps may hold any bytes, so the outputs are compared as
+    // bytes and only converted for the report.
+    let expected = fs::read(&expected_path)
+        .unwrap_or_else(|err| panic!("{}: {}", expected_path.display(), err));
+
+    // The names of archive members are printed with the path of the
+    // archive, which is relative in the expected outputs.
+    let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .current_dir(root)
+        .arg("--compat=readelf")
+        .args(args)
+        .arg(Path::new("test_programs").join(fixture))
+        .output()
+        .expect("failed to run writeork");
+    if output.stdout == expected {
+        return None;
+    }
+    let actual = String::from_utf8_lossy(&output.stdout);
+    let expected = String::from_utf8_lossy(&expected);
+    let line = actual.lines().zip(expected.lines())
+        .position(|(a, e)| a != e)
+        .unwrap_or_else(|| actual.lines().count().min(expected.lines().count()));
+    Some(format!(
+        "{} {}: line {} differs\n  expected: {:?}\n  actual:   {:?}",
+        name, fixture, line + 1,
+        expected.lines().nth(line).unwrap_or("<end of output>"),
+        actual.lines().nth(line).unwrap_or("<end of output>")))
+}
+
+#[test]
+fn matches_readelf() {
+    let mut failures = Vec::new();
+    for fixture in FIXTURES {
+        for option in OPTIONS {
+            if let Some(failure) = check(fixture, option, &[&format!("--{}", option)]) {
+                failures.push(failure);
+            }
+        }
+        if let Some(failure) = check(fixture, "section-dumps", SECTION_DUMPS) {
+            failures.push(failure);
+        }
+        if is_compressed(fixture) {
+            if let Some(failure) = check(fixture, "decompressed-dumps", DECOMPRESSED_DUMPS) {
+                failures.push(failure);
+            }
+        }
+        if has_dwarf(fixture) {
+            if let Some(failure) = check(fixture, "debug-info", &["--debug-dump=info"]) {
+                failures.push(failure);
+            }
+        }
+        for dump in FRAME_DUMPS {
+            if let Some(failure) = check(fixture, dump, &[&format!("--debug-dump={}", dump)]) {
+                failures.push(failure);
+            }
+        }
+        if is_archive(fixture) {
+            if let Some(failure) = check(fixture, "archive-index", &["--archive-index"]) {
+                failures.push(failure);
+            }
+        }
+    }
+    for option in MANY_SECTIONS_OPTIONS {
+        if let Some(failure) = check(MANY_SECTIONS, option, &[&format!("--{}", option)]) {
+            failures.push(failure);
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+/// Like readelf, options may follow the file, including the repeatable
+/// dumps: the output is the same as with them before it.
+#[test]
+fn reads_options_after_file() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    for fixture in ["x86_64.o", "x86_64-pie"] {
+        let mut expected = Vec::new();
+        for name in ["file-header", "section-dumps"] {
+            expected.extend(fs::read(root.join("test_results").join(fixture).join(name)).unwrap());
+        }
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .arg("--compat=readelf")
+            .arg(fixture_path(fixture))
+            .args(SECTION_DUMPS)
+            .arg("-h")
+            .output()
+            .expect("failed to run writeork");
+        assert!(output.stdout == expected, "{}:\n{}", fixture, String::from_utf8_lossy(&output.stdout));
+    }
+}
+
+/// The key of `-S` only lists the GNU flags for the OS/ABIs that know
+/// them, as readelf does.
+#[test]
+fn flags_key_depends_on_osabi() {
+    let mut data = read_fixture("x86_64.o");
+    let path = std::env::temp_dir().join(format!("writeork-osabi-{}", std::process::id()));
+    let cases: &[(u8, &str)] = &[
+        (0, "  D (mbind), l (large), p (processor specific)"),
+        (3, "  R (retain), D (mbind), l (large), p (processor specific)"),
+        (6, "  l (large), p (processor specific)"),
+        (9, "  R (retain), D (mbind), l (large), p (processor specific)"),
+    ];
+    for &(osabi, expected) in cases {
+        data[7] = osabi;
+        fs::write(&path, &data).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .arg("--section-headers")
+            .arg(&path)
+            .output()
+            .expect("failed to run writeork");
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().last(), Some(expected), "OS/ABI {}", osabi);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+/// OS/ABI values from 64 up are named by machine, as readelf does.
+#[test]
+fn osabi_depends_on_machine() {
+    let path = std::env::temp_dir().join(format!("writeork-machine-{}", std::process::id()));
+    let cases: &[(&str, u8, &str)] = &[
+        ("armv7.o", 64, "<unknown: 40>"),
+        ("armv7.o", 65, "ARM FDPIC"),
+        ("armv7.o", 97, "ARM"),
+        ("x86_64.o", 65, "<unknown: 41>"),
+        ("x86_64.o", 97, "<unknown: 61>"),
+    ];
+    for &(fixture, osabi, expected) in cases {
+        let mut data = read_fixture(fixture);
+        data[7] = osabi;
+        fs::write(&path, &data).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_writeork"))
+            .arg("--file-header")
+            .arg(&path)
+            .output()
+            .expect("failed to run writeork");
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let line = format!("  OS/ABI:                            {}", expected);
+        assert!(stdout.lines().any(|l| l == line), "{} with OS/ABI {}", fixture, osabi);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+/// A reader that goes away early, like `head`, ends the dump without a
+/// panic or an error message.
+#[test]
+fn stops_quietly_on_broken_pipe() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_writeork"))
+        .arg("--section-headers")
+        .arg(fixture_path(MANY_SECTIONS))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run writeork");
+    let mut start = [0; 16];
+    child.stdout.take().unwrap().read_exact(&mut start).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    assert_eq!(output.status.code(), Some(1));
+}
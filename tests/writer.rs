@@ -0,0 +1,219 @@
+//! Edits the fixtures in `test_programs` with `ElfWriter` and reads the
+//! results back with `ElfFile`.
+
+extern crate writeork;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use common::read_fixture;
+use writeork::elf::elf_shdr_flags::SHF_ALLOC;
+use writeork::elf::elf_shdr_type::{SHT_NOTE, SHT_PROGBITS};
+use writeork::elf::prelude::*;
+
+fn find_section(elf: &ElfFile, name: &str) -> usize {
+    elf.sections().unwrap().iter().position(|s| s.get_name() == name).unwrap()
+}
+
+/// Every ELF fixture, of both classes and byte orders, comes back
+/// unchanged when nothing is edited.
+#[test]
+fn round_trips_unchanged() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_programs");
+    let mut paths: Vec<_> = fs::read_dir(&root).unwrap()
+        .chain(fs::read_dir(root.join("be")).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut count = 0;
+    for path in paths {
+        let data = fs::read(&path).unwrap();
+        let elf = match ElfFile::parse(&data) {
+            Ok(elf) => elf,
+            Err(_) => continue,
+        };
+        let written = ElfWriter::new(&elf).unwrap().write().unwrap();
+        assert!(written == data, "{} changed", path.display());
+        count += 1;
+    }
+    assert!(count >= 30, "only {} fixtures", count);
+}
+
+#[test]
+fn edits_header() {
+    let data = read_fixture("x86_64-pie");
+    let elf = ElfFile::parse(&data).unwrap();
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    writer.header_mut().set_entry(0x1234);
+    writer.header_mut().set_flags(ElfEhdrFlags::from(0x5));
+    let written = writer.write().unwrap();
+
+    let edited = ElfFile::parse(&written).unwrap();
+    assert_eq!(edited.entry(), 0x1234);
+    assert_eq!(edited.flags().get(), 0x5);
+    assert_eq!(written.len(), data.len());
+    assert_eq!(edited.program_headers().unwrap(), elf.program_headers().unwrap());
+    assert_eq!(edited.sections().unwrap(), elf.sections().unwrap());
+}
+
+#[test]
+fn grows_section() {
+    let data = read_fixture("x86_64.o");
+    let elf = ElfFile::parse(&data).unwrap();
+    let index = find_section(&elf, ".data");
+    let mut contents = elf.section_data(&elf.sections().unwrap()[index]).unwrap().to_vec();
+    contents.extend_from_slice(&[0xaa; 0x10]);
+
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    let offset = writer.set_section_data(index, &contents).unwrap();
+    assert!(offset >= data.len() as u64);
+    assert_eq!(offset % 8, 0);
+    let written = writer.write().unwrap();
+
+    let edited = ElfFile::parse(&written).unwrap();
+    let sections = edited.sections().unwrap();
+    assert_eq!(edited.section_data(&sections[index]).unwrap(), &contents[..]);
+    for (old, new) in elf.sections().unwrap().iter().zip(sections) {
+        if old.get_name() == ".data" {
+            continue;
+        }
+        assert_eq!(old, new);
+        assert_eq!(elf.section_data(old).unwrap(), edited.section_data(new).unwrap());
+    }
+}
+
+#[test]
+fn shrinks_section_in_place() {
+    let data = read_fixture("i386.o");
+    let elf = ElfFile::parse(&data).unwrap();
+    let index = find_section(&elf, ".rodata.str1.1");
+
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    let offset = writer.set_section_data(index, b"hi\0").unwrap();
+    assert_eq!(offset, elf.sections().unwrap()[index].get_offset());
+    let written = writer.write().unwrap();
+
+    assert_eq!(written.len(), data.len());
+    let edited = ElfFile::parse(&written).unwrap();
+    assert_eq!(edited.section_data(&edited.sections().unwrap()[index]).unwrap(), b"hi\0");
+}
+
+/// Adding a section moves the section header table and adds its name to
+/// `.shstrtab`, in either byte order.
+#[test]
+fn adds_section() {
+    for fixture in &["x86_64.o", "mips.o", "powerpc64.o", "i386.o"] {
+        let data = read_fixture(fixture);
+        let elf = ElfFile::parse(&data).unwrap();
+        let count = elf.sections().unwrap().len();
+
+        let mut writer = ElfWriter::new(&elf).unwrap();
+        let mut shdr = ElfShdr::new(".note.writeork", SHT_NOTE.into());
+        shdr.set_addralign(4);
+        let index = writer.add_section(shdr, b"\x04\0\0\0\0\0\0\0\x01\0\0\0test").unwrap();
+        assert_eq!(index, count);
+        let written = writer.write().unwrap();
+
+        let edited = ElfFile::parse(&written).unwrap();
+        let sections = edited.sections().unwrap();
+        assert_eq!(sections.len(), count + 1, "{}", fixture);
+        assert_eq!(sections[index].get_name(), ".note.writeork", "{}", fixture);
+        assert_eq!(sections[index].get_offset() % 4, 0, "{}", fixture);
+        assert_eq!(&edited.section_data(&sections[index]).unwrap()[12..], b"test");
+        let names: Vec<_> = sections[..count].iter().map(|s| s.get_name()).collect();
+        let old_names: Vec<_> = elf.sections().unwrap().iter().map(|s| s.get_name()).collect();
+        assert_eq!(names, old_names, "{}", fixture);
+    }
+}
+
+/// A program header more than the table has room for moves it to the
+/// end of the file, and `PT_PHDR` follows.
+#[test]
+fn moves_program_headers() {
+    let data = read_fixture("x86_64-pie");
+    let elf = ElfFile::parse(&data).unwrap();
+    let count = elf.program_headers().unwrap().len();
+
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    let mut phdr = ElfPhdr::new(ElfPhdrType::PT_LOAD);
+    phdr.set_flags(ElfPhdrFlags::from(4));
+    phdr.set_align(0x1000);
+    writer.program_headers_mut().push(phdr);
+    let written = writer.write().unwrap();
+
+    let edited = ElfFile::parse(&written).unwrap();
+    let phoff = edited.header().get_phoff();
+    assert!(phoff >= data.len() as u64);
+    assert_eq!(phoff % 8, 0);
+    let phdrs = edited.program_headers().unwrap();
+    assert_eq!(phdrs.len(), count + 1);
+    assert_eq!(phdrs[count], phdr);
+    let pt_phdr = phdrs.iter().find(|p| p.get_type() == ElfPhdrType::PT_PHDR).unwrap();
+    assert_eq!(pt_phdr.get_offset(), phoff);
+    assert_eq!(pt_phdr.get_filesz(), (count as u64 + 1) * 56);
+    assert_eq!(&written[..0x18], &data[..0x18]);
+}
+
+#[test]
+fn keeps_addresses_of_moved_sections() {
+    let data = read_fixture("x86_64-pie");
+    let elf = ElfFile::parse(&data).unwrap();
+    let index = elf.sections().unwrap().iter()
+        .position(|s| s.get_type().get() == SHT_PROGBITS && s.get_flags().contains(SHF_ALLOC))
+        .unwrap();
+    let addr = elf.sections().unwrap()[index].get_addr();
+
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    let offset = writer.set_section_data(index, &vec![0x90; 0x2000]).unwrap();
+    assert_eq!(offset % 0x1000, addr % 0x1000);
+    let written = writer.write().unwrap();
+
+    let edited = ElfFile::parse(&written).unwrap();
+    assert_eq!(edited.sections().unwrap()[index].get_addr(), addr);
+    assert_eq!(edited.sections().unwrap()[index].get_size(), 0x2000);
+}
+
+#[test]
+fn rejects_values_too_large_for_class() {
+    let data = read_fixture("i386.o");
+    let elf = ElfFile::parse(&data).unwrap();
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    writer.header_mut().set_entry(1 << 32);
+    match writer.write() {
+        Err(ElfError::DoesNotFit { what: "e_entry", value }) => assert_eq!(value, 1 << 32),
+        other => panic!("unexpected {:?}", other.map(|_| ())),
+    }
+}
+
+/// Headers that put the end of the segments past the address space, or
+/// far past the end of the file, make the writer fail instead of trying
+/// to pad the file to get there.
+#[test]
+fn rejects_bogus_segments() {
+    let mut data = read_fixture("x86_64-pie");
+    let load = {
+        let elf = ElfFile::parse(&data).unwrap();
+        let index = elf.program_headers().unwrap().iter()
+            .position(|p| p.get_type() == ElfPhdrType::PT_LOAD)
+            .unwrap();
+        (elf.header().get_phoff() + index as u64 * 56) as usize
+    };
+    for memsz in [u64::MAX - 0x1000, 1 << 62] {
+        data[load + 40..load + 48].copy_from_slice(&memsz.to_le_bytes());
+        let elf = ElfFile::parse(&data).unwrap();
+        let mut writer = ElfWriter::new(&elf).unwrap();
+        match writer.add_load_segment(4, ElfPhdrFlags::from(4)) {
+            Err(ElfError::CannotPatch(_)) => {}
+            other => panic!("unexpected {:?} for p_memsz {:#x}", other, memsz),
+        }
+    }
+
+    let elf = ElfFile::parse(&data).unwrap();
+    let mut writer = ElfWriter::new(&elf).unwrap();
+    let count = writer.sections().len();
+    assert!(writer.section_data(count).is_err());
+    assert!(writer.set_section_data(count, b"data").is_err());
+}